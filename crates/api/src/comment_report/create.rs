@@ -2,6 +2,7 @@ use crate::check_report_reason;
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
+  automod_engine::{run_automod_for_comment, AutomodTrigger},
   comment::{CommentReportResponse, CreateCommentReport},
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
@@ -56,7 +57,7 @@ pub async fn create_comment_report(
   let report_form = CommentReportForm {
     creator_id: person_id,
     comment_id,
-    original_comment_text: comment_view.comment.content,
+    original_comment_text: comment_view.comment.content.clone(),
     reason,
   };
 
@@ -90,6 +91,8 @@ pub async fn create_comment_report(
   )
   .await?;
 
+  run_automod_for_comment(&comment_view.comment, AutomodTrigger::Report, &context).await?;
+
   Ok(Json(CommentReportResponse {
     comment_report_view,
   }))
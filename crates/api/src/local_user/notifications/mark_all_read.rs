@@ -3,7 +3,7 @@ use lemmy_api_common::{context::LemmyContext, person::GetRepliesResponse};
 use lemmy_db_schema::source::{
   comment_reply::CommentReply,
   person_mention::PersonMention,
-  private_message::PrivateMessage,
+  private_message::PrivateMessageRead,
 };
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
@@ -26,7 +26,7 @@ pub async fn mark_all_notifications_read(
     .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
 
   // Mark all private_messages as read
-  PrivateMessage::mark_all_as_read(&mut context.pool(), person_id)
+  PrivateMessageRead::mark_all_as_read(&mut context.pool(), person_id, None)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
 
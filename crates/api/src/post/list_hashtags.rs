@@ -0,0 +1,28 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{HashtagView, ListHashtags, ListHashtagsResponse},
+  utils::check_private_instance,
+};
+use lemmy_db_schema::source::hashtag::Hashtag;
+use lemmy_db_views::structs::{LocalUserView, SiteView};
+use lemmy_utils::error::LemmyResult;
+
+/// Lists hashtags which are used in posts, most used first
+#[tracing::instrument(skip(context))]
+pub async fn list_hashtags(
+  data: Query<ListHashtags>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<ListHashtagsResponse>> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &site_view.local_site)?;
+
+  let hashtags = Hashtag::list(&mut context.pool(), data.page, data.limit)
+    .await?
+    .into_iter()
+    .map(|(hashtag, posts)| HashtagView { hashtag, posts })
+    .collect();
+
+  Ok(Json(ListHashtagsResponse { hashtags }))
+}
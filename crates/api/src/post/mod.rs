@@ -2,6 +2,7 @@ pub mod feature;
 pub mod get_link_metadata;
 pub mod hide;
 pub mod like;
+pub mod list_hashtags;
 pub mod list_post_likes;
 pub mod lock;
 pub mod mark_read;
@@ -2,6 +2,7 @@ use crate::check_report_reason;
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
+  automod_engine::{run_automod_for_post, AutomodTrigger},
   context::LemmyContext,
   post::{CreatePostReport, PostReportResponse},
   send_activity::{ActivityChannel, SendActivityData},
@@ -51,9 +52,9 @@ pub async fn create_post_report(
   let report_form = PostReportForm {
     creator_id: person_id,
     post_id,
-    original_post_name: post_view.post.name,
-    original_post_url: post_view.post.url,
-    original_post_body: post_view.post.body,
+    original_post_name: post_view.post.name.clone(),
+    original_post_url: post_view.post.url.clone(),
+    original_post_body: post_view.post.body.clone(),
     reason,
   };
 
@@ -87,5 +88,7 @@ pub async fn create_post_report(
   )
   .await?;
 
+  run_automod_for_post(&post_view.post, AutomodTrigger::Report, &context).await?;
+
   Ok(Json(PostReportResponse { post_report_view }))
 }
@@ -0,0 +1,49 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  private_message::{EditPrivateMessageConversation, PrivateMessageConversationResponse},
+};
+use lemmy_db_schema::source::private_message_conversation::{
+  PrivateMessageConversationParticipant,
+  PrivateMessageConversationParticipantUpdateForm,
+};
+use lemmy_db_views::structs::{LocalUserView, PrivateMessageConversationView};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn edit_pm_conversation(
+  data: Json<EditPrivateMessageConversation>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PrivateMessageConversationResponse>> {
+  let conversation_id = data.conversation_id;
+  let person_id = local_user_view.person.id;
+
+  // Only participants have any state to change
+  PrivateMessageConversationParticipant::read(&mut context.pool(), conversation_id, person_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPrivateMessageConversation)?;
+
+  let form = PrivateMessageConversationParticipantUpdateForm {
+    muted: data.muted,
+    archived: data.archived,
+    request: data.request,
+    ..Default::default()
+  };
+  PrivateMessageConversationParticipant::update(
+    &mut context.pool(),
+    conversation_id,
+    person_id,
+    &form,
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
+
+  let conversation_view =
+    PrivateMessageConversationView::read(&mut context.pool(), conversation_id, person_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPrivateMessageConversation)?;
+  Ok(Json(PrivateMessageConversationResponse {
+    conversation_view,
+  }))
+}
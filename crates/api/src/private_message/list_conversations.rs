@@ -0,0 +1,30 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  private_message::{ListPrivateMessageConversations, ListPrivateMessageConversationsResponse},
+};
+use lemmy_db_views::{
+  private_message_conversation_view::PrivateMessageConversationQuery,
+  structs::LocalUserView,
+};
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_pm_conversations(
+  data: Query<ListPrivateMessageConversations>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListPrivateMessageConversationsResponse>> {
+  let conversations = PrivateMessageConversationQuery {
+    archived_only: data.archived_only.unwrap_or_default(),
+    requests_only: data.requests_only.unwrap_or_default(),
+    page: data.page,
+    limit: data.limit,
+  }
+  .list(&mut context.pool(), local_user_view.person.id)
+  .await?;
+
+  Ok(Json(ListPrivateMessageConversationsResponse {
+    conversations,
+  }))
+}
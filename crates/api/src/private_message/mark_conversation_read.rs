@@ -0,0 +1,38 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  private_message::{MarkPrivateMessageConversationAsRead, PrivateMessageConversationResponse},
+};
+use lemmy_db_schema::source::private_message_conversation::PrivateMessageConversationParticipant;
+use lemmy_db_views::structs::{LocalUserView, PrivateMessageConversationView};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn mark_pm_conversation_as_read(
+  data: Json<MarkPrivateMessageConversationAsRead>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PrivateMessageConversationResponse>> {
+  let conversation_id = data.conversation_id;
+  let person_id = local_user_view.person.id;
+
+  PrivateMessageConversationParticipant::read(&mut context.pool(), conversation_id, person_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPrivateMessageConversation)?;
+
+  PrivateMessageConversationParticipant::mark_as_read(
+    &mut context.pool(),
+    conversation_id,
+    person_id,
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
+
+  let conversation_view =
+    PrivateMessageConversationView::read(&mut context.pool(), conversation_id, person_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPrivateMessageConversation)?;
+  Ok(Json(PrivateMessageConversationResponse {
+    conversation_view,
+  }))
+}
@@ -4,7 +4,10 @@ use lemmy_api_common::{
   private_message::{MarkPrivateMessageAsRead, PrivateMessageResponse},
 };
 use lemmy_db_schema::{
-  source::private_message::{PrivateMessage, PrivateMessageUpdateForm},
+  source::{
+    private_message::{PrivateMessage, PrivateMessageRead},
+    private_message_conversation::PrivateMessageConversationParticipant,
+  },
   traits::Crud,
 };
 use lemmy_db_views::structs::{LocalUserView, PrivateMessageView};
@@ -21,25 +24,28 @@ pub async fn mark_pm_as_read(
   let orig_private_message = PrivateMessage::read(&mut context.pool(), private_message_id)
     .await?
     .ok_or(LemmyErrorType::CouldntFindPrivateMessage)?;
-  if local_user_view.person.id != orig_private_message.recipient_id {
+  // Any participant of the conversation can mark messages of others as read
+  let person_id = local_user_view.person.id;
+  let participant = PrivateMessageConversationParticipant::read(
+    &mut context.pool(),
+    orig_private_message.conversation_id,
+    person_id,
+  )
+  .await?;
+  if participant.is_none() || person_id == orig_private_message.creator_id {
     Err(LemmyErrorType::CouldntUpdatePrivateMessage)?
   }
 
   // Doing the update
   let private_message_id = data.private_message_id;
-  let read = data.read;
-  PrivateMessage::update(
-    &mut context.pool(),
-    private_message_id,
-    &PrivateMessageUpdateForm {
-      read: Some(read),
-      ..Default::default()
-    },
-  )
-  .await
+  if data.read {
+    PrivateMessageRead::mark_as_read(&mut context.pool(), private_message_id, person_id).await
+  } else {
+    PrivateMessageRead::mark_as_unread(&mut context.pool(), private_message_id, person_id).await
+  }
   .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
 
-  let view = PrivateMessageView::read(&mut context.pool(), private_message_id)
+  let view = PrivateMessageView::read(&mut context.pool(), private_message_id, person_id)
     .await?
     .ok_or(LemmyErrorType::CouldntFindPrivateMessage)?;
   Ok(Json(PrivateMessageResponse {
@@ -1 +1,4 @@
+pub mod edit_conversation;
+pub mod list_conversations;
+pub mod mark_conversation_read;
 pub mod mark_read;
@@ -14,7 +14,10 @@ use lemmy_db_schema::{
   traits::{Crud, Reportable},
 };
 use lemmy_db_views::structs::{LocalUserView, PrivateMessageReportView};
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  utils::validation::is_valid_body_field,
+};
 
 #[tracing::instrument(skip(context))]
 pub async fn create_pm_report(
@@ -38,11 +41,22 @@ pub async fn create_pm_report(
     Err(LemmyErrorType::CouldntCreateReport)?
   }
 
+  // Only encrypted messages need an excerpt, the text of others is available to admins anyway
+  let decrypted_excerpt = match &data.decrypted_excerpt {
+    Some(excerpt) if private_message.encrypted => {
+      let excerpt = excerpt.trim().to_string();
+      is_valid_body_field(&excerpt, false)?;
+      Some(excerpt)
+    }
+    _ => None,
+  };
+
   let report_form = PrivateMessageReportForm {
     creator_id: person_id,
     private_message_id,
     original_pm_text: private_message.content,
     reason,
+    decrypted_excerpt,
   };
 
   let report = PrivateMessageReport::report(&mut context.pool(), &report_form)
@@ -0,0 +1,48 @@
+use super::parse_domain;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::AdminBlockInstance,
+  utils::{check_expire_time, is_admin},
+  SuccessResponse,
+};
+use lemmy_db_schema::source::{
+  federation_blocklist::{FederationBlockList, FederationBlockListForm},
+  instance::Instance,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Adds a single instance to the federation blocklist, or removes it. Unlike `update_site` this
+/// keeps the other entries unchanged.
+#[tracing::instrument(skip(context))]
+pub async fn admin_block_instance(
+  data: Json<AdminBlockInstance>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let domain = parse_domain(&data.instance).ok_or(LemmyErrorType::InvalidUrl)?;
+  let instance = Instance::read_or_create(&mut context.pool(), domain).await?;
+  if instance.id == local_user_view.person.instance_id {
+    Err(LemmyErrorType::CantBlockLocalInstance)?
+  }
+
+  if data.block {
+    let form = FederationBlockListForm {
+      instance_id: instance.id,
+      reason: data.reason.clone(),
+      comment: data.comment.clone(),
+      expires: check_expire_time(data.expires)?,
+      ..Default::default()
+    };
+    FederationBlockList::block(&mut context.pool(), &form).await?;
+  } else {
+    FederationBlockList::unblock(&mut context.pool(), instance.id).await?;
+  }
+
+  Ok(Json(SuccessResponse::default()))
+}
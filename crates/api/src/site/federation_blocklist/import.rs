@@ -0,0 +1,224 @@
+use super::parse_domain;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{ImportFederationBlocklist, ImportFederationBlocklistResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::source::{
+  federation_blocklist::{FederationBlockList, FederationBlockListForm},
+  federation_limitlist::{FederationLimitList, FederationLimitListForm},
+  instance::Instance,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+use std::collections::HashSet;
+
+/// Imports a shared blocklist. Entries with severity `suspend` are blocked, and entries with
+/// severity `silence` get limited federation. For domains which are already blocked or limited,
+/// the reason and comment are updated. With `dry_run` nothing is changed, but the response
+/// shows which followed communities and users would be cut off.
+#[tracing::instrument(skip(context, data))]
+pub async fn import_federation_blocklist(
+  data: Json<ImportFederationBlocklist>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ImportFederationBlocklistResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let local_domain = context.settings().get_hostname_without_port()?;
+  let blocked_domains: HashSet<String> = FederationBlockList::list(&mut context.pool())
+    .await?
+    .into_iter()
+    .map(|(instance, _)| instance.domain.to_lowercase())
+    .collect();
+  let limited_domains: HashSet<String> = FederationLimitList::list(&mut context.pool())
+    .await?
+    .into_iter()
+    .map(|(instance, _)| instance.domain.to_lowercase())
+    .collect();
+
+  let mut imported_domains = HashSet::from([local_domain]);
+  let mut blocks = vec![];
+  let mut limits = vec![];
+  let mut block_updates = vec![];
+  let mut limit_updates = vec![];
+  let mut skipped = vec![];
+  for entry in parse_blocklist(&data.csv) {
+    let Some(domain) =
+      parse_domain(&entry.domain).filter(|domain| imported_domains.insert(domain.clone()))
+    else {
+      skipped.push(entry.domain);
+      continue;
+    };
+    let is_blocked = blocked_domains.contains(&domain);
+    let is_limited = limited_domains.contains(&domain);
+    let has_reason = entry.reason.is_some() || entry.comment.is_some();
+    match entry.severity.as_deref() {
+      None | Some("suspend") if !is_blocked => blocks.push((domain, entry)),
+      None | Some("suspend") if has_reason => block_updates.push((domain, entry)),
+      Some("silence") if !is_blocked && !is_limited => limits.push((domain, entry)),
+      Some("silence") if is_limited && has_reason => limit_updates.push((domain, entry)),
+      _ => skipped.push(entry.domain),
+    }
+  }
+  let blocked: Vec<String> = blocks.iter().map(|(domain, _)| domain.clone()).collect();
+  let limited: Vec<String> = limits.iter().map(|(domain, _)| domain.clone()).collect();
+  let updated: Vec<String> = block_updates
+    .iter()
+    .chain(&limit_updates)
+    .map(|(domain, _)| domain.clone())
+    .collect();
+
+  let cut_off_communities =
+    FederationBlockList::followed_communities(&mut context.pool(), &blocked).await?;
+  let cut_off_persons =
+    FederationBlockList::followed_persons(&mut context.pool(), &blocked).await?;
+
+  if !data.dry_run.unwrap_or_default() {
+    // Reasons of known domains are updated, while keeping their expiry.
+    for (domain, entry) in blocks.into_iter().chain(block_updates) {
+      let instance = Instance::read_or_create(&mut context.pool(), domain).await?;
+      let form = FederationBlockListForm {
+        instance_id: instance.id,
+        reason: entry.reason,
+        comment: entry.comment,
+        ..Default::default()
+      };
+      FederationBlockList::block(&mut context.pool(), &form).await?;
+    }
+    for (domain, entry) in limits.into_iter().chain(limit_updates) {
+      let instance = Instance::read_or_create(&mut context.pool(), domain).await?;
+      let form = FederationLimitListForm {
+        instance_id: instance.id,
+        reason: entry.reason,
+        comment: entry.comment,
+        ..Default::default()
+      };
+      FederationLimitList::limit(&mut context.pool(), &form).await?;
+    }
+  }
+
+  Ok(Json(ImportFederationBlocklistResponse {
+    blocked,
+    limited,
+    updated,
+    skipped,
+    cut_off_communities,
+    cut_off_persons,
+  }))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct BlocklistEntry {
+  domain: String,
+  severity: Option<String>,
+  reason: Option<String>,
+  comment: Option<String>,
+}
+
+/// Parses a blocklist in the CSV format exported by Mastodon and used by FediBlock, with a header
+/// like `#domain,#severity,#reject_media,#reject_reports,#public_comment,#obfuscate`. Without a
+/// header row, the first column is used as domain.
+fn parse_blocklist(csv: &str) -> Vec<BlocklistEntry> {
+  let mut rows = csv
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .map(split_csv_line)
+    .peekable();
+  let is_header = |row: &Vec<String>| row.iter().any(|cell| column_name(cell) == "domain");
+  let header = rows.next_if(is_header).unwrap_or_default();
+  let column = |name: &str| header.iter().position(|cell| column_name(cell) == name);
+  let domain_column = column("domain").unwrap_or(0);
+  let severity_column = column("severity");
+  let reason_column = column("public_comment").or(column("comment"));
+  let comment_column = column("private_comment");
+
+  rows
+    .map(|row| {
+      let cell = |column: Option<usize>| {
+        column
+          .and_then(|c| row.get(c))
+          .map(|cell| cell.trim().to_string())
+          .filter(|cell| !cell.is_empty())
+      };
+      BlocklistEntry {
+        domain: cell(Some(domain_column)).unwrap_or_default(),
+        severity: cell(severity_column).map(|s| s.to_lowercase()),
+        reason: cell(reason_column),
+        comment: cell(comment_column),
+      }
+    })
+    .collect()
+}
+
+fn column_name(cell: &str) -> &str {
+  cell.trim().trim_start_matches('#')
+}
+
+/// Splits a line of CSV into cells. Cells may be quoted, with `""` as escaped quote.
+fn split_csv_line(line: &str) -> Vec<String> {
+  let mut cells = vec![];
+  let mut cell = String::new();
+  let mut quoted = false;
+  let mut chars = line.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '"' if quoted && chars.peek() == Some(&'"') => {
+        cell.push('"');
+        chars.next();
+      }
+      '"' => quoted = !quoted,
+      ',' if !quoted => cells.push(std::mem::take(&mut cell)),
+      _ => cell.push(c),
+    }
+  }
+  cells.push(cell);
+  cells
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_parse_mastodon_blocklist() {
+    let csv = r#"#domain,#severity,#reject_media,#reject_reports,#public_comment,#obfuscate
+spam.example,suspend,true,true,"Spam, harassment",false
+quiet.example,silence,false,false,,false
+f*.example,suspend,false,false,"Obfuscated ""domain""",true
+"#;
+    let entries = parse_blocklist(csv);
+    assert_eq!(3, entries.len());
+    assert_eq!(
+      BlocklistEntry {
+        domain: "spam.example".to_string(),
+        severity: Some("suspend".to_string()),
+        reason: Some("Spam, harassment".to_string()),
+        comment: None,
+      },
+      entries[0]
+    );
+    assert_eq!(Some("silence".to_string()), entries[1].severity);
+    assert_eq!(None, entries[1].reason);
+    assert_eq!(Some("Obfuscated \"domain\"".to_string()), entries[2].reason);
+    assert_eq!(None, parse_domain(&entries[2].domain));
+  }
+
+  #[test]
+  fn test_parse_blocklist_without_header() {
+    let entries = parse_blocklist("Spam.Example\n\nother.example,suspend\n");
+    assert_eq!(2, entries.len());
+    assert_eq!(
+      Some("spam.example".to_string()),
+      parse_domain(&entries[0].domain)
+    );
+    assert_eq!(None, entries[0].severity);
+    assert_eq!("other.example", entries[1].domain);
+    assert_eq!(None, entries[1].severity);
+  }
+}
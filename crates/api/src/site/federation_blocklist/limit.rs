@@ -0,0 +1,48 @@
+use super::parse_domain;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::AdminLimitInstance,
+  utils::{check_expire_time, is_admin},
+  SuccessResponse,
+};
+use lemmy_db_schema::source::{
+  federation_limitlist::{FederationLimitList, FederationLimitListForm},
+  instance::Instance,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Limits federation with an instance, or removes the limit. Content from limited instances is
+/// still received, but hidden from the `All` listing for users who don't follow the community.
+#[tracing::instrument(skip(context))]
+pub async fn admin_limit_instance(
+  data: Json<AdminLimitInstance>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let domain = parse_domain(&data.instance).ok_or(LemmyErrorType::InvalidUrl)?;
+  let instance = Instance::read_or_create(&mut context.pool(), domain).await?;
+  if instance.id == local_user_view.person.instance_id {
+    Err(LemmyErrorType::CantBlockLocalInstance)?
+  }
+
+  if data.limit {
+    let form = FederationLimitListForm {
+      instance_id: instance.id,
+      reason: data.reason.clone(),
+      comment: data.comment.clone(),
+      expires: check_expire_time(data.expires)?,
+      ..Default::default()
+    };
+    FederationLimitList::limit(&mut context.pool(), &form).await?;
+  } else {
+    FederationLimitList::unlimit(&mut context.pool(), instance.id).await?;
+  }
+
+  Ok(Json(SuccessResponse::default()))
+}
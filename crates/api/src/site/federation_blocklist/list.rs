@@ -0,0 +1,48 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{DefederatedInstance, GetFederationBlocklistResponse},
+};
+use lemmy_db_schema::source::{
+  federation_blocklist::FederationBlockList,
+  federation_limitlist::FederationLimitList,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+/// Lists the instances which this instance doesn't federate with or limits, and why. Comments are
+/// only shown to admins.
+#[tracing::instrument(skip(context))]
+pub async fn get_federation_blocklist(
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<GetFederationBlocklistResponse>> {
+  let is_admin = local_user_view.is_some_and(|l| l.local_user.admin);
+  let blocklist = FederationBlockList::list(&mut context.pool())
+    .await?
+    .into_iter()
+    .map(|(instance, block)| DefederatedInstance {
+      instance,
+      reason: block.reason,
+      comment: block.comment.filter(|_| is_admin),
+      published: block.published,
+      expires: block.expires,
+    })
+    .collect();
+  let limitlist = FederationLimitList::list(&mut context.pool())
+    .await?
+    .into_iter()
+    .map(|(instance, limit)| DefederatedInstance {
+      instance,
+      reason: limit.reason,
+      comment: limit.comment.filter(|_| is_admin),
+      published: limit.published,
+      expires: limit.expires,
+    })
+    .collect();
+
+  Ok(Json(GetFederationBlocklistResponse {
+    blocklist,
+    limitlist,
+  }))
+}
@@ -0,0 +1,24 @@
+use url::Url;
+
+pub mod block;
+pub mod import;
+pub mod limit;
+pub mod list;
+
+/// Returns the lowercase domain if the input is a valid hostname, without scheme, path or
+/// wildcards. Blocklists use wildcards for obfuscated domains, which can't be blocked.
+fn parse_domain(domain: &str) -> Option<String> {
+  let domain = domain.trim().to_lowercase();
+  if !domain
+    .chars()
+    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+  {
+    return None;
+  }
+  let url = Url::parse(&format!("https://{domain}")).ok()?;
+  if url.domain() == Some(domain.as_str()) && url.path() == "/" {
+    Some(domain)
+  } else {
+    None
+  }
+}
@@ -0,0 +1,20 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::GetFederationHealthResponse,
+  utils::{build_federation_health, is_admin},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_federation_health(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetFederationHealthResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let health = build_federation_health(&mut context.pool()).await?;
+  Ok(Json(health))
+}
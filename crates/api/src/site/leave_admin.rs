@@ -5,6 +5,7 @@ use lemmy_db_schema::{
     actor_language::SiteLanguage,
     language::Language,
     local_site_url_blocklist::LocalSiteUrlBlocklist,
+    local_site_url_cleaning_rule::LocalSiteUrlCleaningRule,
     local_user::{LocalUser, LocalUserUpdateForm},
     moderator::{ModAdd, ModAddForm},
     tagline::Tagline,
@@ -64,6 +65,7 @@ pub async fn leave_admin(
   let custom_emojis =
     CustomEmojiView::get_all(&mut context.pool(), site_view.local_site.id).await?;
   let blocked_urls = LocalSiteUrlBlocklist::get_all(&mut context.pool()).await?;
+  let url_cleaning_rules = LocalSiteUrlCleaningRule::get_all(&mut context.pool()).await?;
 
   Ok(Json(GetSiteResponse {
     site_view,
@@ -75,5 +77,6 @@ pub async fn leave_admin(
     taglines,
     custom_emojis,
     blocked_urls,
+    url_cleaning_rules,
   }))
 }
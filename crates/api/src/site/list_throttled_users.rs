@@ -0,0 +1,47 @@
+use actix_web::web::{Data, Json};
+use chrono::{DateTime, Utc};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{ListThrottledUsersResponse, ThrottledActionCount, ThrottledUser},
+  utils::is_admin,
+};
+use lemmy_db_schema::newtypes::LocalUserId;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_throttled_users(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListThrottledUsersResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let mut throttled_users = vec![];
+  for (local_user_id, throttled, last_throttled) in context.rate_limit_cell().throttled_users() {
+    // The user may have been deleted since
+    let Some(user_view) =
+      LocalUserView::read(&mut context.pool(), LocalUserId(local_user_id)).await?
+    else {
+      continue;
+    };
+    let throttled_actions = throttled
+      .counts
+      .into_iter()
+      .filter(|(_, count)| *count > 0)
+      .map(|(action, count)| ThrottledActionCount {
+        action: action.to_string(),
+        count: count.into(),
+      })
+      .collect();
+    throttled_users.push(ThrottledUser {
+      person: user_view.person,
+      local_user_id: user_view.local_user.id,
+      throttled_actions,
+      last_throttled: DateTime::<Utc>::from(last_throttled),
+    });
+  }
+  throttled_users.sort_by_key(|u| std::cmp::Reverse(u.last_throttled));
+
+  Ok(Json(ListThrottledUsersResponse { throttled_users }))
+}
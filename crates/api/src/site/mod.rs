@@ -1,7 +1,11 @@
 pub mod block;
 pub mod federated_instances;
+pub mod federation_blocklist;
+pub mod federation_health;
 pub mod leave_admin;
 pub mod list_all_media;
+pub mod list_throttled_users;
 pub mod mod_log;
 pub mod purge;
 pub mod registration_applications;
+pub mod reset_federation_queue;
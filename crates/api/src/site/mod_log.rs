@@ -13,6 +13,7 @@ use lemmy_db_views_moderator::structs::{
   AdminPurgePostView,
   ModAddCommunityView,
   ModAddView,
+  ModAutomodActionView,
   ModBanFromCommunityView,
   ModBanView,
   ModFeaturePostView,
@@ -67,6 +68,7 @@ pub async fn get_mod_log(
     page: data.page,
     limit: data.limit,
     hide_modlog_names,
+    before: None,
   };
   let removed_posts = match type_ {
     All | ModRemovePost => ModRemovePostView::list(&mut context.pool(), params).await?,
@@ -112,6 +114,11 @@ pub async fn get_mod_log(
     _ => Default::default(),
   };
 
+  let automod_actions = match type_ {
+    All | ModAutomodAction => ModAutomodActionView::list(&mut context.pool(), params).await?,
+    _ => Default::default(),
+  };
+
   // These arrays are only for the full modlog, when a community isn't given
   let (
     banned,
@@ -183,5 +190,6 @@ pub async fn get_mod_log(
     admin_purged_posts,
     admin_purged_comments,
     hidden_communities,
+    automod_actions,
   }))
 }
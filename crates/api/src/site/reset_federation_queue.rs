@@ -0,0 +1,30 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::ResetFederationQueue,
+  utils::is_admin,
+  SuccessResponse,
+};
+use lemmy_db_schema::source::federation_queue_state::FederationQueueState;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Requests a reset of the federation queue, which is applied by the federation worker within a
+/// minute.
+#[tracing::instrument(skip(context))]
+pub async fn reset_federation_queue(
+  data: Json<ResetFederationQueue>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let updated =
+    FederationQueueState::request_reset(&mut context.pool(), data.instance_id, data.reset).await?;
+  if updated == 0 {
+    Err(LemmyErrorType::CouldntFindFederationQueue)?
+  }
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -0,0 +1,86 @@
+use lemmy_db_schema::{
+  newtypes::{AutomodRuleId, CommunityId},
+  source::automod::AutomodRule,
+  AutomodAction,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create an automod rule. Rules without a community apply to the whole site, and can only be
+/// created by admins.
+pub struct CreateAutomodRule {
+  pub community_id: Option<CommunityId>,
+  pub name: String,
+  pub enabled: Option<bool>,
+  pub apply_to_posts: Option<bool>,
+  pub apply_to_comments: Option<bool>,
+  pub title_regex: Option<String>,
+  pub body_regex: Option<String>,
+  pub domain: Option<String>,
+  pub max_account_age_days: Option<i32>,
+  pub max_karma: Option<i32>,
+  pub min_report_count: Option<i32>,
+  pub action: AutomodAction,
+  pub action_message: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Edit an automod rule. Conditions and the message are replaced, so any which are left out get
+/// cleared.
+pub struct EditAutomodRule {
+  pub id: AutomodRuleId,
+  pub name: String,
+  pub enabled: Option<bool>,
+  pub apply_to_posts: Option<bool>,
+  pub apply_to_comments: Option<bool>,
+  pub title_regex: Option<String>,
+  pub body_regex: Option<String>,
+  pub domain: Option<String>,
+  pub max_account_age_days: Option<i32>,
+  pub max_karma: Option<i32>,
+  pub min_report_count: Option<i32>,
+  pub action: AutomodAction,
+  pub action_message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete an automod rule.
+pub struct DeleteAutomodRule {
+  pub id: AutomodRuleId,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the automod rules of a community, or the site-wide rules if no community is given.
+pub struct ListAutomodRules {
+  pub community_id: Option<CommunityId>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for an automod rule.
+pub struct AutomodRuleResponse {
+  pub automod_rule: AutomodRule,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A list of automod rules.
+pub struct ListAutomodRulesResponse {
+  pub automod_rules: Vec<AutomodRule>,
+}
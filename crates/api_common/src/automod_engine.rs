@@ -0,0 +1,781 @@
+use crate::{
+  build_response::send_local_notifs,
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{
+    generate_inbox_url,
+    generate_local_apub_endpoint,
+    generate_shared_inbox_url,
+    EndpointType,
+  },
+};
+use activitypub_federation::{config::Data, http_signatures::generate_actor_keypair};
+use chrono::{TimeDelta, Utc};
+use lemmy_db_schema::{
+  aggregates::structs::PersonAggregates,
+  newtypes::{CommunityId, DbUrl, PersonId},
+  source::{
+    automod::AutomodRule,
+    comment::{Comment, CommentInsertForm, CommentUpdateForm},
+    comment_report::{CommentReport, CommentReportForm},
+    community::Community,
+    local_site::LocalSite,
+    moderator::{
+      ModAutomodAction,
+      ModAutomodActionForm,
+      ModLockPost,
+      ModLockPostForm,
+      ModRemoveComment,
+      ModRemoveCommentForm,
+      ModRemovePost,
+      ModRemovePostForm,
+    },
+    person::{Person, PersonInsertForm},
+    post::{Post, PostUpdateForm},
+    post_report::{PostReport, PostReportForm},
+  },
+  traits::{ApubActor, Crud},
+  AutomodAction,
+};
+use lemmy_db_views::structs::SiteView;
+use lemmy_db_views_actor::structs::CommunityView;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  utils::validation::build_and_check_regex,
+  CACHE_DURATION_API,
+};
+use moka::future::Cache;
+use std::sync::LazyLock;
+use tracing::warn;
+use url::Url;
+
+/// What caused the automod rules to be checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutomodTrigger {
+  /// The content was created or edited.
+  Write,
+  /// The content received a new report. Only rules with a report count condition are checked,
+  /// so that content restored by a moderator isn't removed again by an unrelated report.
+  Report,
+}
+
+/// Everything about a post or comment which automod rules can match against.
+struct AutomodFacts<'a> {
+  is_post: bool,
+  title: Option<&'a str>,
+  body: Option<&'a str>,
+  url: Option<&'a Url>,
+  account_age: TimeDelta,
+  karma: i64,
+  report_count: i64,
+}
+
+/// Returns the local bot account which is shown as moderator for automated actions. It is
+/// created on first use.
+pub async fn get_system_account(context: &LemmyContext) -> LemmyResult<Person> {
+  static SYSTEM_ACCOUNT: LazyLock<Cache<(), Person>> = LazyLock::new(|| {
+    Cache::builder()
+      .max_capacity(1)
+      .time_to_live(CACHE_DURATION_API)
+      .build()
+  });
+
+  Ok(
+    SYSTEM_ACCOUNT
+      .try_get_with::<_, LemmyError>((), async {
+        let local_site = LocalSite::read(&mut context.pool()).await?;
+        if let Some(person_id) = local_site.system_account_id {
+          if let Some(person) = Person::read(&mut context.pool(), person_id).await? {
+            return Ok(person);
+          }
+        }
+        create_system_account(context).await
+      })
+      .await
+      .map_err(|e| anyhow::anyhow!("Failed to get system account due to `{}`", e))?,
+  )
+}
+
+async fn create_system_account(context: &LemmyContext) -> LemmyResult<Person> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+
+  // Find a free name, in case a user already registered as automod
+  let mut name = "automod".to_string();
+  let mut suffix = 1;
+  while Person::read_from_name(&mut context.pool(), &name, true)
+    .await?
+    .is_some()
+  {
+    suffix += 1;
+    name = format!("automod_{suffix}");
+  }
+
+  let actor_keypair = generate_actor_keypair()?;
+  let actor_id = generate_local_apub_endpoint(
+    EndpointType::Person,
+    &name,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let person_form = PersonInsertForm {
+    actor_id: Some(actor_id.clone()),
+    inbox_url: Some(generate_inbox_url(&actor_id)?),
+    shared_inbox_url: Some(generate_shared_inbox_url(context.settings())?),
+    private_key: Some(actor_keypair.private_key),
+    bot_account: Some(true),
+    ..PersonInsertForm::new(name, actor_keypair.public_key, site_view.site.instance_id)
+  };
+  let person = Person::create(&mut context.pool(), &person_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::UserAlreadyExists)?;
+
+  // Another request may have created a system account at the same time, in that case use theirs.
+  let system_account_id =
+    LocalSite::set_system_account_if_empty(&mut context.pool(), person.id).await?;
+  match system_account_id {
+    Some(id) if id != person.id => {
+      Person::delete(&mut context.pool(), person.id).await?;
+      Ok(
+        Person::read(&mut context.pool(), id)
+          .await?
+          .ok_or(LemmyErrorType::CouldntFindPerson)?,
+      )
+    }
+    _ => Ok(person),
+  }
+}
+
+/// Checks a post against the automod rules of its community and the site, and applies the
+/// actions of all matching rules.
+pub async fn run_automod_for_post(
+  post: &Post,
+  trigger: AutomodTrigger,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let Some(rules) = load_rules(post.community_id, post.creator_id, trigger, context).await? else {
+    return Ok(());
+  };
+  let rules = rules
+    .into_iter()
+    .filter(|r| r.apply_to_posts)
+    .collect::<Vec<_>>();
+  if rules.is_empty() || post.deleted || post.removed {
+    return Ok(());
+  }
+
+  let report_count = if rules.iter().any(|r| r.min_report_count.is_some()) {
+    PostReport::count_unresolved_for_object(&mut context.pool(), post.id).await?
+  } else {
+    0
+  };
+  let (account_age, karma) = creator_facts(post.creator_id, &rules, context).await?;
+  let facts = AutomodFacts {
+    is_post: true,
+    title: Some(&post.name),
+    body: post.body.as_deref(),
+    url: post.url.as_ref().map(DbUrl::inner),
+    account_age,
+    karma,
+    report_count,
+  };
+
+  let community = Community::read(&mut context.pool(), post.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+  let system_account = get_system_account(context).await?;
+  let mut post = post.clone();
+  for rule in rules.iter().filter(|r| rule_matches(r, &facts)) {
+    let removed = apply_post_action(rule, &mut post, &community, &system_account, context).await?;
+    // Nothing left to do once the post is gone
+    if removed {
+      break;
+    }
+  }
+  Ok(())
+}
+
+/// Checks a comment against the automod rules of its community and the site, and applies the
+/// actions of all matching rules.
+pub async fn run_automod_for_comment(
+  comment: &Comment,
+  trigger: AutomodTrigger,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let post = Post::read(&mut context.pool(), comment.post_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+  let Some(rules) = load_rules(post.community_id, comment.creator_id, trigger, context).await?
+  else {
+    return Ok(());
+  };
+  let rules = rules
+    .into_iter()
+    .filter(|r| r.apply_to_comments)
+    .collect::<Vec<_>>();
+  if rules.is_empty() || comment.deleted || comment.removed {
+    return Ok(());
+  }
+
+  let report_count = if rules.iter().any(|r| r.min_report_count.is_some()) {
+    CommentReport::count_unresolved_for_object(&mut context.pool(), comment.id).await?
+  } else {
+    0
+  };
+  let (account_age, karma) = creator_facts(comment.creator_id, &rules, context).await?;
+  let facts = AutomodFacts {
+    is_post: false,
+    title: None,
+    body: Some(&comment.content),
+    url: None,
+    account_age,
+    karma,
+    report_count,
+  };
+
+  let community = Community::read(&mut context.pool(), post.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+  let system_account = get_system_account(context).await?;
+  for rule in rules.iter().filter(|r| rule_matches(r, &facts)) {
+    let removed =
+      apply_comment_action(rule, comment, &post, &community, &system_account, context).await?;
+    if removed {
+      break;
+    }
+  }
+  Ok(())
+}
+
+/// Reads the rules which need to be checked. Returns None if the creator is exempt from automod,
+/// which is the case for moderators, admins and the system account itself.
+async fn load_rules(
+  community_id: CommunityId,
+  creator_id: PersonId,
+  trigger: AutomodTrigger,
+  context: &LemmyContext,
+) -> LemmyResult<Option<Vec<AutomodRule>>> {
+  let mut rules =
+    AutomodRule::list_enabled_for_community(&mut context.pool(), community_id).await?;
+  if trigger == AutomodTrigger::Report {
+    rules.retain(|r| r.min_report_count.is_some());
+  }
+  if rules.is_empty() {
+    return Ok(None);
+  }
+
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let is_exempt = local_site.system_account_id == Some(creator_id)
+    || CommunityView::is_mod_or_admin(&mut context.pool(), creator_id, community_id).await?;
+  Ok((!is_exempt).then_some(rules))
+}
+
+/// Reads account age and karma of the creator, but only if any of the rules needs them.
+async fn creator_facts(
+  creator_id: PersonId,
+  rules: &[AutomodRule],
+  context: &LemmyContext,
+) -> LemmyResult<(TimeDelta, i64)> {
+  let account_age = if rules.iter().any(|r| r.max_account_age_days.is_some()) {
+    let creator = Person::read(&mut context.pool(), creator_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPerson)?;
+    Utc::now() - creator.published
+  } else {
+    TimeDelta::zero()
+  };
+  let karma = if rules.iter().any(|r| r.max_karma.is_some()) {
+    PersonAggregates::read(&mut context.pool(), creator_id)
+      .await?
+      .map(|a| a.post_score + a.comment_score)
+      .unwrap_or_default()
+  } else {
+    0
+  };
+  Ok((account_age, karma))
+}
+
+/// A rule matches if all of its conditions match. Rules without any condition never match.
+fn rule_matches(rule: &AutomodRule, facts: &AutomodFacts) -> bool {
+  let mut has_condition = false;
+
+  if let Some(title_regex) = &rule.title_regex {
+    has_condition = true;
+    if !facts.is_post || !regex_matches(rule, title_regex, facts.title) {
+      return false;
+    }
+  }
+  if let Some(body_regex) = &rule.body_regex {
+    has_condition = true;
+    if !regex_matches(rule, body_regex, facts.body) {
+      return false;
+    }
+  }
+  if let Some(domain) = &rule.domain {
+    has_condition = true;
+    if !facts.url.is_some_and(|u| url_on_domain(u, domain)) {
+      return false;
+    }
+  }
+  if let Some(max_account_age_days) = rule.max_account_age_days {
+    has_condition = true;
+    if facts.account_age >= TimeDelta::days(max_account_age_days.into()) {
+      return false;
+    }
+  }
+  if let Some(max_karma) = rule.max_karma {
+    has_condition = true;
+    if facts.karma > max_karma.into() {
+      return false;
+    }
+  }
+  if let Some(min_report_count) = rule.min_report_count {
+    has_condition = true;
+    if facts.report_count < min_report_count.into() {
+      return false;
+    }
+  }
+
+  has_condition
+}
+
+fn regex_matches(rule: &AutomodRule, regex: &str, text: Option<&str>) -> bool {
+  match build_and_check_regex(&Some(regex)) {
+    Ok(Some(regex)) => text.is_some_and(|t| regex.is_match(t)),
+    Ok(None) => false,
+    Err(e) => {
+      warn!("Invalid regex in automod rule {}: {e}", rule.id.0);
+      false
+    }
+  }
+}
+
+/// Checks if the url is on the given domain or one of its subdomains.
+fn url_on_domain(url: &Url, domain: &str) -> bool {
+  let domain = domain.trim().trim_start_matches("*.").to_lowercase();
+  url.domain().is_some_and(|d| {
+    let d = d.to_lowercase();
+    d == domain || d.ends_with(&format!(".{domain}"))
+  })
+}
+
+/// The reason shown in the modlog and reports, falls back to the rule name.
+fn action_reason(rule: &AutomodRule) -> String {
+  rule
+    .action_message
+    .clone()
+    .unwrap_or_else(|| format!("Automod rule: {}", rule.name))
+}
+
+/// Applies the action of a matching rule to a post. Returns true if the post was removed.
+///
+/// Each rule acts only once on the same post, so that a post which a moderator restored or
+/// unlocked isn't acted on again when it is edited.
+async fn apply_post_action(
+  rule: &AutomodRule,
+  post: &mut Post,
+  community: &Community,
+  system_account: &Person,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<bool> {
+  if ModAutomodAction::exists_for_rule(&mut context.pool(), rule.id, post.id, None).await? {
+    return Ok(false);
+  }
+  let reason = action_reason(rule);
+  let removed = match rule.action {
+    AutomodAction::Remove | AutomodAction::Hold => {
+      *post = remove_post(post, community, system_account, reason.clone(), context).await?;
+      if rule.action == AutomodAction::Hold {
+        report_post(post, community, system_account, reason.clone(), context).await?;
+      }
+      true
+    }
+    AutomodAction::Lock => {
+      if !post.locked {
+        *post = Post::update(
+          &mut context.pool(),
+          post.id,
+          &PostUpdateForm {
+            locked: Some(true),
+            ..Default::default()
+          },
+        )
+        .await?;
+        let form = ModLockPostForm {
+          mod_person_id: system_account.id,
+          post_id: post.id,
+          locked: Some(true),
+        };
+        ModLockPost::create(&mut context.pool(), &form).await?;
+        if community.local {
+          ActivityChannel::submit_activity(
+            SendActivityData::LockPost(post.clone(), system_account.clone(), true),
+            context,
+          )
+          .await?;
+        }
+      }
+      false
+    }
+    AutomodAction::Report => {
+      report_post(post, community, system_account, reason.clone(), context).await?;
+      false
+    }
+    AutomodAction::Reply => {
+      let Some(message) = &rule.action_message else {
+        return Ok(false);
+      };
+      reply(message, post, None, system_account, context).await?;
+      false
+    }
+    AutomodAction::SetFlair => {
+      *post = Post::update(
+        &mut context.pool(),
+        post.id,
+        &PostUpdateForm {
+          flair: Some(rule.action_message.clone()),
+          ..Default::default()
+        },
+      )
+      .await?;
+      false
+    }
+  };
+  let form = ModAutomodActionForm {
+    mod_person_id: system_account.id,
+    rule_id: Some(rule.id),
+    post_id: post.id,
+    comment_id: None,
+    action: rule.action,
+    reason: Some(reason),
+  };
+  ModAutomodAction::create(&mut context.pool(), &form).await?;
+  Ok(removed)
+}
+
+/// Applies the action of a matching rule to a comment. Returns true if the comment was removed.
+/// Like for posts, each rule acts only once on the same comment.
+async fn apply_comment_action(
+  rule: &AutomodRule,
+  comment: &Comment,
+  post: &Post,
+  community: &Community,
+  system_account: &Person,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<bool> {
+  if ModAutomodAction::exists_for_rule(&mut context.pool(), rule.id, post.id, Some(comment.id))
+    .await?
+  {
+    return Ok(false);
+  }
+  let reason = action_reason(rule);
+  let removed = match rule.action {
+    AutomodAction::Remove | AutomodAction::Hold => {
+      let comment =
+        remove_comment(comment, community, system_account, reason.clone(), context).await?;
+      if rule.action == AutomodAction::Hold {
+        report_comment(&comment, community, system_account, reason.clone(), context).await?;
+      }
+      true
+    }
+    AutomodAction::Reply => {
+      let Some(message) = &rule.action_message else {
+        return Ok(false);
+      };
+      reply(message, post, Some(comment), system_account, context).await?;
+      false
+    }
+    AutomodAction::Report => {
+      report_comment(comment, community, system_account, reason.clone(), context).await?;
+      false
+    }
+    // Comments can't be locked or have a flair
+    AutomodAction::Lock | AutomodAction::SetFlair => return Ok(false),
+  };
+  let form = ModAutomodActionForm {
+    mod_person_id: system_account.id,
+    rule_id: Some(rule.id),
+    post_id: post.id,
+    comment_id: Some(comment.id),
+    action: rule.action,
+    reason: Some(reason),
+  };
+  ModAutomodAction::create(&mut context.pool(), &form).await?;
+  Ok(removed)
+}
+
+/// Removes a post in the name of the system account. The removal is only federated for local
+/// communities, as remote instances don't accept it from other moderators.
+pub(crate) async fn remove_post(
+  post: &Post,
+  community: &Community,
+  system_account: &Person,
+  reason: String,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<Post> {
+  let post = Post::update(
+    &mut context.pool(),
+    post.id,
+    &PostUpdateForm {
+      removed: Some(true),
+      ..Default::default()
+    },
+  )
+  .await?;
+  let form = ModRemovePostForm {
+    mod_person_id: system_account.id,
+    post_id: post.id,
+    removed: Some(true),
+    reason: Some(reason.clone()),
+    removal_reason_id: None,
+  };
+  ModRemovePost::create(&mut context.pool(), &form).await?;
+  if community.local {
+    ActivityChannel::submit_activity(
+      SendActivityData::RemovePost {
+        post: post.clone(),
+        moderator: system_account.clone(),
+        reason: Some(reason),
+        removed: true,
+      },
+      context,
+    )
+    .await?;
+  }
+  Ok(post)
+}
+
+/// Removes a comment in the name of the system account, see [remove_post].
+pub(crate) async fn remove_comment(
+  comment: &Comment,
+  community: &Community,
+  system_account: &Person,
+  reason: String,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<Comment> {
+  let comment = Comment::update(
+    &mut context.pool(),
+    comment.id,
+    &CommentUpdateForm {
+      removed: Some(true),
+      ..Default::default()
+    },
+  )
+  .await?;
+  let form = ModRemoveCommentForm {
+    mod_person_id: system_account.id,
+    comment_id: comment.id,
+    removed: Some(true),
+    reason: Some(reason.clone()),
+    removal_reason_id: None,
+  };
+  ModRemoveComment::create(&mut context.pool(), &form).await?;
+  if community.local {
+    ActivityChannel::submit_activity(
+      SendActivityData::RemoveComment {
+        comment: comment.clone(),
+        moderator: system_account.clone(),
+        community: community.clone(),
+        reason: Some(reason),
+      },
+      context,
+    )
+    .await?;
+  }
+  Ok(comment)
+}
+
+/// Reports a post in the name of the system account. Nothing happens if the system account
+/// already reported the post, as there can only be one report per user.
+pub(crate) async fn report_post(
+  post: &Post,
+  community: &Community,
+  system_account: &Person,
+  reason: String,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let report_form = PostReportForm {
+    creator_id: system_account.id,
+    post_id: post.id,
+    original_post_name: post.name.clone(),
+    original_post_url: post.url.clone(),
+    original_post_body: post.body.clone(),
+    reason: reason.clone(),
+  };
+  let report = PostReport::report_once(&mut context.pool(), &report_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntCreateReport)?;
+  if report.is_none() {
+    return Ok(());
+  }
+
+  ActivityChannel::submit_activity(
+    SendActivityData::CreateReport {
+      object_id: post.ap_id.inner().clone(),
+      actor: system_account.clone(),
+      community: community.clone(),
+      reason,
+    },
+    context,
+  )
+  .await
+}
+
+/// Reports a comment in the name of the system account, see [report_post].
+pub(crate) async fn report_comment(
+  comment: &Comment,
+  community: &Community,
+  system_account: &Person,
+  reason: String,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let report_form = CommentReportForm {
+    creator_id: system_account.id,
+    comment_id: comment.id,
+    original_comment_text: comment.content.clone(),
+    reason: reason.clone(),
+  };
+  let report = CommentReport::report_once(&mut context.pool(), &report_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntCreateReport)?;
+  if report.is_none() {
+    return Ok(());
+  }
+
+  ActivityChannel::submit_activity(
+    SendActivityData::CreateReport {
+      object_id: comment.ap_id.inner().clone(),
+      actor: system_account.clone(),
+      community: community.clone(),
+      reason,
+    },
+    context,
+  )
+  .await
+}
+
+/// Replies to the post, or to the comment if given, with a distinguished comment by the system
+/// account.
+async fn reply(
+  message: &str,
+  post: &Post,
+  parent: Option<&Comment>,
+  system_account: &Person,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let comment_form = CommentInsertForm::builder()
+    .content(message.to_string())
+    .post_id(post.id)
+    .creator_id(system_account.id)
+    .distinguished(Some(true))
+    .build();
+  let comment = Comment::create(&mut context.pool(), &comment_form, parent.map(|p| &p.path))
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntCreateComment)?;
+
+  send_local_notifs(vec![], comment.id, system_account, true, context, None).await?;
+
+  ActivityChannel::submit_activity(SendActivityData::CreateComment(comment), context).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lemmy_db_schema::newtypes::AutomodRuleId;
+
+  fn test_rule() -> AutomodRule {
+    AutomodRule {
+      id: AutomodRuleId(1),
+      community_id: None,
+      creator_id: PersonId(1),
+      name: "test".to_string(),
+      enabled: true,
+      apply_to_posts: true,
+      apply_to_comments: true,
+      title_regex: None,
+      body_regex: None,
+      domain: None,
+      max_account_age_days: None,
+      max_karma: None,
+      min_report_count: None,
+      action: AutomodAction::Remove,
+      action_message: None,
+      published: Utc::now(),
+      updated: None,
+    }
+  }
+
+  #[test]
+  fn test_rule_matches() -> LemmyResult<()> {
+    let url = Url::parse("https://cdn.spam.example/image.png")?;
+    let post_facts = AutomodFacts {
+      is_post: true,
+      title: Some("Buy cheap watches"),
+      body: Some("Visit my shop"),
+      url: Some(&url),
+      account_age: TimeDelta::hours(5),
+      karma: 3,
+      report_count: 2,
+    };
+    let comment_facts = AutomodFacts {
+      is_post: false,
+      title: None,
+      url: None,
+      ..post_facts
+    };
+
+    // A rule without conditions never matches
+    assert!(!rule_matches(&test_rule(), &post_facts));
+
+    let rule = AutomodRule {
+      title_regex: Some("cheap (watches|bags)".to_string()),
+      ..test_rule()
+    };
+    assert!(rule_matches(&rule, &post_facts));
+    assert!(!rule_matches(&rule, &comment_facts));
+
+    let rule = AutomodRule {
+      body_regex: Some("SHOP".to_string()),
+      max_account_age_days: Some(1),
+      ..test_rule()
+    };
+    assert!(rule_matches(&rule, &post_facts));
+    assert!(rule_matches(&rule, &comment_facts));
+    let old_account = AutomodFacts {
+      account_age: TimeDelta::days(3),
+      ..post_facts
+    };
+    assert!(!rule_matches(&rule, &old_account));
+
+    let rule = AutomodRule {
+      domain: Some("spam.example".to_string()),
+      max_karma: Some(3),
+      min_report_count: Some(2),
+      ..test_rule()
+    };
+    assert!(rule_matches(&rule, &post_facts));
+    let rule = AutomodRule {
+      min_report_count: Some(3),
+      ..rule
+    };
+    assert!(!rule_matches(&rule, &post_facts));
+
+    // Invalid regexes never match
+    let rule = AutomodRule {
+      body_regex: Some("(unclosed".to_string()),
+      ..test_rule()
+    };
+    assert!(!rule_matches(&rule, &post_facts));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_url_on_domain() -> LemmyResult<()> {
+    let url = Url::parse("https://media.Example.com/a")?;
+    assert!(url_on_domain(&url, "example.com"));
+    assert!(url_on_domain(&url, "*.example.com"));
+    assert!(url_on_domain(&url, "media.example.com"));
+    assert!(!url_on_domain(&url, "other.example.com"));
+    assert!(!url_on_domain(&url, "ample.com"));
+    Ok(())
+  }
+}
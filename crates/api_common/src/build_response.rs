@@ -3,6 +3,7 @@ use crate::{
   community::CommunityResponse,
   context::LemmyContext,
   post::PostResponse,
+  send_activity::{ActivityChannel, SendActivityData},
   utils::{
     check_person_instance_community_block,
     get_interface_language,
@@ -10,22 +11,26 @@ use crate::{
     send_email_to_user,
   },
 };
+use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_db_schema::{
   newtypes::{CommentId, CommunityId, LocalUserId, PostId},
   source::{
     actor_language::CommunityLanguage,
-    comment::Comment,
+    comment::{Comment, CommentInsertForm},
     comment_reply::{CommentReply, CommentReplyInsertForm},
     person::Person,
     person_mention::{PersonMention, PersonMentionInsertForm},
+    post::Post,
+    private_message::{PrivateMessage, PrivateMessageInsertForm},
   },
   traits::Crud,
+  RemovalNotification,
 };
-use lemmy_db_views::structs::{CommentView, LocalUserView, PostView};
+use lemmy_db_views::structs::{CommentView, LocalUserView, PostView, PrivateMessageView};
 use lemmy_db_views_actor::structs::CommunityView;
 use lemmy_utils::{
-  error::LemmyResult,
+  error::{LemmyErrorExt, LemmyResult},
   utils::{markdown::markdown_to_html, mention::MentionData},
   LemmyErrorType,
 };
@@ -263,3 +268,57 @@ pub async fn send_local_notifs(
 
   Ok(recipient_ids)
 }
+
+/// Tells the creator of a removed post or comment why it was removed, either with a private
+/// message from the moderator or with a distinguished reply.
+#[tracing::instrument(skip_all)]
+pub async fn send_removal_notification(
+  notification: RemovalNotification,
+  moderator: &Person,
+  post: &Post,
+  comment: Option<&Comment>,
+  reason: &str,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let (kind, creator_id, ap_id) = match comment {
+    Some(comment) => ("comment", comment.creator_id, &comment.ap_id),
+    None => ("post", post.creator_id, &post.ap_id),
+  };
+
+  match notification {
+    RemovalNotification::PrivateMessage => {
+      let content = format!(
+        "Your {kind} in [{}]({ap_id}) was removed by a moderator. Reason: {reason}",
+        post.name
+      );
+      let form = PrivateMessageInsertForm::builder()
+        .content(content)
+        .creator_id(moderator.id)
+        .recipient_id(creator_id)
+        .build();
+      let private_message = PrivateMessage::create(&mut context.pool(), &form)
+        .await
+        .with_lemmy_type(LemmyErrorType::CouldntCreatePrivateMessage)?;
+      let view = PrivateMessageView::read(&mut context.pool(), private_message.id, moderator.id)
+        .await?
+        .ok_or(LemmyErrorType::CouldntFindPrivateMessage)?;
+
+      ActivityChannel::submit_activity(SendActivityData::CreatePrivateMessage(view), context).await
+    }
+    RemovalNotification::Reply => {
+      let form = CommentInsertForm::builder()
+        .content(format!("This {kind} was removed. Reason: {reason}"))
+        .post_id(post.id)
+        .creator_id(moderator.id)
+        .distinguished(Some(true))
+        .build();
+      let reply = Comment::create(&mut context.pool(), &form, comment.map(|c| &c.path))
+        .await
+        .with_lemmy_type(LemmyErrorType::CouldntCreateComment)?;
+
+      send_local_notifs(vec![], reply.id, moderator, true, context, None).await?;
+
+      ActivityChannel::submit_activity(SendActivityData::CreateComment(reply), context).await
+    }
+  }
+}
@@ -1,7 +1,16 @@
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommentReportId, CommunityId, LanguageId, LocalUserId, PostId},
+  newtypes::{
+    CommentId,
+    CommentReportId,
+    CommunityId,
+    LanguageId,
+    LocalUserId,
+    PostId,
+    RemovalReasonId,
+  },
   CommentSortType,
   ListingType,
+  RemovalNotification,
 };
 use lemmy_db_views::structs::{CommentReportView, CommentView, VoteView};
 use serde::{Deserialize, Serialize};
@@ -70,6 +79,10 @@ pub struct RemoveComment {
   pub comment_id: CommentId,
   pub removed: bool,
   pub reason: Option<String>,
+  /// Use the text of a removal reason template, if no other reason is given.
+  pub removal_reason_id: Option<RemovalReasonId>,
+  /// Tell the comment creator about the removal reason.
+  pub notify_author: Option<RemovalNotification>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -1,11 +1,12 @@
 use lemmy_db_schema::{
-  newtypes::{CommunityId, LanguageId, PersonId},
-  source::site::Site,
+  newtypes::{CommunityId, LanguageId, PersonId, RemovalReasonId},
+  source::{community_backfill::CommunityBackfill, removal_reason::RemovalReason, site::Site},
   CommunityVisibility,
   ListingType,
   SortType,
 };
 use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView, PersonView};
+use lemmy_db_views_moderator::structs::RemovalReasonView;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -56,6 +57,16 @@ pub struct CreateCommunity {
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Users can only comment once per this many seconds in each post. Zero disables it.
+  pub comment_slow_mode_seconds: Option<i32>,
+  /// Users can only comment once per this many seconds anywhere in the community. Zero disables
+  /// it.
+  pub community_comment_slow_mode_seconds: Option<i32>,
+  /// Users can only post once per this many seconds in the community. Zero disables it.
+  pub post_slow_mode_seconds: Option<i32>,
+  /// Reject posts of urls which were already posted to the community within this many days. Zero
+  /// disables it.
+  pub duplicate_post_window_days: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -153,6 +164,16 @@ pub struct EditCommunity {
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Users can only comment once per this many seconds in each post. Zero disables it.
+  pub comment_slow_mode_seconds: Option<i32>,
+  /// Users can only comment once per this many seconds anywhere in the community. Zero disables
+  /// it.
+  pub community_comment_slow_mode_seconds: Option<i32>,
+  /// Users can only post once per this many seconds in the community. Zero disables it.
+  pub post_slow_mode_seconds: Option<i32>,
+  /// Reject posts of urls which were already posted to the community within this many days. Zero
+  /// disables it.
+  pub duplicate_post_window_days: Option<i32>,
 }
 
 #[skip_serializing_none]
@@ -223,3 +244,101 @@ pub struct TransferCommunity {
   pub community_id: CommunityId,
   pub person_id: PersonId,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create a removal reason template for a community.
+pub struct CreateRemovalReason {
+  pub community_id: CommunityId,
+  pub title: String,
+  pub reason: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Edit a removal reason template.
+pub struct EditRemovalReason {
+  pub id: RemovalReasonId,
+  pub title: Option<String>,
+  pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a removal reason template. Modlog entries which used it keep their reason text.
+pub struct DeleteRemovalReason {
+  pub id: RemovalReasonId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the removal reason templates of a community.
+pub struct ListRemovalReasons {
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for a removal reason template.
+pub struct RemovalReasonResponse {
+  pub removal_reason: RemovalReason,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The removal reason templates of a community, with their usage counts.
+pub struct ListRemovalReasonsResponse {
+  pub removal_reasons: Vec<RemovalReasonView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Export a local community with its posts, comments, moderators and followers, so that it can be
+/// imported on another instance. Only for admins.
+pub struct ExportCommunity {
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Move a local community to another instance, where it was imported before. Followers on all
+/// instances are subscribed to the new community. Only for admins.
+pub struct MoveCommunity {
+  pub community_id: CommunityId,
+  /// The new community, in the format `name@instance.tld`.
+  pub target: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Import the full history of a remote community from its outbox, including comments. Only for
+/// admins.
+pub struct BackfillCommunity {
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the progress of a community backfill.
+pub struct GetCommunityBackfill {
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The progress of a community backfill.
+pub struct CommunityBackfillResponse {
+  pub backfill: CommunityBackfill,
+}
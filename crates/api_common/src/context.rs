@@ -17,6 +17,7 @@ pub struct LemmyContext {
   client: Arc<ClientWithMiddleware>,
   secret: Arc<Secret>,
   rate_limit_cell: RateLimitCell,
+  discard_outgoing_activities: bool,
 }
 
 impl LemmyContext {
@@ -31,6 +32,15 @@ impl LemmyContext {
       client: Arc::new(client),
       secret: Arc::new(secret),
       rate_limit_cell,
+      discard_outgoing_activities: false,
+    }
+  }
+  /// Outgoing activities which are submitted with the returned context are discarded instead of
+  /// being sent. Used for replaying received activities.
+  pub fn without_outgoing_activities(self) -> LemmyContext {
+    LemmyContext {
+      discard_outgoing_activities: true,
+      ..self
     }
   }
   pub fn pool(&self) -> DbPool<'_> {
@@ -51,6 +61,9 @@ impl LemmyContext {
   pub fn rate_limit_cell(&self) -> &RateLimitCell {
     &self.rate_limit_cell
   }
+  pub fn discard_outgoing_activities(&self) -> bool {
+    self.discard_outgoing_activities
+  }
 
   /// Initialize a context for use in tests which blocks federation network calls.
   ///
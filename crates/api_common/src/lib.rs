@@ -1,3 +1,6 @@
+pub mod automod;
+#[cfg(feature = "full")]
+pub mod automod_engine;
 #[cfg(feature = "full")]
 pub mod build_response;
 #[cfg(feature = "full")]
@@ -10,11 +13,15 @@ pub mod custom_emoji;
 pub mod person;
 pub mod post;
 pub mod private_message;
+pub mod relay;
 #[cfg(feature = "full")]
 pub mod request;
 #[cfg(feature = "full")]
 pub mod send_activity;
 pub mod site;
+pub mod slur_filter;
+#[cfg(feature = "full")]
+pub mod slur_filter_engine;
 #[cfg(feature = "full")]
 pub mod utils;
 
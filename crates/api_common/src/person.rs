@@ -5,6 +5,7 @@ use lemmy_db_schema::{
   CommentSortType,
   ListingType,
   PostListingMode,
+  PrivateMessagePrivacy,
   SortType,
 };
 use lemmy_db_views::structs::{CommentView, LocalImageView, PostView};
@@ -129,6 +130,8 @@ pub struct SaveUserSettings {
   pub enable_animated_images: Option<bool>,
   /// Whether to auto-collapse bot comments.
   pub collapse_bot_comments: Option<bool>,
+  /// Who is allowed to send you private messages.
+  pub private_message_privacy: Option<PrivateMessagePrivacy>,
   /// Some vote display mode settings
   pub show_scores: Option<bool>,
   pub show_upvotes: Option<bool>,
@@ -346,6 +349,19 @@ pub struct DeleteAccount {
   pub delete_content: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Move your account to another instance.
+///
+/// The new account needs to list this one as alias, which happens when importing the settings
+/// backup of this account there.
+pub struct MoveAccount {
+  /// The new account, in the format `name@instance.tld`.
+  pub target: String,
+  pub password: SensitiveString,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
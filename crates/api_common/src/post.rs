@@ -1,7 +1,10 @@
+use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PostId, PostReportId},
+  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PostId, PostReportId, RemovalReasonId},
+  source::hashtag::Hashtag,
   ListingType,
   PostFeatureType,
+  RemovalNotification,
   SortType,
 };
 use lemmy_db_views::structs::{PaginationCursor, PostReportView, PostView, VoteView};
@@ -30,6 +33,19 @@ pub struct CreatePost {
   pub language_id: Option<LanguageId>,
   /// Instead of fetching a thumbnail, use a custom one.
   pub custom_thumbnail: Option<String>,
+  /// Uploaded images shown as a gallery, in this order.
+  pub gallery: Option<Vec<GalleryImage>>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An image in a post gallery.
+pub struct GalleryImage {
+  pub url: String,
+  pub caption: Option<String>,
+  pub alt_text: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -83,6 +99,8 @@ pub struct GetPosts {
   pub show_read: Option<bool>,
   /// If true, then show the nsfw posts (even if your user setting is to hide them)
   pub show_nsfw: Option<bool>,
+  /// Only show posts with this hashtag.
+  pub hashtag: Option<String>,
   pub page_cursor: Option<PaginationCursor>,
 }
 
@@ -124,6 +142,8 @@ pub struct EditPost {
   pub language_id: Option<LanguageId>,
   /// Instead of fetching a thumbnail, use a custom one.
   pub custom_thumbnail: Option<String>,
+  /// Replaces the gallery. An empty list removes it.
+  pub gallery: Option<Vec<GalleryImage>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -144,6 +164,10 @@ pub struct RemovePost {
   pub post_id: PostId,
   pub removed: bool,
   pub reason: Option<String>,
+  /// Use the text of a removal reason template, if no other reason is given.
+  pub removal_reason_id: Option<RemovalReasonId>,
+  /// Tell the post creator about the removal reason.
+  pub notify_author: Option<RemovalNotification>,
 }
 
 #[skip_serializing_none]
@@ -268,18 +292,30 @@ pub struct LinkMetadata {
   #[serde(flatten)]
   pub opengraph_data: OpenGraphData,
   pub content_type: Option<String>,
+  /// The url which was reached after following redirects, if it differs from the requested one.
+  pub(crate) final_url: Option<DbUrl>,
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
-/// Site metadata, from its opengraph tags.
+/// Site metadata, from its opengraph tags, schema.org JSON-LD or oEmbed.
 pub struct OpenGraphData {
   pub title: Option<String>,
   pub description: Option<String>,
   pub(crate) image: Option<DbUrl>,
   pub embed_video_url: Option<DbUrl>,
+  pub author: Option<String>,
+  pub published: Option<DateTime<Utc>>,
+  /// An iframe for embedding the content, rebuilt from the oEmbed html.
+  pub embed_html: Option<String>,
+  /// In pixels
+  pub embed_video_width: Option<i32>,
+  /// In pixels
+  pub embed_video_height: Option<i32>,
+  /// The canonical url of the page, from `og:url` or `<link rel="canonical">`.
+  pub(crate) canonical_url: Option<DbUrl>,
 }
 
 #[skip_serializing_none]
@@ -300,3 +336,29 @@ pub struct ListPostLikes {
 pub struct ListPostLikesResponse {
   pub post_likes: Vec<VoteView>,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List hashtags, most used first.
+pub struct ListHashtags {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A hashtag with the number of posts which use it.
+pub struct HashtagView {
+  pub hashtag: Hashtag,
+  pub posts: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct ListHashtagsResponse {
+  pub hashtags: Vec<HashtagView>,
+}
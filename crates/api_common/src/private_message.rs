@@ -1,10 +1,21 @@
-use lemmy_db_schema::newtypes::{PersonId, PrivateMessageId, PrivateMessageReportId};
-use lemmy_db_views::structs::{PrivateMessageReportView, PrivateMessageView};
+use lemmy_db_schema::{
+  newtypes::{PersonId, PrivateMessageConversationId, PrivateMessageId, PrivateMessageReportId},
+  source::{
+    person_device_key::PersonDeviceKey,
+    private_message_key_envelope::PrivateMessageKeyEnvelope,
+  },
+};
+use lemmy_db_views::structs::{
+  PrivateMessageConversationView,
+  PrivateMessageReportView,
+  PrivateMessageView,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -12,8 +23,16 @@ use ts_rs::TS;
 pub struct CreatePrivateMessage {
   pub content: String,
   pub recipient_id: PersonId,
+  /// Further recipients, which turn the message into a group conversation.
+  pub additional_recipient_ids: Option<Vec<PersonId>>,
+  /// The content is ciphertext, which the server stores and federates without reading it.
+  pub encrypted: Option<bool>,
+  /// For encrypted messages, the message key for each device of the participants, including
+  /// your own.
+  pub key_envelopes: Option<Vec<KeyEnvelope>>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -21,6 +40,18 @@ pub struct CreatePrivateMessage {
 pub struct EditPrivateMessage {
   pub private_message_id: PrivateMessageId,
   pub content: String,
+  /// For encrypted messages, replaces the key envelopes.
+  pub key_envelopes: Option<Vec<KeyEnvelope>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The key of an encrypted private message, encrypted with the public key of one device.
+pub struct KeyEnvelope {
+  pub person_id: PersonId,
+  pub device_id: String,
+  pub encrypted_key: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -51,6 +82,8 @@ pub struct GetPrivateMessages {
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub creator_id: Option<PersonId>,
+  /// Only show messages from this conversation.
+  pub conversation_id: Option<PrivateMessageConversationId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -59,6 +92,8 @@ pub struct GetPrivateMessages {
 /// The private messages response.
 pub struct PrivateMessagesResponse {
   pub private_messages: Vec<PrivateMessageView>,
+  /// Key envelopes for your devices, of the encrypted messages in the list.
+  pub key_envelopes: Vec<PrivateMessageKeyEnvelope>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -69,6 +104,58 @@ pub struct PrivateMessageResponse {
   pub private_message_view: PrivateMessageView,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List your private message conversations, with the newest message first.
+pub struct ListPrivateMessageConversations {
+  /// List archived conversations instead of active ones.
+  pub archived_only: Option<bool>,
+  /// List message requests instead of accepted conversations.
+  pub requests_only: Option<bool>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The private message conversations response.
+pub struct ListPrivateMessageConversationsResponse {
+  pub conversations: Vec<PrivateMessageConversationView>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mute, archive or accept a private message conversation.
+pub struct EditPrivateMessageConversation {
+  pub conversation_id: PrivateMessageConversationId,
+  pub muted: Option<bool>,
+  pub archived: Option<bool>,
+  /// Set to false to accept a message request.
+  pub request: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mark all messages in a conversation as read.
+pub struct MarkPrivateMessageConversationAsRead {
+  pub conversation_id: PrivateMessageConversationId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A single private message conversation response.
+pub struct PrivateMessageConversationResponse {
+  pub conversation_view: PrivateMessageConversationView,
+}
+
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -76,6 +163,8 @@ pub struct PrivateMessageResponse {
 pub struct CreatePrivateMessageReport {
   pub private_message_id: PrivateMessageId,
   pub reason: String,
+  /// For encrypted messages, the part of the decrypted message which should be reviewed.
+  pub decrypted_excerpt: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -115,3 +204,36 @@ pub struct ListPrivateMessageReports {
 pub struct ListPrivateMessageReportsResponse {
   pub private_message_reports: Vec<PrivateMessageReportView>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Publish the public key of one of your devices, for end-to-end encrypted private messages.
+pub struct SaveDeviceKey {
+  pub device_id: String,
+  pub public_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Remove the public key of one of your devices.
+pub struct DeleteDeviceKey {
+  pub device_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the device keys of a person, to encrypt private messages for them.
+pub struct ListDeviceKeys {
+  pub person_id: PersonId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The device keys response.
+pub struct ListDeviceKeysResponse {
+  pub device_keys: Vec<PersonDeviceKey>,
+}
@@ -0,0 +1,62 @@
+use lemmy_db_schema::{
+  newtypes::{CommunityId, RelayId},
+  source::relay::{Relay, RelayCommunityFilter},
+};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Subscribe the site to an ActivityPub relay. Only admins can do this.
+pub struct AddRelay {
+  /// The actor id of the relay, eg `https://relay.example.com/actor`.
+  pub actor_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Unsubscribe from a relay and delete it.
+pub struct RemoveRelay {
+  pub relay_id: RelayId,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Allow or deny exchanging the posts of a community with a relay. If any community is allowed,
+/// all communities which aren't allowed are excluded.
+pub struct SetRelayCommunityFilter {
+  pub relay_id: RelayId,
+  pub community_id: CommunityId,
+  /// Leave empty to remove the filter for this community.
+  pub allow: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A relay with its community filters.
+pub struct RelayView {
+  pub relay: Relay,
+  pub community_filters: Vec<RelayCommunityFilter>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct RelayResponse {
+  pub relay_view: RelayView,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// All relays of the site.
+pub struct ListRelaysResponse {
+  pub relays: Vec<RelayView>,
+}
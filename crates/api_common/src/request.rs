@@ -3,16 +3,17 @@ use crate::{
   lemmy_db_schema::traits::Crud,
   post::{LinkMetadata, OpenGraphData},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::proxy_image_link,
+  utils::{get_url_cleaning_rules, proxy_image_link},
 };
 use activitypub_federation::config::Data;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use encoding_rs::{Encoding, UTF_8};
 use futures::StreamExt;
 use lemmy_db_schema::{
   newtypes::DbUrl,
   source::{
     images::{ImageDetailsForm, LocalImage, LocalImageForm},
+    link_metadata_cache::{LinkMetadataCache, LinkMetadataCacheForm},
     post::{Post, PostUpdateForm},
     site::Site,
   },
@@ -20,10 +21,12 @@ use lemmy_db_schema::{
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorType, LemmyResult},
   settings::structs::{PictrsImageMode, Settings},
+  utils::{markdown::sanitize_html, validation::UrlCleaningRules},
   REQWEST_TIMEOUT,
   VERSION,
 };
 use mime::Mime;
+use regex::Regex;
 use reqwest::{
   header::{CONTENT_TYPE, RANGE},
   Client,
@@ -32,10 +35,12 @@ use reqwest::{
 };
 use reqwest_middleware::ClientWithMiddleware;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::LazyLock;
 use tracing::info;
 use url::Url;
 use urlencoding::encode;
-use webpage::HTML;
+use webpage::{SchemaOrg, HTML};
 
 pub fn client_builder(settings: &Settings) -> ClientBuilder {
   let user_agent = format!("Lemmy/{VERSION}; +{}", settings.get_protocol_and_hostname());
@@ -47,8 +52,16 @@ pub fn client_builder(settings: &Settings) -> ClientBuilder {
 }
 
 /// Fetches metadata for the given link and optionally generates thumbnail.
+///
+/// Results are cached for a day, so that the same link being shared repeatedly doesn't cause
+/// repeated requests to the target site.
 #[tracing::instrument(skip_all)]
 pub async fn fetch_link_metadata(url: &Url, context: &LemmyContext) -> LemmyResult<LinkMetadata> {
+  let db_url: DbUrl = url.clone().into();
+  if let Some(cached) = LinkMetadataCache::read(&mut context.pool(), &db_url).await? {
+    return Ok(cached.into());
+  }
+
   info!("Fetching site metadata for url: {}", url);
   // We only fetch the first 64kB of data in order to not waste bandwidth especially for large
   // binary files
@@ -62,6 +75,10 @@ pub async fn fetch_link_metadata(url: &Url, context: &LemmyContext) -> LemmyResu
     .send()
     .await?;
 
+  // Requests follow redirects, so this may be a different url than the requested one
+  let final_url = Some(response.url().clone())
+    .filter(|final_url| final_url != url)
+    .map(Into::into);
   let content_type: Option<Mime> = response
     .headers()
     .get(CONTENT_TYPE)
@@ -89,15 +106,90 @@ pub async fn fetch_link_metadata(url: &Url, context: &LemmyContext) -> LemmyResu
 
       // only take first bytes regardless of how many bytes the server returns
       let html_bytes = collect_bytes_until_limit(response, bytes_to_fetch).await?;
-      extract_opengraph_data(&html_bytes, url)
+      let mut opengraph_data = extract_opengraph_data(&html_bytes, url)
         .map_err(|e| info!("{e}"))
-        .unwrap_or_default()
+        .unwrap_or_default();
+      if let Some(oembed_url) = extract_oembed_url(&html_bytes, url) {
+        match fetch_oembed_data(&oembed_url, context).await {
+          Ok(oembed) => oembed.merge_into(&mut opengraph_data, url),
+          Err(e) => info!("Failed to fetch oEmbed data from {oembed_url}: {e}"),
+        }
+      }
+      opengraph_data
     }
   };
-  Ok(LinkMetadata {
+  let metadata = LinkMetadata {
     opengraph_data,
     content_type: content_type.map(|c| c.to_string()),
-  })
+    final_url,
+  };
+
+  let form = LinkMetadataCacheForm {
+    url: db_url,
+    title: metadata.opengraph_data.title.clone(),
+    description: metadata.opengraph_data.description.clone(),
+    image: metadata.opengraph_data.image.clone(),
+    embed_video_url: metadata.opengraph_data.embed_video_url.clone(),
+    author: metadata.opengraph_data.author.clone(),
+    published_date: metadata.opengraph_data.published,
+    embed_html: metadata.opengraph_data.embed_html.clone(),
+    embed_video_width: metadata.opengraph_data.embed_video_width,
+    embed_video_height: metadata.opengraph_data.embed_video_height,
+    content_type: metadata.content_type.clone(),
+    canonical_url: metadata.opengraph_data.canonical_url.clone(),
+    final_url: metadata.final_url.clone(),
+  };
+  LinkMetadataCache::upsert(&mut context.pool(), &form).await?;
+
+  Ok(metadata)
+}
+
+impl From<LinkMetadataCache> for LinkMetadata {
+  fn from(cached: LinkMetadataCache) -> Self {
+    LinkMetadata {
+      opengraph_data: OpenGraphData {
+        title: cached.title,
+        description: cached.description,
+        image: cached.image,
+        embed_video_url: cached.embed_video_url,
+        author: cached.author,
+        published: cached.published_date,
+        embed_html: cached.embed_html,
+        embed_video_width: cached.embed_video_width,
+        embed_video_height: cached.embed_video_height,
+        canonical_url: cached.canonical_url,
+      },
+      content_type: cached.content_type,
+      final_url: cached.final_url,
+    }
+  }
+}
+
+/// The canonical form of a post url, used to find other posts of the same link. Follows
+/// redirects, so that shortened links resolve to their target. Then prefers the canonical url
+/// declared by the page itself, but only if it is on the same site. Neither may lead to just the
+/// homepage, as some sites redirect removed pages there or declare it as canonical url for all
+/// their pages.
+pub fn canonical_post_url(
+  url: &Url,
+  metadata: &LinkMetadata,
+  url_cleaning_rules: &UrlCleaningRules,
+) -> Url {
+  let domain = |u: &Url| u.domain().map(|d| d.trim_start_matches("www.").to_string());
+  let is_homepage_of = |c: &Url, u: &Url| c.path() == "/" && u.path() != "/";
+  let url = metadata
+    .final_url
+    .as_ref()
+    .map(DbUrl::inner)
+    .filter(|f| !is_homepage_of(f, url))
+    .unwrap_or(url);
+  let declared = metadata
+    .opengraph_data
+    .canonical_url
+    .as_ref()
+    .map(DbUrl::inner)
+    .filter(|c| domain(c) == domain(url) && !is_homepage_of(c, url));
+  url_cleaning_rules.canonicalize_url(declared.unwrap_or(url))
 }
 
 async fn collect_bytes_until_limit(
@@ -136,6 +228,11 @@ pub async fn generate_post_link_metadata(
     Some(url) => fetch_link_metadata(url, &context).await.unwrap_or_default(),
     _ => Default::default(),
   };
+  let url_cleaning_rules = get_url_cleaning_rules(&context).await?;
+  let url_canonical = post
+    .url
+    .as_ref()
+    .map(|url| canonical_post_url(url, &metadata, &url_cleaning_rules).into());
 
   let is_image_post = metadata
     .content_type
@@ -170,6 +267,7 @@ pub async fn generate_post_link_metadata(
     embed_video_url: Some(metadata.opengraph_data.embed_video_url),
     thumbnail_url: Some(thumbnail_url),
     url_content_type: Some(metadata.content_type),
+    url_canonical: Some(url_canonical),
     ..Default::default()
   };
   let updated_post = Post::update(&mut context.pool(), post.id, &form).await?;
@@ -179,7 +277,8 @@ pub async fn generate_post_link_metadata(
   Ok(())
 }
 
-/// Extract site metadata from HTML Opengraph attributes.
+/// Extract site metadata from HTML Opengraph attributes, falling back to schema.org JSON-LD and
+/// plain meta tags for sites without Opengraph.
 fn extract_opengraph_data(html_bytes: &[u8], url: &Url) -> LemmyResult<OpenGraphData> {
   let html = String::from_utf8_lossy(html_bytes);
 
@@ -198,6 +297,7 @@ fn extract_opengraph_data(html_bytes: &[u8], url: &Url) -> LemmyResult<OpenGraph
 
   let page_title = page.title;
   let page_description = page.description;
+  let page_canonical_url = page.url;
 
   let og_description = page
     .opengraph
@@ -215,21 +315,279 @@ fn extract_opengraph_data(html_bytes: &[u8], url: &Url) -> LemmyResult<OpenGraph
     .first()
     // join also works if the target URL is absolute
     .and_then(|ogo| url.join(&ogo.url).ok());
-  let og_embed_url = page
-    .opengraph
-    .videos
-    .first()
+  let og_video = page.opengraph.videos.first();
+  let og_embed_url = og_video
     // join also works if the target URL is absolute
     .and_then(|v| url.join(&v.url).ok());
+  let og_video_dimension =
+    |name: &str| og_video.and_then(|v| v.properties.get(name)?.parse::<i32>().ok());
+  let meta_author = page
+    .meta
+    .get("article:author")
+    .or(page.meta.get("author"))
+    // article:author may also be a link to a profile
+    .filter(|a| Url::parse(a).is_err())
+    .cloned();
+  let meta_published = page
+    .meta
+    .get("article:published_time")
+    .and_then(|p| parse_date(p));
+
+  let og_canonical_url = page
+    .opengraph
+    .properties
+    .get("url")
+    .or(page_canonical_url.as_ref())
+    .and_then(|u| url.join(u).ok());
+
+  let json_ld = JsonLdData::from_schema_org(&page.schema_org, url);
 
   Ok(OpenGraphData {
-    title: og_title.or(page_title),
-    description: og_description.or(page_description),
-    image: og_image.map(Into::into),
-    embed_video_url: og_embed_url.map(Into::into),
+    title: og_title.or(json_ld.title).or(page_title),
+    description: og_description.or(json_ld.description).or(page_description),
+    image: og_image.or(json_ld.image).map(Into::into),
+    embed_video_url: og_embed_url.or(json_ld.embed_video_url).map(Into::into),
+    author: meta_author.or(json_ld.author),
+    published: meta_published.or(json_ld.published),
+    embed_html: None,
+    embed_video_width: og_video_dimension("width").or(json_ld.embed_video_width),
+    embed_video_height: og_video_dimension("height").or(json_ld.embed_video_height),
+    canonical_url: og_canonical_url.map(Into::into),
   })
 }
 
+/// Metadata read from schema.org objects embedded in the page as JSON-LD.
+#[derive(Default)]
+struct JsonLdData {
+  title: Option<String>,
+  description: Option<String>,
+  image: Option<Url>,
+  embed_video_url: Option<Url>,
+  author: Option<String>,
+  published: Option<DateTime<Utc>>,
+  embed_video_width: Option<i32>,
+  embed_video_height: Option<i32>,
+}
+
+impl JsonLdData {
+  /// Types which describe the content of the page itself, rather than the website, breadcrumbs
+  /// or similar.
+  const CONTENT_TYPES: [&'static str; 9] = [
+    "Article",
+    "NewsArticle",
+    "BlogPosting",
+    "Report",
+    "ScholarlyArticle",
+    "SocialMediaPosting",
+    "VideoObject",
+    "Recipe",
+    "WebPage",
+  ];
+
+  fn from_schema_org(schema_org: &[SchemaOrg], url: &Url) -> Self {
+    let Some(object) = Self::CONTENT_TYPES.iter().find_map(|t| {
+      schema_org
+        .iter()
+        .find(|s| &s.schema_type == t)
+        .map(|s| &s.value)
+    }) else {
+      return Default::default();
+    };
+    let string = |key: &str| object.get(key).and_then(json_ld_text);
+    let url_value = |key: &str| {
+      object
+        .get(key)
+        .and_then(json_ld_url)
+        .and_then(|u| url.join(&u).ok())
+    };
+    JsonLdData {
+      title: string("headline").or(string("name")),
+      description: string("description"),
+      image: url_value("image").or(url_value("thumbnailUrl")),
+      embed_video_url: url_value("embedUrl"),
+      author: string("author"),
+      published: string("datePublished")
+        .or(string("uploadDate"))
+        .and_then(|p| parse_date(&p)),
+      embed_video_width: object.get("width").and_then(json_dimension),
+      embed_video_height: object.get("height").and_then(json_dimension),
+    }
+  }
+}
+
+/// Reads a JSON-LD text value, which may be given directly as a string, as an object with a name
+/// (eg `Person`), or as a list of those.
+fn json_ld_text(value: &Value) -> Option<String> {
+  match value {
+    Value::String(s) => Some(s.clone()).filter(|s| !s.is_empty()),
+    Value::Array(a) => a.iter().find_map(json_ld_text),
+    Value::Object(o) => o.get("name").and_then(json_ld_text),
+    _ => None,
+  }
+}
+
+/// Reads a JSON-LD link, which may be given directly as a string, as an object with a url (eg
+/// `ImageObject`), or as a list of those.
+fn json_ld_url(value: &Value) -> Option<String> {
+  match value {
+    Value::String(s) => Some(s.clone()).filter(|s| !s.is_empty()),
+    Value::Array(a) => a.iter().find_map(json_ld_url),
+    Value::Object(o) => o
+      .get("url")
+      .or(o.get("contentUrl"))
+      .or(o.get("@id"))
+      .and_then(json_ld_url),
+    _ => None,
+  }
+}
+
+/// Reads a size in pixels, which may be given as number or string.
+fn json_dimension(value: &Value) -> Option<i32> {
+  match value {
+    Value::Number(n) => n.as_i64().and_then(|n| n.try_into().ok()),
+    Value::String(s) => s.trim_end_matches("px").parse().ok(),
+    // schema.org QuantitativeValue
+    Value::Object(o) => o.get("value").and_then(json_dimension),
+    _ => None,
+  }
+}
+
+/// Parses a publish date, which may be a full timestamp or only a date.
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+  DateTime::parse_from_rfc3339(date)
+    .map(|d| d.with_timezone(&Utc))
+    .ok()
+    .or_else(|| {
+      NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
+    })
+}
+
+/// Finds the oEmbed endpoint which the page advertises via `<link rel="alternate">`.
+fn extract_oembed_url(html_bytes: &[u8], url: &Url) -> Option<Url> {
+  static LINK_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<link\s[^>]*>").expect("compile regex"));
+  static HREF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\shref\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("compile regex")
+  });
+
+  let html = String::from_utf8_lossy(html_bytes);
+  LINK_TAG
+    .find_iter(&html)
+    .map(|tag| tag.as_str())
+    .filter(|tag| tag.to_lowercase().contains("application/json+oembed"))
+    .find_map(|tag| {
+      let href = HREF.captures(tag)?;
+      let href = href.get(1).or(href.get(2))?.as_str().replace("&amp;", "&");
+      url.join(&href).ok()
+    })
+    .filter(|u| u.scheme() == "https" || u.scheme() == "http")
+}
+
+/// Response of an oEmbed endpoint, see <https://oembed.com/#section2.3>
+#[derive(Deserialize, Debug, Default)]
+struct OEmbedResponse {
+  #[serde(rename = "type")]
+  kind: Option<String>,
+  title: Option<String>,
+  author_name: Option<String>,
+  thumbnail_url: Option<String>,
+  html: Option<String>,
+  width: Option<Value>,
+  height: Option<Value>,
+}
+
+impl OEmbedResponse {
+  /// Fills in the metadata which wasn't available from the page itself, and the embed html which
+  /// is only available via oEmbed.
+  fn merge_into(self, data: &mut OpenGraphData, url: &Url) {
+    data.title = data.title.take().or(self.title);
+    data.author = data.author.take().or(self.author_name);
+    if data.image.is_none() {
+      data.image = self
+        .thumbnail_url
+        .and_then(|t| url.join(&t).ok())
+        .map(Into::into);
+    }
+    let is_embed = matches!(self.kind.as_deref(), Some("video" | "rich"));
+    if let (true, Some(html)) = (is_embed, self.html) {
+      data.embed_html = sanitize_embed_html(&html);
+      data.embed_video_width = data
+        .embed_video_width
+        .or(self.width.as_ref().and_then(json_dimension));
+      data.embed_video_height = data
+        .embed_video_height
+        .or(self.height.as_ref().and_then(json_dimension));
+    }
+  }
+}
+
+/// oEmbed responses only contain a few short fields, so anything larger is not read completely and
+/// fails to parse.
+const MAX_OEMBED_BYTES: usize = 64 * 1024;
+
+#[tracing::instrument(skip_all)]
+async fn fetch_oembed_data(
+  oembed_url: &Url,
+  context: &LemmyContext,
+) -> LemmyResult<OEmbedResponse> {
+  let response = context
+    .client()
+    .get(oembed_url.as_str())
+    .send()
+    .await?
+    .error_for_status()?;
+  let bytes = collect_bytes_until_limit(response, MAX_OEMBED_BYTES).await?;
+  Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// The html returned by oEmbed providers can contain arbitrary markup and scripts. Only keep a
+/// single https iframe, and rebuild it from scratch so that no other attributes are passed
+/// through.
+fn sanitize_embed_html(html: &str) -> Option<String> {
+  static IFRAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<iframe\s[^>]*>").expect("compile regex"));
+  static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\s(src|width|height)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#)
+      .expect("compile regex")
+  });
+
+  let iframe = IFRAME.find(html)?.as_str();
+  let mut src = None;
+  let mut width = None;
+  let mut height = None;
+  for attribute in ATTRIBUTE.captures_iter(iframe) {
+    let Some(value) = attribute
+      .get(2)
+      .or(attribute.get(3))
+      .or(attribute.get(4))
+      .map(|v| v.as_str().replace("&amp;", "&"))
+    else {
+      continue;
+    };
+    match attribute[1].to_lowercase().as_str() {
+      "src" => src = Url::parse(&value).ok().filter(|u| u.scheme() == "https"),
+      "width" => width = value.parse::<u32>().ok(),
+      "height" => height = value.parse::<u32>().ok(),
+      _ => {}
+    }
+  }
+
+  let mut sanitized = format!(r#"<iframe src="{}""#, sanitize_html(src?.as_str()));
+  if let Some(width) = width {
+    sanitized.push_str(&format!(r#" width="{width}""#));
+  }
+  if let Some(height) = height {
+    sanitized.push_str(&format!(r#" height="{height}""#));
+  }
+  sanitized.push_str(
+    r#" sandbox="allow-scripts allow-same-origin allow-popups" allowfullscreen></iframe>"#,
+  );
+  Some(sanitized)
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct PictrsResponse {
   pub files: Option<Vec<PictrsFile>>,
@@ -476,8 +834,18 @@ mod tests {
 
   use crate::{
     context::LemmyContext,
-    request::{extract_opengraph_data, fetch_link_metadata},
+    post::{LinkMetadata, OpenGraphData},
+    request::{
+      canonical_post_url,
+      extract_oembed_url,
+      extract_opengraph_data,
+      fetch_link_metadata,
+      sanitize_embed_html,
+      OEmbedResponse,
+    },
   };
+  use chrono::{TimeZone, Utc};
+  use lemmy_utils::utils::validation::UrlCleaningRules;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
   use url::Url;
@@ -553,4 +921,197 @@ mod tests {
       Some(Url::parse("https://example.com/image.jpg").unwrap().into())
     );
   }
+
+  #[test]
+  fn test_extract_json_ld() {
+    let url = Url::parse("https://example.com/news/1").unwrap();
+    let html_bytes = br#"<!DOCTYPE html><html><head><title>Page title</title>
+      <script type="application/ld+json">{
+        "@context": "https://schema.org",
+        "@graph": [
+          {"@type": "WebSite", "name": "Example news"},
+          {
+            "@type": "NewsArticle",
+            "headline": "Something happened",
+            "description": "Details about it",
+            "image": [{"@type": "ImageObject", "url": "/img/1.jpg"}],
+            "author": [{"@type": "Person", "name": "Jane Doe"}],
+            "datePublished": "2024-10-01T08:30:00+02:00"
+          }
+        ]
+      }</script></head><body></body></html>"#;
+    let metadata = extract_opengraph_data(html_bytes, &url).unwrap();
+    assert_eq!(Some("Something happened".to_string()), metadata.title);
+    assert_eq!(Some("Details about it".to_string()), metadata.description);
+    assert_eq!(
+      Some(Url::parse("https://example.com/img/1.jpg").unwrap().into()),
+      metadata.image
+    );
+    assert_eq!(Some("Jane Doe".to_string()), metadata.author);
+    assert_eq!(
+      Some(Utc.with_ymd_and_hms(2024, 10, 1, 6, 30, 0).unwrap()),
+      metadata.published
+    );
+
+    // Opengraph takes precedence
+    let html_bytes = br#"<!DOCTYPE html><html><head>
+      <meta property="og:title" content="Opengraph title">
+      <meta property="og:video" content="https://example.com/embed/1">
+      <meta property="og:video:width" content="1280">
+      <meta property="og:video:height" content="720">
+      <script type="application/ld+json">
+        {"@type": "VideoObject", "name": "Video", "uploadDate": "2024-09-30", "width": 640}
+      </script></head><body></body></html>"#;
+    let metadata = extract_opengraph_data(html_bytes, &url).unwrap();
+    assert_eq!(Some("Opengraph title".to_string()), metadata.title);
+    assert_eq!(
+      Some(Url::parse("https://example.com/embed/1").unwrap().into()),
+      metadata.embed_video_url
+    );
+    assert_eq!(Some(1280), metadata.embed_video_width);
+    assert_eq!(Some(720), metadata.embed_video_height);
+    assert_eq!(
+      Some(Utc.with_ymd_and_hms(2024, 9, 30, 0, 0, 0).unwrap()),
+      metadata.published
+    );
+  }
+
+  #[test]
+  fn test_oembed() {
+    let url = Url::parse("https://video.example.com/watch/1").unwrap();
+    let html_bytes = br#"<!DOCTYPE html><html><head>
+      <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+      <link rel="alternate" type="application/json+oembed"
+        href="/oembed?url=https%3A%2F%2Fvideo.example.com%2Fwatch%2F1&amp;format=json">
+      </head><body></body></html>"#;
+    assert_eq!(
+      Some(
+        Url::parse(
+          "https://video.example.com/oembed?url=https%3A%2F%2Fvideo.example.com%2Fwatch%2F1&format=json"
+        )
+        .unwrap()
+      ),
+      extract_oembed_url(html_bytes, &url)
+    );
+
+    let oembed = OEmbedResponse {
+      kind: Some("video".to_string()),
+      title: Some("oEmbed title".to_string()),
+      author_name: Some("Channel".to_string()),
+      thumbnail_url: Some("https://video.example.com/thumb/1.jpg".to_string()),
+      html: Some(
+        r#"<iframe width="560" height="315" src="https://video.example.com/embed/1?a=1&amp;b=2" onload="alert(1)"></iframe><script>alert(2)</script>"#
+          .to_string(),
+      ),
+      width: Some(560.into()),
+      height: Some("315".into()),
+    };
+    let mut metadata = OpenGraphData {
+      title: Some("Page title".to_string()),
+      ..Default::default()
+    };
+    oembed.merge_into(&mut metadata, &url);
+    assert_eq!(Some("Page title".to_string()), metadata.title);
+    assert_eq!(Some("Channel".to_string()), metadata.author);
+    assert_eq!(
+      Some(
+        Url::parse("https://video.example.com/thumb/1.jpg")
+          .unwrap()
+          .into()
+      ),
+      metadata.image
+    );
+    assert_eq!(
+      Some(r#"<iframe src="https://video.example.com/embed/1?a=1&amp;b=2" width="560" height="315" sandbox="allow-scripts allow-same-origin allow-popups" allowfullscreen></iframe>"#.to_string()),
+      metadata.embed_html
+    );
+    assert_eq!(Some(560), metadata.embed_video_width);
+    assert_eq!(Some(315), metadata.embed_video_height);
+  }
+
+  #[test]
+  fn test_sanitize_embed_html() {
+    // Only https iframes are allowed
+    assert_eq!(None, sanitize_embed_html("<script>alert(1)</script>"));
+    assert_eq!(
+      None,
+      sanitize_embed_html(r#"<iframe src="javascript:alert(1)"></iframe>"#)
+    );
+    assert_eq!(
+      None,
+      sanitize_embed_html(r#"<iframe src="http://example.com/embed"></iframe>"#)
+    );
+    // Quotes can't break out of the attribute
+    assert_eq!(
+      Some(r#"<iframe src="https://example.com/embed%22onload=%22alert(1)" sandbox="allow-scripts allow-same-origin allow-popups" allowfullscreen></iframe>"#.to_string()),
+      sanitize_embed_html(r#"<iframe src='https://example.com/embed"onload="alert(1)'></iframe>"#)
+    );
+  }
+
+  #[test]
+  fn test_canonical_post_url() {
+    let url = Url::parse("https://www.example.com/news/1?utm_source=feed").unwrap();
+    let metadata = |canonical: &str| LinkMetadata {
+      opengraph_data: OpenGraphData {
+        canonical_url: Some(Url::parse(canonical).unwrap().into()),
+        ..Default::default()
+      },
+      content_type: None,
+      final_url: None,
+    };
+    let rules = UrlCleaningRules::default();
+
+    assert_eq!(
+      Url::parse("https://example.com/news/1").unwrap(),
+      canonical_post_url(&url, &Default::default(), &rules)
+    );
+    assert_eq!(
+      Url::parse("https://example.com/news/1-something-happened").unwrap(),
+      canonical_post_url(
+        &url,
+        &metadata("https://example.com/news/1-something-happened"),
+        &rules
+      )
+    );
+    // Canonical urls pointing to the homepage or other sites are ignored
+    assert_eq!(
+      Url::parse("https://example.com/news/1").unwrap(),
+      canonical_post_url(&url, &metadata("https://example.com/"), &rules)
+    );
+    assert_eq!(
+      Url::parse("https://example.com/news/1").unwrap(),
+      canonical_post_url(&url, &metadata("https://other.com/news/1"), &rules)
+    );
+
+    // Redirects are followed to other sites, unless they lead to the homepage
+    let redirected = |final_url: &str| LinkMetadata {
+      final_url: Some(Url::parse(final_url).unwrap().into()),
+      ..Default::default()
+    };
+    let short_url = Url::parse("https://short.link/abc").unwrap();
+    assert_eq!(
+      Url::parse("https://example.com/news/1").unwrap(),
+      canonical_post_url(
+        &short_url,
+        &redirected("https://www.example.com/news/1/"),
+        &rules
+      )
+    );
+    assert_eq!(
+      Url::parse("https://example.com/news/1").unwrap(),
+      canonical_post_url(&url, &redirected("https://example.com/"), &rules)
+    );
+
+    // Admin rules apply to the result
+    let params = [Some("ref".to_string())];
+    let rules = UrlCleaningRules::new([("example\\.com", &params[..])]).unwrap();
+    assert_eq!(
+      Url::parse("https://example.com/news/2").unwrap(),
+      canonical_post_url(
+        &short_url,
+        &redirected("https://example.com/news/2?ref=short"),
+        &rules
+      )
+    );
+  }
 }
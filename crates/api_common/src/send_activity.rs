@@ -125,8 +125,11 @@ impl ActivityChannel {
 
   pub async fn submit_activity(
     data: SendActivityData,
-    _context: &Data<LemmyContext>,
+    context: &Data<LemmyContext>,
   ) -> LemmyResult<()> {
+    if context.discard_outgoing_activities() {
+      return Ok(());
+    }
     // could do `ACTIVITY_CHANNEL.keepalive_sender.lock()` instead and get rid of weak_sender,
     // not sure which way is more efficient
     if let Some(sender) = ACTIVITY_CHANNEL.weak_sender.upgrade() {
@@ -2,10 +2,12 @@ use crate::federate_retry_sleep_duration;
 use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
   newtypes::{
+    ActivityId,
     CommentId,
     CommunityId,
     InstanceId,
     LanguageId,
+    LocalUserId,
     PersonId,
     PostId,
     RegistrationApplicationId,
@@ -16,14 +18,18 @@ use lemmy_db_schema::{
     instance::Instance,
     language::Language,
     local_site_url_blocklist::LocalSiteUrlBlocklist,
+    local_site_url_cleaning_rule::LocalSiteUrlCleaningRule,
     person::Person,
+    received_activity_raw::{ReceivedActivityRaw, TableChanges},
     tagline::Tagline,
   },
+  FederationQueueReset,
   ListingType,
   ModlogActionType,
   PostListingMode,
   RegistrationMode,
   SearchType,
+  SlurFilterAction,
   SortType,
 };
 use lemmy_db_views::structs::{
@@ -47,6 +53,7 @@ use lemmy_db_views_moderator::structs::{
   AdminPurgePostView,
   ModAddCommunityView,
   ModAddView,
+  ModAutomodActionView,
   ModBanFromCommunityView,
   ModBanView,
   ModFeaturePostView,
@@ -152,6 +159,7 @@ pub struct GetModlogResponse {
   pub admin_purged_posts: Vec<AdminPurgePostView>,
   pub admin_purged_comments: Vec<AdminPurgeCommentView>,
   pub hidden_communities: Vec<ModHideCommunityView>,
+  pub automod_actions: Vec<ModAutomodActionView>,
 }
 
 #[skip_serializing_none]
@@ -179,6 +187,7 @@ pub struct CreateSite {
   pub hide_modlog_mod_names: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub slur_filter_regex: Option<String>,
+  pub slur_filter_action: Option<SlurFilterAction>,
   pub actor_name_max_length: Option<i32>,
   pub rate_limit_message: Option<i32>,
   pub rate_limit_message_per_second: Option<i32>,
@@ -192,6 +201,12 @@ pub struct CreateSite {
   pub rate_limit_comment_per_second: Option<i32>,
   pub rate_limit_search: Option<i32>,
   pub rate_limit_search_per_second: Option<i32>,
+  pub rate_limit_vote: Option<i32>,
+  pub rate_limit_vote_per_second: Option<i32>,
+  pub rate_limit_report: Option<i32>,
+  pub rate_limit_report_per_second: Option<i32>,
+  pub rate_limit_private_message: Option<i32>,
+  pub rate_limit_private_message_per_second: Option<i32>,
   pub federation_enabled: Option<bool>,
   pub federation_debug: Option<bool>,
   pub captcha_enabled: Option<bool>,
@@ -245,6 +260,8 @@ pub struct EditSite {
   pub discussion_languages: Option<Vec<LanguageId>>,
   /// A regex string of items to filter.
   pub slur_filter_regex: Option<String>,
+  /// What happens to posts and comments matching the slur filter regex.
+  pub slur_filter_action: Option<SlurFilterAction>,
   /// The max length of actor names.
   pub actor_name_max_length: Option<i32>,
   /// The number of messages allowed in a given time frame.
@@ -265,6 +282,15 @@ pub struct EditSite {
   /// The number of searches allowed in a given time frame.
   pub rate_limit_search: Option<i32>,
   pub rate_limit_search_per_second: Option<i32>,
+  /// The number of votes allowed in a given time frame.
+  pub rate_limit_vote: Option<i32>,
+  pub rate_limit_vote_per_second: Option<i32>,
+  /// The number of reports allowed in a given time frame.
+  pub rate_limit_report: Option<i32>,
+  pub rate_limit_report_per_second: Option<i32>,
+  /// The number of private messages allowed in a given time frame.
+  pub rate_limit_private_message: Option<i32>,
+  pub rate_limit_private_message_per_second: Option<i32>,
   /// Whether to enable federation.
   pub federation_enabled: Option<bool>,
   /// Enables federation debugging.
@@ -279,6 +305,8 @@ pub struct EditSite {
   pub blocked_instances: Option<Vec<String>>,
   /// A list of blocked URLs
   pub blocked_urls: Option<Vec<String>>,
+  /// Extra url cleaning rules, applied after the ones bundled with ClearURLs.
+  pub url_cleaning_rules: Option<Vec<UrlCleaningRule>>,
   /// A list of taglines shown at the top of the front page.
   pub taglines: Option<Vec<String>>,
   pub registration_mode: Option<RegistrationMode>,
@@ -291,6 +319,17 @@ pub struct EditSite {
   pub default_post_listing_mode: Option<PostListingMode>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A url cleaning rule. Both patterns are case insensitive regexes.
+pub struct UrlCleaningRule {
+  /// The urls this rule applies to.
+  pub url_pattern: String,
+  /// Query parameters to remove, each pattern has to match the whole parameter name.
+  pub params: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -317,6 +356,7 @@ pub struct GetSiteResponse {
   /// A list of custom emojis your site supports.
   pub custom_emojis: Vec<CustomEmojiView>,
   pub blocked_urls: Vec<LocalSiteUrlBlocklist>,
+  pub url_cleaning_rules: Vec<LocalSiteUrlCleaningRule>,
 }
 
 #[skip_serializing_none]
@@ -359,9 +399,9 @@ pub struct FederatedInstances {
 #[cfg_attr(feature = "full", ts(export))]
 pub struct ReadableFederationState {
   #[serde(flatten)]
-  internal_state: FederationQueueState,
+  pub internal_state: FederationQueueState,
   /// timestamp of the next retry attempt (null if fail count is 0)
-  next_retry: Option<DateTime<Utc>>,
+  pub next_retry: Option<DateTime<Utc>>,
 }
 
 impl From<FederationQueueState> for ReadableFederationState {
@@ -500,3 +540,210 @@ pub struct BlockInstance {
 pub struct BlockInstanceResponse {
   pub blocked: bool,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// How often a rate limit action was rejected for a user.
+pub struct ThrottledActionCount {
+  /// The rate limit type, for example `Vote` or `Report`.
+  pub action: String,
+  pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A local user who was rejected by the rate limiter within the last day.
+pub struct ThrottledUser {
+  pub person: Person,
+  pub local_user_id: LocalUserId,
+  pub throttled_actions: Vec<ThrottledActionCount>,
+  pub last_throttled: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The users which were rate limited within the last day, most recently throttled first.
+pub struct ListThrottledUsersResponse {
+  pub throttled_users: Vec<ThrottledUser>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The state of outgoing federation to an instance.
+pub struct InstanceFederationHealth {
+  pub instance: Instance,
+  pub federation_state: ReadableFederationState,
+  /// Number of activities which were not sent to the instance yet.
+  pub activities_behind: i64,
+  /// A reset which will be applied by the federation worker shortly.
+  pub requested_reset: Option<FederationQueueReset>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The state of outgoing federation to all instances. Only for admins.
+pub struct GetFederationHealthResponse {
+  /// Id of the most recent outgoing activity.
+  pub latest_activity_id: Option<ActivityId>,
+  pub instances: Vec<InstanceFederationHealth>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Reset the federation queue of an instance which is stuck, for example after it was down for a
+/// long time.
+pub struct ResetFederationQueue {
+  pub instance_id: InstanceId,
+  pub reset: FederationQueueReset,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Block or unblock federation with an instance. Only for admins.
+pub struct AdminBlockInstance {
+  /// The domain of the instance.
+  pub instance: String,
+  pub block: bool,
+  /// A public reason for the block.
+  pub reason: Option<String>,
+  /// A private comment, only visible to admins.
+  pub comment: Option<String>,
+  /// A time that the block will expire, in unix epoch seconds.
+  pub expires: Option<i64>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Limit federation with an instance, or remove the limit. Only for admins.
+pub struct AdminLimitInstance {
+  /// The domain of the instance.
+  pub instance: String,
+  pub limit: bool,
+  /// A public reason for the limit.
+  pub reason: Option<String>,
+  /// A private comment, only visible to admins.
+  pub comment: Option<String>,
+  /// A time that the limit will expire, in unix epoch seconds.
+  pub expires: Option<i64>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An instance which is blocked or has limited federation.
+pub struct DefederatedInstance {
+  pub instance: Instance,
+  pub reason: Option<String>,
+  /// Only shown to admins.
+  pub comment: Option<String>,
+  pub published: DateTime<Utc>,
+  pub expires: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The federation blocklist of this instance.
+pub struct GetFederationBlocklistResponse {
+  pub blocklist: Vec<DefederatedInstance>,
+  /// Instances with limited federation. Their content is hidden from the `All` listing, unless
+  /// the user follows the community.
+  pub limitlist: Vec<DefederatedInstance>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Import a blocklist in the CSV format used by Mastodon and FediBlock. Only for admins.
+pub struct ImportFederationBlocklist {
+  pub csv: String,
+  /// Only preview the result, without blocking anything.
+  pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct ImportFederationBlocklistResponse {
+  /// Domains which are newly blocked by the import.
+  pub blocked: Vec<String>,
+  /// Domains which newly get limited federation by the import.
+  pub limited: Vec<String>,
+  /// Domains which were already blocked or limited, and whose reason or comment is updated by the
+  /// import.
+  pub updated: Vec<String>,
+  /// Entries which were not imported because they are obfuscated, duplicates, have a severity
+  /// other than `suspend` or `silence`, or are already blocked or limited without a reason.
+  pub skipped: Vec<String>,
+  /// Remote communities followed by local users, which are cut off by the import.
+  pub cut_off_communities: Vec<Community>,
+  /// Remote users followed by local users, which are cut off by the import.
+  pub cut_off_persons: Vec<Person>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Generate a new keypair for a local user or community, or for the instance actor if neither is
+/// given. Only for admins.
+///
+/// If `federation_signed_fetch` is enabled, fetches are signed with the new instance key after
+/// restarting Lemmy.
+pub struct AdminRotateKeys {
+  pub person_id: Option<PersonId>,
+  pub community_id: Option<CommunityId>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List incoming activities which were stored for debugging. Only for admins.
+pub struct ListReceivedActivities {
+  /// Only list activities which failed to be received.
+  pub only_errors: Option<bool>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct ListReceivedActivitiesResponse {
+  pub activities: Vec<ReceivedActivityRaw>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Receive a stored activity again to debug it. All changes are rolled back afterwards. Only for
+/// admins.
+pub struct ReplayActivity {
+  pub ap_id: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct ReplayActivityResponse {
+  /// The error which occurred while receiving the activity.
+  pub error: Option<String>,
+  /// Rows which would have been changed by the activity.
+  pub changes: Vec<TableChanges>,
+}
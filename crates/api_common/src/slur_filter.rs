@@ -0,0 +1,55 @@
+use lemmy_db_schema::{
+  newtypes::SlurFilterListId,
+  source::slur_filter_list::SlurFilterList,
+  SlurFilterAction,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create a slur filter list. Only admins can do this.
+pub struct CreateSlurFilterList {
+  pub name: String,
+  pub regex: String,
+  pub action: SlurFilterAction,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Edit a slur filter list.
+pub struct EditSlurFilterList {
+  pub id: SlurFilterListId,
+  pub name: Option<String>,
+  pub regex: Option<String>,
+  pub action: Option<SlurFilterAction>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a slur filter list.
+pub struct DeleteSlurFilterList {
+  pub id: SlurFilterListId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for a slur filter list.
+pub struct SlurFilterListResponse {
+  pub slur_filter_list: SlurFilterList,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// All slur filter lists of the site.
+pub struct ListSlurFilterListsResponse {
+  pub slur_filter_lists: Vec<SlurFilterList>,
+}
@@ -0,0 +1,281 @@
+use crate::{
+  automod_engine::{get_system_account, remove_comment, remove_post, report_comment, report_post},
+  context::LemmyContext,
+};
+use activitypub_federation::config::Data;
+use lemmy_db_schema::{
+  source::{
+    comment::Comment,
+    community::Community,
+    local_site::LocalSite,
+    post::Post,
+    slur_filter_list::SlurFilterList,
+  },
+  traits::Crud,
+  SlurFilterAction,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType, LemmyResult},
+  utils::{
+    slurs::{build_slur_regex, check_slurs, remove_slurs},
+    validation::build_and_check_regex,
+  },
+  CACHE_DURATION_FEDERATION,
+};
+use moka::future::Cache;
+use regex::Regex;
+use std::sync::LazyLock;
+use tracing::warn;
+
+/// Name under which matches of the site slur filter regex are reported.
+const SITE_FILTER_NAME: &str = "site";
+
+struct SlurFilter {
+  name: String,
+  regex: Option<Regex>,
+  /// If empty, post titles are rejected and text is masked.
+  action: Option<SlurFilterAction>,
+}
+
+/// The slur filter regex of the site and the additional word lists, which are checked against
+/// posts and comments.
+pub struct SlurFilters(Vec<SlurFilter>);
+
+/// Filters with report or hold action which matched accepted content. These can only be applied
+/// once the content is saved.
+#[derive(Debug, Default)]
+pub struct SlurFilterMatches(Vec<(String, SlurFilterAction)>);
+
+impl SlurFilters {
+  pub async fn read(local_site: Option<&LocalSite>, context: &LemmyContext) -> LemmyResult<Self> {
+    static SLUR_FILTER_LISTS: LazyLock<Cache<(), Vec<SlurFilterList>>> = LazyLock::new(|| {
+      Cache::builder()
+        .max_capacity(1)
+        .time_to_live(CACHE_DURATION_FEDERATION)
+        .build()
+    });
+
+    let lists = SLUR_FILTER_LISTS
+      .try_get_with::<_, LemmyError>((), async {
+        Ok(SlurFilterList::get_all(&mut context.pool()).await?)
+      })
+      .await
+      .map_err(|e| anyhow::anyhow!("Failed to read slur filter lists due to `{}`", e))?;
+    Ok(Self::new(local_site, lists))
+  }
+
+  fn new(local_site: Option<&LocalSite>, lists: Vec<SlurFilterList>) -> Self {
+    let site_filter = local_site.map(|local_site| SlurFilter {
+      name: SITE_FILTER_NAME.to_string(),
+      regex: build_slur_regex(local_site.slur_filter_regex.as_deref()),
+      action: local_site.slur_filter_action,
+    });
+    let list_filters = lists.into_iter().filter_map(|list| {
+      // Lists are validated on saving, so this only fails if the validation changed since
+      match build_and_check_regex(&Some(&list.regex)) {
+        Ok(regex) => Some(SlurFilter {
+          name: list.name,
+          regex,
+          action: Some(list.action),
+        }),
+        Err(e) => {
+          warn!("Invalid regex in slur filter list {}: {e}", list.id.0);
+          None
+        }
+      }
+    });
+    Self(site_filter.into_iter().chain(list_filters).collect())
+  }
+
+  /// Checks a post title. Returns the title with masked words, or an error if it is rejected.
+  pub fn check_title(&self, title: &str, matches: &mut SlurFilterMatches) -> LemmyResult<String> {
+    self.check(title, SlurFilterAction::Reject, matches)
+  }
+
+  /// Checks a post body or comment text. Returns the text with masked words, or an error if it
+  /// is rejected.
+  pub fn check_text(&self, text: &str, matches: &mut SlurFilterMatches) -> LemmyResult<String> {
+    self.check(text, SlurFilterAction::Mask, matches)
+  }
+
+  pub fn check_text_opt(
+    &self,
+    text: &Option<String>,
+    matches: &mut SlurFilterMatches,
+  ) -> LemmyResult<Option<String>> {
+    text
+      .as_deref()
+      .map(|t| self.check_text(t, matches))
+      .transpose()
+  }
+
+  fn check(
+    &self,
+    text: &str,
+    default_action: SlurFilterAction,
+    matches: &mut SlurFilterMatches,
+  ) -> LemmyResult<String> {
+    // All filters look at the original text, so that masking by one list doesn't hide words from
+    // another list with a stricter action.
+    let mut masked = text.to_string();
+    for filter in &self.0 {
+      match filter.action.unwrap_or(default_action) {
+        SlurFilterAction::Reject => check_slurs(text, &filter.regex)?,
+        SlurFilterAction::Mask => masked = remove_slurs(&masked, &filter.regex),
+        action => {
+          if filter.regex.as_ref().is_some_and(|r| r.is_match(text)) {
+            matches.0.push((filter.name.clone(), action));
+          }
+        }
+      }
+    }
+    Ok(masked)
+  }
+}
+
+impl SlurFilterMatches {
+  /// Reports or holds the post if any filter matched.
+  pub async fn apply_to_post(&self, post: &Post, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    let Some((action, reason)) = self.action_and_reason() else {
+      return Ok(());
+    };
+    if post.deleted || post.removed {
+      return Ok(());
+    }
+    let community = Community::read(&mut context.pool(), post.community_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+    let system_account = get_system_account(context).await?;
+    let post = if action == SlurFilterAction::Hold {
+      remove_post(post, &community, &system_account, reason.clone(), context).await?
+    } else {
+      post.clone()
+    };
+    report_post(&post, &community, &system_account, reason, context).await
+  }
+
+  /// Reports or holds the comment if any filter matched.
+  pub async fn apply_to_comment(
+    &self,
+    comment: &Comment,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<()> {
+    let Some((action, reason)) = self.action_and_reason() else {
+      return Ok(());
+    };
+    if comment.deleted || comment.removed {
+      return Ok(());
+    }
+    let post = Post::read(&mut context.pool(), comment.post_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    let community = Community::read(&mut context.pool(), post.community_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+    let system_account = get_system_account(context).await?;
+    let comment = if action == SlurFilterAction::Hold {
+      remove_comment(
+        comment,
+        &community,
+        &system_account,
+        reason.clone(),
+        context,
+      )
+      .await?
+    } else {
+      comment.clone()
+    };
+    report_comment(&comment, &community, &system_account, reason, context).await
+  }
+
+  /// Holding takes precedence over reporting. The reason lists the names of all matching filters.
+  fn action_and_reason(&self) -> Option<(SlurFilterAction, String)> {
+    let action = if self.0.iter().any(|m| m.1 == SlurFilterAction::Hold) {
+      SlurFilterAction::Hold
+    } else {
+      self.0.first()?.1
+    };
+    let mut names = self.0.iter().map(|m| m.0.as_str()).collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    Some((action, format!("Slur filter: {}", names.join(", "))))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::Utc;
+  use lemmy_db_schema::newtypes::SlurFilterListId;
+  use pretty_assertions::assert_eq;
+
+  fn test_list(id: i32, name: &str, regex: &str, action: SlurFilterAction) -> SlurFilterList {
+    SlurFilterList {
+      id: SlurFilterListId(id),
+      name: name.to_string(),
+      regex: regex.to_string(),
+      action,
+      published: Utc::now(),
+      updated: None,
+    }
+  }
+
+  #[test]
+  fn test_default_site_filter() -> LemmyResult<()> {
+    let local_site = LocalSite {
+      slur_filter_regex: Some("badword".to_string()),
+      ..Default::default()
+    };
+    let filters = SlurFilters::new(Some(&local_site), vec![]);
+    let mut matches = SlurFilterMatches::default();
+
+    assert!(filters.check_title("a badword", &mut matches).is_err());
+    assert_eq!(
+      "a *removed*",
+      filters.check_text("a BADWORD", &mut matches)?
+    );
+    assert_eq!("fine", filters.check_title("fine", &mut matches)?);
+    assert_eq!(None, matches.action_and_reason());
+    Ok(())
+  }
+
+  #[test]
+  fn test_slur_filter_lists() -> LemmyResult<()> {
+    let local_site = LocalSite {
+      slur_filter_regex: Some("badword".to_string()),
+      slur_filter_action: Some(SlurFilterAction::Report),
+      ..Default::default()
+    };
+    let lists = vec![
+      test_list(1, "insults", "idiot", SlurFilterAction::Mask),
+      test_list(2, "spam", "casino", SlurFilterAction::Hold),
+      test_list(3, "invalid", "(unclosed", SlurFilterAction::Reject),
+    ];
+    let filters = SlurFilters::new(Some(&local_site), lists);
+
+    let mut matches = SlurFilterMatches::default();
+    let title = filters.check_title("idiot with a badword", &mut matches)?;
+    assert_eq!("*removed* with a badword", title);
+    assert_eq!(
+      Some((SlurFilterAction::Report, "Slur filter: site".to_string())),
+      matches.action_and_reason()
+    );
+
+    let text = filters.check_text("badword casino", &mut matches)?;
+    assert_eq!("badword casino", text);
+    assert_eq!(
+      Some((
+        SlurFilterAction::Hold,
+        "Slur filter: site, spam".to_string()
+      )),
+      matches.action_and_reason()
+    );
+
+    let reject = SlurFilters::new(
+      None,
+      vec![test_list(4, "strict", "idiot", SlurFilterAction::Reject)],
+    );
+    assert!(reject.check_text("idiot", &mut matches).is_err());
+    Ok(())
+  }
+}
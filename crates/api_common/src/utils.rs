@@ -1,36 +1,50 @@
 use crate::{
   context::LemmyContext,
+  post::GalleryImage,
   request::{
     delete_image_from_pictrs,
     fetch_pictrs_proxied_image_details,
     purge_image_from_pictrs,
   },
-  site::{FederatedInstances, InstanceWithFederationState},
+  site::{
+    FederatedInstances,
+    GetFederationHealthResponse,
+    InstanceFederationHealth,
+    InstanceWithFederationState,
+  },
 };
-use chrono::{DateTime, Days, Local, TimeZone, Utc};
+use chrono::{DateTime, Days, Local, TimeDelta, TimeZone, Utc};
 use enum_map::{enum_map, EnumMap};
 use lemmy_db_schema::{
   aggregates::structs::{PersonPostAggregates, PersonPostAggregatesForm},
-  newtypes::{CommunityId, DbUrl, InstanceId, PersonId, PostId},
+  newtypes::{CommunityId, DbUrl, InstanceId, PersonId, PostId, RemovalReasonId},
   source::{
+    activity::SentActivity,
     comment::{Comment, CommentUpdateForm},
     community::{Community, CommunityModerator, CommunityUpdateForm},
     community_block::CommunityBlock,
     email_verification::{EmailVerification, EmailVerificationForm},
+    federation_queue_state::FederationQueueState,
+    hashtag::Hashtag,
     images::RemoteImage,
     instance::Instance,
     instance_block::InstanceBlock,
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
     local_site_url_blocklist::LocalSiteUrlBlocklist,
+    local_site_url_cleaning_rule::LocalSiteUrlCleaningRule,
     password_reset_request::PasswordResetRequest,
     person::{Person, PersonUpdateForm},
     person_block::PersonBlock,
     post::{Post, PostRead},
+    post_gallery_image::PostGalleryImageForm,
+    private_message_conversation::PrivateMessageConversation,
+    removal_reason::RemovalReason,
     site::Site,
   },
   traits::Crud,
   utils::DbPool,
+  PrivateMessagePrivacy,
 };
 use lemmy_db_views::{
   comment_view::CommentQuery,
@@ -47,16 +61,27 @@ use lemmy_utils::{
   rate_limit::{ActionType, BucketConfig},
   settings::structs::{PictrsImageMode, Settings},
   utils::{
+    hashtag::{scrape_text_for_hashtags, MAX_HASHTAGS_PER_POST},
     markdown::{markdown_check_for_blocked_urls, markdown_rewrite_image_links},
     slurs::{build_slur_regex, remove_slurs},
-    validation::clean_urls_in_text,
+    validation::{
+      is_url_blocked,
+      is_valid_alt_text_field,
+      is_valid_post_gallery,
+      is_valid_url,
+      UrlCleaningRules,
+      POST_GALLERY_MAX_IMAGES,
+    },
   },
   CACHE_DURATION_FEDERATION,
 };
 use moka::future::Cache;
 use regex::{escape, Regex, RegexSet};
 use rosetta_i18n::{Language, LanguageId};
-use std::{collections::HashSet, sync::LazyLock};
+use std::{
+  collections::HashSet,
+  sync::{Arc, LazyLock},
+};
 use tracing::warn;
 use url::{ParseError, Url};
 use urlencoding::encode;
@@ -157,6 +182,31 @@ pub async fn mark_post_as_read(
   Ok(())
 }
 
+/// Stores the hashtags used in the title and body of a post. Additional hashtags can be passed,
+/// for example from the `tag` field of federated posts. Only the first [MAX_HASHTAGS_PER_POST]
+/// are stored.
+#[tracing::instrument(skip_all)]
+pub async fn update_post_hashtags(
+  post: &Post,
+  extra_hashtags: Vec<String>,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  let text = format!(
+    "{}\n{}",
+    post.name,
+    post.body.as_deref().unwrap_or_default()
+  );
+  let mut seen = HashSet::new();
+  let hashtags = scrape_text_for_hashtags(&text)
+    .into_iter()
+    .chain(extra_hashtags)
+    .filter(|h| seen.insert(h.clone()))
+    .take(MAX_HASHTAGS_PER_POST)
+    .collect();
+  Hashtag::update_for_post(pool, post.id, hashtags).await?;
+  Ok(())
+}
+
 /// Updates the read comment count for a post. Usually done when reading or creating a new comment.
 #[tracing::instrument(skip_all)]
 pub async fn update_read_comments(
@@ -253,6 +303,136 @@ pub async fn check_community_mod_action(
   Ok(())
 }
 
+/// Returns the reason for a mod removal. If no reason was written, the text of the removal reason
+/// template is used. The template must belong to the community of the removed content.
+///
+/// The template is also returned if its text was used for a removal, so that it is counted in the
+/// usage stats. It isn't if the moderator wrote their own reason or restored the content.
+pub async fn removal_reason_text(
+  reason: &Option<String>,
+  removal_reason_id: Option<RemovalReasonId>,
+  removed: bool,
+  community_id: CommunityId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<(Option<String>, Option<RemovalReasonId>)> {
+  let Some(removal_reason_id) = removal_reason_id else {
+    return Ok((reason.clone(), None));
+  };
+  let removal_reason = RemovalReason::read(pool, removal_reason_id)
+    .await?
+    .filter(|r| r.community_id == community_id)
+    .ok_or(LemmyErrorType::CouldntFindRemovalReason)?;
+  match reason {
+    Some(reason) => Ok((Some(reason.clone()), None)),
+    None => Ok((
+      Some(removal_reason.reason),
+      removed.then_some(removal_reason.id),
+    )),
+  }
+}
+
+/// Returns an error with the remaining wait time if the user's last comment or post was less than
+/// `slow_mode_seconds` ago.
+pub fn check_slow_mode(
+  last_published: Option<DateTime<Utc>>,
+  slow_mode_seconds: Option<i32>,
+) -> LemmyResult<()> {
+  let (Some(last_published), Some(slow_mode_seconds)) = (last_published, slow_mode_seconds) else {
+    return Ok(());
+  };
+  let next_allowed = last_published + TimeDelta::seconds(slow_mode_seconds.into());
+  let remaining = next_allowed - Utc::now();
+  if remaining > TimeDelta::zero() {
+    // Round up, so that clients don't retry too early
+    let seconds = (remaining.num_milliseconds() + 999) / 1000;
+    Err(LemmyErrorType::SlowMode(
+      i32::try_from(seconds).unwrap_or(slow_mode_seconds),
+    ))?
+  } else {
+    Ok(())
+  }
+}
+
+/// Returns an error with the id of the existing post if the url was already posted to the
+/// community within its duplicate post window.
+pub async fn check_duplicate_post(
+  community: &Community,
+  url_canonical: &DbUrl,
+  post_id: Option<PostId>,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  let Some(window_days) = community.duplicate_post_window_days else {
+    return Ok(());
+  };
+  let published_after = Utc::now() - TimeDelta::days(window_days.into());
+  let duplicate =
+    Post::find_duplicate(pool, community.id, url_canonical, published_after, post_id).await?;
+  if let Some(duplicate) = duplicate {
+    Err(LemmyErrorType::DuplicatePost(duplicate.0))?
+  }
+  Ok(())
+}
+
+/// Checks the images of a post gallery, and cleans their urls.
+pub fn check_post_gallery(
+  gallery: &[GalleryImage],
+  url_blocklist: &RegexSet,
+  url_cleaning_rules: &UrlCleaningRules,
+) -> LemmyResult<Vec<PostGalleryImageForm>> {
+  is_valid_post_gallery(gallery.iter().map(|image| image.caption.as_deref()))?;
+  gallery
+    .iter()
+    .map(|image| check_gallery_image(image, url_blocklist, url_cleaning_rules))
+    .collect()
+}
+
+/// Like [check_post_gallery], but for galleries received over federation. Instead of rejecting
+/// the post, invalid images are left out, along with captions or alt texts which are too long,
+/// and only the first images up to the limit are kept.
+pub fn sanitize_post_gallery(
+  gallery: &[GalleryImage],
+  url_blocklist: &RegexSet,
+  url_cleaning_rules: &UrlCleaningRules,
+) -> Vec<PostGalleryImageForm> {
+  gallery
+    .iter()
+    .filter_map(|image| {
+      let image = GalleryImage {
+        caption: image
+          .caption
+          .clone()
+          .filter(|c| is_valid_post_gallery([Some(c.as_str())].into_iter()).is_ok()),
+        alt_text: image
+          .alt_text
+          .clone()
+          .filter(|a| is_valid_alt_text_field(a).is_ok()),
+        ..image.clone()
+      };
+      check_gallery_image(&image, url_blocklist, url_cleaning_rules).ok()
+    })
+    .take(POST_GALLERY_MAX_IMAGES)
+    .collect()
+}
+
+fn check_gallery_image(
+  image: &GalleryImage,
+  url_blocklist: &RegexSet,
+  url_cleaning_rules: &UrlCleaningRules,
+) -> LemmyResult<PostGalleryImageForm> {
+  let url = Url::parse(&image.url).with_lemmy_type(LemmyErrorType::InvalidUrl)?;
+  let url = url_cleaning_rules.clean_url(&url);
+  is_url_blocked(&url, url_blocklist)?;
+  is_valid_url(&url)?;
+  if let Some(alt_text) = &image.alt_text {
+    is_valid_alt_text_field(alt_text)?;
+  }
+  Ok(PostGalleryImageForm {
+    url: url.into(),
+    caption: image.caption.clone(),
+    alt_text: image.alt_text.clone(),
+  })
+}
+
 /// Don't allow creating reports for removed / deleted posts
 pub fn check_post_deleted_or_removed(post: &Post) -> LemmyResult<()> {
   if post.deleted || post.removed {
@@ -285,6 +465,40 @@ pub async fn check_person_block(
   }
 }
 
+/// Throws an error if the privacy settings of a local recipient don't allow private messages from
+/// the sender. Remote recipients are checked by their own instance. Local admins can always send
+/// messages, eg to explain moderation actions.
+#[tracing::instrument(skip_all)]
+pub async fn check_private_message_privacy(
+  sender: &Person,
+  recipient_id: PersonId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  let Some(recipient) = LocalUserView::read_person(pool, recipient_id).await? else {
+    return Ok(());
+  };
+  if sender.local {
+    let sender = LocalUserView::read_person(pool, sender.id).await?;
+    if sender.is_some_and(|s| s.local_user.admin) {
+      return Ok(());
+    }
+  }
+  let allowed = match recipient.local_user.private_message_privacy {
+    PrivateMessagePrivacy::Everyone => true,
+    PrivateMessagePrivacy::LocalOnly => sender.local,
+    PrivateMessagePrivacy::Connected => {
+      PrivateMessageConversation::is_contact(pool, recipient_id, sender.id).await?
+    }
+    PrivateMessagePrivacy::Nobody => false,
+  };
+  // Replies are always allowed
+  if allowed || PrivateMessageConversation::has_written_to(pool, recipient_id, sender.id).await? {
+    Ok(())
+  } else {
+    Err(LemmyErrorType::PrivateMessagesNotAllowed)?
+  }
+}
+
 /// Throws an error if a recipient has blocked a community.
 #[tracing::instrument(skip_all)]
 async fn check_community_block(
@@ -399,6 +613,34 @@ pub async fn build_federated_instances(
   }
 }
 
+/// Reads the state of outgoing federation to all instances.
+pub async fn build_federation_health(
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<GetFederationHealthResponse> {
+  let latest_activity_id = SentActivity::read_latest_id(pool).await?;
+  let instances = FederationQueueState::read_all_with_instance(pool)
+    .await?
+    .into_iter()
+    .map(|(instance, state, requested_reset)| {
+      let last_successful_id = state.last_successful_id.map(|id| id.0).unwrap_or(0);
+      let activities_behind = latest_activity_id
+        .map(|latest| (latest.0 - last_successful_id).max(0))
+        .unwrap_or(0);
+      InstanceFederationHealth {
+        instance,
+        federation_state: state.into(),
+        activities_behind,
+        requested_reset,
+      }
+    })
+    .collect();
+
+  Ok(GetFederationHealthResponse {
+    latest_activity_id,
+    instances,
+  })
+}
+
 /// Checks the password length
 pub fn password_length_check(pass: &str) -> LemmyResult<()> {
   if !(10..=60).contains(&pass.chars().count()) {
@@ -520,6 +762,9 @@ pub fn local_site_rate_limit_to_rate_limit_config(
     ActionType::Comment => (l.comment, l.comment_per_second),
     ActionType::Search => (l.search, l.search_per_second),
     ActionType::ImportUserSettings => (l.import_user_settings, l.import_user_settings_per_second),
+    ActionType::Vote => (l.vote, l.vote_per_second),
+    ActionType::Report => (l.report, l.report_per_second),
+    ActionType::PrivateMessage => (l.private_message, l.private_message_per_second),
   }
   .map(|_key, (capacity, secs_to_refill)| BucketConfig {
     capacity: u32::try_from(capacity).unwrap_or(0),
@@ -562,6 +807,30 @@ pub async fn get_url_blocklist(context: &LemmyContext) -> LemmyResult<RegexSet>
   )
 }
 
+pub async fn get_url_cleaning_rules(context: &LemmyContext) -> LemmyResult<Arc<UrlCleaningRules>> {
+  static URL_CLEANING_RULES: LazyLock<Cache<(), Arc<UrlCleaningRules>>> = LazyLock::new(|| {
+    Cache::builder()
+      .max_capacity(1)
+      .time_to_live(CACHE_DURATION_FEDERATION)
+      .build()
+  });
+
+  Ok(
+    URL_CLEANING_RULES
+      .try_get_with::<_, LemmyError>((), async {
+        let rules = LocalSiteUrlCleaningRule::get_all(&mut context.pool()).await?;
+        let rules = UrlCleaningRules::new(
+          rules
+            .iter()
+            .map(|r| (r.url_pattern.as_str(), r.params.as_slice())),
+        )?;
+        Ok(Arc::new(rules))
+      })
+      .await
+      .map_err(|e| anyhow::anyhow!("Failed to build URL cleaning rules due to `{}`", e))?,
+  )
+}
+
 pub async fn send_application_approved_email(
   user: &LocalUserView,
   settings: &Settings,
@@ -941,7 +1210,9 @@ pub async fn process_markdown(
   context: &LemmyContext,
 ) -> LemmyResult<String> {
   let text = remove_slurs(text, slur_regex);
-  let text = clean_urls_in_text(&text);
+  let text = get_url_cleaning_rules(context)
+    .await?
+    .clean_urls_in_text(&text);
 
   markdown_check_for_blocked_urls(&text, url_blocklist)?;
 
@@ -1071,6 +1342,16 @@ fn build_proxied_image_url(
 mod tests {
 
   use super::*;
+  use lemmy_db_schema::{
+    source::{
+      community::CommunityInsertForm,
+      local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
+      person::{PersonFollower, PersonFollowerForm, PersonInsertForm},
+      private_message::{PrivateMessage, PrivateMessageInsertForm},
+      removal_reason::RemovalReasonInsertForm,
+    },
+    traits::Followable,
+  };
   use pretty_assertions::assert_eq;
   use serial_test::serial;
 
@@ -1091,6 +1372,201 @@ mod tests {
     assert!(honeypot_check(&Some("message".to_string())).is_err());
   }
 
+  #[test]
+  fn slow_mode() {
+    let now = Utc::now();
+    assert!(check_slow_mode(None, Some(60)).is_ok());
+    assert!(check_slow_mode(Some(now), None).is_ok());
+    assert!(check_slow_mode(Some(now - TimeDelta::seconds(61)), Some(60)).is_ok());
+    let err = check_slow_mode(Some(now - TimeDelta::milliseconds(20_500)), Some(60)).unwrap_err();
+    assert_eq!(LemmyErrorType::SlowMode(40), err.error_type);
+  }
+
+  #[test]
+  fn test_sanitize_post_gallery() -> LemmyResult<()> {
+    let blocklist = RegexSet::new(["blocked\\.com"])?;
+    let rules = UrlCleaningRules::default();
+    let image = |url: &str| GalleryImage {
+      url: url.to_string(),
+      caption: None,
+      alt_text: None,
+    };
+    let mut gallery = vec![
+      image("https://example.com/1.png"),
+      image("https://blocked.com/2.png"),
+      image("not a url"),
+      GalleryImage {
+        caption: Some("c".repeat(2000)),
+        alt_text: Some("Alt text".to_string()),
+        ..image("https://example.com/3.png")
+      },
+    ];
+    gallery.extend((0..30).map(|i| image(&format!("https://example.com/more/{i}.png"))));
+    assert!(check_post_gallery(&gallery, &blocklist, &rules).is_err());
+
+    let sanitized = sanitize_post_gallery(&gallery, &blocklist, &rules);
+    assert_eq!(POST_GALLERY_MAX_IMAGES, sanitized.len());
+    assert_eq!(
+      Some("https://example.com/1.png"),
+      sanitized.first().map(|i| i.url.as_str())
+    );
+    let long_caption = sanitized.get(1);
+    assert_eq!(
+      Some("https://example.com/3.png"),
+      long_caption.map(|i| i.url.as_str())
+    );
+    assert_eq!(None, long_caption.and_then(|i| i.caption.as_deref()));
+    assert_eq!(
+      Some("Alt text"),
+      long_caption.and_then(|i| i.alt_text.as_deref())
+    );
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_removal_reason_text() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let mut communities = vec![];
+    for name in ["removal_reason_1", "removal_reason_2"] {
+      let form = CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(instance.id)
+        .build();
+      communities.push(Community::create(pool, &form).await?);
+    }
+    let community_id = communities[0].id;
+    let form = RemovalReasonInsertForm::builder()
+      .community_id(community_id)
+      .title("spam".to_string())
+      .reason("Rule 1: No spam".to_string())
+      .build();
+    let template = RemovalReason::create(pool, &form).await?;
+    let template_text = Some("Rule 1: No spam".to_string());
+    let own_reason = Some("Off topic".to_string());
+
+    // Without a template the written reason is used
+    let text = removal_reason_text(&own_reason, None, true, community_id, pool).await?;
+    assert_eq!((own_reason.clone(), None), text);
+
+    // The template text is used and counted if no reason was written
+    let text = removal_reason_text(&None, Some(template.id), true, community_id, pool).await?;
+    assert_eq!((template_text.clone(), Some(template.id)), text);
+
+    // A written reason overrides the template, which is then not counted
+    let text =
+      removal_reason_text(&own_reason, Some(template.id), true, community_id, pool).await?;
+    assert_eq!((own_reason, None), text);
+
+    // Restoring doesn't count as use of the template
+    let text = removal_reason_text(&None, Some(template.id), false, community_id, pool).await?;
+    assert_eq!((template_text, None), text);
+
+    // Templates of other communities can't be used
+    let other = removal_reason_text(&None, Some(template.id), true, communities[1].id, pool).await;
+    assert!(other.is_err());
+
+    for community in communities {
+      Community::delete(pool, community.id).await?;
+    }
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  /// Changes the privacy setting of the recipient, then checks if the sender may message them.
+  async fn private_message_allowed(
+    privacy: PrivateMessagePrivacy,
+    sender: &Person,
+    recipient: &LocalUser,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<bool> {
+    let form = LocalUserUpdateForm {
+      private_message_privacy: Some(privacy),
+      ..Default::default()
+    };
+    LocalUser::update(pool, recipient.id, &form).await?;
+    match check_private_message_privacy(sender, recipient.person_id, pool).await {
+      Ok(()) => Ok(true),
+      Err(e) if e.error_type == LemmyErrorType::PrivateMessagesNotAllowed => Ok(false),
+      Err(e) => Err(e),
+    }
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_private_message_privacy() -> LemmyResult<()> {
+    use PrivateMessagePrivacy::*;
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let remote_instance = Instance::read_or_create(pool, "remote.tld".to_string()).await?;
+    let recipient = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "pm_recipient"),
+    )
+    .await?;
+    let recipient =
+      LocalUser::create(pool, &LocalUserInsertForm::test_form(recipient.id), vec![]).await?;
+    let local = Person::create(pool, &PersonInsertForm::test_form(instance.id, "pm_local")).await?;
+    let remote_form = PersonInsertForm {
+      local: Some(false),
+      ..PersonInsertForm::test_form(remote_instance.id, "pm_remote")
+    };
+    let remote = Person::create(pool, &remote_form).await?;
+    let admin = Person::create(pool, &PersonInsertForm::test_form(instance.id, "pm_admin")).await?;
+    LocalUser::create(
+      pool,
+      &LocalUserInsertForm::test_form_admin(admin.id),
+      vec![],
+    )
+    .await?;
+
+    assert!(private_message_allowed(Everyone, &local, &recipient, pool).await?);
+    assert!(private_message_allowed(Everyone, &remote, &recipient, pool).await?);
+
+    assert!(private_message_allowed(LocalOnly, &local, &recipient, pool).await?);
+    assert!(!private_message_allowed(LocalOnly, &remote, &recipient, pool).await?);
+
+    assert!(!private_message_allowed(Nobody, &local, &recipient, pool).await?);
+    assert!(!private_message_allowed(Nobody, &remote, &recipient, pool).await?);
+
+    // Only people whom the recipient follows are connected
+    assert!(!private_message_allowed(Connected, &local, &recipient, pool).await?);
+    assert!(!private_message_allowed(Connected, &remote, &recipient, pool).await?);
+    let follow_form = PersonFollowerForm {
+      person_id: remote.id,
+      follower_id: recipient.person_id,
+      pending: false,
+    };
+    PersonFollower::follow(pool, &follow_form).await?;
+    assert!(private_message_allowed(Connected, &remote, &recipient, pool).await?);
+    assert!(!private_message_allowed(Connected, &local, &recipient, pool).await?);
+
+    // Admins can always send messages
+    for privacy in [LocalOnly, Connected, Nobody] {
+      assert!(private_message_allowed(privacy, &admin, &recipient, pool).await?);
+    }
+
+    // Replies to the recipient are always allowed
+    let form = PrivateMessageInsertForm::builder()
+      .creator_id(recipient.person_id)
+      .recipient_id(local.id)
+      .content(String::new())
+      .build();
+    PrivateMessage::create(pool, &form).await?;
+    assert!(private_message_allowed(Nobody, &local, &recipient, pool).await?);
+
+    Instance::delete(pool, instance.id).await?;
+    Instance::delete(pool, remote_instance.id).await?;
+    Ok(())
+  }
+
   #[test]
   fn test_limit_ban_term() {
     // Ban expires in past, should throw error
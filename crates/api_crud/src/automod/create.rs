@@ -0,0 +1,67 @@
+use crate::automod::{check_automod_rule, AutomodRuleParams};
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  automod::{AutomodRuleResponse, CreateAutomodRule},
+  context::LemmyContext,
+  utils::is_mod_or_admin_opt,
+};
+use lemmy_db_schema::{
+  source::{
+    automod::{AutomodRule, AutomodRuleInsertForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::diesel_string_update,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn create_automod_rule(
+  data: Json<CreateAutomodRule>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<AutomodRuleResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  // Community rules can be created by mods, site-wide rules only by admins
+  is_mod_or_admin_opt(
+    &mut context.pool(),
+    Some(&local_user_view),
+    data.community_id,
+  )
+  .await?;
+
+  let params = AutomodRuleParams {
+    name: &data.name,
+    title_regex: data.title_regex.as_deref(),
+    body_regex: data.body_regex.as_deref(),
+    domain: data.domain.as_deref(),
+    max_account_age_days: data.max_account_age_days,
+    max_karma: data.max_karma,
+    min_report_count: data.min_report_count,
+    action: data.action,
+    action_message: data.action_message.as_deref(),
+  };
+  check_automod_rule(&params, &local_site)?;
+
+  let form = AutomodRuleInsertForm::builder()
+    .community_id(data.community_id)
+    .creator_id(local_user_view.person.id)
+    .name(data.name.trim().to_string())
+    .enabled(data.enabled)
+    .apply_to_posts(data.apply_to_posts)
+    .apply_to_comments(data.apply_to_comments)
+    .title_regex(diesel_string_update(data.title_regex.as_deref()).flatten())
+    .body_regex(diesel_string_update(data.body_regex.as_deref()).flatten())
+    .domain(diesel_string_update(data.domain.as_deref()).flatten())
+    .max_account_age_days(data.max_account_age_days)
+    .max_karma(data.max_karma)
+    .min_report_count(data.min_report_count)
+    .action(data.action)
+    .action_message(diesel_string_update(data.action_message.as_deref()).flatten())
+    .build();
+  let automod_rule = AutomodRule::create(&mut context.pool(), &form).await?;
+
+  Ok(Json(AutomodRuleResponse { automod_rule }))
+}
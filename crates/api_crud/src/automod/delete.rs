@@ -0,0 +1,32 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  automod::DeleteAutomodRule,
+  context::LemmyContext,
+  utils::is_mod_or_admin_opt,
+  SuccessResponse,
+};
+use lemmy_db_schema::{source::automod::AutomodRule, traits::Crud};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_automod_rule(
+  data: Json<DeleteAutomodRule>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  let rule = AutomodRule::read(&mut context.pool(), data.id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindAutomodRule)?;
+  is_mod_or_admin_opt(
+    &mut context.pool(),
+    Some(&local_user_view),
+    rule.community_id,
+  )
+  .await?;
+
+  AutomodRule::delete(&mut context.pool(), data.id).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -0,0 +1,29 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  automod::{ListAutomodRules, ListAutomodRulesResponse},
+  context::LemmyContext,
+  utils::is_mod_or_admin_opt,
+};
+use lemmy_db_schema::source::automod::AutomodRule;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_automod_rules(
+  data: Query<ListAutomodRules>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListAutomodRulesResponse>> {
+  // Rules are only visible to those who can manage them, so that they can't be worked around
+  is_mod_or_admin_opt(
+    &mut context.pool(),
+    Some(&local_user_view),
+    data.community_id,
+  )
+  .await?;
+
+  let automod_rules = AutomodRule::list(&mut context.pool(), data.community_id).await?;
+
+  Ok(Json(ListAutomodRulesResponse { automod_rules }))
+}
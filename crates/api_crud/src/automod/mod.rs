@@ -0,0 +1,51 @@
+use lemmy_api_common::utils::local_site_to_slur_regex;
+use lemmy_db_schema::{source::local_site::LocalSite, AutomodAction};
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  utils::{slurs::check_slurs, validation::build_and_check_regex},
+};
+
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod update;
+
+/// The conditions and action of an automod rule, as sent by the client.
+struct AutomodRuleParams<'a> {
+  name: &'a str,
+  title_regex: Option<&'a str>,
+  body_regex: Option<&'a str>,
+  domain: Option<&'a str>,
+  max_account_age_days: Option<i32>,
+  max_karma: Option<i32>,
+  min_report_count: Option<i32>,
+  action: AutomodAction,
+  action_message: Option<&'a str>,
+}
+
+/// Validates a rule before saving it. A rule needs at least one condition, as it would otherwise
+/// match all content.
+fn check_automod_rule(params: &AutomodRuleParams, local_site: &LocalSite) -> LemmyResult<()> {
+  check_slurs(params.name, &local_site_to_slur_regex(local_site))?;
+  build_and_check_regex(&params.title_regex)?;
+  build_and_check_regex(&params.body_regex)?;
+
+  let has_condition = [params.title_regex, params.body_regex, params.domain]
+    .iter()
+    .any(|c| c.is_some_and(|c| !c.is_empty()))
+    || params.max_account_age_days.is_some()
+    || params.max_karma.is_some()
+    || params.min_report_count.is_some();
+  if !has_condition {
+    Err(LemmyErrorType::AutomodRuleWithoutCondition)?
+  }
+
+  let needs_message = matches!(
+    params.action,
+    AutomodAction::Reply | AutomodAction::SetFlair
+  );
+  if needs_message && params.action_message.map_or(true, str::is_empty) {
+    Err(LemmyErrorType::AutomodActionNeedsMessage)?
+  }
+  Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::automod::{check_automod_rule, AutomodRuleParams};
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  automod::{AutomodRuleResponse, EditAutomodRule},
+  context::LemmyContext,
+  utils::is_mod_or_admin_opt,
+};
+use lemmy_db_schema::{
+  source::{
+    automod::{AutomodRule, AutomodRuleUpdateForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::{diesel_string_update, naive_now},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn update_automod_rule(
+  data: Json<EditAutomodRule>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<AutomodRuleResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let orig_rule = AutomodRule::read(&mut context.pool(), data.id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindAutomodRule)?;
+  is_mod_or_admin_opt(
+    &mut context.pool(),
+    Some(&local_user_view),
+    orig_rule.community_id,
+  )
+  .await?;
+
+  let params = AutomodRuleParams {
+    name: &data.name,
+    title_regex: data.title_regex.as_deref(),
+    body_regex: data.body_regex.as_deref(),
+    domain: data.domain.as_deref(),
+    max_account_age_days: data.max_account_age_days,
+    max_karma: data.max_karma,
+    min_report_count: data.min_report_count,
+    action: data.action,
+    action_message: data.action_message.as_deref(),
+  };
+  check_automod_rule(&params, &local_site)?;
+
+  // Conditions which are left out get cleared
+  let clear_or_set = |s: &Option<String>| Some(diesel_string_update(s.as_deref()).flatten());
+  let form = AutomodRuleUpdateForm {
+    name: Some(data.name.trim().to_string()),
+    enabled: data.enabled,
+    apply_to_posts: data.apply_to_posts,
+    apply_to_comments: data.apply_to_comments,
+    title_regex: clear_or_set(&data.title_regex),
+    body_regex: clear_or_set(&data.body_regex),
+    domain: clear_or_set(&data.domain),
+    max_account_age_days: Some(data.max_account_age_days),
+    max_karma: Some(data.max_karma),
+    min_report_count: Some(data.min_report_count),
+    action: Some(data.action),
+    action_message: clear_or_set(&data.action_message),
+    updated: Some(Some(naive_now())),
+  };
+  let automod_rule = AutomodRule::update(&mut context.pool(), data.id, &form).await?;
+
+  Ok(Json(AutomodRuleResponse { automod_rule }))
+}
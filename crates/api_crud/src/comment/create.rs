@@ -1,16 +1,18 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
+  automod_engine::{run_automod_for_comment, AutomodTrigger},
   build_response::{build_comment_response, send_local_notifs},
   comment::{CommentResponse, CreateComment},
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
+  slur_filter_engine::{SlurFilterMatches, SlurFilters},
   utils::{
     check_community_user_action,
     check_post_deleted_or_removed,
+    check_slow_mode,
     get_url_blocklist,
     is_mod_or_admin,
-    local_site_to_slur_regex,
     process_markdown,
     update_read_comments,
   },
@@ -42,9 +44,11 @@ pub async fn create_comment(
 ) -> LemmyResult<Json<CommentResponse>> {
   let local_site = LocalSite::read(&mut context.pool()).await?;
 
-  let slur_regex = local_site_to_slur_regex(&local_site);
+  let slur_filters = SlurFilters::read(Some(&local_site), &context).await?;
+  let mut slur_filter_matches = SlurFilterMatches::default();
+  let content = slur_filters.check_text(&data.content, &mut slur_filter_matches)?;
   let url_blocklist = get_url_blocklist(&context).await?;
-  let content = process_markdown(&data.content, &slur_regex, &url_blocklist, &context).await?;
+  let content = process_markdown(&content, &None, &url_blocklist, &context).await?;
   is_valid_body_field(&content, false)?;
 
   // Check for a community ban
@@ -74,6 +78,33 @@ pub async fn create_comment(
     Err(LemmyErrorType::Locked)?
   }
 
+  if !is_mod_or_admin && post_view.community.comment_slow_mode_seconds.is_some() {
+    let last_comment = Comment::latest_published_by_creator_in_post(
+      &mut context.pool(),
+      local_user_view.person.id,
+      post.id,
+    )
+    .await?;
+    check_slow_mode(last_comment, post_view.community.comment_slow_mode_seconds)?;
+  }
+  if !is_mod_or_admin
+    && post_view
+      .community
+      .community_comment_slow_mode_seconds
+      .is_some()
+  {
+    let last_comment = Comment::latest_published_by_creator_in_community(
+      &mut context.pool(),
+      local_user_view.person.id,
+      post.community_id,
+    )
+    .await?;
+    check_slow_mode(
+      last_comment,
+      post_view.community.community_comment_slow_mode_seconds,
+    )?;
+  }
+
   // Fetch the parent, if it exists
   let parent_opt = if let Some(parent_id) = data.parent_id {
     Comment::read(&mut context.pool(), parent_id).await.ok()
@@ -156,6 +187,11 @@ pub async fn create_comment(
   )
   .await?;
 
+  run_automod_for_comment(&inserted_comment, AutomodTrigger::Write, &context).await?;
+  slur_filter_matches
+    .apply_to_comment(&inserted_comment, &context)
+    .await?;
+
   // Update the read comments, so your own new comment doesn't appear as a +1 unread
   update_read_comments(
     local_user_view.person.id,
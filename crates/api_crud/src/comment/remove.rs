@@ -1,11 +1,11 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
-  build_response::{build_comment_response, send_local_notifs},
+  build_response::{build_comment_response, send_local_notifs, send_removal_notification},
   comment::{CommentResponse, RemoveComment},
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::check_community_mod_action,
+  utils::{check_community_mod_action, removal_reason_text},
 };
 use lemmy_db_schema::{
   source::{
@@ -56,6 +56,15 @@ pub async fn remove_comment(
     return Err(LemmyErrorType::CouldntUpdateComment.into());
   }
 
+  let (reason, removal_reason_id) = removal_reason_text(
+    &data.reason,
+    data.removal_reason_id,
+    data.removed,
+    orig_comment.community.id,
+    &mut context.pool(),
+  )
+  .await?;
+
   // Do the remove
   let removed = data.removed;
   let updated_comment = Comment::update(
@@ -77,10 +86,24 @@ pub async fn remove_comment(
     mod_person_id: local_user_view.person.id,
     comment_id: data.comment_id,
     removed: Some(removed),
-    reason: data.reason.clone(),
+    reason: reason.clone(),
+    removal_reason_id,
   };
   ModRemoveComment::create(&mut context.pool(), &form).await?;
 
+  // Tell the creator why their comment was removed
+  if let (true, Some(notification), Some(reason)) = (removed, data.notify_author, &reason) {
+    send_removal_notification(
+      notification,
+      &local_user_view.person,
+      &orig_comment.post,
+      Some(&updated_comment),
+      reason,
+      &context,
+    )
+    .await?;
+  }
+
   let recipient_ids = send_local_notifs(
     vec![],
     comment_id,
@@ -97,7 +120,7 @@ pub async fn remove_comment(
       comment: updated_comment,
       moderator: local_user_view.person.clone(),
       community: orig_comment.community,
-      reason: data.reason.clone(),
+      reason,
     },
     &context,
   )
@@ -1,16 +1,13 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
+  automod_engine::{run_automod_for_comment, AutomodTrigger},
   build_response::{build_comment_response, send_local_notifs},
   comment::{CommentResponse, EditComment},
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{
-    check_community_user_action,
-    get_url_blocklist,
-    local_site_to_slur_regex,
-    process_markdown_opt,
-  },
+  slur_filter_engine::{SlurFilterMatches, SlurFilters},
+  utils::{check_community_user_action, get_url_blocklist, process_markdown_opt},
 };
 use lemmy_db_schema::{
   source::{
@@ -64,9 +61,11 @@ pub async fn update_comment(
   )
   .await?;
 
-  let slur_regex = local_site_to_slur_regex(&local_site);
+  let slur_filters = SlurFilters::read(Some(&local_site), &context).await?;
+  let mut slur_filter_matches = SlurFilterMatches::default();
+  let content = slur_filters.check_text_opt(&data.content, &mut slur_filter_matches)?;
   let url_blocklist = get_url_blocklist(&context).await?;
-  let content = process_markdown_opt(&data.content, &slur_regex, &url_blocklist, &context).await?;
+  let content = process_markdown_opt(&content, &None, &url_blocklist, &context).await?;
   if let Some(content) = &content {
     is_valid_body_field(content, false)?;
   }
@@ -101,6 +100,11 @@ pub async fn update_comment(
   )
   .await?;
 
+  run_automod_for_comment(&updated_comment, AutomodTrigger::Write, &context).await?;
+  slur_filter_matches
+    .apply_to_comment(&updated_comment, &context)
+    .await?;
+
   Ok(Json(
     build_comment_response(
       &context,
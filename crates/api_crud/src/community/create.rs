@@ -1,3 +1,4 @@
+use crate::community::{duplicate_post_window_update, slow_mode_update};
 use activitypub_federation::{config::Data, http_signatures::generate_actor_keypair};
 use actix_web::web::Json;
 use lemmy_api_common::{
@@ -104,6 +105,14 @@ pub async fn create_community(
     .posting_restricted_to_mods(data.posting_restricted_to_mods)
     .instance_id(site_view.site.instance_id)
     .visibility(data.visibility)
+    .comment_slow_mode_seconds(slow_mode_update(data.comment_slow_mode_seconds)?.flatten())
+    .community_comment_slow_mode_seconds(
+      slow_mode_update(data.community_comment_slow_mode_seconds)?.flatten(),
+    )
+    .post_slow_mode_seconds(slow_mode_update(data.post_slow_mode_seconds)?.flatten())
+    .duplicate_post_window_days(
+      duplicate_post_window_update(data.duplicate_post_window_days)?.flatten(),
+    )
     .build();
 
   let inserted_community = Community::create(&mut context.pool(), &community_form)
@@ -1,5 +1,34 @@
+use lemmy_utils::{
+  error::LemmyResult,
+  utils::validation::{is_valid_duplicate_post_window_days, is_valid_slow_mode_seconds},
+};
+
 pub mod create;
 pub mod delete;
 pub mod list;
 pub mod remove;
 pub mod update;
+
+/// Converts a slow mode interval from the API into a form value. Zero disables slow mode.
+fn slow_mode_update(seconds: Option<i32>) -> LemmyResult<Option<Option<i32>>> {
+  match seconds {
+    Some(0) => Ok(Some(None)),
+    Some(seconds) => {
+      is_valid_slow_mode_seconds(seconds)?;
+      Ok(Some(Some(seconds)))
+    }
+    None => Ok(None),
+  }
+}
+
+/// Converts a duplicate post window from the API into a form value. Zero disables it.
+fn duplicate_post_window_update(days: Option<i32>) -> LemmyResult<Option<Option<i32>>> {
+  match days {
+    Some(0) => Ok(Some(None)),
+    Some(days) => {
+      is_valid_duplicate_post_window_days(days)?;
+      Ok(Some(Some(days)))
+    }
+    None => Ok(None),
+  }
+}
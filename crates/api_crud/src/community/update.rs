@@ -1,3 +1,4 @@
+use crate::community::{duplicate_post_window_update, slow_mode_update};
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
@@ -92,6 +93,12 @@ pub async fn update_community(
     nsfw: data.nsfw,
     posting_restricted_to_mods: data.posting_restricted_to_mods,
     visibility: data.visibility,
+    comment_slow_mode_seconds: slow_mode_update(data.comment_slow_mode_seconds)?,
+    community_comment_slow_mode_seconds: slow_mode_update(
+      data.community_comment_slow_mode_seconds,
+    )?,
+    post_slow_mode_seconds: slow_mode_update(data.post_slow_mode_seconds)?,
+    duplicate_post_window_days: duplicate_post_window_update(data.duplicate_post_window_days)?,
     updated: Some(Some(naive_now())),
     ..Default::default()
   };
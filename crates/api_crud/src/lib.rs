@@ -1,7 +1,10 @@
+pub mod automod;
 pub mod comment;
 pub mod community;
 pub mod custom_emoji;
 pub mod post;
 pub mod private_message;
+pub mod removal_reason;
 pub mod site;
+pub mod slur_filter;
 pub mod user;
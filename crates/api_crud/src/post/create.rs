@@ -1,27 +1,36 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
+  automod_engine::{run_automod_for_post, AutomodTrigger},
   build_response::build_post_response,
   context::LemmyContext,
   post::{CreatePost, PostResponse},
-  request::generate_post_link_metadata,
+  request::{canonical_post_url, fetch_link_metadata, generate_post_link_metadata},
   send_activity::SendActivityData,
+  slur_filter_engine::{SlurFilterMatches, SlurFilters},
   utils::{
     check_community_user_action,
+    check_duplicate_post,
+    check_post_gallery,
+    check_slow_mode,
     get_url_blocklist,
+    get_url_cleaning_rules,
     honeypot_check,
-    local_site_to_slur_regex,
+    is_mod_or_admin,
     mark_post_as_read,
     process_markdown_opt,
+    update_post_hashtags,
   },
 };
 use lemmy_db_schema::{
   impls::actor_language::default_post_language,
+  newtypes::DbUrl,
   source::{
     actor_language::CommunityLanguage,
     community::Community,
     local_site::LocalSite,
     post::{Post, PostInsertForm, PostLike, PostLikeForm},
+    post_gallery_image::PostGalleryImage,
   },
   traits::{Crud, Likeable},
   utils::diesel_url_create,
@@ -32,15 +41,12 @@ use lemmy_db_views_actor::structs::CommunityModeratorView;
 use lemmy_utils::{
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
   spawn_try_task,
-  utils::{
-    slurs::check_slurs,
-    validation::{
-      is_url_blocked,
-      is_valid_alt_text_field,
-      is_valid_body_field,
-      is_valid_post_title,
-      is_valid_url,
-    },
+  utils::validation::{
+    is_url_blocked,
+    is_valid_alt_text_field,
+    is_valid_body_field,
+    is_valid_post_title,
+    is_valid_url,
   },
 };
 use tracing::Instrument;
@@ -57,15 +63,19 @@ pub async fn create_post(
 
   honeypot_check(&data.honeypot)?;
 
-  let slur_regex = local_site_to_slur_regex(&local_site);
-  check_slurs(&data.name, &slur_regex)?;
+  let slur_filters = SlurFilters::read(Some(&local_site), &context).await?;
+  let mut slur_filter_matches = SlurFilterMatches::default();
+  let name = slur_filters.check_title(data.name.trim(), &mut slur_filter_matches)?;
+  let body = slur_filters.check_text_opt(&data.body, &mut slur_filter_matches)?;
   let url_blocklist = get_url_blocklist(&context).await?;
 
-  let body = process_markdown_opt(&data.body, &slur_regex, &url_blocklist, &context).await?;
-  let url = diesel_url_create(data.url.as_deref())?;
+  let body = process_markdown_opt(&body, &None, &url_blocklist, &context).await?;
+  let url_cleaning_rules = get_url_cleaning_rules(&context).await?;
+  let url: Option<DbUrl> =
+    diesel_url_create(data.url.as_deref())?.map(|url| url_cleaning_rules.clean_url(&url).into());
   let custom_thumbnail = diesel_url_create(data.custom_thumbnail.as_deref())?;
 
-  is_valid_post_title(&data.name)?;
+  is_valid_post_title(&name)?;
 
   if let Some(url) = &url {
     is_url_blocked(url, &url_blocklist)?;
@@ -76,6 +86,12 @@ pub async fn create_post(
     is_valid_url(custom_thumbnail)?;
   }
 
+  let gallery = data
+    .gallery
+    .as_deref()
+    .map(|gallery| check_post_gallery(gallery, &url_blocklist, &url_cleaning_rules))
+    .transpose()?;
+
   if let Some(alt_text) = &data.alt_text {
     is_valid_alt_text_field(alt_text)?;
   }
@@ -108,6 +124,41 @@ pub async fn create_post(
     }
   }
 
+  if community.post_slow_mode_seconds.is_some()
+    && is_mod_or_admin(&mut context.pool(), &local_user_view.person, community_id)
+      .await
+      .is_err()
+  {
+    let last_post = Post::latest_published_by_creator_in_community(
+      &mut context.pool(),
+      local_user_view.person.id,
+      community_id,
+    )
+    .await?;
+    check_slow_mode(last_post, community.post_slow_mode_seconds)?;
+  }
+
+  // Normalize the url to find reposts of it, and reject them if the community doesn't allow them.
+  // Otherwise the canonical url is set later by generate_post_link_metadata, so that the post
+  // doesn't need to wait for the linked site.
+  let url_canonical: Option<DbUrl> = match (&url, community.duplicate_post_window_days) {
+    (Some(url), Some(_)) => {
+      let metadata = fetch_link_metadata(url.inner(), &context)
+        .await
+        .unwrap_or_default();
+      Some(canonical_post_url(url.inner(), &metadata, &url_cleaning_rules).into())
+    }
+    _ => None,
+  };
+  if let Some(url_canonical) = &url_canonical {
+    if is_mod_or_admin(&mut context.pool(), &local_user_view.person, community_id)
+      .await
+      .is_err()
+    {
+      check_duplicate_post(&community, url_canonical, None, &mut context.pool()).await?;
+    }
+  }
+
   // Only need to check if language is allowed in case user set it explicitly. When using default
   // language, it already only returns allowed languages.
   CommunityLanguage::is_allowed_community_language(
@@ -131,7 +182,7 @@ pub async fn create_post(
   };
 
   let post_form = PostInsertForm::builder()
-    .name(data.name.trim().to_string())
+    .name(name)
     .url(url.map(Into::into))
     .body(body)
     .alt_text(data.alt_text.clone())
@@ -139,11 +190,16 @@ pub async fn create_post(
     .creator_id(local_user_view.person.id)
     .nsfw(data.nsfw)
     .language_id(language_id)
+    .url_canonical(url_canonical)
     .build();
 
   let inserted_post = Post::create(&mut context.pool(), &post_form)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntCreatePost)?;
+  update_post_hashtags(&inserted_post, vec![], &mut context.pool()).await?;
+  if let Some(gallery) = gallery {
+    PostGalleryImage::replace(&mut context.pool(), inserted_post.id, gallery).await?;
+  }
 
   generate_post_link_metadata(
     inserted_post.clone(),
@@ -153,6 +209,14 @@ pub async fn create_post(
   )
   .await?;
 
+  // Only run automod and slur filter actions once the Create activity is queued. Outgoing
+  // activities are sent in the order they are queued, so remote instances never receive a Remove
+  // or Lock before the post itself.
+  run_automod_for_post(&inserted_post, AutomodTrigger::Write, &context).await?;
+  slur_filter_matches
+    .apply_to_post(&inserted_post, &context)
+    .await?;
+
   // They like their own post by default
   let person_id = local_user_view.person.id;
   let post_id = inserted_post.id;
@@ -92,6 +92,11 @@ pub async fn get_post(
   let cross_posts = if let Some(url) = &post_view.post.url {
     let mut x_posts = PostQuery {
       url_search: Some(url.inner().as_str().into()),
+      url_canonical_search: post_view
+        .post
+        .url_canonical
+        .as_ref()
+        .map(|u| u.inner().as_str().into()),
       local_user: local_user.as_ref(),
       ..Default::default()
     }
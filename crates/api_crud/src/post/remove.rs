@@ -1,11 +1,11 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
-  build_response::build_post_response,
+  build_response::{build_post_response, send_removal_notification},
   context::LemmyContext,
   post::{PostResponse, RemovePost},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::check_community_mod_action,
+  utils::{check_community_mod_action, removal_reason_text},
 };
 use lemmy_db_schema::{
   source::{
@@ -46,6 +46,15 @@ pub async fn remove_post(
   )
   .await?;
 
+  let (reason, removal_reason_id) = removal_reason_text(
+    &data.reason,
+    data.removal_reason_id,
+    data.removed,
+    orig_post.community_id,
+    &mut context.pool(),
+  )
+  .await?;
+
   // Update the post
   let post_id = data.post_id;
   let removed = data.removed;
@@ -67,15 +76,29 @@ pub async fn remove_post(
     mod_person_id: local_user_view.person.id,
     post_id: data.post_id,
     removed: Some(removed),
-    reason: data.reason.clone(),
+    reason: reason.clone(),
+    removal_reason_id,
   };
   ModRemovePost::create(&mut context.pool(), &form).await?;
 
+  // Tell the creator why their post was removed
+  if let (true, Some(notification), Some(reason)) = (removed, data.notify_author, &reason) {
+    send_removal_notification(
+      notification,
+      &local_user_view.person,
+      &post,
+      None,
+      reason,
+      &context,
+    )
+    .await?;
+  }
+
   ActivityChannel::submit_activity(
     SendActivityData::RemovePost {
       post,
       moderator: local_user_view.person.clone(),
-      reason: data.reason.clone(),
+      reason,
       removed: data.removed,
     },
     &context,
@@ -1,23 +1,32 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
+  automod_engine::{run_automod_for_post, AutomodTrigger},
   build_response::build_post_response,
   context::LemmyContext,
   post::{EditPost, PostResponse},
-  request::generate_post_link_metadata,
+  request::{canonical_post_url, fetch_link_metadata, generate_post_link_metadata},
   send_activity::SendActivityData,
+  slur_filter_engine::{SlurFilterMatches, SlurFilters},
   utils::{
     check_community_user_action,
+    check_duplicate_post,
+    check_post_gallery,
     get_url_blocklist,
-    local_site_to_slur_regex,
+    get_url_cleaning_rules,
+    is_mod_or_admin,
     process_markdown_opt,
+    update_post_hashtags,
   },
 };
 use lemmy_db_schema::{
+  newtypes::DbUrl,
   source::{
     actor_language::CommunityLanguage,
+    community::Community,
     local_site::LocalSite,
     post::{Post, PostUpdateForm},
+    post_gallery_image::PostGalleryImage,
   },
   traits::Crud,
   utils::{diesel_string_update, diesel_url_update, naive_now},
@@ -25,15 +34,12 @@ use lemmy_db_schema::{
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::{
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
-  utils::{
-    slurs::check_slurs,
-    validation::{
-      is_url_blocked,
-      is_valid_alt_text_field,
-      is_valid_body_field,
-      is_valid_post_title,
-      is_valid_url,
-    },
+  utils::validation::{
+    is_url_blocked,
+    is_valid_alt_text_field,
+    is_valid_body_field,
+    is_valid_post_title,
+    is_valid_url,
   },
 };
 use std::ops::Deref;
@@ -46,25 +52,33 @@ pub async fn update_post(
 ) -> LemmyResult<Json<PostResponse>> {
   let local_site = LocalSite::read(&mut context.pool()).await?;
 
-  let url = diesel_url_update(data.url.as_deref())?;
+  let url_cleaning_rules = get_url_cleaning_rules(&context).await?;
+  let url: Option<Option<DbUrl>> = diesel_url_update(data.url.as_deref())?
+    .map(|url| url.map(|url| url_cleaning_rules.clean_url(&url).into()));
 
   let custom_thumbnail = diesel_url_update(data.custom_thumbnail.as_deref())?;
 
   let url_blocklist = get_url_blocklist(&context).await?;
 
-  let slur_regex = local_site_to_slur_regex(&local_site);
+  let slur_filters = SlurFilters::read(Some(&local_site), &context).await?;
+  let mut slur_filter_matches = SlurFilterMatches::default();
+  let body = slur_filters.check_text_opt(&data.body, &mut slur_filter_matches)?;
 
   let body = diesel_string_update(
-    process_markdown_opt(&data.body, &slur_regex, &url_blocklist, &context)
+    process_markdown_opt(&body, &None, &url_blocklist, &context)
       .await?
       .as_deref(),
   );
 
   let alt_text = diesel_string_update(data.alt_text.as_deref());
 
-  if let Some(name) = &data.name {
+  let name = data
+    .name
+    .as_deref()
+    .map(|name| slur_filters.check_title(name, &mut slur_filter_matches))
+    .transpose()?;
+  if let Some(name) = &name {
     is_valid_post_title(name)?;
-    check_slurs(name, &slur_regex)?;
   }
 
   if let Some(Some(body)) = &body {
@@ -84,6 +98,12 @@ pub async fn update_post(
     is_valid_url(custom_thumbnail)?;
   }
 
+  let gallery = data
+    .gallery
+    .as_deref()
+    .map(|gallery| check_post_gallery(gallery, &url_blocklist, &url_cleaning_rules))
+    .transpose()?;
+
   let post_id = data.post_id;
   let orig_post = Post::read(&mut context.pool(), post_id)
     .await?
@@ -109,8 +129,8 @@ pub async fn update_post(
   )
   .await?;
 
-  let post_form = PostUpdateForm {
-    name: data.name.clone(),
+  let mut post_form = PostUpdateForm {
+    name,
     url,
     body,
     alt_text,
@@ -119,11 +139,54 @@ pub async fn update_post(
     updated: Some(Some(naive_now())),
     ..Default::default()
   };
+  // Results of checking the previous url don't apply to the new one
+  if post_form
+    .url
+    .as_ref()
+    .is_some_and(|url| url != &orig_post.url)
+  {
+    post_form.url_status = Some(None);
+    post_form.url_checked = Some(None);
+    post_form.url_broken = Some(false);
+    post_form.url_archive = Some(None);
+    post_form.url_canonical = Some(None);
+
+    // Only fetch the link here if reposts need to be checked, otherwise the canonical url is set
+    // by generate_post_link_metadata.
+    if let Some(Some(url)) = &post_form.url {
+      let community = Community::read(&mut context.pool(), orig_post.community_id)
+        .await?
+        .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+      if community.duplicate_post_window_days.is_some() {
+        let metadata = fetch_link_metadata(url.inner(), &context)
+          .await
+          .unwrap_or_default();
+        let url_canonical = canonical_post_url(url.inner(), &metadata, &url_cleaning_rules).into();
+        if is_mod_or_admin(&mut context.pool(), &local_user_view.person, community.id)
+          .await
+          .is_err()
+        {
+          check_duplicate_post(
+            &community,
+            &url_canonical,
+            Some(post_id),
+            &mut context.pool(),
+          )
+          .await?;
+        }
+        post_form.url_canonical = Some(Some(url_canonical));
+      }
+    }
+  }
 
   let post_id = data.post_id;
   let updated_post = Post::update(&mut context.pool(), post_id, &post_form)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
+  update_post_hashtags(&updated_post, vec![], &mut context.pool()).await?;
+  if let Some(gallery) = gallery {
+    PostGalleryImage::replace(&mut context.pool(), post_id, gallery).await?;
+  }
 
   generate_post_link_metadata(
     updated_post.clone(),
@@ -133,6 +196,11 @@ pub async fn update_post(
   )
   .await?;
 
+  run_automod_for_post(&updated_post, AutomodTrigger::Write, &context).await?;
+  slur_filter_matches
+    .apply_to_post(&updated_post, &context)
+    .await?;
+
   build_post_response(
     context.deref(),
     orig_post.community_id,
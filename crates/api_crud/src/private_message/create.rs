@@ -1,3 +1,4 @@
+use super::{check_key_envelopes, save_key_envelopes};
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
@@ -6,6 +7,7 @@ use lemmy_api_common::{
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
     check_person_block,
+    check_private_message_privacy,
     get_interface_language,
     get_url_blocklist,
     local_site_to_slur_regex,
@@ -17,6 +19,11 @@ use lemmy_db_schema::{
   source::{
     local_site::LocalSite,
     private_message::{PrivateMessage, PrivateMessageInsertForm},
+    private_message_conversation::{
+      PrivateMessageConversation,
+      PrivateMessageConversationParticipant,
+      MAX_CONVERSATION_PARTICIPANTS,
+    },
   },
   traits::Crud,
 };
@@ -34,42 +41,93 @@ pub async fn create_private_message(
 ) -> LemmyResult<Json<PrivateMessageResponse>> {
   let local_site = LocalSite::read(&mut context.pool()).await?;
 
-  let slur_regex = local_site_to_slur_regex(&local_site);
-  let url_blocklist = get_url_blocklist(&context).await?;
-  let content = process_markdown(&data.content, &slur_regex, &url_blocklist, &context).await?;
+  let encrypted = data.encrypted.unwrap_or_default();
+  let content = if encrypted {
+    // Ciphertext can't be checked for slurs or rewritten
+    data.content.clone()
+  } else {
+    let slur_regex = local_site_to_slur_regex(&local_site);
+    let url_blocklist = get_url_blocklist(&context).await?;
+    process_markdown(&data.content, &slur_regex, &url_blocklist, &context).await?
+  };
   is_valid_body_field(&content, false)?;
 
-  check_person_block(
-    local_user_view.person.id,
-    data.recipient_id,
-    &mut context.pool(),
-  )
-  .await?;
+  let my_person_id = local_user_view.person.id;
+  let mut recipient_ids = vec![data.recipient_id];
+  recipient_ids.extend(data.additional_recipient_ids.iter().flatten());
+  recipient_ids.sort_by_key(|r| r.0);
+  recipient_ids.dedup();
+  if recipient_ids.len() >= MAX_CONVERSATION_PARTICIPANTS {
+    Err(LemmyErrorType::TooManyRecipients)?
+  }
+  for recipient_id in &recipient_ids {
+    check_person_block(my_person_id, *recipient_id, &mut context.pool()).await?;
+    check_private_message_privacy(&local_user_view.person, *recipient_id, &mut context.pool())
+      .await?;
+  }
+
+  if let Some(key_envelopes) = &data.key_envelopes {
+    let participants = [my_person_id]
+      .into_iter()
+      .chain(recipient_ids.iter().copied());
+    check_key_envelopes(encrypted, key_envelopes, &participants.collect::<Vec<_>>())?;
+  }
+
+  let conversation =
+    PrivateMessageConversation::read_or_create(&mut context.pool(), my_person_id, &recipient_ids)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreatePrivateMessage)?;
 
   let private_message_form = PrivateMessageInsertForm::builder()
     .content(content.clone())
-    .creator_id(local_user_view.person.id)
+    .creator_id(my_person_id)
     .recipient_id(data.recipient_id)
+    .conversation_id(Some(conversation.id))
+    .encrypted(Some(encrypted))
     .build();
 
   let inserted_private_message = PrivateMessage::create(&mut context.pool(), &private_message_form)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntCreatePrivateMessage)?;
+  if let Some(key_envelopes) = &data.key_envelopes {
+    save_key_envelopes(&inserted_private_message, key_envelopes, &context).await?;
+  }
 
-  let view = PrivateMessageView::read(&mut context.pool(), inserted_private_message.id)
-    .await?
-    .ok_or(LemmyErrorType::CouldntFindPrivateMessage)?;
+  let view = PrivateMessageView::read(
+    &mut context.pool(),
+    inserted_private_message.id,
+    my_person_id,
+  )
+  .await?
+  .ok_or(LemmyErrorType::CouldntFindPrivateMessage)?;
 
-  // Send email to the local recipient, if one exists
-  if view.recipient.local {
-    let recipient_id = data.recipient_id;
-    let local_recipient = LocalUserView::read_person(&mut context.pool(), recipient_id)
-      .await?
-      .ok_or(LemmyErrorType::CouldntFindPerson)?;
+  // Send email to the local recipients, unless they muted the conversation or it is a message
+  // request
+  for recipient_id in recipient_ids {
+    let Some(local_recipient) =
+      LocalUserView::read_person(&mut context.pool(), recipient_id).await?
+    else {
+      continue;
+    };
+    let silent = PrivateMessageConversationParticipant::read(
+      &mut context.pool(),
+      conversation.id,
+      recipient_id,
+    )
+    .await?
+    .is_some_and(|p| p.muted || p.request);
+    if silent {
+      continue;
+    }
     let lang = get_interface_language(&local_recipient);
     let inbox_link = format!("{}/inbox", context.settings().get_protocol_and_hostname());
     let sender_name = &local_user_view.person.name;
-    let content = markdown_to_html(&content);
+    // The server can't read encrypted messages, so they aren't included in the email
+    let content = if encrypted {
+      String::new()
+    } else {
+      markdown_to_html(&content)
+    };
     send_email_to_user(
       &local_recipient,
       &lang.notification_private_message_subject(sender_name),
@@ -47,9 +47,13 @@ pub async fn delete_private_message(
   )
   .await?;
 
-  let view = PrivateMessageView::read(&mut context.pool(), private_message_id)
-    .await?
-    .ok_or(LemmyErrorType::CouldntFindPrivateMessage)?;
+  let view = PrivateMessageView::read(
+    &mut context.pool(),
+    private_message_id,
+    orig_private_message.creator_id,
+  )
+  .await?
+  .ok_or(LemmyErrorType::CouldntFindPrivateMessage)?;
   Ok(Json(PrivateMessageResponse {
     private_message_view: view,
   }))
@@ -1,4 +1,74 @@
+use lemmy_api_common::{context::LemmyContext, private_message::KeyEnvelope};
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  source::{
+    private_message::PrivateMessage,
+    private_message_key_envelope::{PrivateMessageKeyEnvelope, PrivateMessageKeyEnvelopeForm},
+  },
+};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
 pub mod create;
 pub mod delete;
 pub mod read;
 pub mod update;
+
+/// Checks the key envelopes for a message, before it is stored. Only encrypted messages can have
+/// them, and they can only be addressed to participants of the conversation.
+fn check_key_envelopes(
+  encrypted: bool,
+  envelopes: &[KeyEnvelope],
+  participants: &[PersonId],
+) -> LemmyResult<()> {
+  if !encrypted
+    || envelopes
+      .iter()
+      .any(|e| !participants.contains(&e.person_id))
+  {
+    Err(LemmyErrorType::InvalidKeyEnvelope)?
+  }
+  Ok(())
+}
+
+/// Stores the key envelopes of an encrypted message, which were checked with
+/// [check_key_envelopes].
+async fn save_key_envelopes(
+  private_message: &PrivateMessage,
+  envelopes: &[KeyEnvelope],
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let forms = envelopes
+    .iter()
+    .map(|e| PrivateMessageKeyEnvelopeForm {
+      private_message_id: private_message.id,
+      person_id: e.person_id,
+      device_id: e.device_id.clone(),
+      encrypted_key: e.encrypted_key.clone(),
+    })
+    .collect::<Vec<_>>();
+  PrivateMessageKeyEnvelope::replace_for_message(&mut context.pool(), private_message.id, &forms)
+    .await?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+  use crate::private_message::check_key_envelopes;
+  use lemmy_api_common::private_message::KeyEnvelope;
+  use lemmy_db_schema::newtypes::PersonId;
+
+  #[test]
+  fn test_check_key_envelopes() {
+    let envelope = |person_id| KeyEnvelope {
+      person_id: PersonId(person_id),
+      device_id: "device".to_string(),
+      encrypted_key: "key".to_string(),
+    };
+    let participants = [PersonId(1), PersonId(2)];
+    let envelopes = [envelope(1), envelope(2)];
+    assert!(check_key_envelopes(true, &envelopes, &participants).is_ok());
+    assert!(check_key_envelopes(false, &envelopes, &participants).is_err());
+    assert!(check_key_envelopes(true, &[envelope(3)], &participants).is_err());
+  }
+}
@@ -3,6 +3,7 @@ use lemmy_api_common::{
   context::LemmyContext,
   private_message::{GetPrivateMessages, PrivateMessagesResponse},
 };
+use lemmy_db_schema::source::private_message_key_envelope::PrivateMessageKeyEnvelope;
 use lemmy_db_views::{private_message_view::PrivateMessageQuery, structs::LocalUserView};
 use lemmy_utils::error::LemmyResult;
 
@@ -18,16 +19,28 @@ pub async fn get_private_message(
   let limit = data.limit;
   let unread_only = data.unread_only.unwrap_or_default();
   let creator_id = data.creator_id;
+  let conversation_id = data.conversation_id;
   let messages = PrivateMessageQuery {
     page,
     limit,
     unread_only,
     creator_id,
+    conversation_id,
   }
   .list(&mut context.pool(), person_id)
   .await?;
 
+  let encrypted_ids: Vec<_> = messages
+    .iter()
+    .filter(|m| m.private_message.encrypted)
+    .map(|m| m.private_message.id)
+    .collect();
+  let key_envelopes =
+    PrivateMessageKeyEnvelope::list_for_person(&mut context.pool(), &encrypted_ids, person_id)
+      .await?;
+
   Ok(Json(PrivateMessagesResponse {
     private_messages: messages,
+    key_envelopes,
   }))
 }
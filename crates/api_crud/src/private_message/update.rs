@@ -1,3 +1,4 @@
+use super::{check_key_envelopes, save_key_envelopes};
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
@@ -10,6 +11,7 @@ use lemmy_db_schema::{
   source::{
     local_site::LocalSite,
     private_message::{PrivateMessage, PrivateMessageUpdateForm},
+    private_message_conversation::PrivateMessageConversation,
   },
   traits::Crud,
   utils::naive_now,
@@ -38,10 +40,22 @@ pub async fn update_private_message(
   }
 
   // Doing the update
-  let slur_regex = local_site_to_slur_regex(&local_site);
-  let url_blocklist = get_url_blocklist(&context).await?;
-  let content = process_markdown(&data.content, &slur_regex, &url_blocklist, &context).await?;
+  let content = if orig_private_message.encrypted {
+    data.content.clone()
+  } else {
+    let slur_regex = local_site_to_slur_regex(&local_site);
+    let url_blocklist = get_url_blocklist(&context).await?;
+    process_markdown(&data.content, &slur_regex, &url_blocklist, &context).await?
+  };
   is_valid_body_field(&content, false)?;
+  if let Some(key_envelopes) = &data.key_envelopes {
+    let participants = PrivateMessageConversation::list_participants(
+      &mut context.pool(),
+      orig_private_message.conversation_id,
+    )
+    .await?;
+    check_key_envelopes(orig_private_message.encrypted, key_envelopes, &participants)?;
+  }
 
   let private_message_id = data.private_message_id;
   PrivateMessage::update(
@@ -55,10 +69,17 @@ pub async fn update_private_message(
   )
   .await
   .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
+  if let Some(key_envelopes) = &data.key_envelopes {
+    save_key_envelopes(&orig_private_message, key_envelopes, &context).await?;
+  }
 
-  let view = PrivateMessageView::read(&mut context.pool(), private_message_id)
-    .await?
-    .ok_or(LemmyErrorType::CouldntFindPrivateMessage)?;
+  let view = PrivateMessageView::read(
+    &mut context.pool(),
+    private_message_id,
+    local_user_view.person.id,
+  )
+  .await?
+  .ok_or(LemmyErrorType::CouldntFindPrivateMessage)?;
 
   ActivityChannel::submit_activity(
     SendActivityData::UpdatePrivateMessage(view.clone()),
@@ -0,0 +1,49 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{CreateRemovalReason, RemovalReasonResponse},
+  context::LemmyContext,
+  utils::{check_community_mod_action, local_site_to_slur_regex},
+};
+use lemmy_db_schema::{
+  source::{
+    local_site::LocalSite,
+    removal_reason::{RemovalReason, RemovalReasonInsertForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::LemmyResult,
+  utils::{slurs::check_slurs, validation::is_valid_body_field},
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn create_removal_reason(
+  data: Json<CreateRemovalReason>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<RemovalReasonResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_community_mod_action(
+    &local_user_view.person,
+    data.community_id,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
+
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  check_slurs(&data.title, &slur_regex)?;
+  check_slurs(&data.reason, &slur_regex)?;
+  is_valid_body_field(&data.reason, false)?;
+
+  let form = RemovalReasonInsertForm::builder()
+    .community_id(data.community_id)
+    .title(data.title.trim().to_string())
+    .reason(data.reason.trim().to_string())
+    .build();
+  let removal_reason = RemovalReason::create(&mut context.pool(), &form).await?;
+
+  Ok(Json(RemovalReasonResponse { removal_reason }))
+}
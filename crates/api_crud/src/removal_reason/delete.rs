@@ -0,0 +1,33 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::DeleteRemovalReason,
+  context::LemmyContext,
+  utils::check_community_mod_action,
+  SuccessResponse,
+};
+use lemmy_db_schema::{source::removal_reason::RemovalReason, traits::Crud};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_removal_reason(
+  data: Json<DeleteRemovalReason>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  let removal_reason = RemovalReason::read(&mut context.pool(), data.id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindRemovalReason)?;
+  check_community_mod_action(
+    &local_user_view.person,
+    removal_reason.community_id,
+    true,
+    &mut context.pool(),
+  )
+  .await?;
+
+  RemovalReason::delete(&mut context.pool(), data.id).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -0,0 +1,28 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  community::{ListRemovalReasons, ListRemovalReasonsResponse},
+  context::LemmyContext,
+  lemmy_db_views_moderator::structs::RemovalReasonView,
+  utils::is_mod_or_admin,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_removal_reasons(
+  data: Query<ListRemovalReasons>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListRemovalReasonsResponse>> {
+  is_mod_or_admin(
+    &mut context.pool(),
+    &local_user_view.person,
+    data.community_id,
+  )
+  .await?;
+
+  let removal_reasons = RemovalReasonView::list(&mut context.pool(), data.community_id).await?;
+
+  Ok(Json(ListRemovalReasonsResponse { removal_reasons }))
+}
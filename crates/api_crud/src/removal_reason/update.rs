@@ -0,0 +1,56 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{EditRemovalReason, RemovalReasonResponse},
+  context::LemmyContext,
+  utils::{check_community_mod_action, local_site_to_slur_regex},
+};
+use lemmy_db_schema::{
+  source::{
+    local_site::LocalSite,
+    removal_reason::{RemovalReason, RemovalReasonUpdateForm},
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::LemmyResult,
+  utils::{slurs::check_slurs_opt, validation::is_valid_body_field},
+  LemmyErrorType,
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn update_removal_reason(
+  data: Json<EditRemovalReason>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<RemovalReasonResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let orig_removal_reason = RemovalReason::read(&mut context.pool(), data.id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindRemovalReason)?;
+  check_community_mod_action(
+    &local_user_view.person,
+    orig_removal_reason.community_id,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
+
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  check_slurs_opt(&data.title, &slur_regex)?;
+  check_slurs_opt(&data.reason, &slur_regex)?;
+  if let Some(reason) = &data.reason {
+    is_valid_body_field(reason, false)?;
+  }
+
+  let form = RemovalReasonUpdateForm {
+    title: data.title.as_ref().map(|t| t.trim().to_string()),
+    reason: data.reason.as_ref().map(|r| r.trim().to_string()),
+    updated: Some(Some(naive_now())),
+  };
+  let removal_reason = RemovalReason::update(&mut context.pool(), data.id, &form).await?;
+
+  Ok(Json(RemovalReasonResponse { removal_reason }))
+}
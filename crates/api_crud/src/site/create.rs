@@ -104,6 +104,7 @@ pub async fn create_site(
     hide_modlog_mod_names: data.hide_modlog_mod_names,
     updated: Some(Some(naive_now())),
     slur_filter_regex: diesel_string_update(data.slur_filter_regex.as_deref()),
+    slur_filter_action: data.slur_filter_action.map(Some),
     actor_name_max_length: data.actor_name_max_length,
     federation_enabled: data.federation_enabled,
     captcha_enabled: data.captcha_enabled,
@@ -127,6 +128,12 @@ pub async fn create_site(
     comment_per_second: data.rate_limit_comment_per_second,
     search: data.rate_limit_search,
     search_per_second: data.rate_limit_search_per_second,
+    vote: data.rate_limit_vote,
+    vote_per_second: data.rate_limit_vote_per_second,
+    report: data.rate_limit_report,
+    report_per_second: data.rate_limit_report_per_second,
+    private_message: data.rate_limit_private_message,
+    private_message_per_second: data.rate_limit_private_message_per_second,
     ..Default::default()
   };
 
@@ -583,6 +590,12 @@ mod tests {
       rate_limit_comment_per_second: None,
       rate_limit_search: None,
       rate_limit_search_per_second: None,
+      rate_limit_vote: None,
+      rate_limit_vote_per_second: None,
+      rate_limit_report: None,
+      rate_limit_report_per_second: None,
+      rate_limit_private_message: None,
+      rate_limit_private_message_per_second: None,
       federation_enabled: site_is_federated,
       federation_debug: None,
       captcha_enabled: None,
@@ -593,6 +606,7 @@ mod tests {
       registration_mode: site_registration_mode,
       content_warning: None,
       default_post_listing_mode: None,
+      slur_filter_action: None,
     }
   }
 }
@@ -9,6 +9,7 @@ use lemmy_db_schema::source::{
   instance_block::InstanceBlock,
   language::Language,
   local_site_url_blocklist::LocalSiteUrlBlocklist,
+  local_site_url_cleaning_rule::LocalSiteUrlCleaningRule,
   person_block::PersonBlock,
   tagline::Tagline,
 };
@@ -45,6 +46,7 @@ pub async fn get_site(
       let custom_emojis =
         CustomEmojiView::get_all(&mut context.pool(), site_view.local_site.id).await?;
       let blocked_urls = LocalSiteUrlBlocklist::get_all(&mut context.pool()).await?;
+      let url_cleaning_rules = LocalSiteUrlCleaningRule::get_all(&mut context.pool()).await?;
       Ok(GetSiteResponse {
         site_view,
         admins,
@@ -55,6 +57,7 @@ pub async fn get_site(
         taglines,
         custom_emojis,
         blocked_urls,
+        url_cleaning_rules,
       })
     })
     .await
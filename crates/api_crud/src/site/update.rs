@@ -4,7 +4,7 @@ use actix_web::web::Json;
 use lemmy_api_common::{
   context::LemmyContext,
   request::replace_image,
-  site::{EditSite, SiteResponse},
+  site::{EditSite, SiteResponse, UrlCleaningRule},
   utils::{
     get_url_blocklist,
     is_admin,
@@ -22,6 +22,7 @@ use lemmy_db_schema::{
     local_site::{LocalSite, LocalSiteUpdateForm},
     local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitUpdateForm},
     local_site_url_blocklist::LocalSiteUrlBlocklist,
+    local_site_url_cleaning_rule::{LocalSiteUrlCleaningRule, LocalSiteUrlCleaningRuleForm},
     local_user::LocalUser,
     site::{Site, SiteUpdateForm},
     tagline::Tagline,
@@ -42,6 +43,7 @@ use lemmy_utils::{
       is_valid_body_field,
       site_description_length_check,
       site_name_length_check,
+      UrlCleaningRules,
     },
   },
 };
@@ -113,6 +115,7 @@ pub async fn update_site(
     hide_modlog_mod_names: data.hide_modlog_mod_names,
     updated: Some(Some(naive_now())),
     slur_filter_regex: diesel_string_update(data.slur_filter_regex.as_deref()),
+    slur_filter_action: data.slur_filter_action.map(Some),
     actor_name_max_length: data.actor_name_max_length,
     federation_enabled: data.federation_enabled,
     captcha_enabled: data.captcha_enabled,
@@ -139,6 +142,12 @@ pub async fn update_site(
     comment_per_second: data.rate_limit_comment_per_second,
     search: data.rate_limit_search,
     search_per_second: data.rate_limit_search_per_second,
+    vote: data.rate_limit_vote,
+    vote_per_second: data.rate_limit_vote_per_second,
+    report: data.rate_limit_report,
+    report_per_second: data.rate_limit_report_per_second,
+    private_message: data.rate_limit_private_message,
+    private_message_per_second: data.rate_limit_private_message_per_second,
     ..Default::default()
   };
 
@@ -157,6 +166,11 @@ pub async fn update_site(
     LocalSiteUrlBlocklist::replace(&mut context.pool(), parsed_urls).await?;
   }
 
+  if let Some(url_cleaning_rules) = &data.url_cleaning_rules {
+    let forms = url_cleaning_rule_forms(url_cleaning_rules);
+    LocalSiteUrlCleaningRule::replace(&mut context.pool(), forms).await?;
+  }
+
   // TODO can't think of a better way to do this.
   // If the server suddenly requires email verification, or required applications, no old users
   // will be able to log in. It really only wants this to be a requirement for NEW signups.
@@ -235,6 +249,16 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
     is_valid_body_field(body, false)?;
   }
 
+  // Check that the url cleaning rules compile
+  if let Some(url_cleaning_rules) = &edit_site.url_cleaning_rules {
+    let forms = url_cleaning_rule_forms(url_cleaning_rules);
+    UrlCleaningRules::new(
+      forms
+        .iter()
+        .map(|f| (f.url_pattern.as_str(), f.params.as_slice())),
+    )?;
+  }
+
   application_question_check(
     &local_site.application_question,
     &edit_site.application_question,
@@ -244,6 +268,17 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
   )
 }
 
+fn url_cleaning_rule_forms(rules: &[UrlCleaningRule]) -> Vec<LocalSiteUrlCleaningRuleForm> {
+  rules
+    .iter()
+    .map(|r| LocalSiteUrlCleaningRuleForm {
+      url_pattern: r.url_pattern.clone(),
+      params: r.params.iter().cloned().map(Some).collect(),
+      updated: None,
+    })
+    .collect()
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::indexing_slicing)]
@@ -595,6 +630,12 @@ mod tests {
       rate_limit_comment_per_second: None,
       rate_limit_search: None,
       rate_limit_search_per_second: None,
+      rate_limit_vote: None,
+      rate_limit_vote_per_second: None,
+      rate_limit_report: None,
+      rate_limit_report_per_second: None,
+      rate_limit_private_message: None,
+      rate_limit_private_message_per_second: None,
       federation_enabled: site_is_federated,
       federation_debug: None,
       captcha_enabled: None,
@@ -602,11 +643,13 @@ mod tests {
       allowed_instances: None,
       blocked_instances: None,
       blocked_urls: None,
+      url_cleaning_rules: None,
       taglines: None,
       registration_mode: site_registration_mode,
       reports_email_admins: None,
       content_warning: None,
       default_post_listing_mode: None,
+      slur_filter_action: None,
     }
   }
 }
@@ -0,0 +1,36 @@
+use crate::slur_filter::check_slur_filter_regex;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  slur_filter::{CreateSlurFilterList, SlurFilterListResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::slur_filter_list::{SlurFilterList, SlurFilterListInsertForm},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn create_slur_filter_list(
+  data: Json<CreateSlurFilterList>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SlurFilterListResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+  check_slur_filter_regex(&data.regex)?;
+
+  let form = SlurFilterListInsertForm::builder()
+    .name(data.name.trim().to_string())
+    .regex(data.regex.clone())
+    .action(data.action)
+    .build();
+  let slur_filter_list = SlurFilterList::create(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::SlurFilterListAlreadyExists)?;
+
+  Ok(Json(SlurFilterListResponse { slur_filter_list }))
+}
@@ -0,0 +1,28 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  slur_filter::DeleteSlurFilterList,
+  utils::is_admin,
+  SuccessResponse,
+};
+use lemmy_db_schema::{source::slur_filter_list::SlurFilterList, traits::Crud};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_slur_filter_list(
+  data: Json<DeleteSlurFilterList>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+  SlurFilterList::read(&mut context.pool(), data.id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindSlurFilterList)?;
+
+  SlurFilterList::delete(&mut context.pool(), data.id).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -0,0 +1,23 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  slur_filter::ListSlurFilterListsResponse,
+  utils::is_admin,
+};
+use lemmy_db_schema::source::slur_filter_list::SlurFilterList;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_slur_filter_lists(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListSlurFilterListsResponse>> {
+  // Word lists are only visible to admins, so that they can't be worked around
+  is_admin(&local_user_view)?;
+
+  let slur_filter_lists = SlurFilterList::get_all(&mut context.pool()).await?;
+
+  Ok(Json(ListSlurFilterListsResponse { slur_filter_lists }))
+}
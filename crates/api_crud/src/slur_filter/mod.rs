@@ -0,0 +1,15 @@
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  utils::validation::build_and_check_regex,
+};
+
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod update;
+
+/// A list without words would never match, so the regex is required.
+fn check_slur_filter_regex(regex: &str) -> LemmyResult<()> {
+  build_and_check_regex(&Some(regex))?.ok_or(LemmyErrorType::InvalidRegex)?;
+  Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::slur_filter::check_slur_filter_regex;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  slur_filter::{EditSlurFilterList, SlurFilterListResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::slur_filter_list::{SlurFilterList, SlurFilterListUpdateForm},
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn update_slur_filter_list(
+  data: Json<EditSlurFilterList>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SlurFilterListResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+  if let Some(regex) = &data.regex {
+    check_slur_filter_regex(regex)?;
+  }
+  SlurFilterList::read(&mut context.pool(), data.id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindSlurFilterList)?;
+
+  let form = SlurFilterListUpdateForm {
+    name: data.name.as_ref().map(|n| n.trim().to_string()),
+    regex: data.regex.clone(),
+    action: data.action,
+    updated: Some(Some(naive_now())),
+  };
+  let slur_filter_list = SlurFilterList::update(&mut context.pool(), data.id, &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::SlurFilterListAlreadyExists)?;
+
+  Ok(Json(SlurFilterListResponse { slur_filter_list }))
+}
@@ -0,0 +1,101 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity, verify_is_public, GetActorType},
+  insert_received_activity,
+  objects::{instance::ApubSite, person::ApubPerson},
+  protocol::activities::actor::update::{PersonOrInstance, UpdateActor},
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::{activity::UpdateType, public},
+  protocol::verification::{verify_domains_match, verify_urls_match},
+  traits::{ActivityHandler, Actor, Object},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::activity::ActivitySendTargets;
+use lemmy_utils::error::{LemmyError, LemmyResult};
+use url::Url;
+
+impl UpdateActor {
+  /// Sends the profile of a local user to all instances.
+  pub(crate) async fn send_person(
+    person: &ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<()> {
+    let object = PersonOrInstance::Person(Box::new(person.clone().into_json(context).await?));
+    UpdateActor::send(person, object, context).await
+  }
+
+  /// Sends the local instance actor to all instances.
+  pub(crate) async fn send_site(site: &ApubSite, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    let object = PersonOrInstance::Instance(Box::new(site.clone().into_json(context).await?));
+    UpdateActor::send(site, object, context).await
+  }
+
+  async fn send<ActorT>(
+    actor: &ActorT,
+    object: PersonOrInstance,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<()>
+  where
+    ActorT: Actor + GetActorType,
+  {
+    let update = UpdateActor {
+      actor: actor.id().into(),
+      to: vec![public()],
+      object,
+      kind: UpdateType::Update,
+      id: generate_activity_id(
+        UpdateType::Update,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+    };
+    let inboxes = ActivitySendTargets::to_all_instances();
+    send_lemmy_activity(context, update, actor, inboxes, false).await
+  }
+
+  fn object_id(&self) -> &Url {
+    match &self.object {
+      PersonOrInstance::Person(p) => p.id.inner(),
+      PersonOrInstance::Instance(i) => i.id.inner(),
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for UpdateActor {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    verify_is_public(&self.to, &[])?;
+    verify_urls_match(self.actor.inner(), self.object_id())?;
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    match &self.object {
+      PersonOrInstance::Person(p) => ApubPerson::verify(p, self.actor.inner(), context).await,
+      PersonOrInstance::Instance(i) => ApubSite::verify(i, self.actor.inner(), context).await,
+    }
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    insert_received_activity(&self.id, context).await?;
+    match self.object {
+      PersonOrInstance::Person(p) => {
+        ApubPerson::from_json(*p, context).await?;
+      }
+      PersonOrInstance::Instance(i) => {
+        ApubSite::from_json(*i, context).await?;
+      }
+    }
+    Ok(())
+  }
+}
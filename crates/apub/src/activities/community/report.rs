@@ -15,6 +15,7 @@ use activitypub_federation::{
   traits::{ActivityHandler, Actor},
 };
 use lemmy_api_common::{
+  automod_engine::{run_automod_for_comment, run_automod_for_post, AutomodTrigger},
   context::LemmyContext,
   utils::{check_comment_deleted_or_removed, check_post_deleted_or_removed},
 };
@@ -123,6 +124,9 @@ impl ActivityHandler for Report {
           original_post_body: post.body.clone(),
         };
         PostReport::report(&mut context.pool(), &report_form).await?;
+        if let Err(e) = run_automod_for_post(&post, AutomodTrigger::Report, context).await {
+          tracing::warn!("Failed to run automod for post {}: {e}", post.ap_id);
+        }
       }
       PostOrComment::Comment(comment) => {
         check_comment_deleted_or_removed(&comment)?;
@@ -134,6 +138,9 @@ impl ActivityHandler for Report {
           reason,
         };
         CommentReport::report(&mut context.pool(), &report_form).await?;
+        if let Err(e) = run_automod_for_comment(&comment, AutomodTrigger::Report, context).await {
+          tracing::warn!("Failed to run automod for comment {}: {e}", comment.ap_id);
+        }
       }
     };
     Ok(())
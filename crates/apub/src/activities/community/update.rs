@@ -8,7 +8,12 @@ use crate::{
   },
   activity_lists::AnnouncableActivities,
   insert_received_activity,
-  objects::{community::ApubCommunity, person::ApubPerson, read_from_string_or_source_opt},
+  objects::{
+    community::ApubCommunity,
+    person::ApubPerson,
+    read_from_string_or_source_opt,
+    remember_previous_key,
+  },
   protocol::{activities::community::update::UpdateCommunity, InCommunity},
 };
 use activitypub_federation::{
@@ -89,6 +94,13 @@ impl ActivityHandler for UpdateCommunity {
   async fn receive(self, context: &Data<Self::DataType>) -> LemmyResult<()> {
     insert_received_activity(&self.id, context).await?;
     let community = self.community(context).await?;
+    remember_previous_key(
+      &community.actor_id,
+      Some(community.public_key.clone()),
+      &self.object.public_key.public_key_pem,
+      context,
+    )
+    .await?;
 
     let community_update_form = CommunityUpdateForm {
       title: Some(self.object.name.unwrap_or(self.object.preferred_username)),
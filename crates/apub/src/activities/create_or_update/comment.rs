@@ -47,6 +47,31 @@ use lemmy_utils::{
 use url::Url;
 
 impl CreateOrUpdateNote {
+  pub(crate) async fn new(
+    comment: ApubComment,
+    actor: &ApubPerson,
+    community: &ApubCommunity,
+    kind: CreateOrUpdateType,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<CreateOrUpdateNote> {
+    let id = generate_activity_id(
+      kind.clone(),
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let note = comment.into_json(context).await?;
+
+    Ok(CreateOrUpdateNote {
+      actor: actor.id().into(),
+      to: vec![public()],
+      cc: note.cc.clone(),
+      tag: note.tag.clone(),
+      object: note,
+      kind,
+      id,
+      audience: Some(community.id().into()),
+    })
+  }
+
   #[tracing::instrument(skip(comment, person_id, kind, context))]
   pub(crate) async fn send(
     comment: Comment,
@@ -69,22 +94,8 @@ impl CreateOrUpdateNote {
       .ok_or(LemmyErrorType::CouldntFindCommunity)?
       .into();
 
-    let id = generate_activity_id(
-      kind.clone(),
-      &context.settings().get_protocol_and_hostname(),
-    )?;
-    let note = ApubComment(comment).into_json(&context).await?;
-
-    let create_or_update = CreateOrUpdateNote {
-      actor: person.id().into(),
-      to: vec![public()],
-      cc: note.cc.clone(),
-      tag: note.tag.clone(),
-      object: note,
-      kind,
-      id: id.clone(),
-      audience: Some(community.id().into()),
-    };
+    let create_or_update =
+      CreateOrUpdateNote::new(ApubComment(comment), &person, &community, kind, &context).await?;
 
     let tagged_users: Vec<ObjectId<ApubPerson>> = create_or_update
       .tag
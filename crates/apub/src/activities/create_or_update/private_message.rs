@@ -9,13 +9,19 @@ use crate::{
 };
 use activitypub_federation::{
   config::Data,
+  error::Error as ActivityPubError,
   protocol::verification::{verify_domains_match, verify_urls_match},
   traits::{ActivityHandler, Actor, Object},
 };
-use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::source::activity::ActivitySendTargets;
+use lemmy_api_common::{context::LemmyContext, utils::check_private_message_privacy};
+use lemmy_db_schema::source::{
+  activity::ActivitySendTargets,
+  federation_limitlist::FederationLimitList,
+  person::PersonFollower,
+  private_message::PrivateMessage,
+};
 use lemmy_db_views::structs::PrivateMessageView;
-use lemmy_utils::error::{LemmyError, LemmyResult};
+use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
 use url::Url;
 
 pub(crate) async fn send_create_or_update_pm(
@@ -24,7 +30,8 @@ pub(crate) async fn send_create_or_update_pm(
   context: Data<LemmyContext>,
 ) -> LemmyResult<()> {
   let actor: ApubPerson = pm_view.creator.into();
-  let recipient: ApubPerson = pm_view.recipient.into();
+  let pm = ApubPrivateMessage(pm_view.private_message);
+  let recipients = pm.recipients(&context).await?;
 
   let id = generate_activity_id(
     kind.clone(),
@@ -33,14 +40,13 @@ pub(crate) async fn send_create_or_update_pm(
   let create_or_update = CreateOrUpdateChatMessage {
     id: id.clone(),
     actor: actor.id().into(),
-    to: [recipient.id().into()],
-    object: ApubPrivateMessage(pm_view.private_message.clone())
-      .into_json(&context)
-      .await?,
+    to: recipients.iter().map(|r| r.id().into()).collect(),
+    object: pm.into_json(&context).await?,
     kind,
   };
-  let inbox = ActivitySendTargets::to_inbox(recipient.shared_inbox_or_inbox());
-  send_lemmy_activity(&context, create_or_update, &actor, inbox, true).await
+  let mut inboxes = ActivitySendTargets::empty();
+  inboxes.add_inboxes(recipients.iter().map(Actor::shared_inbox_or_inbox));
+  send_lemmy_activity(&context, create_or_update, &actor, inboxes, true).await
 }
 
 #[async_trait::async_trait]
@@ -60,7 +66,11 @@ impl ActivityHandler for CreateOrUpdateChatMessage {
   async fn verify(&self, context: &Data<Self::DataType>) -> LemmyResult<()> {
     verify_person(&self.actor, context).await?;
     verify_domains_match(self.actor.inner(), self.object.id.inner())?;
-    verify_domains_match(self.to[0].inner(), self.object.to[0].inner())?;
+    if self.to != self.object.to {
+      Err(ActivityPubError::UrlVerificationError(
+        "Activity and object have different recipients",
+      ))?
+    }
     verify_urls_match(self.actor.inner(), self.object.attributed_to.inner())?;
     ApubPrivateMessage::verify(&self.object, self.actor.inner(), context).await?;
     Ok(())
@@ -69,7 +79,133 @@ impl ActivityHandler for CreateOrUpdateChatMessage {
   #[tracing::instrument(skip_all)]
   async fn receive(self, context: &Data<Self::DataType>) -> LemmyResult<()> {
     insert_received_activity(&self.id, context).await?;
+    // Users from instances with limited federation can only message users who follow them
+    let creator = self.actor.dereference(context).await?;
+    if FederationLimitList::is_limited(&mut context.pool(), creator.instance_id).await? {
+      for recipient in &self.to {
+        let recipient = recipient.dereference(context).await?;
+        if recipient.local
+          && !PersonFollower::is_following(&mut context.pool(), recipient.id, creator.id).await?
+        {
+          Err(LemmyErrorType::InstanceIsLimited)?
+        }
+      }
+    }
+    // Edits of existing messages are allowed after the recipient changed their privacy settings.
+    // Whether the message is new is checked in the database, because an Update activity with an
+    // unknown object id creates a new message as well.
+    let existing =
+      PrivateMessage::read_from_apub_id(&mut context.pool(), self.object.id.inner().clone())
+        .await?;
+    if existing.is_none() {
+      for recipient in &self.to {
+        let recipient = recipient.dereference(context).await?;
+        check_private_message_privacy(&creator, recipient.id, &mut context.pool()).await?;
+      }
+    }
     ApubPrivateMessage::from_json(self.object, context).await?;
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::person::tests::parse_lemmy_person,
+    protocol::{objects::chat_message::ChatMessage, tests::file_to_json_object},
+  };
+  use lemmy_db_schema::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
+      person::{Person, PersonInsertForm},
+      site::Site,
+    },
+    traits::Crud,
+    PrivateMessagePrivacy,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_receive_private_message_privacy() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (sender, site) = parse_lemmy_person(&context).await?;
+    let instance =
+      Instance::read_or_create(&mut context.pool(), "my_domain.tld".to_string()).await?;
+    let form = PersonInsertForm::test_form(instance.id, "pm_inbox");
+    let recipient = Person::create(&mut context.pool(), &form).await?;
+    let form = LocalUserInsertForm {
+      private_message_privacy: Some(PrivateMessagePrivacy::Nobody),
+      ..LocalUserInsertForm::test_form(recipient.id)
+    };
+    let local_user = LocalUser::create(&mut context.pool(), &form, vec![]).await?;
+
+    let mut object: ChatMessage = file_to_json_object("assets/lemmy/objects/chat_message.json")?;
+    object.to = vec![recipient.actor_id.clone().into()];
+    let pm_id = object.id.clone();
+    let activity = |id: &str, kind: CreateOrUpdateType| -> LemmyResult<CreateOrUpdateChatMessage> {
+      Ok(CreateOrUpdateChatMessage {
+        id: Url::parse(&format!(
+          "https://enterprise.lemmy.ml/activities/create/{id}"
+        ))?,
+        actor: sender.id().into(),
+        to: object.to.clone(),
+        object: object.clone(),
+        kind,
+      })
+    };
+
+    // The recipient doesn't accept any messages
+    let err = activity("1", CreateOrUpdateType::Create)?
+      .receive(&context)
+      .await
+      .err();
+    assert_eq!(
+      Some(LemmyErrorType::PrivateMessagesNotAllowed),
+      err.map(|e| e.error_type)
+    );
+    let pm = PrivateMessage::read_from_apub_id(&mut context.pool(), pm_id.inner().clone()).await?;
+    assert!(pm.is_none());
+
+    // An update of a message which doesn't exist yet is a new message as well
+    let err = activity("2", CreateOrUpdateType::Update)?
+      .receive(&context)
+      .await
+      .err();
+    assert_eq!(
+      Some(LemmyErrorType::PrivateMessagesNotAllowed),
+      err.map(|e| e.error_type)
+    );
+    let pm = PrivateMessage::read_from_apub_id(&mut context.pool(), pm_id.inner().clone()).await?;
+    assert!(pm.is_none());
+
+    let form = LocalUserUpdateForm {
+      private_message_privacy: Some(PrivateMessagePrivacy::Everyone),
+      ..Default::default()
+    };
+    LocalUser::update(&mut context.pool(), local_user.id, &form).await?;
+    activity("3", CreateOrUpdateType::Create)?
+      .receive(&context)
+      .await?;
+    let pm = PrivateMessage::read_from_apub_id(&mut context.pool(), pm_id.inner().clone()).await?;
+    assert_eq!(Some(recipient.id), pm.map(|pm| pm.recipient_id));
+
+    // Edits of the existing message are still allowed after messages are turned off
+    let form = LocalUserUpdateForm {
+      private_message_privacy: Some(PrivateMessagePrivacy::Nobody),
+      ..Default::default()
+    };
+    LocalUser::update(&mut context.pool(), local_user.id, &form).await?;
+    activity("4", CreateOrUpdateType::Update)?
+      .receive(&context)
+      .await?;
+
+    Person::delete(&mut context.pool(), sender.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Instance::delete(&mut context.pool(), instance.id).await?;
+    Ok(())
+  }
+}
@@ -84,20 +84,22 @@ pub(crate) async fn send_apub_delete_private_message(
   deleted: bool,
   context: Data<LemmyContext>,
 ) -> LemmyResult<()> {
-  let recipient_id = pm.recipient_id;
-  let recipient: ApubPerson = Person::read(&mut context.pool(), recipient_id)
-    .await?
+  let pm = ApubPrivateMessage(pm);
+  let recipients = pm.recipients(&context).await?;
+  let recipient = recipients
+    .first()
     .ok_or(LemmyErrorType::CouldntFindPerson)?
-    .into();
+    .id();
 
-  let deletable = DeletableObjects::PrivateMessage(pm.into());
-  let inbox = ActivitySendTargets::to_inbox(recipient.shared_inbox_or_inbox());
+  let deletable = DeletableObjects::PrivateMessage(pm);
+  let mut inboxes = ActivitySendTargets::empty();
+  inboxes.add_inboxes(recipients.iter().map(Actor::shared_inbox_or_inbox));
   if deleted {
-    let delete: Delete = Delete::new(actor, deletable, recipient.id(), None, None, &context)?;
-    send_lemmy_activity(&context, delete, actor, inbox, true).await?;
+    let delete: Delete = Delete::new(actor, deletable, recipient, None, None, &context)?;
+    send_lemmy_activity(&context, delete, actor, inboxes, true).await?;
   } else {
-    let undo = UndoDelete::new(actor, deletable, recipient.id(), None, None, &context)?;
-    send_lemmy_activity(&context, undo, actor, inbox, true).await?;
+    let undo = UndoDelete::new(actor, deletable, recipient, None, None, &context)?;
+    send_lemmy_activity(&context, undo, actor, inboxes, true).await?;
   };
   Ok(())
 }
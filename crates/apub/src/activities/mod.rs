@@ -48,11 +48,14 @@ use tracing::info;
 use url::{ParseError, Url};
 use uuid::Uuid;
 
+pub mod actor;
 pub mod block;
 pub mod community;
 pub mod create_or_update;
 pub mod deletion;
 pub mod following;
+pub mod moving;
+pub mod relay;
 pub mod voting;
 
 /// Checks that the specified Url actually identifies a Person (by fetching it), and that the person
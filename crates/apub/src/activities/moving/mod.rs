@@ -0,0 +1,103 @@
+use crate::{activities::following::send_follow_community, objects::community::ApubCommunity};
+use activitypub_federation::config::Data;
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{
+    community::{Community, CommunityFollower, CommunityFollowerForm, CommunityUpdateForm},
+    person::{Person, PersonUpdateForm},
+  },
+  traits::{Crud, Followable},
+};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use std::ops::Deref;
+
+pub mod move_actor;
+
+/// Checks that the new actor lists the old one in `alsoKnownAs`, which shows that both belong to
+/// the same user, or that the community was imported from the old one.
+pub(crate) fn verify_move_alias(
+  old_actor_id: &DbUrl,
+  new_actor_id: &DbUrl,
+  new_also_known_as: &[Option<DbUrl>],
+) -> LemmyResult<()> {
+  if old_actor_id == new_actor_id {
+    Err(LemmyErrorType::InvalidMoveTarget)?
+  }
+  if !new_also_known_as.contains(&Some(old_actor_id.clone())) {
+    Err(LemmyErrorType::MoveTargetMissingAlias)?
+  }
+  Ok(())
+}
+
+/// Marks the old account as moved, so that its profile redirects to the new account. Followers
+/// of the old account and its subscriptions to local communities are moved over.
+///
+/// If both accounts are local, subscriptions to remote communities are moved by sending a new
+/// follow for each of them, which stays pending until the community accepts it. Otherwise this is
+/// up to the instance of the new account.
+pub(crate) async fn move_person(
+  old: &Person,
+  new: &Person,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let form = PersonUpdateForm {
+    moved_to: Some(Some(new.actor_id.clone())),
+    ..Default::default()
+  };
+  Person::update(&mut context.pool(), old.id, &form).await?;
+  Person::transfer_follows(&mut context.pool(), old.id, new.id).await?;
+
+  if old.local && new.local {
+    let communities =
+      CommunityFollower::list_remote_communities_followed_by(&mut context.pool(), old.id).await?;
+    for community in communities {
+      let follow_form = CommunityFollowerForm {
+        community_id: community.id,
+        person_id: new.id,
+        pending: true,
+      };
+      CommunityFollower::follow(&mut context.pool(), &follow_form).await?;
+      send_follow_community(community.clone(), new.clone(), true, context).await?;
+      let unfollow_form = CommunityFollowerForm {
+        person_id: old.id,
+        ..follow_form
+      };
+      CommunityFollower::unfollow(&mut context.pool(), &unfollow_form).await?;
+      send_follow_community(community, old.clone(), false, context).await?;
+    }
+  }
+  Ok(())
+}
+
+/// Marks the old community as moved, and subscribes its local followers to the new community
+/// instead.
+pub(crate) async fn move_community(
+  old: &Community,
+  new: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let form = CommunityUpdateForm {
+    moved_to: Some(Some(new.actor_id.clone())),
+    ..Default::default()
+  };
+  Community::update(&mut context.pool(), old.id, &form).await?;
+
+  for person in CommunityFollower::list_local_followers(&mut context.pool(), old.id).await? {
+    let follow_form = CommunityFollowerForm {
+      community_id: new.id,
+      person_id: person.id,
+      pending: !new.local,
+    };
+    CommunityFollower::follow(&mut context.pool(), &follow_form).await?;
+    if !new.local {
+      send_follow_community(new.deref().clone(), person.clone(), true, context).await?;
+    }
+    let unfollow_form = CommunityFollowerForm {
+      community_id: old.id,
+      ..follow_form
+    };
+    CommunityFollower::unfollow(&mut context.pool(), &unfollow_form).await?;
+  }
+  Ok(())
+}
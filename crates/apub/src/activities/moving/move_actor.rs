@@ -0,0 +1,107 @@
+use crate::{
+  activities::{
+    generate_activity_id,
+    moving::{move_community, move_person, verify_move_alias},
+    send_lemmy_activity,
+    GetActorType,
+  },
+  fetcher::user_or_community::UserOrCommunity,
+  insert_received_activity,
+  protocol::activities::moving::move_actor::Move,
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::{activity::MoveType, public},
+  protocol::verification::{verify_domains_match, verify_urls_match},
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::activity::ActivitySendTargets;
+use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
+use url::Url;
+
+impl Move {
+  /// Sends a move of a local user or community to all instances.
+  #[tracing::instrument(skip_all)]
+  pub(crate) async fn send<ActorT>(
+    old: &ActorT,
+    new: &ActorT,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<()>
+  where
+    ActorT: Actor + GetActorType,
+  {
+    let move_ = Move {
+      actor: old.id().into(),
+      to: vec![public()],
+      object: old.id().into(),
+      target: new.id().into(),
+      kind: MoveType::Move,
+      id: generate_activity_id(
+        MoveType::Move,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+    };
+    let inboxes = ActivitySendTargets::to_all_instances();
+    send_lemmy_activity(context, move_, old, inboxes, false).await
+  }
+
+  /// Reads the moved actor and its target, which must be of the same type.
+  async fn actors(
+    &self,
+    refetch_target: bool,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<(UserOrCommunity, UserOrCommunity)> {
+    let old = self.actor.dereference(context).await?;
+    let new = if refetch_target {
+      self.target.dereference_forced(context).await?
+    } else {
+      self.target.dereference(context).await?
+    };
+    Ok((old, new))
+  }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for Move {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    verify_urls_match(self.actor.inner(), self.object.inner())?;
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    // Refetch the target so that a recently added alias is known
+    match self.actors(true, context).await? {
+      (UserOrCommunity::User(old), UserOrCommunity::User(new)) => {
+        verify_move_alias(&old.actor_id, &new.actor_id, &new.also_known_as)
+      }
+      (UserOrCommunity::Community(old), UserOrCommunity::Community(new)) => {
+        verify_move_alias(&old.actor_id, &new.actor_id, &new.also_known_as)
+      }
+      _ => Err(LemmyErrorType::InvalidMoveTarget.into()),
+    }
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    insert_received_activity(&self.id, context).await?;
+    match self.actors(false, context).await? {
+      (UserOrCommunity::User(old), UserOrCommunity::User(new)) => {
+        move_person(&old, &new, context).await
+      }
+      (UserOrCommunity::Community(old), UserOrCommunity::Community(new)) => {
+        move_community(&old, &new, context).await
+      }
+      _ => Err(LemmyErrorType::InvalidMoveTarget.into()),
+    }
+  }
+}
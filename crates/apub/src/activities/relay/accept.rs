@@ -0,0 +1,55 @@
+use crate::{
+  insert_received_activity,
+  protocol::{activities::relay::accept::AcceptRelayFollow, IdOrNestedObject},
+};
+use activitypub_federation::{
+  config::Data,
+  protocol::verification::verify_urls_match,
+  traits::ActivityHandler,
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  source::relay::{Relay, RelayUpdateForm},
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
+use url::Url;
+
+/// Handle accepted relay follows
+#[async_trait::async_trait]
+impl ActivityHandler for AcceptRelayFollow {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    // Some relays only include the id of our follow
+    if let IdOrNestedObject::NestedObject(follow) = &self.object {
+      verify_urls_match(self.actor.inner(), follow.object.inner())?;
+      if !follow.actor.is_local(context) {
+        Err(LemmyErrorType::CannotReceiveRelayFollow)?
+      }
+    }
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    insert_received_activity(&self.id, context).await?;
+    let relay = self.actor.dereference(context).await?;
+    let form = RelayUpdateForm {
+      follow_accepted: Some(true),
+      ..Default::default()
+    };
+    Relay::update(&mut context.pool(), relay.id, &form).await?;
+    Ok(())
+  }
+}
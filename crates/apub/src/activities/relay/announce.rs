@@ -0,0 +1,79 @@
+use crate::{
+  fetcher::post_or_comment::PostOrComment,
+  insert_received_activity,
+  objects::community::ApubCommunity,
+  protocol::activities::relay::announce::RelayAnnounce,
+};
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId, traits::ActivityHandler};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::relay::RelayCommunityFilter;
+use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
+use url::Url;
+
+/// Limits how deeply nested activities are unwrapped, eg `Announce(Announce(Create(Page)))`.
+const MAX_RELAYED_DEPTH: usize = 4;
+
+impl RelayAnnounce {
+  /// Unwraps the relayed activities to find the post or comment and its community.
+  async fn relayed_content(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<Option<(Url, ObjectId<ApubCommunity>)>> {
+    let mut current = self.object.clone();
+    let mut audience = None;
+    for _ in 0..MAX_RELAYED_DEPTH {
+      let object = current.object(context).await?;
+      audience = audience.or(object.audience);
+      match object.object {
+        Some(inner) => current = *inner,
+        None => return Ok(audience.map(|a| (object.id, a))),
+      }
+    }
+    Ok(None)
+  }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for RelayAnnounce {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    let relay = self.actor.dereference(context).await?;
+    if !relay.follow_accepted {
+      Err(LemmyErrorType::RelayFollowNotAccepted)?
+    }
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    insert_received_activity(&self.id, context).await?;
+    // Content without a community (eg microblog posts) is ignored
+    let Some((object_id, community)) = self.relayed_content(context).await? else {
+      return Ok(());
+    };
+    let community = community.dereference(context).await?;
+    if community.local {
+      return Ok(());
+    }
+    let relay = self.actor.dereference(context).await?;
+    let filters = RelayCommunityFilter::list_for_relay(&mut context.pool(), relay.id).await?;
+    if !RelayCommunityFilter::allows(&filters, community.id) {
+      return Ok(());
+    }
+    ObjectId::<PostOrComment>::from(object_id)
+      .dereference(context)
+      .await?;
+    Ok(())
+  }
+}
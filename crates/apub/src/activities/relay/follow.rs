@@ -0,0 +1,66 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity},
+  objects::{instance::ApubSite, relay::ApubRelay},
+  protocol::activities::relay::follow::RelayFollow,
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::activity::FollowType,
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::activity::ActivitySendTargets;
+use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
+use url::Url;
+
+impl RelayFollow {
+  pub(in crate::activities::relay) fn new(
+    site: &ApubSite,
+    relay: &ApubRelay,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<RelayFollow> {
+    Ok(RelayFollow {
+      actor: site.id().into(),
+      object: relay.id().into(),
+      kind: FollowType::Follow,
+      id: generate_activity_id(
+        FollowType::Follow,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+    })
+  }
+
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    site: &ApubSite,
+    relay: &ApubRelay,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<()> {
+    let follow = RelayFollow::new(site, relay, context)?;
+    let inbox = ActivitySendTargets::to_inbox(relay.inbox());
+    send_lemmy_activity(context, follow, site, inbox, false).await
+  }
+}
+
+/// Only sent by Lemmy, relays are never followed back.
+#[async_trait::async_trait]
+impl ActivityHandler for RelayFollow {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    Ok(())
+  }
+
+  async fn receive(self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    Err(LemmyErrorType::CannotReceiveRelayFollow.into())
+  }
+}
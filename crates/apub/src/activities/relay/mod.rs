@@ -0,0 +1,28 @@
+use crate::{
+  objects::{instance::ApubSite, relay::ApubRelay},
+  protocol::activities::relay::{follow::RelayFollow, undo_follow::UndoRelayFollow},
+};
+use activitypub_federation::config::Data;
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::{relay::Relay, site::Site};
+use lemmy_utils::error::LemmyResult;
+
+pub mod accept;
+pub mod announce;
+pub mod follow;
+pub mod undo_follow;
+
+/// Subscribes or unsubscribes the local site actor to the relay.
+pub async fn send_follow_relay(
+  relay: Relay,
+  follow: bool,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let site: ApubSite = Site::read_local(&mut context.pool()).await?.into();
+  let relay: ApubRelay = relay.into();
+  if follow {
+    RelayFollow::send(&site, &relay, context).await
+  } else {
+    UndoRelayFollow::send(&site, &relay, context).await
+  }
+}
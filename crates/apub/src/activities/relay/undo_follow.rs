@@ -0,0 +1,58 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity},
+  objects::{instance::ApubSite, relay::ApubRelay},
+  protocol::activities::relay::{follow::RelayFollow, undo_follow::UndoRelayFollow},
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::activity::UndoType,
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::activity::ActivitySendTargets;
+use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
+use url::Url;
+
+impl UndoRelayFollow {
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    site: &ApubSite,
+    relay: &ApubRelay,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<()> {
+    let object = RelayFollow::new(site, relay, context)?;
+    let undo = UndoRelayFollow {
+      actor: site.id().into(),
+      object,
+      kind: UndoType::Undo,
+      id: generate_activity_id(
+        UndoType::Undo,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+    };
+    let inbox = ActivitySendTargets::to_inbox(relay.inbox());
+    send_lemmy_activity(context, undo, site, inbox, false).await
+  }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for UndoRelayFollow {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    Ok(())
+  }
+
+  async fn receive(self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    Err(LemmyErrorType::CannotReceiveRelayFollow.into())
+  }
+}
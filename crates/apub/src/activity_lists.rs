@@ -2,6 +2,7 @@ use crate::{
   objects::community::ApubCommunity,
   protocol::{
     activities::{
+      actor::update::UpdateActor,
       block::{block_user::BlockUser, undo_block_user::UndoBlockUser},
       community::{
         announce::{AnnounceActivity, RawAnnouncableActivities},
@@ -18,6 +19,8 @@ use crate::{
       },
       deletion::{delete::Delete, undo_delete::UndoDelete},
       following::{accept::AcceptFollow, follow::Follow, undo_follow::UndoFollow},
+      moving::move_actor::Move,
+      relay::{accept::AcceptRelayFollow, announce::RelayAnnounce},
       voting::{undo_vote::UndoVote, vote::Vote},
     },
     objects::page::Page,
@@ -45,10 +48,21 @@ pub enum SharedInboxActivities {
   CreateOrUpdatePrivateMessage(CreateOrUpdateChatMessage),
   Report(Report),
   AnnounceActivity(AnnounceActivity),
+  Move(Move),
+  UpdateActor(UpdateActor),
   /// This is a catch-all and needs to be last
   RawAnnouncableActivities(RawAnnouncableActivities),
 }
 
+/// List of activities which are accepted from relays followed by the local site.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+#[enum_delegate::implement(ActivityHandler)]
+pub enum RelayInboxActivities {
+  AcceptFollow(AcceptRelayFollow),
+  Announce(RelayAnnounce),
+}
+
 /// List of activities which the group inbox can handle.
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(untagged)]
@@ -127,7 +141,12 @@ impl InCommunity for AnnouncableActivities {
 mod tests {
 
   use crate::{
-    activity_lists::{GroupInboxActivities, PersonInboxActivities, SharedInboxActivities},
+    activity_lists::{
+      GroupInboxActivities,
+      PersonInboxActivities,
+      RelayInboxActivities,
+      SharedInboxActivities,
+    },
     protocol::tests::{test_json, test_parse_lemmy_item},
   };
   use lemmy_utils::error::LemmyResult;
@@ -156,11 +175,21 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_relay_inbox() -> LemmyResult<()> {
+    test_json::<RelayInboxActivities>("assets/litepub_relay/activities/accept_follow.json")?;
+    test_json::<RelayInboxActivities>("assets/litepub_relay/activities/announce.json")?;
+    Ok(())
+  }
+
   #[test]
   fn test_shared_inbox() -> LemmyResult<()> {
     test_parse_lemmy_item::<SharedInboxActivities>(
       "assets/lemmy/activities/deletion/delete_user.json",
     )?;
+    test_parse_lemmy_item::<SharedInboxActivities>(
+      "assets/lemmy/activities/moving/move_person.json",
+    )?;
     Ok(())
   }
 }
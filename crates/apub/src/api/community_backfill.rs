@@ -0,0 +1,200 @@
+use crate::{
+  collections::community_outbox::{receive_outbox_item, OutboxItem},
+  objects::community::ApubCommunity,
+  protocol::{
+    activities::community::announce::AnnounceActivity,
+    collections::group_outbox::{GroupOutbox, GroupOutboxPage},
+    objects::group::Group,
+  },
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::fetch_object_http,
+  protocol::verification::verify_domains_match,
+};
+use actix_web::web::{Json, Query};
+use chrono::{TimeDelta, Utc};
+use lemmy_api_common::{
+  community::{BackfillCommunity, CommunityBackfillResponse, GetCommunityBackfill},
+  context::LemmyContext,
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    community_backfill::{CommunityBackfill, CommunityBackfillUpdateForm},
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  spawn_try_task,
+};
+use std::time::Duration;
+use tracing::{info, warn};
+use url::Url;
+
+/// Pause between fetching two outbox pages, to avoid overloading the remote instance.
+const PAGE_DELAY: Duration = Duration::from_secs(2);
+
+/// How often fetching a page is retried. Outbox pages are rate limited by the remote instance, as
+/// they are expensive to build.
+const PAGE_RETRIES: u32 = 5;
+
+/// Pause before retrying to fetch a page.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Stop after this many pages, in case the remote instance returns an endless list of pages.
+const MAX_PAGES: i32 = 10_000;
+
+/// A backfill without progress for this long was interrupted, e.g. by a restart, and can be
+/// started again.
+const STALE_AFTER_MINUTES: i64 = 10;
+
+/// Starts importing the full history of a remote community in the background.
+#[tracing::instrument(skip(context))]
+pub async fn backfill_community(
+  data: Json<BackfillCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommunityBackfillResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let community: ApubCommunity = Community::read(&mut context.pool(), data.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?
+    .into();
+  if community.local {
+    Err(LemmyErrorType::CantBackfillLocalCommunity)?
+  }
+
+  let previous = CommunityBackfill::read(&mut context.pool(), community.id).await?;
+  if let Some(previous) = previous {
+    let last_progress = previous.updated.unwrap_or(previous.published);
+    let stale = Utc::now() - last_progress > TimeDelta::minutes(STALE_AFTER_MINUTES);
+    if previous.finished.is_none() && !stale {
+      Err(LemmyErrorType::CommunityBackfillAlreadyRunning)?
+    }
+  }
+
+  let backfill = CommunityBackfill::start(&mut context.pool(), community.id).await?;
+
+  let context = context.reset_request_count();
+  spawn_try_task(async move {
+    info!("Starting backfill of community {}", community.actor_id);
+    let res = walk_outbox(&community, &context).await;
+    let form = CommunityBackfillUpdateForm {
+      error: Some(res.err().map(|e| e.error_type.to_string())),
+      finished: Some(Some(naive_now())),
+      ..Default::default()
+    };
+    let backfill = CommunityBackfill::update(&mut context.pool(), community.id, &form).await?;
+    info!(
+      "Finished backfill of community {}: {} pages, {} posts, {} comments",
+      community.actor_id,
+      backfill.pages_fetched,
+      backfill.posts_imported,
+      backfill.comments_imported
+    );
+    Ok(())
+  });
+
+  Ok(Json(CommunityBackfillResponse { backfill }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn get_community_backfill(
+  data: Query<GetCommunityBackfill>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommunityBackfillResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let backfill = CommunityBackfill::read(&mut context.pool(), data.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunityBackfill)?;
+  Ok(Json(CommunityBackfillResponse { backfill }))
+}
+
+/// Follows the pages of the community outbox and imports all posts and comments, storing the
+/// progress after each page.
+async fn walk_outbox(community: &ApubCommunity, context: &Data<LemmyContext>) -> LemmyResult<()> {
+  let group: Group = fetch_object_http(community.actor_id.inner(), context)
+    .await?
+    .object;
+  let outbox: GroupOutbox = fetch_object_http(&group.outbox.into(), context)
+    .await?
+    .object;
+  verify_domains_match(&outbox.id, community.actor_id.inner())?;
+
+  let mut progress = CommunityBackfillUpdateForm {
+    pages_fetched: Some(0),
+    posts_imported: Some(0),
+    comments_imported: Some(0),
+    ..Default::default()
+  };
+
+  // Without pagination only the items of the outbox itself can be imported
+  let Some(mut next_page) = outbox.first else {
+    return import_page(community, outbox.ordered_items, &mut progress, context).await;
+  };
+  for _ in 0..MAX_PAGES {
+    // Each page is a separate fetch, so it shouldn't count against the limit of the previous ones
+    let context = context.reset_request_count();
+    let page = fetch_page(&next_page, &context).await?;
+    verify_domains_match(&page.id, community.actor_id.inner())?;
+    import_page(community, page.ordered_items, &mut progress, &context).await?;
+
+    match page.next {
+      Some(next) if next != next_page => next_page = next,
+      _ => break,
+    }
+    tokio::time::sleep(PAGE_DELAY).await;
+  }
+  Ok(())
+}
+
+/// Fetches a page of the outbox, retrying after a pause if it fails.
+async fn fetch_page(url: &Url, context: &Data<LemmyContext>) -> LemmyResult<GroupOutboxPage> {
+  let mut retries = 0;
+  loop {
+    match fetch_object_http(url, context).await {
+      Ok(res) => return Ok(res.object),
+      Err(e) if retries < PAGE_RETRIES => {
+        warn!("Failed to fetch outbox page {url}, retrying: {e}");
+        retries += 1;
+        tokio::time::sleep(RETRY_DELAY).await;
+      }
+      Err(e) => return Err(e.into()),
+    }
+  }
+}
+
+async fn import_page(
+  community: &ApubCommunity,
+  items: Vec<AnnounceActivity>,
+  progress: &mut CommunityBackfillUpdateForm,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  // Items are received one after another, because comments need their post and parent comment.
+  // Errors are ignored, as the outbox may contain objects which can't be parsed.
+  for item in items {
+    match receive_outbox_item(item, context).await {
+      Ok(Some(OutboxItem::Post)) => increment(&mut progress.posts_imported),
+      Ok(Some(OutboxItem::Comment)) => increment(&mut progress.comments_imported),
+      _ => {}
+    }
+  }
+  increment(&mut progress.pages_fetched);
+  progress.updated = Some(Some(naive_now()));
+  CommunityBackfill::update(&mut context.pool(), community.id, progress).await?;
+  Ok(())
+}
+
+fn increment(count: &mut Option<i32>) {
+  *count = Some(count.unwrap_or_default() + 1);
+}
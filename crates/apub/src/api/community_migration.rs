@@ -0,0 +1,308 @@
+use crate::{
+  activities::moving::{self, verify_move_alias},
+  api::user_settings_backup::fetch_and_import,
+  collections::{
+    community_moderators::ApubCommunityModerators,
+    community_outbox::{receive_outbox_item, ApubCommunityOutbox},
+  },
+  fetcher::resolve_actor_identifier,
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::{
+    activities::moving::move_actor::Move,
+    collections::group_outbox::GroupOutboxPage,
+    objects::group::Group,
+  },
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::{collection_id::CollectionId, object_id::ObjectId},
+  http_signatures::generate_actor_keypair,
+  traits::Object,
+};
+use actix_web::{
+  http::header::ContentType,
+  web::{Bytes, Json, Query},
+  HttpResponse,
+};
+use futures::{stream, StreamExt, TryStreamExt};
+use lemmy_api_common::{
+  build_response::build_community_response,
+  community::{CommunityResponse, ExportCommunity, MoveCommunity},
+  context::LemmyContext,
+  utils::{
+    generate_followers_url,
+    generate_inbox_url,
+    generate_local_apub_endpoint,
+    generate_shared_inbox_url,
+    is_admin,
+    EndpointType,
+  },
+  SuccessResponse,
+};
+use lemmy_db_schema::{
+  source::{
+    actor_language::CommunityLanguage,
+    community::{
+      Community,
+      CommunityFollower,
+      CommunityFollowerForm,
+      CommunityInsertForm,
+      CommunityModerator,
+      CommunityModeratorForm,
+    },
+  },
+  traits::{ApubActor, Crud, Followable, Joinable},
+};
+use lemmy_db_views::structs::{LocalUserView, SiteView};
+use lemmy_db_views_actor::structs::CommunityModeratorView;
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  spawn_try_task,
+  utils::validation::is_valid_actor_name,
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// Backups contain the entire community history, so they need a much larger request size limit
+/// than other API calls.
+pub const MAX_COMMUNITY_BACKUP_SIZE: usize = 100 * 1024 * 1024;
+
+/// Stop exporting after this many outbox pages.
+const MAX_EXPORT_PAGES: i64 = 10_000;
+
+/// Everything which is needed to recreate a community on another instance. Posts and comments are
+/// stored as pages of the community outbox, in the same format as they are federated. The
+/// community itself and its moderators are fetched from the original instance during import.
+///
+/// This data should not be parsed by apps/clients, but directly downloaded as a file.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommunityBackup {
+  pub community: Group,
+  #[serde(default)]
+  pub followers: Vec<ObjectId<ApubPerson>>,
+  #[serde(default)]
+  pub outbox: Vec<GroupOutboxPage>,
+}
+
+/// Streams the backup one outbox page at a time, so that the entire community history doesn't
+/// need to be held in memory.
+#[tracing::instrument(skip(context))]
+pub async fn export_community(
+  data: Query<ExportCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<HttpResponse> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let community: ApubCommunity = Community::read(&mut context.pool(), data.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?
+    .into();
+  if !community.local {
+    Err(LemmyErrorType::CantMigrateRemoteCommunity)?
+  }
+
+  let followers: Vec<ObjectId<ApubPerson>> =
+    CommunityFollower::list_follower_actor_ids(&mut context.pool(), community.id)
+      .await?
+      .into_iter()
+      .map(Into::into)
+      .collect();
+  // Written by hand so that outbox pages can be appended one by one. Field names need to match
+  // `CommunityBackup`.
+  let head = format!(
+    r#"{{"community":{},"followers":{},"outbox":["#,
+    serde_json::to_string(&community.clone().into_json(&context).await?)?,
+    serde_json::to_string(&followers)?
+  );
+
+  let pages = stream::try_unfold(Some(1), move |page| {
+    let (community, context) = (community.clone(), context.reset_request_count());
+    async move {
+      let Some(page) = page.filter(|p| *p <= MAX_EXPORT_PAGES) else {
+        return LemmyResult::Ok(None);
+      };
+      let outbox_page = ApubCommunityOutbox::read_local_page(&community, page, &context).await?;
+      let next = outbox_page.next.as_ref().map(|_| page + 1);
+      let separator = if page == 1 { "" } else { "," };
+      let json = format!("{separator}{}", serde_json::to_string(&outbox_page)?);
+      Ok(Some((Bytes::from(json), next)))
+    }
+  });
+  let body = stream::once(async move { Ok(Bytes::from(head)) })
+    .chain(pages)
+    .chain(stream::once(async { Ok(Bytes::from_static(b"]}")) }))
+    .map_err(actix_web::Error::from);
+
+  Ok(
+    HttpResponse::Ok()
+      .content_type(ContentType::json())
+      .streaming(body),
+  )
+}
+
+/// Creates a local community from the backup of a community on another instance. Moderators,
+/// followers, posts and comments are imported in the background. Once this is done, the old
+/// community can be moved here.
+#[tracing::instrument(skip(context))]
+pub async fn import_community(
+  data: Json<CommunityBackup>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommunityResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let backup = data.into_inner();
+  if backup.community.id.is_local(&context) {
+    Err(LemmyErrorType::InvalidMoveTarget)?
+  }
+  // The community in the backup can't be trusted, as it would allow replacing the public key of
+  // the old community. So it is refetched from the original instance instead.
+  let old = backup.community.id.dereference_forced(&context).await?;
+
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  is_valid_actor_name(
+    &old.name,
+    site_view.local_site.actor_name_max_length as usize,
+  )?;
+  let actor_id = generate_local_apub_endpoint(
+    EndpointType::Community,
+    &old.name,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  if Community::read_from_apub_id(&mut context.pool(), &actor_id)
+    .await?
+    .is_some()
+  {
+    Err(LemmyErrorType::CommunityAlreadyExists)?
+  }
+
+  let keypair = generate_actor_keypair()?;
+  let form = CommunityInsertForm::builder()
+    .name(old.name.clone())
+    .title(old.title.clone())
+    .description(old.description.clone())
+    .icon(old.icon.clone())
+    .banner(old.banner.clone())
+    .nsfw(Some(old.nsfw))
+    .actor_id(Some(actor_id.clone()))
+    .private_key(Some(keypair.private_key))
+    .public_key(keypair.public_key)
+    .followers_url(Some(generate_followers_url(&actor_id)?))
+    .inbox_url(Some(generate_inbox_url(&actor_id)?))
+    .shared_inbox_url(Some(generate_shared_inbox_url(context.settings())?))
+    .posting_restricted_to_mods(Some(old.posting_restricted_to_mods))
+    .instance_id(site_view.site.instance_id)
+    .visibility(Some(old.visibility))
+    .also_known_as(Some(vec![Some(old.actor_id.clone())]))
+    .build();
+  let community: ApubCommunity = Community::create(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CommunityAlreadyExists)?
+    .into();
+  let languages = CommunityLanguage::read(&mut context.pool(), old.id).await?;
+  CommunityLanguage::update(&mut context.pool(), languages, community.id).await?;
+
+  let community_ = community.clone();
+  let context_ = context.reset_request_count();
+  spawn_try_task(async move {
+    let (community, context) = (community_, context_);
+    info!(
+      "Starting import of community {} into {}",
+      old.actor_id, community.actor_id
+    );
+
+    // Moderators are also fetched from the original instance and then copied over.
+    if let Some(moderators_url) = old.moderators_url.clone() {
+      CollectionId::<ApubCommunityModerators>::from(moderators_url)
+        .dereference(&old, &context)
+        .await?;
+    }
+    for moderator in CommunityModeratorView::for_community(&mut context.pool(), old.id).await? {
+      let form = CommunityModeratorForm {
+        community_id: community.id,
+        person_id: moderator.moderator.id,
+      };
+      CommunityModerator::join(&mut context.pool(), &form).await?;
+    }
+
+    let community_id = community.id;
+    let failed_followers = fetch_and_import(
+      backup.followers,
+      &context,
+      |(follower, context)| async move {
+        let person = follower.dereference(&context).await?;
+        let form = CommunityFollowerForm {
+          community_id,
+          person_id: person.id,
+          pending: false,
+        };
+        CommunityFollower::follow(&mut context.pool(), &form).await?;
+        LemmyResult::Ok(())
+      },
+    )
+    .await?;
+
+    // Posts and comments are received into the old community first, where they are found when
+    // fetching parent objects. Afterwards all of them are moved over together.
+    let mut failed_items = 0;
+    for page in backup.outbox {
+      for item in page.ordered_items {
+        let context = context.reset_request_count();
+        if receive_outbox_item(item, &context).await.is_err() {
+          failed_items += 1;
+        }
+      }
+    }
+    Community::move_posts(&mut context.pool(), old.id, community.id).await?;
+    moving::move_community(&old, &community, &context).await?;
+
+    info!(
+      "Import of community {} completed, {failed_items} posts and comments failed, the following followers failed: {failed_followers}",
+      community.actor_id
+    );
+    Ok(())
+  });
+
+  build_community_response(&context, local_user_view, community.id).await
+}
+
+/// Moves a local community to another instance where it was imported. Remote instances subscribe
+/// their users to the new community when they receive the `Move`.
+#[tracing::instrument(skip(context))]
+pub async fn move_community(
+  data: Json<MoveCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let community: ApubCommunity = Community::read(&mut context.pool(), data.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?
+    .into();
+  if !community.local {
+    Err(LemmyErrorType::CantMigrateRemoteCommunity)?
+  }
+
+  let target: ApubCommunity = resolve_actor_identifier::<ApubCommunity, Community>(
+    &data.target,
+    &context,
+    &Some(local_user_view),
+    false,
+  )
+  .await?;
+  // Refetch the target so that the alias is known
+  let target = ObjectId::<ApubCommunity>::from(target.actor_id.clone())
+    .dereference_forced(&context)
+    .await?;
+  verify_move_alias(&community.actor_id, &target.actor_id, &target.also_known_as)?;
+
+  moving::move_community(&community, &target, &context).await?;
+  Move::send(&community, &target, &context).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
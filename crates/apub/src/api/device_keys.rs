@@ -0,0 +1,75 @@
+use crate::{objects::person::ApubPerson, protocol::activities::actor::update::UpdateActor};
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  private_message::{DeleteDeviceKey, ListDeviceKeys, ListDeviceKeysResponse, SaveDeviceKey},
+  SuccessResponse,
+};
+use lemmy_db_schema::source::person_device_key::{
+  PersonDeviceKey,
+  PersonDeviceKeyForm,
+  MAX_DEVICE_KEYS,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+const MAX_DEVICE_ID_LENGTH: usize = 100;
+const MAX_PUBLIC_KEY_LENGTH: usize = 10_000;
+
+/// Publishes the public key of a device on your profile, so that others can send encrypted
+/// private messages to it.
+#[tracing::instrument(skip(context))]
+pub async fn save_device_key(
+  data: Json<SaveDeviceKey>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  let person_id = local_user_view.person.id;
+  if data.device_id.is_empty()
+    || data.device_id.len() > MAX_DEVICE_ID_LENGTH
+    || data.public_key.is_empty()
+    || data.public_key.len() > MAX_PUBLIC_KEY_LENGTH
+  {
+    Err(LemmyErrorType::InvalidDeviceKey)?
+  }
+  let keys = PersonDeviceKey::list_for_person(&mut context.pool(), person_id).await?;
+  if keys.len() >= MAX_DEVICE_KEYS && !keys.iter().any(|k| k.device_id == data.device_id) {
+    Err(LemmyErrorType::InvalidDeviceKey)?
+  }
+
+  let form = PersonDeviceKeyForm {
+    person_id,
+    device_id: data.device_id.clone(),
+    public_key: data.public_key.clone(),
+  };
+  PersonDeviceKey::upsert(&mut context.pool(), &form).await?;
+
+  UpdateActor::send_person(&ApubPerson(local_user_view.person), &context).await?;
+  Ok(Json(SuccessResponse::default()))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_device_key(
+  data: Json<DeleteDeviceKey>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  let person_id = local_user_view.person.id;
+  PersonDeviceKey::delete(&mut context.pool(), person_id, &data.device_id).await?;
+
+  UpdateActor::send_person(&ApubPerson(local_user_view.person), &context).await?;
+  Ok(Json(SuccessResponse::default()))
+}
+
+/// Lists the device keys of a local or remote person. Keys of remote people are updated along
+/// with their profile.
+#[tracing::instrument(skip(context))]
+pub async fn list_device_keys(
+  data: Query<ListDeviceKeys>,
+  context: Data<LemmyContext>,
+  _local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListDeviceKeysResponse>> {
+  let device_keys = PersonDeviceKey::list_for_person(&mut context.pool(), data.person_id).await?;
+  Ok(Json(ListDeviceKeysResponse { device_keys }))
+}
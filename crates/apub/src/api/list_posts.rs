@@ -84,6 +84,7 @@ pub async fn list_posts(
     show_hidden,
     show_read,
     show_nsfw,
+    hashtag: data.hashtag.clone(),
     ..Default::default()
   }
   .list(&local_site.site, &mut context.pool())
@@ -5,11 +5,18 @@ use lemmy_db_schema::{
   SortType,
 };
 
+pub mod community_backfill;
+pub mod community_migration;
+pub mod device_keys;
 pub mod list_comments;
 pub mod list_posts;
+pub mod move_account;
 pub mod read_community;
 pub mod read_person;
+pub mod relay;
+pub mod replay_activity;
 pub mod resolve_object;
+pub mod rotate_keys;
 pub mod search;
 pub mod user_settings_backup;
 
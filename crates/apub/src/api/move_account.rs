@@ -0,0 +1,51 @@
+use crate::{
+  activities::moving::{move_person, verify_move_alias},
+  fetcher::resolve_actor_identifier,
+  objects::person::ApubPerson,
+  protocol::activities::moving::move_actor::Move,
+};
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
+use actix_web::web::Json;
+use bcrypt::verify;
+use lemmy_api_common::{context::LemmyContext, person::MoveAccount, SuccessResponse};
+use lemmy_db_schema::source::person::Person;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Moves the account to another instance. Afterwards the profile redirects to the new account, and
+/// followers are moved over on all instances which receive the `Move` activity.
+#[tracing::instrument(skip(context))]
+pub async fn move_account(
+  data: Json<MoveAccount>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Verify the password
+  let valid: bool = verify(
+    &data.password,
+    &local_user_view.local_user.password_encrypted,
+  )
+  .unwrap_or(false);
+  if !valid {
+    Err(LemmyErrorType::IncorrectLogin)?
+  }
+
+  let target: ApubPerson = resolve_actor_identifier::<ApubPerson, Person>(
+    &data.target,
+    &context,
+    &Some(local_user_view.clone()),
+    false,
+  )
+  .await?;
+  // Refetch the target so that a recently added alias is known
+  let target = ObjectId::<ApubPerson>::from(target.actor_id.clone())
+    .dereference_forced(&context)
+    .await?;
+  let person: ApubPerson = local_user_view.person.into();
+  verify_move_alias(&person.actor_id, &target.actor_id, &target.also_known_as)?;
+
+  move_person(&person, &target, &context).await?;
+  Move::send(&person, &target, &context).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
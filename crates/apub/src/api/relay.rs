@@ -0,0 +1,120 @@
+use crate::{activities::relay::send_follow_relay, objects::relay::ApubRelay};
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  relay::{
+    AddRelay,
+    ListRelaysResponse,
+    RelayResponse,
+    RelayView,
+    RemoveRelay,
+    SetRelayCommunityFilter,
+  },
+  utils::is_admin,
+  SuccessResponse,
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    relay::{Relay, RelayCommunityFilter, RelayCommunityFilterForm},
+  },
+  traits::Crud,
+  utils::DbPool,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use std::ops::Deref;
+use url::Url;
+
+/// Fetches the relay actor and sends it a follow. Relayed content is only accepted once the relay
+/// has confirmed the follow.
+#[tracing::instrument(skip(context))]
+pub async fn add_relay(
+  data: Json<AddRelay>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<RelayResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let actor_id = Url::parse(&data.actor_id).with_lemmy_type(LemmyErrorType::InvalidUrl)?;
+  let relay: ApubRelay = ObjectId::from(actor_id).dereference(&context).await?;
+  let relay = relay.deref().clone();
+  send_follow_relay(relay.clone(), true, &context).await?;
+
+  let relay_view = relay_view(relay, &mut context.pool()).await?;
+  Ok(Json(RelayResponse { relay_view }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn remove_relay(
+  data: Json<RemoveRelay>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let relay = Relay::read(&mut context.pool(), data.relay_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindRelay)?;
+  Relay::delete(&mut context.pool(), relay.id).await?;
+  send_follow_relay(relay, false, &context).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn list_relays(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListRelaysResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let mut relays = Vec::new();
+  for relay in Relay::list(&mut context.pool()).await? {
+    relays.push(relay_view(relay, &mut context.pool()).await?);
+  }
+  Ok(Json(ListRelaysResponse { relays }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn set_relay_community_filter(
+  data: Json<SetRelayCommunityFilter>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<RelayResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let relay = Relay::read(&mut context.pool(), data.relay_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindRelay)?;
+  Community::read(&mut context.pool(), data.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+
+  if let Some(allow) = data.allow {
+    let form = RelayCommunityFilterForm {
+      relay_id: relay.id,
+      community_id: data.community_id,
+      allow,
+    };
+    RelayCommunityFilter::set(&mut context.pool(), &form).await?;
+  } else {
+    RelayCommunityFilter::remove(&mut context.pool(), relay.id, data.community_id).await?;
+  }
+
+  let relay_view = relay_view(relay, &mut context.pool()).await?;
+  Ok(Json(RelayResponse { relay_view }))
+}
+
+async fn relay_view(relay: Relay, pool: &mut DbPool<'_>) -> LemmyResult<RelayView> {
+  let community_filters = RelayCommunityFilter::list_for_relay(pool, relay.id).await?;
+  Ok(RelayView {
+    relay,
+    community_filters,
+  })
+}
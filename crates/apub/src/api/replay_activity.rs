@@ -0,0 +1,107 @@
+use crate::{activity_lists::SharedInboxActivities, VerifyUrlData, FEDERATION_HTTP_FETCH_LIMIT};
+use activitypub_federation::{
+  config::{Data, FederationConfig},
+  traits::ActivityHandler,
+};
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{
+    ListReceivedActivities,
+    ListReceivedActivitiesResponse,
+    ReplayActivity,
+    ReplayActivityResponse,
+  },
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{
+    activity::ReceivedActivity,
+    received_activity_raw::{ReceivedActivityRaw, TableChanges},
+  },
+  utils::build_rollback_db_pool,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use std::time::Duration;
+use tokio::time::timeout;
+use url::Url;
+
+/// Replaying may take longer than receiving an activity normally, because it has to wait for the
+/// single database connection.
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[tracing::instrument(skip(context))]
+pub async fn list_received_activities(
+  data: Query<ListReceivedActivities>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListReceivedActivitiesResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let activities = ReceivedActivityRaw::list(
+    &mut context.pool(),
+    data.only_errors.unwrap_or_default(),
+    data.page,
+    data.limit,
+  )
+  .await?;
+  Ok(Json(ListReceivedActivitiesResponse { activities }))
+}
+
+/// Runs a stored activity through the shared inbox handler again, in a transaction which is rolled
+/// back afterwards. The HTTP signature is not checked again, as it is only valid for a short time.
+/// Outgoing activities which are triggered by the replay are discarded, and all other changes are
+/// rolled back.
+#[tracing::instrument(skip(context))]
+pub async fn replay_activity(
+  data: Json<ReplayActivity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ReplayActivityResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let ap_id: DbUrl = Url::parse(&data.ap_id)?.into();
+  let raw = ReceivedActivityRaw::read(&mut context.pool(), &ap_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindActivity)?;
+
+  let pool = build_rollback_db_pool()?;
+  let replay_context = LemmyContext::create(
+    pool.clone(),
+    context.client().clone(),
+    context.secret().clone(),
+    context.rate_limit_cell().clone(),
+  )
+  .without_outgoing_activities();
+  let config = FederationConfig::builder()
+    .domain(context.settings().hostname.clone())
+    .app_data(replay_context)
+    .client(context.client().clone())
+    .http_fetch_limit(FEDERATION_HTTP_FETCH_LIMIT)
+    .debug(cfg!(debug_assertions))
+    .http_signature_compat(true)
+    .url_verifier(Box::new(VerifyUrlData(pool)))
+    .build()
+    .await?;
+  let replay_data = config.to_request_data();
+
+  let replay = async {
+    let activity: SharedInboxActivities = serde_json::from_str(&raw.data)?;
+    // Otherwise the activity is rejected as duplicate
+    ReceivedActivity::delete(&mut replay_data.pool(), &ap_id).await?;
+    activity.verify(&replay_data).await?;
+    activity.receive(&replay_data).await
+  };
+  let error = timeout(REPLAY_TIMEOUT, replay)
+    .await
+    .unwrap_or_else(|_| Err(LemmyErrorType::InboxTimeout.into()))
+    .err()
+    .map(|e| e.inner.to_string());
+  let changes = TableChanges::read_for_transaction(&mut replay_data.pool()).await?;
+
+  Ok(Json(ReplayActivityResponse { error, changes }))
+}
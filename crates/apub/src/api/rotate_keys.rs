@@ -0,0 +1,119 @@
+use crate::{
+  activities::community::update::send_update_community,
+  objects::{instance::ApubSite, person::ApubPerson, remember_previous_key},
+  protocol::activities::actor::update::UpdateActor,
+};
+use activitypub_federation::{config::Data, http_signatures::generate_actor_keypair};
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::AdminRotateKeys,
+  utils::is_admin,
+  SuccessResponse,
+};
+use lemmy_db_schema::{
+  source::{
+    community::{Community, CommunityUpdateForm},
+    person::{Person, PersonUpdateForm},
+    site::{Site, SiteUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Generates a new keypair for your account, and sends the new public key to other instances.
+/// They keep accepting signatures made with the previous key for a while. The previous key is also
+/// stored locally, which lets the HTTP server and the activity sender notice the rotation and
+/// reload their cached private keys.
+#[tracing::instrument(skip(context))]
+pub async fn rotate_keys(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  rotate_person_keys(local_user_view.person, &context).await?;
+  Ok(Json(SuccessResponse::default()))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn admin_rotate_keys(
+  data: Json<AdminRotateKeys>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  if let Some(person_id) = data.person_id {
+    let person = Person::read(&mut context.pool(), person_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPerson)?;
+    rotate_person_keys(person, &context).await?;
+  } else if let Some(community_id) = data.community_id {
+    let community = Community::read(&mut context.pool(), community_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+    if !community.local {
+      Err(LemmyErrorType::ObjectNotLocal)?
+    }
+    let keypair = generate_actor_keypair()?;
+    let form = CommunityUpdateForm {
+      public_key: Some(keypair.public_key.clone()),
+      private_key: Some(Some(keypair.private_key)),
+      ..Default::default()
+    };
+    remember_previous_key(
+      &community.actor_id,
+      Some(community.public_key),
+      &keypair.public_key,
+      &context,
+    )
+    .await?;
+    let community = Community::update(&mut context.pool(), community.id, &form).await?;
+    send_update_community(community, local_user_view.person, context).await?;
+  } else {
+    let site = Site::read_local(&mut context.pool()).await?;
+    let keypair = generate_actor_keypair()?;
+    let form = SiteUpdateForm {
+      public_key: Some(keypair.public_key.clone()),
+      private_key: Some(Some(keypair.private_key)),
+      ..Default::default()
+    };
+    remember_previous_key(
+      &site.actor_id,
+      Some(site.public_key),
+      &keypair.public_key,
+      &context,
+    )
+    .await?;
+    let site: ApubSite = Site::update(&mut context.pool(), site.id, &form)
+      .await?
+      .into();
+    UpdateActor::send_site(&site, &context).await?;
+  }
+
+  Ok(Json(SuccessResponse::default()))
+}
+
+async fn rotate_person_keys(person: Person, context: &Data<LemmyContext>) -> LemmyResult<()> {
+  if !person.local {
+    Err(LemmyErrorType::ObjectNotLocal)?
+  }
+  let keypair = generate_actor_keypair()?;
+  let form = PersonUpdateForm {
+    public_key: Some(keypair.public_key.clone()),
+    private_key: Some(Some(keypair.private_key)),
+    ..Default::default()
+  };
+  remember_previous_key(
+    &person.actor_id,
+    Some(person.public_key),
+    &keypair.public_key,
+    context,
+  )
+  .await?;
+  let person: ApubPerson = Person::update(&mut context.pool(), person.id, &form)
+    .await?
+    .into();
+  UpdateActor::send_person(&person, context).await
+}
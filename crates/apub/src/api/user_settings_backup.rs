@@ -1,8 +1,6 @@
-use crate::objects::{
-  comment::ApubComment,
-  community::ApubCommunity,
-  person::ApubPerson,
-  post::ApubPost,
+use crate::{
+  activities::following::send_follow_community,
+  objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson, post::ApubPost},
 };
 use activitypub_federation::{config::Data, fetch::object_id::ObjectId, traits::Object};
 use actix_web::web::Json;
@@ -31,7 +29,7 @@ use lemmy_utils::{
   spawn_try_task,
 };
 use serde::{Deserialize, Serialize};
-use std::future::Future;
+use std::{future::Future, ops::Deref};
 use tracing::info;
 
 const PARALLELISM: usize = 10;
@@ -46,6 +44,9 @@ const PARALLELISM: usize = 10;
 /// importing older backups.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct UserSettingsBackup {
+  /// The backed up account. When importing into a different account, it is added to
+  /// `alsoKnownAs` so that the old account can be moved to the new one.
+  pub actor_id: Option<DbUrl>,
   pub display_name: Option<String>,
   pub bio: Option<String>,
   pub avatar: Option<DbUrl>,
@@ -79,6 +80,7 @@ pub async fn export_settings(
 
   let vec_into = |vec: Vec<_>| vec.into_iter().map(Into::into).collect();
   Ok(Json(UserSettingsBackup {
+    actor_id: Some(local_user_view.person.actor_id),
     display_name: local_user_view.person.display_name,
     bio: local_user_view.person.bio,
     avatar: local_user_view.person.avatar,
@@ -102,11 +104,20 @@ pub async fn import_settings(
   local_user_view: LocalUserView,
   context: Data<LemmyContext>,
 ) -> LemmyResult<Json<SuccessResponse>> {
+  let mut also_known_as = local_user_view.person.also_known_as.clone();
+  let alias = data
+    .actor_id
+    .clone()
+    .filter(|a| a != &local_user_view.person.actor_id);
+  if alias.is_some() && !also_known_as.contains(&alias) {
+    also_known_as.push(alias);
+  }
   let person_form = PersonUpdateForm {
     display_name: Some(data.display_name.clone()),
     bio: Some(data.bio.clone()),
     matrix_user_id: Some(data.matrix_id.clone()),
     bot_account: data.bot_account,
+    also_known_as: Some(also_known_as),
     ..Default::default()
   };
   Person::update(&mut context.pool(), local_user_view.person.id, &person_form).await?;
@@ -129,6 +140,7 @@ pub async fn import_settings(
     auto_expand: data.settings.as_ref().map(|s| s.auto_expand),
     infinite_scroll_enabled: data.settings.as_ref().map(|s| s.infinite_scroll_enabled),
     post_listing_mode: data.settings.as_ref().map(|s| s.post_listing_mode),
+    private_message_privacy: data.settings.as_ref().map(|s| s.private_message_privacy),
     ..Default::default()
   };
   LocalUser::update(
@@ -177,17 +189,22 @@ pub async fn import_settings(
       local_user_view.person.name
     );
 
+    let person = &local_user_view.person;
     let failed_followed_communities = fetch_and_import(
       data.followed_communities.clone(),
       &context,
       |(followed, context)| async move {
         let community = followed.dereference(&context).await?;
+        // Remote communities only add the follower once they accept the follow
         let form = CommunityFollowerForm {
           person_id,
           community_id: community.id,
-          pending: true,
+          pending: !community.local,
         };
         CommunityFollower::follow(&mut context.pool(), &form).await?;
+        if !community.local {
+          send_follow_community(community.deref().clone(), person.clone(), true, &context).await?;
+        }
         LemmyResult::Ok(())
       },
     )
@@ -274,7 +291,7 @@ pub async fn import_settings(
   Ok(Json(Default::default()))
 }
 
-async fn fetch_and_import<Kind, Fut>(
+pub(crate) async fn fetch_and_import<Kind, Fut>(
   objects: Vec<ObjectId<Kind>>,
   context: &Data<LemmyContext>,
   import_fn: impl FnMut((ObjectId<Kind>, Data<LemmyContext>)) -> Fut,
@@ -393,6 +410,11 @@ mod tests {
       import_user_updated.person.display_name
     );
     assert_eq!(export_user.person.bio, import_user_updated.person.bio);
+    // The exported account is an alias of the importing one, so that it can be moved there
+    assert_eq!(
+      vec![Some(export_user.person.actor_id.clone())],
+      import_user_updated.person.also_known_as
+    );
 
     let follows =
       CommunityFollowerView::for_person(&mut context.pool(), import_user.person.id).await?;
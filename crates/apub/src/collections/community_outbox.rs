@@ -4,28 +4,147 @@ use crate::{
   protocol::{
     activities::{
       community::announce::AnnounceActivity,
-      create_or_update::page::CreateOrUpdatePage,
+      create_or_update::{note::CreateOrUpdateNote, page::CreateOrUpdatePage},
       CreateOrUpdateType,
     },
-    collections::group_outbox::GroupOutbox,
+    collections::group_outbox::{GroupOutbox, GroupOutboxPage},
   },
 };
 use activitypub_federation::{
   config::Data,
-  kinds::collection::OrderedCollectionType,
+  kinds::collection::{OrderedCollectionPageType, OrderedCollectionType},
   protocol::verification::verify_domains_match,
   traits::{ActivityHandler, Collection},
 };
 use futures::future::join_all;
+use itertools::Itertools;
 use lemmy_api_common::{context::LemmyContext, utils::generate_outbox_url};
-use lemmy_db_schema::{source::site::Site, utils::FETCH_LIMIT_MAX, SortType};
+use lemmy_db_schema::{
+  source::{comment::Comment, site::Site},
+  utils::FETCH_LIMIT_MAX,
+  SortType,
+};
 use lemmy_db_views::post_view::PostQuery;
 use lemmy_utils::error::{LemmyError, LemmyResult};
 use url::Url;
 
+/// Number of posts in each page of the outbox history
+const OUTBOX_PAGE_POSTS: i64 = 10;
+
+/// Maximum number of comments which are included for each post in outbox pages
+const OUTBOX_PAGE_COMMENTS_PER_POST: i64 = 5 * FETCH_LIMIT_MAX;
+
 #[derive(Clone, Debug)]
 pub(crate) struct ApubCommunityOutbox(());
 
+/// The kind of object which was received from an outbox item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OutboxItem {
+  Post,
+  Comment,
+}
+
+impl ApubCommunityOutbox {
+  /// Returns a page of the full community history, starting at 1. Unlike the outbox itself, this
+  /// also contains the comments of each post, so that new instances can import entire threads.
+  #[tracing::instrument(skip_all)]
+  pub(crate) async fn read_local_page(
+    owner: &ApubCommunity,
+    page: i64,
+    data: &Data<LemmyContext>,
+  ) -> LemmyResult<GroupOutboxPage> {
+    let site = Site::read_local(&mut data.pool()).await?;
+    let outbox_url: Url = generate_outbox_url(&owner.actor_id)?.into();
+
+    let post_views = PostQuery {
+      community_id: Some(owner.id),
+      sort: Some(SortType::New),
+      page: Some(page),
+      limit: Some(OUTBOX_PAGE_POSTS),
+      ..Default::default()
+    }
+    .list(&site, &mut data.pool())
+    .await?;
+    let has_next = post_views.len() as i64 == OUTBOX_PAGE_POSTS;
+
+    // Oldest comments first, so that parent comments are always received before their replies
+    let post_ids = post_views.iter().map(|p| p.post.id).collect();
+    let comments = Comment::list_for_posts_with_creator(
+      &mut data.pool(),
+      post_ids,
+      OUTBOX_PAGE_COMMENTS_PER_POST,
+    )
+    .await?;
+    let mut comments_by_post = comments.into_iter().into_group_map_by(|(c, _)| c.post_id);
+
+    let mut ordered_items = vec![];
+    for post_view in post_views {
+      let post_id = post_view.post.id;
+      let create = CreateOrUpdatePage::new(
+        post_view.post.into(),
+        &post_view.creator.into(),
+        owner,
+        CreateOrUpdateType::Create,
+        data,
+      )
+      .await?;
+      let announcable = AnnouncableActivities::CreateOrUpdatePost(create);
+      ordered_items.push(AnnounceActivity::new(announcable.try_into()?, owner, data)?);
+
+      for (comment, creator) in comments_by_post.remove(&post_id).unwrap_or_default() {
+        let create = CreateOrUpdateNote::new(
+          comment.into(),
+          &creator.into(),
+          owner,
+          CreateOrUpdateType::Create,
+          data,
+        )
+        .await?;
+        let announcable = AnnouncableActivities::CreateOrUpdateComment(create);
+        ordered_items.push(AnnounceActivity::new(announcable.try_into()?, owner, data)?);
+      }
+    }
+
+    Ok(GroupOutboxPage {
+      r#type: OrderedCollectionPageType::OrderedCollectionPage,
+      id: outbox_page_url(&outbox_url, page)?,
+      next: has_next
+        .then(|| outbox_page_url(&outbox_url, page + 1))
+        .transpose()?,
+      part_of: outbox_url,
+      ordered_items,
+    })
+  }
+}
+
+fn outbox_page_url(outbox_url: &Url, page: i64) -> LemmyResult<Url> {
+  Ok(Url::parse(&format!("{outbox_url}?page={page}"))?)
+}
+
+/// Receives a post or comment which is contained in an outbox item.
+///
+/// Receiving announce requires at least one local community follower for anti spam purposes.
+/// This won't be the case for newly fetched communities, so we extract the inner activity and
+/// handle it directly to bypass this check.
+pub(crate) async fn receive_outbox_item(
+  activity: AnnounceActivity,
+  data: &Data<LemmyContext>,
+) -> LemmyResult<Option<OutboxItem>> {
+  match activity.object.object(data).await?.try_into()? {
+    AnnouncableActivities::CreateOrUpdatePost(inner) => {
+      inner.verify(data).await?;
+      inner.receive(data).await?;
+      Ok(Some(OutboxItem::Post))
+    }
+    AnnouncableActivities::CreateOrUpdateComment(inner) => {
+      inner.verify(data).await?;
+      inner.receive(data).await?;
+      Ok(Some(OutboxItem::Comment))
+    }
+    _ => Ok(None),
+  }
+}
+
 #[async_trait::async_trait]
 impl Collection for ApubCommunityOutbox {
   type Owner = ApubCommunity;
@@ -61,9 +180,11 @@ impl Collection for ApubCommunityOutbox {
       ordered_items.push(announce);
     }
 
+    let outbox_url = generate_outbox_url(&owner.actor_id)?.into();
     Ok(GroupOutbox {
       r#type: OrderedCollectionType::OrderedCollection,
-      id: generate_outbox_url(&owner.actor_id)?.into(),
+      first: Some(outbox_page_url(&outbox_url, 1)?),
+      id: outbox_url,
       total_items: ordered_items.len() as i32,
       ordered_items,
     })
@@ -98,20 +219,11 @@ impl Collection for ApubCommunityOutbox {
     // item and only parse the ones that work.
     // process items in parallel, to avoid long delay from fetch_site_metadata() and other
     // processing
-    join_all(outbox_activities.into_iter().map(|activity| {
-      async {
-        // Receiving announce requires at least one local community follower for anti spam purposes.
-        // This won't be the case for newly fetched communities, so we extract the inner activity
-        // and handle it directly to bypass this check.
-        let inner = activity.object.object(data).await.map(TryInto::try_into);
-        if let Ok(Ok(AnnouncableActivities::CreateOrUpdatePost(inner))) = inner {
-          let verify = inner.verify(data).await;
-          if verify.is_ok() {
-            inner.receive(data).await.ok();
-          }
-        }
-      }
-    }))
+    join_all(
+      outbox_activities
+        .into_iter()
+        .map(|activity| async { receive_outbox_item(activity, data).await.ok() }),
+    )
     .await;
 
     // This return value is unused, so just set an empty vec
@@ -6,11 +6,16 @@ use crate::{
     community_moderators::ApubCommunityModerators,
     community_outbox::ApubCommunityOutbox,
   },
-  http::{check_community_public, create_apub_response, create_apub_tombstone_response},
+  http::{
+    check_community_public,
+    create_apub_response,
+    create_apub_tombstone_response,
+    previous_key::receive_activity_with_previous_key,
+    record_received_activity,
+  },
   objects::{community::ApubCommunity, person::ApubPerson},
 };
 use activitypub_federation::{
-  actix_web::inbox::receive_activity,
   config::Data,
   protocol::context::WithContext,
   traits::{Collection, Object},
@@ -18,7 +23,7 @@ use activitypub_federation::{
 use actix_web::{web, web::Bytes, HttpRequest, HttpResponse};
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{source::community::Community, traits::ApubActor};
-use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+use lemmy_utils::{error::LemmyResult, rate_limit::get_ip, LemmyErrorType};
 use serde::Deserialize;
 
 #[derive(Deserialize, Clone)]
@@ -26,6 +31,12 @@ pub(crate) struct CommunityQuery {
   community_name: String,
 }
 
+#[derive(Deserialize, Clone, Default)]
+pub(crate) struct CommunityOutboxQuery {
+  /// If set, returns this page of the full community history instead of the outbox
+  page: Option<i64>,
+}
+
 /// Return the ActivityPub json representation of a local community over HTTP.
 #[tracing::instrument(skip_all)]
 pub(crate) async fn get_apub_community_http(
@@ -54,10 +65,11 @@ pub async fn community_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  receive_activity::<WithContext<GroupInboxActivities>, ApubPerson, LemmyContext>(
-    request, body, &data,
-  )
-  .await
+  let receive_fut = receive_activity_with_previous_key::<
+    WithContext<GroupInboxActivities>,
+    ApubPerson,
+  >(request, body.clone(), &data);
+  record_received_activity(&body, &data, receive_fut).await
 }
 
 /// Returns an empty followers collection, only populating the size (for privacy).
@@ -74,9 +86,13 @@ pub(crate) async fn get_apub_community_followers(
 }
 
 /// Returns the community outbox, which is populated by a maximum of 20 posts (but no other
-/// activities like votes or comments).
+/// activities like votes or comments). With the `page` parameter, returns a page of the full
+/// community history including comments. These pages are expensive to build, so they count
+/// against the search rate limit.
 pub(crate) async fn get_apub_community_outbox(
   info: web::Path<CommunityQuery>,
+  query: web::Query<CommunityOutboxQuery>,
+  request: HttpRequest,
   context: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
   let community: ApubCommunity =
@@ -85,6 +101,14 @@ pub(crate) async fn get_apub_community_outbox(
       .ok_or(LemmyErrorType::CouldntFindCommunity)?
       .into();
   check_community_public(&community)?;
+  if let Some(page) = query.page {
+    let ip_addr = get_ip(&request.connection_info());
+    if !context.rate_limit_cell().search().check(ip_addr, None) {
+      Err(LemmyErrorType::RateLimitError)?
+    }
+    let outbox_page = ApubCommunityOutbox::read_local_page(&community, page, &context).await?;
+    return create_apub_response(&outbox_page);
+  }
   let outbox = ApubCommunityOutbox::read_local(&community, &context).await?;
   create_apub_response(&outbox)
 }
@@ -125,8 +149,11 @@ pub(crate) async fn get_apub_community_featured(
 pub(crate) mod tests {
 
   use super::*;
-  use crate::protocol::objects::{group::Group, tombstone::Tombstone};
-  use actix_web::body::to_bytes;
+  use crate::protocol::{
+    collections::group_outbox::GroupOutboxPage,
+    objects::{group::Group, tombstone::Tombstone},
+  };
+  use actix_web::{body::to_bytes, test::TestRequest};
   use lemmy_db_schema::{
     newtypes::InstanceId,
     source::{
@@ -224,8 +251,24 @@ pub(crate) mod tests {
     let res =
       get_apub_community_moderators(query.clone().into(), context.reset_request_count()).await?;
     assert_eq!(200, res.status());
-    let res = get_apub_community_outbox(query.into(), context.reset_request_count()).await?;
+    let res = get_apub_community_outbox(
+      query.clone().into(),
+      web::Query(CommunityOutboxQuery::default()),
+      TestRequest::default().to_http_request(),
+      context.reset_request_count(),
+    )
+    .await?;
+    assert_eq!(200, res.status());
+    let res = get_apub_community_outbox(
+      query.into(),
+      web::Query(CommunityOutboxQuery { page: Some(1) }),
+      TestRequest::default().to_http_request(),
+      context.reset_request_count(),
+    )
+    .await?;
     assert_eq!(200, res.status());
+    let outbox_page: GroupOutboxPage = decode_response(res).await?;
+    assert_eq!(None, outbox_page.next);
 
     Instance::delete(&mut context.pool(), instance.id).await?;
     Ok(())
@@ -255,7 +298,13 @@ pub(crate) mod tests {
     let res =
       get_apub_community_moderators(query.clone().into(), context.reset_request_count()).await;
     assert!(res.is_err());
-    let res = get_apub_community_outbox(query.into(), context.reset_request_count()).await;
+    let res = get_apub_community_outbox(
+      query.into(),
+      web::Query(CommunityOutboxQuery::default()),
+      TestRequest::default().to_http_request(),
+      context.reset_request_count(),
+    )
+    .await;
     assert!(res.is_err());
 
     //Community::delete(&mut context.pool(), community.id).await?;
@@ -283,7 +332,13 @@ pub(crate) mod tests {
     let res =
       get_apub_community_moderators(query.clone().into(), context.reset_request_count()).await;
     assert!(res.is_err());
-    let res = get_apub_community_outbox(query.into(), context.reset_request_count()).await;
+    let res = get_apub_community_outbox(
+      query.into(),
+      web::Query(CommunityOutboxQuery::default()),
+      TestRequest::default().to_http_request(),
+      context.reset_request_count(),
+    )
+    .await;
     assert!(res.is_err());
 
     Instance::delete(&mut context.pool(), instance.id).await?;
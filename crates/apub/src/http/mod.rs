@@ -1,6 +1,8 @@
 use crate::{
-  activity_lists::SharedInboxActivities,
-  fetcher::user_or_community::UserOrCommunity,
+  activity_lists::{RelayInboxActivities, SharedInboxActivities},
+  fetcher::site_or_community_or_user::SiteOrCommunityOrUser,
+  http::previous_key::receive_activity_with_previous_key,
+  objects::relay::ApubRelay,
   protocol::objects::tombstone::Tombstone,
   FEDERATION_CONTEXT,
 };
@@ -8,25 +10,33 @@ use activitypub_federation::{
   actix_web::inbox::receive_activity,
   config::Data,
   protocol::context::WithContext,
+  traits::ActivityHandler,
   FEDERATION_CONTENT_TYPE,
 };
 use actix_web::{web, web::Bytes, HttpRequest, HttpResponse};
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
   newtypes::DbUrl,
-  source::{activity::SentActivity, community::Community},
+  source::{
+    activity::SentActivity,
+    community::Community,
+    received_activity_raw::{ReceivedActivityRaw, ReceivedActivityRawForm},
+    relay::Relay,
+  },
   CommunityVisibility,
 };
-use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
 use serde::{Deserialize, Serialize};
-use std::{ops::Deref, time::Duration};
+use std::{cell::Cell, future::Future, ops::Deref, time::Duration};
 use tokio::time::timeout;
+use tracing::warn;
 use url::Url;
 
 mod comment;
 mod community;
 mod person;
 mod post;
+mod previous_key;
 pub mod routes;
 pub mod site;
 
@@ -37,15 +47,149 @@ pub async fn shared_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  let receive_fut =
-    receive_activity::<SharedInboxActivities, UserOrCommunity, LemmyContext>(request, body, &data);
-  // Set a timeout shorter than `REQWEST_TIMEOUT` for processing incoming activities. This is to
-  // avoid taking a long time to process an incoming activity when a required data fetch times out.
-  // In this case our own instance would timeout and be marked as dead by the sender. Better to
-  // consider the activity broken and move on.
-  timeout(INCOMING_ACTIVITY_TIMEOUT, receive_fut)
-    .await
-    .map_err(|_| LemmyErrorType::InboxTimeout)?
+  let from_relay = is_from_relay(&body, &data).await?;
+  let receive_fut = async {
+    let receive_fut = async {
+      if from_relay {
+        receive_activity::<RelayInboxActivities, ApubRelay, LemmyContext>(
+          request,
+          body.clone(),
+          &data,
+        )
+        .await
+      } else {
+        receive_activity_with_previous_key::<SharedInboxActivities, SiteOrCommunityOrUser>(
+          request,
+          body.clone(),
+          &data,
+        )
+        .await
+      }
+    };
+    // Set a timeout shorter than `REQWEST_TIMEOUT` for processing incoming activities. This is to
+    // avoid taking a long time to process an incoming activity when a required data fetch times
+    // out. In this case our own instance would timeout and be marked as dead by the sender. Better
+    // to consider the activity broken and move on.
+    timeout(INCOMING_ACTIVITY_TIMEOUT, receive_fut)
+      .await
+      .unwrap_or_else(|_| Err(LemmyErrorType::InboxTimeout.into()))
+  };
+  if from_relay {
+    receive_fut.await
+  } else {
+    record_received_activity(&body, &data, receive_fut).await
+  }
+}
+
+tokio::task_local! {
+  /// Set once the signature of the activity which is currently being received was verified.
+  static SIGNATURE_VERIFIED: Cell<bool>;
+}
+
+/// Wrapper which notes in [SIGNATURE_VERIFIED] that the signature of the activity is valid. This
+/// works because the library only calls [ActivityHandler::verify] after checking the signature.
+#[derive(Deserialize)]
+#[serde(transparent)]
+pub(crate) struct SignatureVerified<Activity>(Activity);
+
+#[async_trait::async_trait]
+impl<Activity> ActivityHandler for SignatureVerified<Activity>
+where
+  Activity: ActivityHandler<DataType = LemmyContext, Error = LemmyError> + Send + Sync,
+{
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    self.0.id()
+  }
+
+  fn actor(&self) -> &Url {
+    self.0.actor()
+  }
+
+  async fn verify(&self, data: &Data<Self::DataType>) -> LemmyResult<()> {
+    // Not set if the activity isn't received through one of the inboxes, eg when replaying it
+    SIGNATURE_VERIFIED.try_with(|v| v.set(true)).ok();
+    self.0.verify(data).await
+  }
+
+  async fn receive(self, data: &Data<Self::DataType>) -> LemmyResult<()> {
+    self.0.receive(data).await
+  }
+}
+
+#[derive(Deserialize)]
+struct ActivityId {
+  id: Url,
+}
+
+/// Receives an activity, then stores its raw json together with the processing error, so that
+/// admins can replay it later. Only activities with a valid signature are stored, and an activity
+/// which was already stored is never overwritten, as its id is chosen by the sender. Only enabled
+/// if `record_received_activities_max_bytes` is set in the config.
+pub(crate) async fn record_received_activity<F>(
+  body: &Bytes,
+  data: &Data<LemmyContext>,
+  receive_fut: F,
+) -> LemmyResult<HttpResponse>
+where
+  F: Future<Output = LemmyResult<HttpResponse>>,
+{
+  let (res, verified) = SIGNATURE_VERIFIED
+    .scope(Cell::new(false), async {
+      let res = receive_fut.await;
+      (res, SIGNATURE_VERIFIED.with(Cell::get))
+    })
+    .await;
+  if verified {
+    if let Err(e) = insert_received_activity(body, &res, data).await {
+      warn!("Failed to record received activity: {e}");
+    }
+  }
+  res
+}
+
+async fn insert_received_activity(
+  body: &Bytes,
+  res: &LemmyResult<HttpResponse>,
+  data: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let max_bytes = data
+    .settings()
+    .federation
+    .record_received_activities_max_bytes;
+  if max_bytes == 0 || body.len() > max_bytes {
+    return Ok(());
+  }
+  let Ok(activity) = serde_json::from_slice::<ActivityId>(body) else {
+    return Ok(());
+  };
+  let form = ReceivedActivityRawForm {
+    ap_id: activity.id.into(),
+    data: String::from_utf8_lossy(body).into_owned(),
+    error: res.as_ref().err().map(|e| e.inner.to_string()),
+  };
+  ReceivedActivityRaw::insert(&mut data.pool(), &form).await?;
+  Ok(())
+}
+
+#[derive(Deserialize)]
+struct ActivityActor {
+  actor: Url,
+}
+
+/// Relays aren't persons or communities, so their activities need to be handled separately. Only
+/// relays which were added by an admin are recognized.
+async fn is_from_relay(body: &Bytes, data: &Data<LemmyContext>) -> LemmyResult<bool> {
+  let Ok(activity) = serde_json::from_slice::<ActivityActor>(body) else {
+    return Ok(false);
+  };
+  Ok(
+    Relay::read_from_apub_id(&mut data.pool(), &activity.actor.into())
+      .await?
+      .is_some(),
+  )
 }
 
 /// Convert the data to json and turn it into an HTTP Response with the correct ActivityPub
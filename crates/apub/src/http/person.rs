@@ -1,16 +1,16 @@
 use crate::{
   activity_lists::PersonInboxActivities,
   fetcher::user_or_community::UserOrCommunity,
-  http::{create_apub_response, create_apub_tombstone_response},
+  http::{
+    create_apub_response,
+    create_apub_tombstone_response,
+    previous_key::receive_activity_with_previous_key,
+    record_received_activity,
+  },
   objects::person::ApubPerson,
   protocol::collections::empty_outbox::EmptyOutbox,
 };
-use activitypub_federation::{
-  actix_web::inbox::receive_activity,
-  config::Data,
-  protocol::context::WithContext,
-  traits::Object,
-};
+use activitypub_federation::{config::Data, protocol::context::WithContext, traits::Object};
 use actix_web::{web, web::Bytes, HttpRequest, HttpResponse};
 use lemmy_api_common::{context::LemmyContext, utils::generate_outbox_url};
 use lemmy_db_schema::{source::person::Person, traits::ApubActor};
@@ -50,10 +50,11 @@ pub async fn person_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  receive_activity::<WithContext<PersonInboxActivities>, UserOrCommunity, LemmyContext>(
-    request, body, &data,
-  )
-  .await
+  let receive_fut = receive_activity_with_previous_key::<
+    WithContext<PersonInboxActivities>,
+    UserOrCommunity,
+  >(request, body.clone(), &data);
+  record_received_activity(&body, &data, receive_fut).await
 }
 
 #[tracing::instrument(skip_all)]
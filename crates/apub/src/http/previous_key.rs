@@ -0,0 +1,152 @@
+use crate::http::{ActivityActor, SignatureVerified};
+use activitypub_federation::{
+  actix_web::inbox::receive_activity,
+  config::Data,
+  error::Error,
+  traits::{ActivityHandler, Actor, Object},
+};
+use actix_web::{web::Bytes, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::actor_previous_key::ActorPreviousKey;
+use lemmy_utils::error::{LemmyError, LemmyResult};
+use serde::{de::DeserializeOwned, Deserialize};
+use url::Url;
+
+/// Receives an activity in the same way as [receive_activity]. If the signature is invalid and the
+/// actor rotated its keypair recently, the signature is checked again with the previous key. This
+/// way activities which were signed shortly before the rotation aren't lost.
+pub(crate) async fn receive_activity_with_previous_key<Activity, ActorT>(
+  request: HttpRequest,
+  body: Bytes,
+  data: &Data<LemmyContext>,
+) -> LemmyResult<HttpResponse>
+where
+  Activity: ActivityHandler<DataType = LemmyContext, Error = LemmyError>
+    + DeserializeOwned
+    + Send
+    + Sync
+    + 'static,
+  ActorT: Object<DataType = LemmyContext, Error = LemmyError> + Actor + Send + Sync + 'static,
+  for<'de2> <ActorT as Object>::Kind: Deserialize<'de2> + Send + Sync,
+{
+  let res = receive_activity::<SignatureVerified<Activity>, ActorT, LemmyContext>(
+    request.clone(),
+    body.clone(),
+    data,
+  )
+  .await;
+  match res {
+    Err(e) if is_invalid_signature(&e) && has_previous_key(&body, data).await? => {
+      receive_activity::<SignatureVerified<Activity>, PreviousKey<ActorT>, LemmyContext>(
+        request, body, data,
+      )
+      .await
+    }
+    res => res,
+  }
+}
+
+fn is_invalid_signature(e: &LemmyError) -> bool {
+  matches!(
+    e.inner.downcast_ref::<Error>(),
+    Some(Error::ActivitySignatureInvalid)
+  )
+}
+
+async fn has_previous_key(body: &Bytes, data: &Data<LemmyContext>) -> LemmyResult<bool> {
+  let Ok(activity) = serde_json::from_slice::<ActivityActor>(body) else {
+    return Ok(false);
+  };
+  Ok(
+    ActorPreviousKey::read_valid(&mut data.pool(), &activity.actor.into())
+      .await?
+      .is_some(),
+  )
+}
+
+/// An actor whose previous public key is used for signature verification, instead of the current
+/// one.
+#[derive(Debug)]
+struct PreviousKey<ActorT> {
+  actor: ActorT,
+  public_key: String,
+}
+
+impl<ActorT> PreviousKey<ActorT>
+where
+  ActorT: Actor,
+{
+  async fn new(actor: ActorT, data: &Data<LemmyContext>) -> LemmyResult<Self> {
+    let public_key = ActorPreviousKey::read_valid(&mut data.pool(), &actor.id().into())
+      .await?
+      .ok_or(Error::ActivitySignatureInvalid)?
+      .public_key;
+    Ok(PreviousKey { actor, public_key })
+  }
+}
+
+#[async_trait::async_trait]
+impl<ActorT> Object for PreviousKey<ActorT>
+where
+  ActorT: Object<DataType = LemmyContext, Error = LemmyError> + Actor + Send + Sync + 'static,
+  <ActorT as Object>::Kind: Send + Sync,
+{
+  type DataType = LemmyContext;
+  type Kind = ActorT::Kind;
+  type Error = LemmyError;
+
+  fn last_refreshed_at(&self) -> Option<DateTime<Utc>> {
+    self.actor.last_refreshed_at()
+  }
+
+  async fn read_from_id(object_id: Url, data: &Data<Self::DataType>) -> LemmyResult<Option<Self>> {
+    match ActorT::read_from_id(object_id, data).await? {
+      Some(actor) => Ok(Some(PreviousKey::new(actor, data).await?)),
+      None => Ok(None),
+    }
+  }
+
+  async fn delete(self, data: &Data<Self::DataType>) -> LemmyResult<()> {
+    self.actor.delete(data).await
+  }
+
+  async fn into_json(self, data: &Data<Self::DataType>) -> LemmyResult<Self::Kind> {
+    self.actor.into_json(data).await
+  }
+
+  async fn verify(
+    json: &Self::Kind,
+    expected_domain: &Url,
+    data: &Data<Self::DataType>,
+  ) -> LemmyResult<()> {
+    ActorT::verify(json, expected_domain, data).await
+  }
+
+  async fn from_json(json: Self::Kind, data: &Data<Self::DataType>) -> LemmyResult<Self> {
+    let actor = ActorT::from_json(json, data).await?;
+    PreviousKey::new(actor, data).await
+  }
+}
+
+impl<ActorT> Actor for PreviousKey<ActorT>
+where
+  ActorT: Object<DataType = LemmyContext, Error = LemmyError> + Actor + Send + Sync + 'static,
+  <ActorT as Object>::Kind: Send + Sync,
+{
+  fn id(&self) -> Url {
+    self.actor.id()
+  }
+
+  fn public_key_pem(&self) -> &str {
+    &self.public_key
+  }
+
+  fn private_key_pem(&self) -> Option<String> {
+    None
+  }
+
+  fn inbox(&self) -> Url {
+    self.actor.inbox()
+  }
+}
@@ -17,8 +17,10 @@ use activitypub_federation::{
 };
 use chrono::{DateTime, Utc};
 use lemmy_api_common::{
+  automod_engine::{run_automod_for_comment, AutomodTrigger},
   context::LemmyContext,
-  utils::{get_url_blocklist, is_mod_or_admin, local_site_opt_to_slur_regex, process_markdown},
+  slur_filter_engine::{SlurFilterMatches, SlurFilters},
+  utils::{get_url_blocklist, is_mod_or_admin, process_markdown},
 };
 use lemmy_db_schema::{
   source::{
@@ -174,12 +176,15 @@ impl Object for ApubComment {
     let content = read_from_string_or_source(&note.content, &note.media_type, &note.source);
 
     let local_site = LocalSite::read(&mut context.pool()).await.ok();
-    let slur_regex = &local_site_opt_to_slur_regex(&local_site);
+    let slur_filters = SlurFilters::read(local_site.as_ref(), context).await?;
+    let mut slur_filter_matches = SlurFilterMatches::default();
+    let content = slur_filters.check_text(&content, &mut slur_filter_matches)?;
     let url_blocklist = get_url_blocklist(context).await?;
-    let content = process_markdown(&content, slur_regex, &url_blocklist, context).await?;
+    let content = process_markdown(&content, &None, &url_blocklist, context).await?;
     let language_id =
       LanguageTag::to_language_id_single(note.language, &mut context.pool()).await?;
 
+    let previous = Comment::read_from_apub_id(&mut context.pool(), note.id.inner().clone()).await?;
     let form = CommentInsertForm {
       creator_id: creator.id,
       post_id: post.id,
@@ -202,6 +207,22 @@ impl Object for ApubComment {
       parent_comment_path.as_ref(),
     )
     .await?;
+    // Automod and slur filter actions apply to new comments and edits, see ApubPost::from_json
+    let is_new_or_edited = previous.map_or(true, |c| c.content != comment.content);
+    if is_new_or_edited {
+      if let Err(e) = run_automod_for_comment(&comment, AutomodTrigger::Write, context).await {
+        tracing::warn!("Failed to run automod for comment {}: {e}", comment.ap_id);
+      }
+      if let Err(e) = slur_filter_matches
+        .apply_to_comment(&comment, context)
+        .await
+      {
+        tracing::warn!(
+          "Failed to apply slur filters to comment {}: {e}",
+          comment.ap_id
+        );
+      }
+    }
     Ok(comment.into())
   }
 }
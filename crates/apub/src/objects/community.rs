@@ -2,7 +2,11 @@ use crate::{
   activities::GetActorType,
   check_apub_id_valid,
   local_site_data_cached,
-  objects::{instance::fetch_instance_actor_for_object, read_from_string_or_source_opt},
+  objects::{
+    instance::fetch_instance_actor_for_object,
+    read_from_string_or_source_opt,
+    remember_previous_key,
+  },
   protocol::{
     objects::{group::Group, Endpoints, LanguageTag},
     ImageObject,
@@ -28,6 +32,7 @@ use lemmy_api_common::{
   },
 };
 use lemmy_db_schema::{
+  newtypes::DbUrl,
   sensitive::SensitiveString,
   source::{
     activity::ActorType,
@@ -124,6 +129,14 @@ impl Object for ApubCommunity {
       updated: self.updated,
       posting_restricted_to_mods: Some(self.posting_restricted_to_mods),
       attributed_to: Some(generate_moderators_url(&self.actor_id)?.into()),
+      also_known_as: self
+        .also_known_as
+        .iter()
+        .flatten()
+        .cloned()
+        .map(Into::into)
+        .collect(),
+      moved_to: self.moved_to.clone().map(Into::into),
     };
     Ok(group)
   }
@@ -151,6 +164,7 @@ impl Object for ApubCommunity {
     let icon = proxy_image_link_opt_apub(group.icon.map(|i| i.url), context).await?;
     let banner = proxy_image_link_opt_apub(group.image.map(|i| i.url), context).await?;
 
+    let actor_id: DbUrl = group.id.into();
     let form = CommunityInsertForm {
       name: group.preferred_username.clone(),
       title: group.name.unwrap_or(group.preferred_username.clone()),
@@ -159,7 +173,7 @@ impl Object for ApubCommunity {
       updated: group.updated,
       deleted: Some(false),
       nsfw: Some(group.sensitive.unwrap_or(false)),
-      actor_id: Some(group.id.into()),
+      actor_id: Some(actor_id.clone()),
       local: Some(false),
       public_key: group.public_key.public_key_pem,
       last_refreshed_at: Some(naive_now()),
@@ -172,11 +186,27 @@ impl Object for ApubCommunity {
       posting_restricted_to_mods: group.posting_restricted_to_mods,
       instance_id,
       featured_url: group.featured.clone().map(Into::into),
+      moved_to: group.moved_to.clone().map(Into::into),
+      also_known_as: Some(
+        group
+          .also_known_as
+          .iter()
+          .map(|a| Some(a.clone().into()))
+          .collect(),
+      ),
       ..Default::default()
     };
     let languages =
       LanguageTag::to_language_id_multiple(group.language, &mut context.pool()).await?;
 
+    let stored = Community::read_from_apub_id(&mut context.pool(), &actor_id).await?;
+    remember_previous_key(
+      &actor_id,
+      stored.map(|c| c.public_key),
+      &form.public_key,
+      context,
+    )
+    .await?;
     let timestamp = group.updated.or(group.published).unwrap_or_else(naive_now);
     let community = Community::insert_apub(&mut context.pool(), timestamp, &form).await?;
     CommunityLanguage::update(&mut context.pool(), languages, community.id).await?;
@@ -3,7 +3,7 @@ use crate::{
   activities::GetActorType,
   check_apub_id_valid_with_strictness,
   local_site_data_cached,
-  objects::read_from_string_or_source_opt,
+  objects::{read_from_string_or_source_opt, remember_previous_key},
   protocol::{
     objects::{instance::Instance, LanguageTag},
     ImageObject,
@@ -172,6 +172,15 @@ impl Object for ApubSite {
     let languages =
       LanguageTag::to_language_id_multiple(apub.language, &mut context.pool()).await?;
 
+    let actor_id = apub.id.clone().into();
+    let stored = Site::read_from_apub_id(&mut context.pool(), &actor_id).await?;
+    remember_previous_key(
+      &actor_id,
+      stored.map(|s| s.public_key),
+      &apub.public_key.public_key_pem,
+      context,
+    )
+    .await?;
     let site = Site::create(&mut context.pool(), &site_form).await?;
     SiteLanguage::update(&mut context.pool(), languages, &site).await?;
     Ok(site.into())
@@ -6,8 +6,13 @@ use activitypub_federation::{
   traits::Object,
 };
 use anyhow::anyhow;
+use chrono::{TimeDelta, Utc};
 use html2md::parse_html;
 use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::actor_previous_key::{ActorPreviousKey, ActorPreviousKeyForm},
+};
 use lemmy_utils::error::LemmyResult;
 use serde::Deserialize;
 use std::fmt::Debug;
@@ -18,6 +23,11 @@ pub mod instance;
 pub mod person;
 pub mod post;
 pub mod private_message;
+pub mod relay;
+
+/// How long signatures made with the previous key of an actor are accepted after it rotated its
+/// keypair.
+const PREVIOUS_KEY_VALIDITY: TimeDelta = TimeDelta::days(7);
 
 pub(crate) fn read_from_string_or_source(
   content: &str,
@@ -64,3 +74,23 @@ where
     Ok(())
   }
 }
+
+/// If an actor has a different public key than the stored one, it rotated its keypair. The old key
+/// is remembered for a while so that activities which were signed before the rotation can still be
+/// verified.
+pub(crate) async fn remember_previous_key(
+  actor_id: &DbUrl,
+  stored_key: Option<String>,
+  new_key: &str,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  if let Some(stored_key) = stored_key.filter(|k| k != new_key) {
+    let form = ActorPreviousKeyForm {
+      actor_id: actor_id.clone(),
+      public_key: stored_key,
+      expires: Utc::now() + PREVIOUS_KEY_VALIDITY,
+    };
+    ActorPreviousKey::upsert(&mut context.pool(), &form).await?;
+  }
+  Ok(())
+}
@@ -3,10 +3,14 @@ use crate::{
   activities::GetActorType,
   check_apub_id_valid_with_strictness,
   local_site_data_cached,
-  objects::{instance::fetch_instance_actor_for_object, read_from_string_or_source_opt},
+  objects::{
+    instance::fetch_instance_actor_for_object,
+    read_from_string_or_source_opt,
+    remember_previous_key,
+  },
   protocol::{
     objects::{
-      person::{Person, UserTypes},
+      person::{DeviceKey, Person, UserTypes},
       Endpoints,
     },
     ImageObject,
@@ -30,11 +34,13 @@ use lemmy_api_common::{
   },
 };
 use lemmy_db_schema::{
+  newtypes::DbUrl,
   sensitive::SensitiveString,
   source::{
     activity::ActorType,
     local_site::LocalSite,
     person::{Person as DbPerson, PersonInsertForm, PersonUpdateForm},
+    person_device_key::{PersonDeviceKey, PersonDeviceKeyForm, MAX_DEVICE_KEYS},
   },
   traits::{ApubActor, Crud},
   utils::naive_now,
@@ -98,7 +104,15 @@ impl Object for ApubPerson {
   }
 
   #[tracing::instrument(skip_all)]
-  async fn into_json(self, _context: &Data<Self::DataType>) -> LemmyResult<Person> {
+  async fn into_json(self, context: &Data<Self::DataType>) -> LemmyResult<Person> {
+    let device_keys = PersonDeviceKey::list_for_person(&mut context.pool(), self.id)
+      .await?
+      .into_iter()
+      .map(|k| DeviceKey {
+        device_id: k.device_id,
+        public_key: k.public_key,
+      })
+      .collect();
     let kind = if self.bot_account {
       UserTypes::Service
     } else {
@@ -123,6 +137,15 @@ impl Object for ApubPerson {
       public_key: self.public_key(),
       updated: self.updated,
       inbox: self.inbox_url.clone().into(),
+      also_known_as: self
+        .also_known_as
+        .iter()
+        .flatten()
+        .cloned()
+        .map(Into::into)
+        .collect(),
+      moved_to: self.moved_to.clone().map(Into::into),
+      device_keys,
     };
     Ok(person)
   }
@@ -162,7 +185,9 @@ impl Object for ApubPerson {
     // Some Mastodon users have `name: ""` (empty string), need to convert that to `None`
     // https://github.com/mastodon/mastodon/issues/25233
     let display_name = person.name.filter(|n| !n.is_empty());
+    let device_keys = person.device_keys;
 
+    let actor_id: DbUrl = person.id.into();
     let person_form = PersonInsertForm {
       name: person.preferred_username,
       display_name,
@@ -173,7 +198,7 @@ impl Object for ApubPerson {
       banner,
       published: person.published.map(Into::into),
       updated: person.updated.map(Into::into),
-      actor_id: Some(person.id.into()),
+      actor_id: Some(actor_id.clone()),
       bio,
       local: Some(false),
       bot_account: Some(person.kind == UserTypes::Service),
@@ -184,9 +209,36 @@ impl Object for ApubPerson {
       shared_inbox_url: person.endpoints.map(|e| e.shared_inbox.into()),
       matrix_user_id: person.matrix_user_id,
       instance_id,
+      moved_to: person.moved_to.map(Into::into),
+      also_known_as: Some(
+        person
+          .also_known_as
+          .into_iter()
+          .map(|a| Some(a.into()))
+          .collect(),
+      ),
     };
+    let stored = DbPerson::read_from_apub_id(&mut context.pool(), &actor_id).await?;
+    remember_previous_key(
+      &actor_id,
+      stored.map(|p| p.public_key),
+      &person_form.public_key,
+      context,
+    )
+    .await?;
     let person = DbPerson::upsert(&mut context.pool(), &person_form).await?;
 
+    let device_key_forms: Vec<_> = device_keys
+      .into_iter()
+      .take(MAX_DEVICE_KEYS)
+      .map(|k| PersonDeviceKeyForm {
+        person_id: person.id,
+        device_id: k.device_id,
+        public_key: k.public_key,
+      })
+      .collect();
+    PersonDeviceKey::replace_for_person(&mut context.pool(), person.id, &device_key_forms).await?;
+
     Ok(person.into())
   }
 }
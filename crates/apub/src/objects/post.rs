@@ -1,11 +1,10 @@
 use crate::{
   activities::{verify_is_public, verify_person_in_community},
   check_apub_id_valid_with_strictness,
-  local_site_data_cached,
   objects::{read_from_string_or_source_opt, verify_is_remote_object},
   protocol::{
     objects::{
-      page::{Attachment, AttributedTo, Hashtag, HashtagType, Page, PageType},
+      page::{Attachment, AttributedTo, Hashtag, HashtagOrValue, HashtagType, Page, PageType},
       LanguageTag,
     },
     ImageObject,
@@ -23,16 +22,26 @@ use anyhow::anyhow;
 use chrono::{DateTime, Utc};
 use html2text::{from_read_with_decorator, render::text_renderer::TrivialDecorator};
 use lemmy_api_common::{
+  automod_engine::{run_automod_for_post, AutomodTrigger},
   context::LemmyContext,
   request::generate_post_link_metadata,
-  utils::{get_url_blocklist, local_site_opt_to_slur_regex, process_markdown_opt},
+  slur_filter_engine::{SlurFilterMatches, SlurFilters},
+  utils::{
+    get_url_blocklist,
+    get_url_cleaning_rules,
+    process_markdown_opt,
+    sanitize_post_gallery,
+    update_post_hashtags,
+  },
 };
 use lemmy_db_schema::{
   source::{
     community::Community,
+    hashtag::Hashtag as PostHashtag,
     local_site::LocalSite,
     person::Person,
     post::{Post, PostInsertForm, PostUpdateForm},
+    post_gallery_image::PostGalleryImage,
   },
   traits::Crud,
   utils::naive_now,
@@ -42,8 +51,8 @@ use lemmy_utils::{
   error::{LemmyError, LemmyErrorType, LemmyResult},
   spawn_try_task,
   utils::{
+    hashtag::{scrape_text_for_hashtags, MAX_HASHTAGS_PER_POST},
     markdown::markdown_to_html,
-    slurs::check_slurs_opt,
     validation::{is_url_blocked, is_valid_url},
   },
 };
@@ -53,6 +62,14 @@ use url::Url;
 
 const MAX_TITLE_LENGTH: usize = 200;
 
+/// Hashtags link to the RSS feed of posts which use them.
+fn hashtag_feed_url(name: &str, context: &LemmyContext) -> LemmyResult<Url> {
+  Ok(Url::parse(&format!(
+    "{}/feeds/tag/{name}.xml",
+    context.settings().get_protocol_and_hostname()
+  ))?)
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ApubPost(pub(crate) Post);
 
@@ -116,6 +133,7 @@ impl Object for ApubPost {
       .ok_or(LemmyErrorType::CouldntFindCommunity)?;
     let language = LanguageTag::new_single(self.language_id, &mut context.pool()).await?;
 
+    let gallery = PostGalleryImage::list_for_post(&mut context.pool(), self.id).await?;
     let attachment = self
       .url
       .clone()
@@ -127,12 +145,21 @@ impl Object for ApubPost {
         )
       })
       .into_iter()
+      .chain(gallery.into_iter().map(Attachment::new_gallery_image))
       .collect();
     let hashtag = Hashtag {
       href: self.ap_id.clone().into(),
       name: format!("#{}", &community.name),
       kind: HashtagType::Hashtag,
     };
+    let mut tag = vec![HashtagOrValue::Hashtag(hashtag)];
+    for hashtag in PostHashtag::list_for_post(&mut context.pool(), self.id).await? {
+      tag.push(HashtagOrValue::Hashtag(Hashtag {
+        href: hashtag_feed_url(&hashtag.name, context)?,
+        name: format!("#{}", hashtag.name),
+        kind: HashtagType::Hashtag,
+      }));
+    }
 
     let page = Page {
       kind: PageType::Page,
@@ -152,7 +179,7 @@ impl Object for ApubPost {
       updated: self.updated,
       audience: Some(community.actor_id.into()),
       in_reply_to: None,
-      tag: vec![hashtag],
+      tag,
     };
     Ok(page)
   }
@@ -170,10 +197,6 @@ impl Object for ApubPost {
     check_apub_id_valid_with_strictness(page.id.inner(), community.local, context).await?;
     verify_person_in_community(&page.creator()?, &community, context).await?;
 
-    let local_site_data = local_site_data_cached(&mut context.pool()).await?;
-    let slur_regex = &local_site_opt_to_slur_regex(&local_site_data.local_site);
-    check_slurs_opt(&page.name, slur_regex)?;
-
     verify_domains_match(page.creator()?.inner(), page.id.inner())?;
     verify_is_public(&page.to, &page.cc)?;
     Ok(())
@@ -219,7 +242,12 @@ impl Object for ApubPost {
       name = name.chars().take(MAX_TITLE_LENGTH).collect();
     }
 
-    let first_attachment = page.attachment.first();
+    let (marked_gallery, attachments): (Vec<_>, Vec<_>) = page
+      .attachment
+      .iter()
+      .cloned()
+      .partition(Attachment::is_marked_gallery_image);
+    let first_attachment = attachments.first();
     let local_site = LocalSite::read(&mut context.pool()).await.ok();
 
     let url = if let Some(attachment) = first_attachment.cloned() {
@@ -231,6 +259,10 @@ impl Object for ApubPost {
       None
     };
 
+    // Remote instances may not clean urls, or use different rules
+    let url_cleaning_rules = get_url_cleaning_rules(context).await?;
+    let url = url.map(|url| url_cleaning_rules.clean_url(&url));
+
     let url_blocklist = get_url_blocklist(context).await?;
 
     if let Some(url) = &url {
@@ -239,17 +271,35 @@ impl Object for ApubPost {
     }
 
     let alt_text = first_attachment.cloned().and_then(Attachment::alt_text);
+    let gallery = if marked_gallery.is_empty() {
+      attachments.into_iter().skip(1).collect()
+    } else {
+      marked_gallery
+    };
+    let gallery = gallery
+      .into_iter()
+      .filter_map(Attachment::gallery_image)
+      .collect::<Vec<_>>();
+    let gallery = sanitize_post_gallery(&gallery, &url_blocklist, &url_cleaning_rules);
 
-    let slur_regex = &local_site_opt_to_slur_regex(&local_site);
+    let slur_filters = SlurFilters::read(local_site.as_ref(), context).await?;
+    let mut slur_filter_matches = SlurFilterMatches::default();
+    let name = slur_filters.check_title(&name, &mut slur_filter_matches)?;
 
     let body = read_from_string_or_source_opt(&page.content, &page.media_type, &page.source);
-    let body = process_markdown_opt(&body, slur_regex, &url_blocklist, context).await?;
+    let body = slur_filters.check_text_opt(&body, &mut slur_filter_matches)?;
+    let body = process_markdown_opt(&body, &None, &url_blocklist, context).await?;
     let language_id =
       LanguageTag::to_language_id_single(page.language, &mut context.pool()).await?;
 
+    // Replaced with the canonical url declared by the page once its metadata is fetched
+    let url_canonical = url
+      .as_ref()
+      .map(|url| url_cleaning_rules.canonicalize_url(url).into());
     let form = PostInsertForm::builder()
       .name(name)
       .url(url.map(Into::into))
+      .url_canonical(url_canonical)
       .body(body)
       .alt_text(alt_text)
       .creator_id(creator.id)
@@ -263,8 +313,36 @@ impl Object for ApubPost {
       .language_id(language_id)
       .build();
 
+    let previous = Post::read_from_apub_id(&mut context.pool(), page.id.inner().clone()).await?;
     let timestamp = page.updated.or(page.published).unwrap_or_else(naive_now);
     let post = Post::insert_apub(&mut context.pool(), timestamp, &form).await?;
+    PostGalleryImage::replace(&mut context.pool(), post.id, gallery).await?;
+    // The hashtag with the community name, which Lemmy adds for Mastodon, links to the post itself
+    let hashtags = page
+      .tag
+      .iter()
+      .filter_map(|t| match t {
+        HashtagOrValue::Hashtag(h) if &h.href != page.id.inner() => Some(h),
+        _ => None,
+      })
+      .take(MAX_HASHTAGS_PER_POST)
+      .flat_map(|h| scrape_text_for_hashtags(&format!("#{}", h.name.trim_start_matches('#'))))
+      .collect();
+    update_post_hashtags(&post, hashtags, &mut context.pool()).await?;
+    // Automod and slur filter actions apply to new posts and edits, like for local ones. Fetches of
+    // an unchanged post are skipped. Automod rules act only once on the same post, and the system
+    // account only reports it once.
+    let is_new_or_edited = previous.map_or(true, |p| {
+      p.name != post.name || p.body != post.body || p.url != post.url
+    });
+    if is_new_or_edited {
+      if let Err(e) = run_automod_for_post(&post, AutomodTrigger::Write, context).await {
+        tracing::warn!("Failed to run automod for post {}: {e}", post.ap_id);
+      }
+      if let Err(e) = slur_filter_matches.apply_to_post(&post, context).await {
+        tracing::warn!("Failed to apply slur filters to post {}: {e}", post.ap_id);
+      }
+    }
     let post_ = post.clone();
     let context_ = context.reset_request_count();
 
@@ -288,7 +366,7 @@ mod tests {
     },
     protocol::tests::file_to_json_object,
   };
-  use lemmy_db_schema::source::site::Site;
+  use lemmy_db_schema::source::{post_gallery_image::PostGalleryImageForm, site::Site};
   use pretty_assertions::assert_eq;
   use serial_test::serial;
 
@@ -310,6 +388,12 @@ mod tests {
     assert_eq!(post.body.as_ref().map(std::string::String::len), Some(45));
     assert!(!post.locked);
     assert!(!post.featured_community);
+    let gallery = PostGalleryImage::list_for_post(&mut context.pool(), post.id).await?;
+    assert_eq!(2, gallery.len());
+    assert_eq!(
+      Some("Main bridge"),
+      gallery.first().and_then(|g| g.caption.as_deref())
+    );
     assert_eq!(context.request_count(), 0);
 
     Post::delete(&mut context.pool(), post.id).await?;
@@ -319,6 +403,64 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_gallery_without_url_round_trip() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let (person, site) = parse_lemmy_person(&context).await?;
+    let community = parse_lemmy_community(&context).await?;
+
+    let form = PostInsertForm::builder()
+      .name("Gallery without url".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .ap_id(Some(
+        Url::parse("https://enterprise.lemmy.ml/post/55144")?.into(),
+      ))
+      .local(Some(false))
+      .build();
+    let post = Post::create(&mut context.pool(), &form).await?;
+    let image = |url: &str, caption: Option<&str>| PostGalleryImageForm {
+      url: Url::parse(url).expect("parse url").into(),
+      caption: caption.map(ToString::to_string),
+      alt_text: None,
+    };
+    let gallery = vec![
+      image(
+        "https://enterprise.lemmy.ml/pictrs/image/1.jpg",
+        Some("First"),
+      ),
+      image("https://enterprise.lemmy.ml/pictrs/image/2.jpg", None),
+    ];
+    PostGalleryImage::replace(&mut context.pool(), post.id, gallery).await?;
+
+    let page = ApubPost(post.clone()).into_json(&context).await?;
+    assert_eq!(2, page.attachment.len());
+    PostGalleryImage::replace(&mut context.pool(), post.id, vec![]).await?;
+
+    let received = ApubPost::from_json(page, &context).await?;
+    assert_eq!(post.id, received.id);
+    assert_eq!(None, received.url);
+    let gallery = PostGalleryImage::list_for_post(&mut context.pool(), post.id).await?;
+    assert_eq!(
+      vec![
+        "https://enterprise.lemmy.ml/pictrs/image/1.jpg",
+        "https://enterprise.lemmy.ml/pictrs/image/2.jpg"
+      ],
+      gallery.iter().map(|g| g.url.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+      Some("First"),
+      gallery.first().and_then(|g| g.caption.as_deref())
+    );
+
+    Post::delete(&mut context.pool(), post.id).await?;
+    Person::delete(&mut context.pool(), person.id).await?;
+    Community::delete(&mut context.pool(), community.id).await?;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_convert_mastodon_post_title() -> LemmyResult<()> {
@@ -332,6 +474,11 @@ mod tests {
     let post = ApubPost::from_json(json, &context).await?;
 
     assert_eq!(post.name, "Variable never resetting at refresh");
+    let hashtags = PostHashtag::list_for_post(&mut context.pool(), post.id).await?;
+    assert_eq!(
+      vec!["perchance"],
+      hashtags.iter().map(|h| h.name.as_str()).collect::<Vec<_>>()
+    );
 
     Post::delete(&mut context.pool(), post.id).await?;
     Person::delete(&mut context.pool(), person.id).await?;
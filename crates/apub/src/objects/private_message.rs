@@ -1,9 +1,9 @@
 use super::verify_is_remote_object;
 use crate::{
   check_apub_id_valid_with_strictness,
-  objects::read_from_string_or_source,
+  objects::{person::ApubPerson, read_from_string_or_source},
   protocol::{
-    objects::chat_message::{ChatMessage, ChatMessageType},
+    objects::chat_message::{ChatMessage, ChatMessageType, KeyEnvelope},
     Source,
   },
 };
@@ -22,6 +22,8 @@ use lemmy_db_schema::{
     local_site::LocalSite,
     person::Person,
     private_message::{PrivateMessage, PrivateMessageInsertForm},
+    private_message_conversation::{PrivateMessageConversation, MAX_CONVERSATION_PARTICIPANTS},
+    private_message_key_envelope::{PrivateMessageKeyEnvelope, PrivateMessageKeyEnvelopeForm},
   },
   traits::Crud,
   utils::naive_now,
@@ -30,7 +32,7 @@ use lemmy_utils::{
   error::{LemmyError, LemmyErrorType, LemmyResult},
   utils::markdown::markdown_to_html,
 };
-use std::ops::Deref;
+use std::{collections::HashMap, ops::Deref};
 use url::Url;
 
 #[derive(Clone, Debug)]
@@ -49,6 +51,32 @@ impl From<PrivateMessage> for ApubPrivateMessage {
   }
 }
 
+impl ApubPrivateMessage {
+  /// Everyone in the conversation except the creator, starting with the main recipient.
+  pub(crate) async fn recipients(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<Vec<ApubPerson>> {
+    let participants =
+      PrivateMessageConversation::list_participants(&mut context.pool(), self.conversation_id)
+        .await?;
+    let mut recipient_ids = vec![self.recipient_id];
+    recipient_ids.extend(
+      participants
+        .into_iter()
+        .filter(|p| *p != self.creator_id && *p != self.recipient_id),
+    );
+    let mut recipients = vec![];
+    for recipient_id in recipient_ids {
+      let recipient = Person::read(&mut context.pool(), recipient_id)
+        .await?
+        .ok_or(LemmyErrorType::CouldntFindPerson)?;
+      recipients.push(recipient.into());
+    }
+    Ok(recipients)
+  }
+}
+
 #[async_trait::async_trait]
 impl Object for ApubPrivateMessage {
   type DataType = LemmyContext;
@@ -83,21 +111,52 @@ impl Object for ApubPrivateMessage {
       .await?
       .ok_or(LemmyErrorType::CouldntFindPerson)?;
 
-    let recipient_id = self.recipient_id;
-    let recipient = Person::read(&mut context.pool(), recipient_id)
-      .await?
-      .ok_or(LemmyErrorType::CouldntFindPerson)?;
+    let recipients = self.recipients(context).await?;
+    let to = recipients
+      .iter()
+      .map(|r| r.actor_id.clone().into())
+      .collect();
+
+    // Encrypted content is passed on exactly as the client submitted it
+    let (content, media_type, source, key_envelopes) = if self.encrypted {
+      let key_envelopes = PrivateMessageKeyEnvelope::list_for_message(&mut context.pool(), self.id)
+        .await?
+        .into_iter()
+        .filter_map(|e| {
+          let person = if e.person_id == creator.id {
+            &creator.actor_id
+          } else {
+            &recipients.iter().find(|r| r.id == e.person_id)?.actor_id
+          };
+          Some(KeyEnvelope {
+            person: person.clone().into(),
+            device_id: e.device_id,
+            encrypted_key: e.encrypted_key,
+          })
+        })
+        .collect();
+      (self.content.clone(), None, None, key_envelopes)
+    } else {
+      (
+        markdown_to_html(&self.content),
+        Some(MediaTypeHtml::Html),
+        Some(Source::new(self.content.clone())),
+        vec![],
+      )
+    };
 
     let note = ChatMessage {
       r#type: ChatMessageType::ChatMessage,
       id: self.ap_id.clone().into(),
       attributed_to: creator.actor_id.into(),
-      to: [recipient.actor_id.into()],
-      content: markdown_to_html(&self.content),
-      media_type: Some(MediaTypeHtml::Html),
-      source: Some(Source::new(self.content.clone())),
+      to,
+      content,
+      media_type,
+      source,
       published: Some(self.published),
       updated: self.updated,
+      encrypted: self.encrypted.then_some(true),
+      key_envelopes,
     };
     Ok(note)
   }
@@ -129,28 +188,66 @@ impl Object for ApubPrivateMessage {
     context: &Data<Self::DataType>,
   ) -> LemmyResult<ApubPrivateMessage> {
     let creator = note.attributed_to.dereference(context).await?;
-    let recipient = note.to[0].dereference(context).await?;
-    check_person_block(creator.id, recipient.id, &mut context.pool()).await?;
+    if note.to.len() >= MAX_CONVERSATION_PARTICIPANTS {
+      Err(LemmyErrorType::TooManyRecipients)?
+    }
+    let mut recipient_ids = vec![];
+    let mut person_ids = HashMap::from([(creator.actor_id.inner().clone(), creator.id)]);
+    for recipient in &note.to {
+      let recipient = recipient.dereference(context).await?;
+      check_person_block(creator.id, recipient.id, &mut context.pool()).await?;
+      recipient_ids.push(recipient.id);
+      person_ids.insert(recipient.actor_id.inner().clone(), recipient.id);
+    }
+    let recipient_id = *recipient_ids
+      .first()
+      .ok_or(LemmyErrorType::CouldntFindPerson)?;
+    let conversation =
+      PrivateMessageConversation::read_or_create(&mut context.pool(), creator.id, &recipient_ids)
+        .await?;
 
     let local_site = LocalSite::read(&mut context.pool()).await.ok();
     let slur_regex = &local_site_opt_to_slur_regex(&local_site);
     let url_blocklist = get_url_blocklist(context).await?;
-    let content = read_from_string_or_source(&note.content, &None, &note.source);
-    let content = process_markdown(&content, slur_regex, &url_blocklist, context).await?;
+    let encrypted = note.encrypted.unwrap_or_default();
+    let content = if encrypted {
+      note.content
+    } else {
+      let content = read_from_string_or_source(&note.content, &None, &note.source);
+      process_markdown(&content, slur_regex, &url_blocklist, context).await?
+    };
 
     let form = PrivateMessageInsertForm {
       creator_id: creator.id,
-      recipient_id: recipient.id,
+      recipient_id,
       content,
       published: note.published.map(Into::into),
       updated: note.updated.map(Into::into),
       deleted: Some(false),
-      read: None,
       ap_id: Some(note.id.into()),
       local: Some(false),
+      conversation_id: Some(conversation.id),
+      encrypted: Some(encrypted),
     };
     let timestamp = note.updated.or(note.published).unwrap_or_else(naive_now);
     let pm = PrivateMessage::insert_apub(&mut context.pool(), timestamp, &form).await?;
+
+    if encrypted {
+      // Envelopes for people outside of the conversation are ignored
+      let forms: Vec<_> = note
+        .key_envelopes
+        .into_iter()
+        .filter_map(|e| {
+          Some(PrivateMessageKeyEnvelopeForm {
+            private_message_id: pm.id,
+            person_id: *person_ids.get(e.person.inner())?,
+            device_id: e.device_id,
+            encrypted_key: e.encrypted_key,
+          })
+        })
+        .collect();
+      PrivateMessageKeyEnvelope::replace_for_message(&mut context.pool(), pm.id, &forms).await?;
+    }
     Ok(pm.into())
   }
 }
@@ -219,6 +316,34 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_parse_encrypted_pm() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let url = Url::parse("https://enterprise.lemmy.ml/private_message/1622")?;
+    let data = prepare_comment_test(&url, &context).await?;
+    let json: ChatMessage =
+      file_to_json_object("assets/lemmy/objects/encrypted_chat_message.json")?;
+    ApubPrivateMessage::verify(&json, &url, &context).await?;
+    let pm = ApubPrivateMessage::from_json(json.clone(), &context).await?;
+
+    // The ciphertext is stored as is, without converting from html to markdown
+    assert!(pm.encrypted);
+    assert_eq!(json.content, pm.content);
+    let envelopes = PrivateMessageKeyEnvelope::list_for_message(&mut context.pool(), pm.id).await?;
+    assert_eq!(2, envelopes.len());
+
+    let pm_id = pm.id;
+    let to_apub = pm.into_json(&context).await?;
+    assert_eq!(None, to_apub.source);
+    assert_eq!(2, to_apub.key_envelopes.len());
+    assert_json_include!(actual: json, expected: to_apub);
+
+    PrivateMessage::delete(&mut context.pool(), pm_id).await?;
+    cleanup(data, &context).await?;
+    Ok(())
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_parse_pleroma_pm() -> LemmyResult<()> {
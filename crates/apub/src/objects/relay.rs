@@ -0,0 +1,147 @@
+use super::verify_is_remote_object;
+use crate::{check_apub_id_valid_with_strictness, protocol::objects::relay::Relay};
+use activitypub_federation::{
+  config::Data,
+  protocol::verification::verify_domains_match,
+  traits::{Actor, Object},
+};
+use chrono::{DateTime, Utc};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  source::{
+    instance::Instance,
+    relay::{Relay as DbRelay, RelayInsertForm},
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyResult},
+  LemmyErrorType,
+};
+use std::ops::Deref;
+use url::Url;
+
+#[derive(Clone, Debug)]
+pub struct ApubRelay(DbRelay);
+
+impl Deref for ApubRelay {
+  type Target = DbRelay;
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl From<DbRelay> for ApubRelay {
+  fn from(r: DbRelay) -> Self {
+    ApubRelay(r)
+  }
+}
+
+#[async_trait::async_trait]
+impl Object for ApubRelay {
+  type DataType = LemmyContext;
+  type Kind = Relay;
+  type Error = LemmyError;
+
+  fn last_refreshed_at(&self) -> Option<DateTime<Utc>> {
+    Some(self.last_refreshed_at)
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn read_from_id(object_id: Url, data: &Data<Self::DataType>) -> LemmyResult<Option<Self>> {
+    Ok(
+      DbRelay::read_from_apub_id(&mut data.pool(), &object_id.into())
+        .await?
+        .map(Into::into),
+    )
+  }
+
+  async fn delete(self, data: &Data<Self::DataType>) -> LemmyResult<()> {
+    DbRelay::delete(&mut data.pool(), self.id).await?;
+    Ok(())
+  }
+
+  /// Relays are always remote, so there is nothing to serve.
+  async fn into_json(self, _data: &Data<Self::DataType>) -> LemmyResult<Self::Kind> {
+    Err(LemmyErrorType::CouldntFindRelay.into())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(
+    apub: &Self::Kind,
+    expected_domain: &Url,
+    data: &Data<Self::DataType>,
+  ) -> LemmyResult<()> {
+    check_apub_id_valid_with_strictness(apub.id.inner(), true, data).await?;
+    verify_domains_match(expected_domain, apub.id.inner())?;
+    verify_is_remote_object(&apub.id, data)?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn from_json(apub: Self::Kind, context: &Data<Self::DataType>) -> LemmyResult<Self> {
+    let domain = apub
+      .id
+      .inner()
+      .domain()
+      .ok_or(LemmyErrorType::UrlWithoutDomain)?;
+    let instance = Instance::read_or_create(&mut context.pool(), domain.to_string()).await?;
+
+    let inbox = apub.endpoints.map(|e| e.shared_inbox).unwrap_or(apub.inbox);
+    let form = RelayInsertForm::builder()
+      .actor_id(apub.id.into())
+      .inbox_url(inbox.into())
+      .public_key(apub.public_key.public_key_pem)
+      .instance_id(instance.id)
+      .last_refreshed_at(Some(naive_now()))
+      .build();
+    Ok(DbRelay::create(&mut context.pool(), &form).await?.into())
+  }
+}
+
+impl Actor for ApubRelay {
+  fn id(&self) -> Url {
+    self.actor_id.inner().clone()
+  }
+
+  fn public_key_pem(&self) -> &str {
+    &self.public_key
+  }
+
+  fn private_key_pem(&self) -> Option<String> {
+    None
+  }
+
+  fn inbox(&self) -> Url {
+    self.inbox_url.clone().into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::protocol::tests::file_to_json_object;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_parse_litepub_relay() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let json: Relay = file_to_json_object("assets/litepub_relay/objects/relay.json")?;
+    let id = Url::parse("https://relay.example.com/actor")?;
+    ApubRelay::verify(&json, &id, &context).await?;
+    let relay = ApubRelay::from_json(json, &context).await?;
+
+    assert_eq!(
+      relay.inbox_url.inner().as_str(),
+      "https://relay.example.com/inbox"
+    );
+    assert!(!relay.follow_accepted);
+    assert_eq!(context.request_count(), 0);
+
+    Instance::delete(&mut context.pool(), relay.instance_id).await?;
+    Ok(())
+  }
+}
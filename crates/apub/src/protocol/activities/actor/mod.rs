@@ -0,0 +1,14 @@
+pub mod update;
+
+#[cfg(test)]
+mod tests {
+  use crate::protocol::{activities::actor::update::UpdateActor, tests::test_parse_lemmy_item};
+  use lemmy_utils::error::LemmyResult;
+
+  #[test]
+  fn test_parse_lemmy_update_actor() -> LemmyResult<()> {
+    test_parse_lemmy_item::<UpdateActor>("assets/lemmy/activities/actor/update_person.json")?;
+    test_parse_lemmy_item::<UpdateActor>("assets/lemmy/activities/actor/update_instance.json")?;
+    Ok(())
+  }
+}
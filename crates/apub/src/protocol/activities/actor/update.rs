@@ -0,0 +1,33 @@
+use crate::{
+  fetcher::site_or_community_or_user::SiteOrCommunityOrUser,
+  protocol::objects::{instance::Instance, person::Person},
+};
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::UpdateType,
+  protocol::helpers::deserialize_one_or_many,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Sent by a user or instance actor to update its profile, for example after rotating its keypair.
+/// Communities are updated with
+/// [UpdateCommunity](crate::protocol::activities::community::update::UpdateCommunity) instead.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateActor {
+  pub(crate) actor: ObjectId<SiteOrCommunityOrUser>,
+  #[serde(deserialize_with = "deserialize_one_or_many", default)]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: PersonOrInstance,
+  #[serde(rename = "type")]
+  pub(crate) kind: UpdateType,
+  pub(crate) id: Url,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PersonOrInstance {
+  Person(Box<Person>),
+  Instance(Box<Instance>),
+}
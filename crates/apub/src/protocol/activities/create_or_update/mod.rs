@@ -28,6 +28,9 @@ mod tests {
     test_parse_lemmy_item::<CreateOrUpdateChatMessage>(
       "assets/lemmy/activities/create_or_update/create_private_message.json",
     )?;
+    test_parse_lemmy_item::<CreateOrUpdateChatMessage>(
+      "assets/lemmy/activities/create_or_update/create_group_private_message.json",
+    )?;
     Ok(())
   }
 }
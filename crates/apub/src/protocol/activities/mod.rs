@@ -1,11 +1,14 @@
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
+pub mod actor;
 pub mod block;
 pub mod community;
 pub mod create_or_update;
 pub mod deletion;
 pub mod following;
+pub mod moving;
+pub mod relay;
 pub mod voting;
 
 #[derive(Clone, Debug, Display, Deserialize, Serialize, PartialEq, Eq)]
@@ -0,0 +1,23 @@
+pub mod move_actor;
+
+#[cfg(test)]
+mod tests {
+  use crate::protocol::{
+    activities::moving::move_actor::Move,
+    tests::{test_json, test_parse_lemmy_item},
+  };
+  use lemmy_utils::error::LemmyResult;
+
+  #[test]
+  fn test_parse_lemmy_move() -> LemmyResult<()> {
+    test_parse_lemmy_item::<Move>("assets/lemmy/activities/moving/move_person.json")?;
+    test_parse_lemmy_item::<Move>("assets/lemmy/activities/moving/move_community.json")?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_mastodon_move() -> LemmyResult<()> {
+    test_json::<Move>("assets/mastodon/activities/move.json")?;
+    Ok(())
+  }
+}
@@ -0,0 +1,23 @@
+use crate::fetcher::user_or_community::UserOrCommunity;
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::MoveType,
+  protocol::helpers::deserialize_one_or_many,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Announces that an actor has moved to a new account on another instance. The target must list
+/// the moved actor in its `alsoKnownAs`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Move {
+  pub(crate) actor: ObjectId<UserOrCommunity>,
+  #[serde(deserialize_with = "deserialize_one_or_many", default)]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: ObjectId<UserOrCommunity>,
+  pub(crate) target: ObjectId<UserOrCommunity>,
+  #[serde(rename = "type")]
+  pub(crate) kind: MoveType,
+  pub(crate) id: Url,
+}
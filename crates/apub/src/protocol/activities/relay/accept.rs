@@ -0,0 +1,17 @@
+use crate::{
+  objects::relay::ApubRelay,
+  protocol::{activities::relay::follow::RelayFollow, IdOrNestedObject},
+};
+use activitypub_federation::{fetch::object_id::ObjectId, kinds::activity::AcceptType};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptRelayFollow {
+  pub(crate) actor: ObjectId<ApubRelay>,
+  pub(crate) object: IdOrNestedObject<RelayFollow>,
+  #[serde(rename = "type")]
+  pub(crate) kind: AcceptType,
+  pub(crate) id: Url,
+}
@@ -0,0 +1,34 @@
+use crate::{
+  objects::{community::ApubCommunity, relay::ApubRelay},
+  protocol::{Id, IdOrNestedObject},
+};
+use activitypub_federation::{fetch::object_id::ObjectId, kinds::activity::AnnounceType};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Content which a relay forwards to its subscribers.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayAnnounce {
+  pub(crate) actor: ObjectId<ApubRelay>,
+  pub(crate) object: IdOrNestedObject<RelayedObject>,
+  #[serde(rename = "type")]
+  pub(crate) kind: AnnounceType,
+  pub(crate) id: Url,
+}
+
+/// Either an activity wrapping the content (eg the announce of a community), or the post or
+/// comment itself. Only the fields needed to find the content and its community are parsed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayedObject {
+  pub(crate) id: Url,
+  pub(crate) object: Option<Box<IdOrNestedObject<RelayedObject>>>,
+  pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+}
+
+impl Id for RelayedObject {
+  fn object_id(&self) -> &Url {
+    &self.id
+  }
+}
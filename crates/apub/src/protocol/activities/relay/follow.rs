@@ -0,0 +1,24 @@
+use crate::{
+  objects::{instance::ApubSite, relay::ApubRelay},
+  protocol::Id,
+};
+use activitypub_federation::{fetch::object_id::ObjectId, kinds::activity::FollowType};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Subscribes the local site actor to a relay, in the way LitePub relays expect it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayFollow {
+  pub(crate) actor: ObjectId<ApubSite>,
+  pub(crate) object: ObjectId<ApubRelay>,
+  #[serde(rename = "type")]
+  pub(crate) kind: FollowType,
+  pub(crate) id: Url,
+}
+
+impl Id for RelayFollow {
+  fn object_id(&self) -> &Url {
+    &self.id
+  }
+}
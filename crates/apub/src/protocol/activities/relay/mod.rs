@@ -0,0 +1,54 @@
+pub mod accept;
+pub mod announce;
+pub mod follow;
+pub mod undo_follow;
+
+#[cfg(test)]
+mod tests {
+  use crate::protocol::{
+    activities::relay::{
+      accept::AcceptRelayFollow,
+      announce::RelayAnnounce,
+      follow::RelayFollow,
+      undo_follow::UndoRelayFollow,
+    },
+    tests::{test_json, test_parse_lemmy_item},
+    IdOrNestedObject,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_parse_lemmy_relay_follow() -> LemmyResult<()> {
+    test_parse_lemmy_item::<RelayFollow>("assets/lemmy/activities/relay/follow.json")?;
+    test_parse_lemmy_item::<UndoRelayFollow>("assets/lemmy/activities/relay/undo_follow.json")?;
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_litepub_relay_activities() -> LemmyResult<()> {
+    let accept =
+      test_json::<AcceptRelayFollow>("assets/litepub_relay/activities/accept_follow.json")?;
+    assert!(matches!(
+      accept.inner().object,
+      IdOrNestedObject::NestedObject(_)
+    ));
+
+    let announce = test_json::<RelayAnnounce>("assets/litepub_relay/activities/announce.json")?;
+    let IdOrNestedObject::NestedObject(community_announce) = &announce.inner().object else {
+      panic!("relayed announce should be embedded");
+    };
+    let Some(IdOrNestedObject::NestedObject(create)) = community_announce.object.as_deref() else {
+      panic!("relayed create should be embedded");
+    };
+    assert_eq!(
+      "https://enterprise.lemmy.ml/c/tenforward",
+      create
+        .audience
+        .as_ref()
+        .map(|a| a.inner().as_str())
+        .unwrap_or_default()
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,14 @@
+use crate::{objects::instance::ApubSite, protocol::activities::relay::follow::RelayFollow};
+use activitypub_federation::{fetch::object_id::ObjectId, kinds::activity::UndoType};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoRelayFollow {
+  pub(crate) actor: ObjectId<ApubSite>,
+  pub(crate) object: RelayFollow,
+  #[serde(rename = "type")]
+  pub(crate) kind: UndoType,
+  pub(crate) id: Url,
+}
@@ -1,8 +1,10 @@
 use crate::protocol::activities::community::announce::AnnounceActivity;
-use activitypub_federation::kinds::collection::OrderedCollectionType;
+use activitypub_federation::kinds::collection::{OrderedCollectionPageType, OrderedCollectionType};
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use url::Url;
 
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupOutbox {
@@ -10,4 +12,19 @@ pub struct GroupOutbox {
   pub(crate) id: Url,
   pub(crate) total_items: i32,
   pub(crate) ordered_items: Vec<AnnounceActivity>,
+  /// First page of the full community history
+  pub(crate) first: Option<Url>,
+}
+
+/// A page of the community history, with posts from newest to oldest. Each post is followed by
+/// its comments.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupOutboxPage {
+  pub(crate) r#type: OrderedCollectionPageType,
+  pub(crate) id: Url,
+  pub(crate) part_of: Url,
+  pub(crate) next: Option<Url>,
+  pub(crate) ordered_items: Vec<AnnounceActivity>,
 }
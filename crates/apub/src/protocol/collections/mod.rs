@@ -12,7 +12,7 @@ mod tests {
       group_featured::GroupFeatured,
       group_followers::GroupFollowers,
       group_moderators::GroupModerators,
-      group_outbox::GroupOutbox,
+      group_outbox::{GroupOutbox, GroupOutboxPage},
     },
     tests::{test_json, test_parse_lemmy_item},
   };
@@ -25,6 +25,9 @@ mod tests {
     let outbox =
       test_parse_lemmy_item::<GroupOutbox>("assets/lemmy/collections/group_outbox.json")?;
     assert_eq!(outbox.ordered_items.len() as i32, outbox.total_items);
+    let outbox_page =
+      test_parse_lemmy_item::<GroupOutboxPage>("assets/lemmy/collections/group_outbox_page.json")?;
+    assert_eq!(2, outbox_page.ordered_items.len());
     test_parse_lemmy_item::<GroupFeatured>("assets/lemmy/collections/group_featured_posts.json")?;
     test_parse_lemmy_item::<GroupModerators>("assets/lemmy/collections/group_moderators.json")?;
     test_parse_lemmy_item::<EmptyOutbox>("assets/lemmy/collections/person_outbox.json")?;
@@ -5,7 +5,7 @@ use crate::{
 use activitypub_federation::{
   fetch::object_id::ObjectId,
   protocol::{
-    helpers::{deserialize_one, deserialize_skip_error},
+    helpers::{deserialize_one_or_many, deserialize_skip_error},
     values::MediaTypeHtml,
   },
 };
@@ -20,8 +20,9 @@ pub struct ChatMessage {
   pub(crate) r#type: ChatMessageType,
   pub(crate) id: ObjectId<ApubPrivateMessage>,
   pub(crate) attributed_to: ObjectId<ApubPerson>,
-  #[serde(deserialize_with = "deserialize_one")]
-  pub(crate) to: [ObjectId<ApubPerson>; 1],
+  /// All participants of the conversation except the creator. Lemmy puts the main recipient first.
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) to: Vec<ObjectId<ApubPerson>>,
   pub(crate) content: String,
 
   pub(crate) media_type: Option<MediaTypeHtml>,
@@ -29,6 +30,19 @@ pub struct ChatMessage {
   pub(crate) source: Option<Source>,
   pub(crate) published: Option<DateTime<Utc>>,
   pub(crate) updated: Option<DateTime<Utc>>,
+  /// If true, the content is ciphertext which can only be decrypted with one of the key envelopes
+  pub(crate) encrypted: Option<bool>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) key_envelopes: Vec<KeyEnvelope>,
+}
+
+/// The message key, encrypted with the public key of one device of a participant
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyEnvelope {
+  pub(crate) person: ObjectId<ApubPerson>,
+  pub(crate) device_id: String,
+  pub(crate) encrypted_key: String,
 }
 
 /// https://docs.pleroma.social/backend/development/ap_extensions/#chatmessages
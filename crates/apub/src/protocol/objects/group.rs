@@ -19,7 +19,7 @@ use activitypub_federation::{
   fetch::{collection_id::CollectionId, object_id::ObjectId},
   kinds::actor::GroupType,
   protocol::{
-    helpers::deserialize_skip_error,
+    helpers::{deserialize_one_or_many, deserialize_skip_error},
     public_key::PublicKey,
     verification::verify_domains_match,
   },
@@ -70,6 +70,12 @@ pub struct Group {
   pub(crate) language: Vec<LanguageTag>,
   pub(crate) published: Option<DateTime<Utc>>,
   pub(crate) updated: Option<DateTime<Utc>>,
+  /// communities which were imported into this one, and which are allowed to move here
+  #[serde(deserialize_with = "deserialize_one_or_many", default)]
+  pub(crate) also_known_as: Vec<Url>,
+  /// set if the community was moved to another instance
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) moved_to: Option<ObjectId<ApubCommunity>>,
 }
 
 impl Group {
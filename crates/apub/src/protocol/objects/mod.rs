@@ -14,6 +14,7 @@ pub(crate) mod instance;
 pub(crate) mod note;
 pub(crate) mod page;
 pub(crate) mod person;
+pub(crate) mod relay;
 pub(crate) mod tombstone;
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -101,7 +102,7 @@ mod tests {
       group::Group,
       instance::Instance,
       note::Note,
-      page::Page,
+      page::{Attachment, Page},
       person::Person,
       tombstone::Tombstone,
     },
@@ -117,6 +118,7 @@ mod tests {
     test_parse_lemmy_item::<Page>("assets/lemmy/objects/page.json")?;
     test_parse_lemmy_item::<Note>("assets/lemmy/objects/note.json")?;
     test_parse_lemmy_item::<ChatMessage>("assets/lemmy/objects/chat_message.json")?;
+    test_parse_lemmy_item::<ChatMessage>("assets/lemmy/objects/encrypted_chat_message.json")?;
     test_parse_lemmy_item::<Tombstone>("assets/lemmy/objects/tombstone.json")?;
     Ok(())
   }
@@ -144,6 +146,25 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_parse_objects_mbin() -> LemmyResult<()> {
+    let page = test_json::<Page>("assets/mbin/objects/page.json")?;
+    let gallery = page
+      .inner()
+      .attachment
+      .iter()
+      .skip(1)
+      .cloned()
+      .filter_map(Attachment::gallery_image)
+      .collect::<Vec<_>>();
+    assert_eq!(1, gallery.len());
+    assert_eq!(
+      Some("A pond with ducks"),
+      gallery.first().and_then(|g| g.alt_text.as_deref())
+    );
+    Ok(())
+  }
+
   #[test]
   fn test_parse_objects_lotide() -> LemmyResult<()> {
     test_json::<Group>("assets/lotide/objects/group.json")?;
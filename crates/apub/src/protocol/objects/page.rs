@@ -19,9 +19,11 @@ use activitypub_federation::{
 };
 use chrono::{DateTime, Utc};
 use itertools::Itertools;
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, post::GalleryImage};
+use lemmy_db_schema::source::post_gallery_image::PostGalleryImage;
 use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use serde_with::skip_serializing_none;
 use url::Url;
 
@@ -55,8 +57,9 @@ pub struct Page {
   pub(crate) media_type: Option<MediaTypeMarkdownOrHtml>,
   #[serde(deserialize_with = "deserialize_skip_error", default)]
   pub(crate) source: Option<Source>,
-  /// most software uses array type for attachment field, so we do the same. nevertheless, we only
-  /// use the first item
+  /// most software uses array type for attachment field, so we do the same. the first item is the
+  /// post url, if any. further images are the gallery, which Lemmy marks explicitly so that posts
+  /// without url keep all of their gallery images
   #[serde(default)]
   pub(crate) attachment: Vec<Attachment>,
   pub(crate) image: Option<ImageObject>,
@@ -65,8 +68,9 @@ pub struct Page {
   pub(crate) updated: Option<DateTime<Utc>>,
   pub(crate) language: Option<LanguageTag>,
   pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+  /// Hashtags, mixed with other tags like mentions which are ignored
   #[serde(deserialize_with = "deserialize_skip_error", default)]
-  pub(crate) tag: Vec<Hashtag>,
+  pub(crate) tag: Vec<HashtagOrValue>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -85,6 +89,12 @@ pub(crate) struct Image {
   url: Url,
   /// Used for alt_text
   name: Option<String>,
+  /// Used for the caption of gallery images
+  #[serde(skip_serializing_if = "Option::is_none")]
+  summary: Option<String>,
+  /// Set by Lemmy for gallery images, to tell them apart from an image post url
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  gallery: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -93,6 +103,8 @@ pub(crate) struct Document {
   #[serde(rename = "type")]
   kind: DocumentType,
   url: Url,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  media_type: Option<String>,
   /// Used for alt_text
   name: Option<String>,
 }
@@ -124,6 +136,37 @@ impl Attachment {
       _ => None,
     }
   }
+
+  /// Whether this is an image explicitly marked as part of the gallery, as sent by Lemmy.
+  pub(crate) fn is_marked_gallery_image(&self) -> bool {
+    matches!(self, Attachment::Image(i) if i.gallery)
+  }
+
+  /// Converts image attachments to gallery images. Without explicitly marked gallery images, the
+  /// ones after the first attachment are the gallery, which covers multi-image posts from Mastodon
+  /// and Kbin/Mbin.
+  pub(crate) fn gallery_image(self) -> Option<GalleryImage> {
+    match self {
+      Attachment::Image(i) => Some(GalleryImage {
+        url: i.url.into(),
+        caption: i.summary,
+        alt_text: i.name,
+      }),
+      Attachment::Document(d)
+        if d
+          .media_type
+          .as_ref()
+          .is_some_and(|m| m.starts_with("image")) =>
+      {
+        Some(GalleryImage {
+          url: d.url.into(),
+          caption: None,
+          alt_text: d.name,
+        })
+      }
+      _ => None,
+    }
+  }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -154,6 +197,13 @@ pub enum HashtagType {
   Hashtag,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum HashtagOrValue {
+  Hashtag(Hashtag),
+  Value(Value),
+}
+
 impl Page {
   pub(crate) fn creator(&self) -> LemmyResult<ObjectId<ApubPerson>> {
     match &self.attributed_to {
@@ -176,6 +226,8 @@ impl Attachment {
         kind: Default::default(),
         url,
         name: alt_text,
+        summary: None,
+        gallery: false,
       })
     } else {
       Attachment::Link(Link {
@@ -185,6 +237,17 @@ impl Attachment {
       })
     }
   }
+
+  /// Creates an attachment for an image of the post gallery.
+  pub(crate) fn new_gallery_image(image: PostGalleryImage) -> Attachment {
+    Attachment::Image(Image {
+      kind: Default::default(),
+      url: image.url.into(),
+      name: image.alt_text,
+      summary: image.caption,
+      gallery: true,
+    })
+  }
 }
 
 // Used for community outbox, so that it can be compatible with Pleroma/Mastodon.
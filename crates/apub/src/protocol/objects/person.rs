@@ -4,7 +4,10 @@ use crate::{
 };
 use activitypub_federation::{
   fetch::object_id::ObjectId,
-  protocol::{helpers::deserialize_skip_error, public_key::PublicKey},
+  protocol::{
+    helpers::{deserialize_one_or_many, deserialize_skip_error},
+    public_key::PublicKey,
+  },
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -46,4 +49,20 @@ pub struct Person {
   pub(crate) endpoints: Option<Endpoints>,
   pub(crate) published: Option<DateTime<Utc>>,
   pub(crate) updated: Option<DateTime<Utc>>,
+  /// previous accounts of the same user, which are allowed to move to this account
+  #[serde(deserialize_with = "deserialize_one_or_many", default)]
+  pub(crate) also_known_as: Vec<Url>,
+  /// set if the account was moved to another instance
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) moved_to: Option<ObjectId<ApubPerson>>,
+  /// public keys of the user's devices, for end-to-end encrypted private messages
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) device_keys: Vec<DeviceKey>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceKey {
+  pub(crate) device_id: String,
+  pub(crate) public_key: String,
 }
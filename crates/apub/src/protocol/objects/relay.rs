@@ -0,0 +1,26 @@
+use crate::{objects::relay::ApubRelay, protocol::objects::Endpoints};
+use activitypub_federation::{fetch::object_id::ObjectId, protocol::public_key::PublicKey};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum RelayTypes {
+  Application,
+  Service,
+  Person,
+}
+
+/// The actor of a LitePub or Mastodon style relay.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Relay {
+  #[serde(rename = "type")]
+  pub(crate) kind: RelayTypes,
+  pub(crate) id: ObjectId<ApubRelay>,
+  pub(crate) preferred_username: Option<String>,
+  pub(crate) inbox: Url,
+  pub(crate) endpoints: Option<Endpoints>,
+  pub(crate) public_key: PublicKey,
+}
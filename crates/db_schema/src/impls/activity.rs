@@ -39,6 +39,16 @@ impl SentActivity {
     let conn = &mut get_conn(pool).await?;
     sent_activity.find(object_id).first(conn).await.optional()
   }
+
+  /// Returns the id of the most recent activity, or `None` if nothing was sent yet.
+  pub async fn read_latest_id(pool: &mut DbPool<'_>) -> Result<Option<ActivityId>, Error> {
+    use crate::schema::sent_activity::dsl::{id, sent_activity};
+    let conn = &mut get_conn(pool).await?;
+    sent_activity
+      .select(diesel::dsl::max(id))
+      .get_result(conn)
+      .await
+  }
 }
 
 impl ReceivedActivity {
@@ -62,6 +72,14 @@ impl ReceivedActivity {
       ))
     }
   }
+
+  pub async fn delete(pool: &mut DbPool<'_>, ap_id_: &DbUrl) -> Result<usize, Error> {
+    use crate::schema::received_activity::dsl::received_activity;
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(received_activity.find(ap_id_))
+      .execute(conn)
+      .await
+  }
 }
 
 #[cfg(test)]
@@ -0,0 +1,118 @@
+use crate::{
+  newtypes::DbUrl,
+  schema::actor_previous_key,
+  source::actor_previous_key::{ActorPreviousKey, ActorPreviousKeyForm},
+  utils::{get_conn, now, DbPool},
+};
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::{insert_into, sql},
+  result::Error,
+  sql_types::Bool,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl ActorPreviousKey {
+  /// Stores the previous key of an actor after it was rotated. Only the most recent previous key
+  /// is kept.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &ActorPreviousKeyForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(actor_previous_key::table)
+      .values(form)
+      .on_conflict(actor_previous_key::actor_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Returns the previous key of the actor, if it hasn't expired yet.
+  pub async fn read_valid(pool: &mut DbPool<'_>, actor_id: &DbUrl) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    actor_previous_key::table
+      .find(actor_id)
+      .filter(actor_previous_key::expires.gt(now()))
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  /// Time when a local actor last rotated its keypair. Used to notice that cached private keys
+  /// need to be reloaded.
+  pub async fn latest_local_rotation(
+    pool: &mut DbPool<'_>,
+  ) -> Result<Option<DateTime<Utc>>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    actor_previous_key::table
+      .filter(sql::<Bool>("actor_id LIKE r.local_url('/%')"))
+      .select(diesel::dsl::max(actor_previous_key::published))
+      .get_result(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    schema::actor_previous_key,
+    source::actor_previous_key::{ActorPreviousKey, ActorPreviousKeyForm},
+    utils::{build_db_pool_for_tests, get_conn},
+  };
+  use chrono::{Duration, Utc};
+  use diesel_async::RunQueryDsl;
+  use lemmy_utils::{error::LemmyResult, settings::SETTINGS};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_previous_key() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let actor_id = Url::parse("https://example.com/u/rotated")?.into();
+    let form = ActorPreviousKeyForm {
+      actor_id: Url::parse("https://example.com/u/rotated")?.into(),
+      public_key: "old key".to_string(),
+      expires: Utc::now() + Duration::days(1),
+    };
+    ActorPreviousKey::upsert(pool, &form).await?;
+    let key = ActorPreviousKey::read_valid(pool, &actor_id).await?;
+    assert_eq!(Some("old key".to_string()), key.map(|k| k.public_key));
+
+    // An expired key is not valid anymore
+    let form = ActorPreviousKeyForm {
+      public_key: "older key".to_string(),
+      expires: Utc::now() - Duration::days(1),
+      ..form
+    };
+    ActorPreviousKey::upsert(pool, &form).await?;
+    assert_eq!(None, ActorPreviousKey::read_valid(pool, &actor_id).await?);
+
+    // Only rotations of local actors are considered
+    assert_eq!(None, ActorPreviousKey::latest_local_rotation(pool).await?);
+    let local_actor_id = format!("{}/u/rotated", SETTINGS.get_protocol_and_hostname());
+    let form = ActorPreviousKeyForm {
+      actor_id: Url::parse(&local_actor_id)?.into(),
+      ..form
+    };
+    let local_key = ActorPreviousKey::upsert(pool, &form).await?;
+    assert_eq!(
+      Some(local_key.published),
+      ActorPreviousKey::latest_local_rotation(pool).await?
+    );
+
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(actor_previous_key::table)
+      .execute(conn)
+      .await?;
+    Ok(())
+  }
+}
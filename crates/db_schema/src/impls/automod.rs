@@ -0,0 +1,157 @@
+use crate::{
+  newtypes::{AutomodRuleId, CommunityId},
+  schema::automod_rule,
+  source::automod::{AutomodRule, AutomodRuleInsertForm, AutomodRuleUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, BoolExpressionMethods, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for AutomodRule {
+  type InsertForm = AutomodRuleInsertForm;
+  type UpdateForm = AutomodRuleUpdateForm;
+  type IdType = AutomodRuleId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(automod_rule::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    rule_id: AutomodRuleId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(automod_rule::table.find(rule_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl AutomodRule {
+  /// Lists the rules of a single community, or the site-wide rules if no community is given.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    for_community_id: Option<CommunityId>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = automod_rule::table.into_boxed();
+    query = match for_community_id {
+      Some(for_community_id) => query.filter(automod_rule::community_id.eq(for_community_id)),
+      None => query.filter(automod_rule::community_id.is_null()),
+    };
+    query.order_by(automod_rule::id).load::<Self>(conn).await
+  }
+
+  /// The enabled rules which apply to content in the given community, site-wide rules first.
+  pub async fn list_enabled_for_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    automod_rule::table
+      .filter(automod_rule::enabled)
+      .filter(
+        automod_rule::community_id
+          .is_null()
+          .or(automod_rule::community_id.eq(for_community_id)),
+      )
+      .order_by((automod_rule::community_id.is_not_null(), automod_rule::id))
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    source::{
+      automod::{AutomodRule, AutomodRuleInsertForm, AutomodRuleUpdateForm},
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+    AutomodAction,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_list_enabled_for_community() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "automod_rule_mod");
+    let inserted_person = Person::create(pool, &new_person).await?;
+
+    let mut communities = vec![];
+    for name in ["automod_community_1", "automod_community_2"] {
+      let form = CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      communities.push(Community::create(pool, &form).await?);
+    }
+
+    let site_rule_form = AutomodRuleInsertForm::builder()
+      .creator_id(inserted_person.id)
+      .name("no spam domains".to_string())
+      .domain(Some("spam.example".to_string()))
+      .action(AutomodAction::Remove)
+      .build();
+    let site_rule = AutomodRule::create(pool, &site_rule_form).await?;
+
+    let community_rule_form = AutomodRuleInsertForm::builder()
+      .community_id(Some(communities[0].id))
+      .creator_id(inserted_person.id)
+      .name("new accounts".to_string())
+      .max_account_age_days(Some(2))
+      .action(AutomodAction::Hold)
+      .build();
+    let community_rule = AutomodRule::create(pool, &community_rule_form).await?;
+
+    // Site-wide rules come first, then community rules
+    let rules = AutomodRule::list_enabled_for_community(pool, communities[0].id).await?;
+    assert_eq!(vec![site_rule.clone(), community_rule.clone()], rules);
+
+    // Rules of other communities are not included
+    let rules = AutomodRule::list_enabled_for_community(pool, communities[1].id).await?;
+    assert_eq!(vec![site_rule.clone()], rules);
+
+    // Disabled rules are skipped
+    let disable_form = AutomodRuleUpdateForm {
+      enabled: Some(false),
+      ..Default::default()
+    };
+    AutomodRule::update(pool, site_rule.id, &disable_form).await?;
+    let rules = AutomodRule::list_enabled_for_community(pool, communities[0].id).await?;
+    assert_eq!(vec![community_rule.clone()], rules);
+
+    // Listing for management returns disabled rules too, but doesn't mix scopes
+    let site_rules = AutomodRule::list(pool, None).await?;
+    assert_eq!(1, site_rules.len());
+    assert!(!site_rules[0].enabled);
+    let community_rules = AutomodRule::list(pool, Some(communities[0].id)).await?;
+    assert_eq!(vec![community_rule], community_rules);
+
+    Instance::delete(pool, inserted_instance.id).await?;
+
+    Ok(())
+  }
+}
@@ -1,21 +1,31 @@
 use crate::{
   diesel::{DecoratableTarget, OptionalExtension},
-  newtypes::{CommentId, DbUrl, PersonId},
-  schema::comment,
-  source::comment::{
-    Comment,
-    CommentInsertForm,
-    CommentLike,
-    CommentLikeForm,
-    CommentSaved,
-    CommentSavedForm,
-    CommentUpdateForm,
+  newtypes::{CommentId, CommunityId, DbUrl, PersonId, PostId},
+  schema::{comment, person, post},
+  source::{
+    comment::{
+      Comment,
+      CommentInsertForm,
+      CommentLike,
+      CommentLikeForm,
+      CommentSaved,
+      CommentSavedForm,
+      CommentUpdateForm,
+    },
+    person::Person,
   },
   traits::{Crud, Likeable, Saveable},
   utils::{functions::coalesce, get_conn, naive_now, DbPool, DELETED_REPLACEMENT_TEXT},
 };
 use chrono::{DateTime, Utc};
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel::{
+  dsl::{insert_into, sql},
+  result::Error,
+  sql_types::{Array, BigInt, Bool, Integer},
+  BoolExpressionMethods,
+  ExpressionMethods,
+  QueryDsl,
+};
 use diesel_async::RunQueryDsl;
 use diesel_ltree::Ltree;
 use url::Url;
@@ -99,6 +109,69 @@ impl Comment {
       .optional()
   }
 
+  /// When the person last commented in the post, including deleted and removed comments. Used
+  /// for community slow mode.
+  pub async fn latest_published_by_creator_in_post(
+    pool: &mut DbPool<'_>,
+    for_creator_id: PersonId,
+    for_post_id: PostId,
+  ) -> Result<Option<DateTime<Utc>>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    comment::table
+      .filter(comment::creator_id.eq(for_creator_id))
+      .filter(comment::post_id.eq(for_post_id))
+      .select(diesel::dsl::max(comment::published))
+      .first(conn)
+      .await
+  }
+
+  /// When the person last commented anywhere in the community, including deleted and removed
+  /// comments. Used for community slow mode.
+  pub async fn latest_published_by_creator_in_community(
+    pool: &mut DbPool<'_>,
+    for_creator_id: PersonId,
+    for_community_id: CommunityId,
+  ) -> Result<Option<DateTime<Utc>>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    comment::table
+      .inner_join(post::table)
+      .filter(comment::creator_id.eq(for_creator_id))
+      .filter(post::community_id.eq(for_community_id))
+      .select(diesel::dsl::max(comment::published))
+      .first(conn)
+      .await
+  }
+
+  /// Reads the oldest comments of the given posts together with their creators, at most
+  /// `limit_per_post` for each post. Deleted and removed comments are left out. Used for pages of
+  /// the community outbox, which include entire threads.
+  pub async fn list_for_posts_with_creator(
+    pool: &mut DbPool<'_>,
+    post_ids: Vec<PostId>,
+    limit_per_post: i64,
+  ) -> Result<Vec<(Self, Person)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let visible = comment::deleted.eq(false).and(comment::removed.eq(false));
+    // Ranks the comments of each post by age, to read no more than the limit for each post
+    let within_limit = sql::<Bool>(
+      "comment.id IN (SELECT id FROM (SELECT id, row_number() OVER (PARTITION BY post_id ORDER BY \
+       published) AS rank FROM comment WHERE NOT deleted AND NOT removed AND post_id = ANY(",
+    )
+    .bind::<Array<Integer>, _>(post_ids.clone())
+    .sql(")) AS ranked WHERE rank <= ")
+    .bind::<BigInt, _>(limit_per_post)
+    .sql(")");
+    comment::table
+      .inner_join(person::table)
+      .filter(comment::post_id.eq_any(post_ids))
+      .filter(visible)
+      .filter(within_limit)
+      .order_by((comment::post_id, comment::published))
+      .select((comment::all_columns, person::all_columns))
+      .load(conn)
+      .await
+  }
+
   pub fn parent_comment_id(&self) -> Option<CommentId> {
     let mut ltree_split: Vec<&str> = self.path.0.split('.').collect();
     ltree_split.remove(0); // The first is always 0
@@ -341,6 +414,16 @@ mod tests {
       .await
       .unwrap()
       .unwrap();
+    let latest_in_community = Comment::latest_published_by_creator_in_community(
+      pool,
+      inserted_person.id,
+      inserted_community.id,
+    )
+    .await
+    .unwrap();
+    let oldest_comments = Comment::list_for_posts_with_creator(pool, vec![inserted_post.id], 1)
+      .await
+      .unwrap();
     let like_removed = CommentLike::remove(pool, inserted_person.id, inserted_comment.id)
       .await
       .unwrap();
@@ -367,6 +450,14 @@ mod tests {
       format!("0.{}.{}", expected_comment.id, inserted_child_comment.id),
       inserted_child_comment.path.0,
     );
+    assert_eq!(Some(inserted_child_comment.published), latest_in_community);
+    assert_eq!(
+      vec![(inserted_comment.id, inserted_person.id)],
+      oldest_comments
+        .iter()
+        .map(|(c, p)| (c.id, p.id))
+        .collect::<Vec<_>>()
+    );
     assert_eq!(1, like_removed);
     assert_eq!(1, saved_removed);
     assert_eq!(1, num_deleted);
@@ -2,6 +2,7 @@ use crate::{
   newtypes::{CommentId, CommentReportId, PersonId},
   schema::comment_report::{
     comment_id,
+    creator_id,
     dsl::{comment_report, resolved, resolver_id, updated},
   },
   source::comment_report::{CommentReport, CommentReportForm},
@@ -12,6 +13,7 @@ use diesel::{
   dsl::{insert_into, update},
   result::Error,
   ExpressionMethods,
+  OptionalExtension,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
@@ -94,3 +96,36 @@ impl Reportable for CommentReport {
       .await
   }
 }
+
+impl CommentReport {
+  /// Number of reports for the comment which haven't been resolved yet.
+  pub async fn count_unresolved_for_object(
+    pool: &mut DbPool<'_>,
+    comment_id_: CommentId,
+  ) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    comment_report
+      .filter(comment_id.eq(comment_id_))
+      .filter(resolved.eq(false))
+      .count()
+      .get_result(conn)
+      .await
+  }
+
+  /// Creates a report, unless the creator already reported the comment. Used for automated reports,
+  /// which may happen more than once and shouldn't reopen reports which moderators resolved.
+  /// Returns None if there was an earlier report.
+  pub async fn report_once(
+    pool: &mut DbPool<'_>,
+    comment_report_form: &CommentReportForm,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(comment_report)
+      .values(comment_report_form)
+      .on_conflict((comment_id, creator_id))
+      .do_nothing()
+      .get_result::<Self>(conn)
+      .await
+      .optional()
+  }
+}
@@ -1,12 +1,14 @@
 use crate::{
   diesel::{DecoratableTarget, OptionalExtension},
-  newtypes::{CommunityId, DbUrl, PersonId},
+  newtypes::{CommunityId, DbUrl, PersonId, PostId},
   schema::{
+    comment,
     community,
     community_follower,
     community_moderator,
     community_person_ban,
     instance,
+    person,
     post,
   },
   source::{
@@ -22,6 +24,7 @@ use crate::{
       CommunityPersonBanForm,
       CommunityUpdateForm,
     },
+    person::Person,
     post::Post,
   },
   traits::{ApubActor, Bannable, Crud, Followable, Joinable},
@@ -36,7 +39,7 @@ use chrono::{DateTime, Utc};
 use diesel::{
   deserialize,
   dsl,
-  dsl::{exists, insert_into},
+  dsl::{exists, insert_into, sql},
   pg::Pg,
   result::Error,
   select,
@@ -47,6 +50,7 @@ use diesel::{
   NullableExpressionMethods,
   QueryDsl,
   Queryable,
+  TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_utils::error::{LemmyErrorType, LemmyResult};
@@ -118,6 +122,66 @@ pub enum CollectionType {
 }
 
 impl Community {
+  /// Moves all posts, and with them their comments, from one community to another. Used when a
+  /// community is imported from another instance, so the posts and comments which were written on
+  /// the instance of the old community also become local and get new local ap_ids. Those written
+  /// on other instances stay remote, as these instances still own them.
+  pub async fn move_posts(
+    pool: &mut DbPool<'_>,
+    from_community_id: CommunityId,
+    to_community_id: CommunityId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let from_actor_id: DbUrl = community::table
+            .find(from_community_id)
+            .select(community::actor_id)
+            .first(conn)
+            .await?;
+          // Matches the ap_ids of objects on the instance of the old community
+          let from_instance = format!("{}/", from_actor_id.origin().ascii_serialization())
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+          let from_instance = format!("{from_instance}%");
+
+          let post_ids: Vec<PostId> =
+            update(post::table.filter(post::community_id.eq(from_community_id)))
+              .set(post::community_id.eq(to_community_id))
+              .returning(post::id)
+              .get_results(conn)
+              .await?;
+          update(
+            post::table
+              .filter(post::id.eq_any(&post_ids))
+              .filter(post::ap_id.like(&from_instance)),
+          )
+          .set((
+            post::local.eq(true),
+            post::ap_id.eq(sql::<sql_types::Varchar>("r.local_url('/post/' || id)")),
+          ))
+          .execute(conn)
+          .await?;
+          update(
+            comment::table
+              .filter(comment::post_id.eq_any(&post_ids))
+              .filter(comment::ap_id.like(&from_instance)),
+          )
+          .set((
+            comment::local.eq(true),
+            comment::ap_id.eq(sql::<sql_types::Varchar>("r.local_url('/comment/' || id)")),
+          ))
+          .execute(conn)
+          .await?;
+          Ok(post_ids.len())
+        }) as _
+      })
+      .await
+  }
+
   pub async fn insert_apub(
     pool: &mut DbPool<'_>,
     timestamp: DateTime<Utc>,
@@ -333,6 +397,51 @@ impl CommunityFollower {
     .get_result(conn)
     .await
   }
+
+  /// Actor ids of all followers of the community, local and remote.
+  pub async fn list_follower_actor_ids(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<DbUrl>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_follower::table
+      .inner_join(person::table)
+      .filter(community_follower::community_id.eq(for_community_id))
+      .filter(community_follower::pending.eq(false))
+      .select(person::actor_id)
+      .load(conn)
+      .await
+  }
+
+  /// Remote communities which the person follows, including pending follows.
+  pub async fn list_remote_communities_followed_by(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> Result<Vec<Community>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_follower::table
+      .inner_join(community::table)
+      .filter(community_follower::person_id.eq(for_person_id))
+      .filter(community::local.eq(false))
+      .select(community::all_columns)
+      .load(conn)
+      .await
+  }
+
+  /// Local users who follow the community.
+  pub async fn list_local_followers(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Person>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_follower::table
+      .inner_join(person::table)
+      .filter(community_follower::community_id.eq(for_community_id))
+      .filter(person::local)
+      .select(person::all_columns)
+      .load(conn)
+      .await
+  }
 }
 
 impl Queryable<sql_types::Nullable<sql_types::Bool>, Pg> for SubscribedType {
@@ -436,6 +545,7 @@ impl ApubActor for Community {
 mod tests {
   use crate::{
     source::{
+      comment::{Comment, CommentInsertForm},
       community::{
         Community,
         CommunityFollower,
@@ -450,6 +560,7 @@ mod tests {
       instance::Instance,
       local_user::LocalUser,
       person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
     },
     traits::{Bannable, Crud, Followable, Joinable},
     utils::build_db_pool_for_tests,
@@ -458,6 +569,7 @@ mod tests {
   use lemmy_utils::{error::LemmyResult, LemmyErrorType};
   use pretty_assertions::assert_eq;
   use serial_test::serial;
+  use url::Url;
 
   #[tokio::test]
   #[serial]
@@ -508,6 +620,12 @@ mod tests {
       posting_restricted_to_mods: false,
       instance_id: inserted_instance.id,
       visibility: CommunityVisibility::Public,
+      comment_slow_mode_seconds: None,
+      post_slow_mode_seconds: None,
+      duplicate_post_window_days: None,
+      community_comment_slow_mode_seconds: None,
+      moved_to: None,
+      also_known_as: vec![],
     };
 
     let community_follower_form = CommunityFollowerForm {
@@ -628,4 +746,99 @@ mod tests {
 
     Ok(())
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_move_posts() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(inserted_instance.id, "move_posts"),
+    )
+    .await?;
+    let community_form = |name: &str| {
+      CommunityInsertForm::builder()
+        .name(name.into())
+        .title(name.to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build()
+    };
+    let old_community = Community::create(
+      pool,
+      &CommunityInsertForm {
+        actor_id: Some(Url::parse("https://old.tld/c/move_posts_old")?.into()),
+        ..community_form("move_posts_old")
+      },
+    )
+    .await?;
+    let new_community = Community::create(pool, &community_form("move_posts_new")).await?;
+
+    let post_form = PostInsertForm::builder()
+      .name("moved post".into())
+      .creator_id(person.id)
+      .community_id(old_community.id)
+      .local(Some(false))
+      .ap_id(Some(Url::parse("https://old.tld/post/1")?.into()))
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+    let comment_form = CommentInsertForm::builder()
+      .content("moved comment".into())
+      .creator_id(person.id)
+      .post_id(post.id)
+      .local(Some(false))
+      .ap_id(Some(Url::parse("https://old.tld/comment/1")?.into()))
+      .build();
+    let comment = Comment::create(pool, &comment_form, None).await?;
+    // Written by a user on a third instance, which still owns the comment
+    let third_comment_form = CommentInsertForm {
+      ap_id: Some(Url::parse("https://third.tld/comment/2")?.into()),
+      ..comment_form
+    };
+    let third_comment = Comment::create(pool, &third_comment_form, None).await?;
+    let follower_form = CommunityFollowerForm {
+      community_id: old_community.id,
+      person_id: person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &follower_form).await?;
+
+    assert_eq!(
+      vec![person.actor_id.clone()],
+      CommunityFollower::list_follower_actor_ids(pool, old_community.id).await?
+    );
+    assert_eq!(
+      vec![person.clone()],
+      CommunityFollower::list_local_followers(pool, old_community.id).await?
+    );
+
+    let moved = Community::move_posts(pool, old_community.id, new_community.id).await?;
+    assert_eq!(1, moved);
+    let post = Post::read(pool, post.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(new_community.id, post.community_id);
+    assert!(post.local);
+    assert!(post.ap_id.as_str().ends_with(&format!("/post/{}", post.id)));
+    let comment = Comment::read(pool, comment.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindComment)?;
+    assert!(comment.local);
+    assert!(comment
+      .ap_id
+      .as_str()
+      .ends_with(&format!("/comment/{}", comment.id)));
+    let third_comment = Comment::read(pool, third_comment.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindComment)?;
+    assert!(!third_comment.local);
+    assert_eq!("https://third.tld/comment/2", third_comment.ap_id.as_str());
+
+    Instance::delete(pool, inserted_instance.id).await?;
+
+    Ok(())
+  }
 }
@@ -0,0 +1,125 @@
+use crate::{
+  diesel::OptionalExtension,
+  newtypes::CommunityId,
+  schema::community_backfill::dsl::{
+    comments_imported,
+    community_backfill,
+    community_id,
+    error,
+    finished,
+    pages_fetched,
+    posts_imported,
+    published,
+    updated,
+  },
+  source::community_backfill::{CommunityBackfill, CommunityBackfillUpdateForm},
+  utils::{get_conn, naive_now, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl CommunityBackfill {
+  /// Creates the progress entry for a new backfill, resetting the progress of a previous one.
+  pub async fn start(pool: &mut DbPool<'_>, for_community_id: CommunityId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_backfill)
+      .values(community_id.eq(for_community_id))
+      .on_conflict(community_id)
+      .do_update()
+      .set((
+        pages_fetched.eq(0),
+        posts_imported.eq(0),
+        comments_imported.eq(0),
+        error.eq(None::<String>),
+        published.eq(naive_now()),
+        updated.eq(None::<chrono::DateTime<chrono::Utc>>),
+        finished.eq(None::<chrono::DateTime<chrono::Utc>>),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_backfill
+      .find(for_community_id)
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn update(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+    form: &CommunityBackfillUpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_backfill.find(for_community_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_backfill::{CommunityBackfill, CommunityBackfillUpdateForm},
+      instance::Instance,
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_restart_backfill() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "backfill.tld".to_string()).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("backfill".into())
+      .title("backfill".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+
+    assert_eq!(None, CommunityBackfill::read(pool, community.id).await?);
+    CommunityBackfill::start(pool, community.id).await?;
+    let form = CommunityBackfillUpdateForm {
+      pages_fetched: Some(3),
+      posts_imported: Some(25),
+      comments_imported: Some(100),
+      error: Some(Some("timeout".to_string())),
+      finished: Some(Some(naive_now())),
+      ..Default::default()
+    };
+    let finished = CommunityBackfill::update(pool, community.id, &form).await?;
+    assert_eq!(25, finished.posts_imported);
+    assert!(finished.finished.is_some());
+
+    // Starting again resets the progress
+    let restarted = CommunityBackfill::start(pool, community.id).await?;
+    assert_eq!(0, restarted.pages_fetched);
+    assert_eq!(0, restarted.posts_imported);
+    assert_eq!(None, restarted.error);
+    assert_eq!(None, restarted.finished);
+    assert_eq!(
+      Some(restarted),
+      CommunityBackfill::read(pool, community.id).await?
+    );
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -1,15 +1,35 @@
 use crate::{
-  schema::federation_blocklist,
+  newtypes::InstanceId,
+  schema::{
+    community,
+    community_follower,
+    federation_blocklist,
+    instance,
+    local_user,
+    person,
+    person_follower,
+  },
   source::{
+    community::Community,
     federation_blocklist::{FederationBlockList, FederationBlockListForm},
     instance::Instance,
+    person::Person,
   },
-  utils::{get_conn, DbPool},
+  utils::{functions::lower, get_conn, DbPool},
+};
+use diesel::{
+  dsl::insert_into,
+  result::Error,
+  ExpressionMethods,
+  JoinOnDsl,
+  QueryDsl,
+  SelectableHelper,
 };
-use diesel::{dsl::insert_into, result::Error};
-use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use diesel_async::RunQueryDsl;
 
 impl FederationBlockList {
+  /// Replaces the blocked instances with the given list of domains. Reason, comment and expiry of
+  /// instances which remain blocked are kept.
   pub async fn replace(pool: &mut DbPool<'_>, list_opt: Option<Vec<String>>) -> Result<(), Error> {
     let conn = &mut get_conn(pool).await?;
     conn
@@ -17,21 +37,29 @@ impl FederationBlockList {
       .run(|conn| {
         Box::pin(async move {
           if let Some(list) = list_opt {
-            Self::clear(conn).await?;
-
+            let mut instance_ids = vec![];
             for domain in list {
               // Upsert all of these as instances
               let instance = Instance::read_or_create(&mut conn.into(), domain).await?;
 
               let form = FederationBlockListForm {
                 instance_id: instance.id,
-                updated: None,
+                ..Default::default()
               };
               insert_into(federation_blocklist::table)
                 .values(form)
-                .get_result::<Self>(conn)
+                .on_conflict(federation_blocklist::instance_id)
+                .do_nothing()
+                .execute(conn)
                 .await?;
+              instance_ids.push(instance.id);
             }
+            diesel::delete(
+              federation_blocklist::table
+                .filter(federation_blocklist::instance_id.ne_all(instance_ids)),
+            )
+            .execute(conn)
+            .await?;
             Ok(())
           } else {
             Ok(())
@@ -41,9 +69,122 @@ impl FederationBlockList {
       .await
   }
 
-  async fn clear(conn: &mut AsyncPgConnection) -> Result<usize, Error> {
-    diesel::delete(federation_blocklist::table)
+  /// Blocks a single instance, or updates reason, comment and expiry if it is already blocked.
+  pub async fn block(pool: &mut DbPool<'_>, form: &FederationBlockListForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_blocklist::table)
+      .values(form)
+      .on_conflict(federation_blocklist::instance_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn unblock(pool: &mut DbPool<'_>, instance_id: InstanceId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(federation_blocklist::table.find(instance_id))
       .execute(conn)
       .await
   }
+
+  /// All blocked instances, ordered by domain.
+  pub async fn list(pool: &mut DbPool<'_>) -> Result<Vec<(Instance, Self)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .inner_join(federation_blocklist::table)
+      .select((Instance::as_select(), Self::as_select()))
+      .order_by(instance::domain)
+      .get_results(conn)
+      .await
+  }
+
+  /// Communities on the given instances which are followed by local users. These are cut off when
+  /// the instances get blocked.
+  pub async fn followed_communities(
+    pool: &mut DbPool<'_>,
+    domains: &[String],
+  ) -> Result<Vec<Community>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let followed = community_follower::table
+      .inner_join(local_user::table.on(local_user::person_id.eq(community_follower::person_id)))
+      .select(community_follower::community_id);
+    community::table
+      .inner_join(instance::table)
+      .filter(lower(instance::domain).eq_any(domains))
+      .filter(community::id.eq_any(followed))
+      .select(Community::as_select())
+      .order_by(community::actor_id)
+      .get_results(conn)
+      .await
+  }
+
+  /// Users on the given instances which are followed by local users.
+  pub async fn followed_persons(
+    pool: &mut DbPool<'_>,
+    domains: &[String],
+  ) -> Result<Vec<Person>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let followed = person_follower::table
+      .inner_join(local_user::table.on(local_user::person_id.eq(person_follower::follower_id)))
+      .select(person_follower::person_id);
+    person::table
+      .inner_join(instance::table)
+      .filter(lower(instance::domain).eq_any(domains))
+      .filter(person::id.eq_any(followed))
+      .select(Person::as_select())
+      .order_by(person::actor_id)
+      .get_results(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    source::{
+      federation_blocklist::{FederationBlockList, FederationBlockListForm},
+      instance::Instance,
+    },
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_blocklist_reason() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "tld1.xyz".to_string()).await?;
+    let form = FederationBlockListForm {
+      instance_id: instance.id,
+      reason: Some("spam".to_string()),
+      comment: Some("reported by several users".to_string()),
+      ..Default::default()
+    };
+    FederationBlockList::block(pool, &form).await?;
+
+    // Replacing the list keeps the reason of instances which stay blocked
+    let domains = vec!["tld1.xyz".to_string(), "tld2.xyz".to_string()];
+    FederationBlockList::replace(pool, Some(domains)).await?;
+    let list = FederationBlockList::list(pool).await?;
+    assert_eq!(2, list.len());
+    assert_eq!("tld1.xyz", list[0].0.domain);
+    assert_eq!(Some("spam".to_string()), list[0].1.reason);
+    assert_eq!(None, list[1].1.reason);
+
+    FederationBlockList::unblock(pool, instance.id).await?;
+    assert_eq!(1, FederationBlockList::list(pool).await?.len());
+
+    FederationBlockList::replace(pool, Some(vec![])).await?;
+    assert_eq!(0, FederationBlockList::list(pool).await?.len());
+
+    Instance::delete_all(pool).await?;
+    Ok(())
+  }
 }
@@ -0,0 +1,59 @@
+use crate::{
+  newtypes::InstanceId,
+  schema::{federation_limitlist, instance},
+  source::{
+    federation_limitlist::{FederationLimitList, FederationLimitListForm},
+    instance::Instance,
+  },
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{exists, insert_into, select},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+  SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+
+impl FederationLimitList {
+  /// Limits federation with an instance, or updates reason, comment and expiry if it is already
+  /// limited.
+  pub async fn limit(pool: &mut DbPool<'_>, form: &FederationLimitListForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_limitlist::table)
+      .values(form)
+      .on_conflict(federation_limitlist::instance_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn unlimit(pool: &mut DbPool<'_>, instance_id: InstanceId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(federation_limitlist::table.find(instance_id))
+      .execute(conn)
+      .await
+  }
+
+  /// All limited instances, ordered by domain.
+  pub async fn list(pool: &mut DbPool<'_>) -> Result<Vec<(Instance, Self)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .inner_join(federation_limitlist::table)
+      .select((Instance::as_select(), Self::as_select()))
+      .order_by(instance::domain)
+      .get_results(conn)
+      .await
+  }
+
+  pub async fn is_limited(pool: &mut DbPool<'_>, instance_id: InstanceId) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      federation_limitlist::table.filter(federation_limitlist::instance_id.eq(instance_id)),
+    ))
+    .get_result(conn)
+    .await
+  }
+}
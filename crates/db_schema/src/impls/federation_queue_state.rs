@@ -1,7 +1,9 @@
 use crate::{
   newtypes::InstanceId,
-  source::federation_queue_state::FederationQueueState,
+  schema::{federation_queue_state, instance},
+  source::{federation_queue_state::FederationQueueState, instance::Instance},
   utils::{get_conn, DbPool},
+  FederationQueueReset,
 };
 use diesel::{prelude::*, result::Error};
 use diesel_async::RunQueryDsl;
@@ -43,4 +45,143 @@ impl FederationQueueState {
       .await?;
     Ok(())
   }
+
+  /// Returns the queue state of all instances which were federated with, along with resets which
+  /// weren't applied yet, ordered by domain.
+  pub async fn read_all_with_instance(
+    pool: &mut DbPool<'_>,
+  ) -> Result<Vec<(Instance, Self, Option<FederationQueueReset>)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    federation_queue_state::table
+      .inner_join(instance::table)
+      .select((
+        Instance::as_select(),
+        Self::as_select(),
+        federation_queue_state::requested_reset,
+      ))
+      .order_by(instance::domain)
+      .get_results(conn)
+      .await
+  }
+
+  /// Requests a reset of the queue, which is applied by the federation worker. Returns the number
+  /// of updated rows, which is 0 if there is no queue for the instance.
+  pub async fn request_reset(
+    pool: &mut DbPool<'_>,
+    for_instance_id: InstanceId,
+    reset: FederationQueueReset,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(federation_queue_state::table.find(for_instance_id))
+      .set(federation_queue_state::requested_reset.eq(reset))
+      .execute(conn)
+      .await
+  }
+
+  /// Returns all queues with a requested reset.
+  pub async fn read_requested_resets(
+    pool: &mut DbPool<'_>,
+  ) -> Result<Vec<(Self, FederationQueueReset)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    federation_queue_state::table
+      .select((
+        Self::as_select(),
+        federation_queue_state::requested_reset.assume_not_null(),
+      ))
+      .filter(federation_queue_state::requested_reset.is_not_null())
+      .get_results(conn)
+      .await
+  }
+
+  /// Saves the state after applying a reset and clears the request.
+  pub async fn finish_reset(
+    pool: &mut DbPool<'_>,
+    state: &FederationQueueState,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(federation_queue_state::table.find(state.instance_id))
+      .set((
+        state,
+        federation_queue_state::requested_reset.eq(None::<FederationQueueReset>),
+      ))
+      .execute(conn)
+      .await?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    newtypes::ActivityId,
+    schema::federation_queue_state,
+    source::{federation_queue_state::FederationQueueState, instance::Instance},
+    utils::{build_db_pool_for_tests, get_conn},
+    FederationQueueReset,
+  };
+  use chrono::Utc;
+  use diesel::QueryDsl;
+  use diesel_async::RunQueryDsl;
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_request_reset() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "stuck-queue.tld".to_string()).await?;
+    let other_instance = Instance::read_or_create(pool, "no-queue.tld".to_string()).await?;
+    let state = FederationQueueState {
+      instance_id: instance.id,
+      last_successful_id: Some(ActivityId(5)),
+      last_successful_published_time: None,
+      fail_count: 12,
+      last_retry: Some(Utc::now()),
+    };
+    FederationQueueState::upsert(pool, &state).await?;
+
+    let updated =
+      FederationQueueState::request_reset(pool, instance.id, FederationQueueReset::Skip).await?;
+    assert_eq!(1, updated);
+    let updated =
+      FederationQueueState::request_reset(pool, other_instance.id, FederationQueueReset::Retry)
+        .await?;
+    assert_eq!(0, updated);
+
+    // Saving the state from a worker doesn't clear the request
+    FederationQueueState::upsert(pool, &state).await?;
+    let requested = FederationQueueState::read_requested_resets(pool).await?;
+    assert_eq!(1, requested.len());
+    assert_eq!(
+      Some(FederationQueueReset::Skip),
+      requested.iter().map(|r| r.1).next()
+    );
+
+    let reset_state = FederationQueueState {
+      fail_count: 0,
+      last_retry: None,
+      ..state
+    };
+    FederationQueueState::finish_reset(pool, &reset_state).await?;
+    assert!(FederationQueueState::read_requested_resets(pool)
+      .await?
+      .is_empty());
+    let all = FederationQueueState::read_all_with_instance(pool).await?;
+    let saved = all
+      .iter()
+      .find(|(i, _, _)| i.id == instance.id)
+      .map(|(_, s, reset)| (s.fail_count, *reset));
+    assert_eq!(Some((0, None)), saved);
+
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(federation_queue_state::table.find(instance.id))
+      .execute(conn)
+      .await?;
+    Instance::delete(pool, instance.id).await?;
+    Instance::delete(pool, other_instance.id).await?;
+    Ok(())
+  }
 }
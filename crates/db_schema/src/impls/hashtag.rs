@@ -0,0 +1,170 @@
+use crate::{
+  newtypes::{HashtagId, PostId},
+  schema::{hashtag, post_hashtag},
+  source::hashtag::Hashtag,
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use diesel::{
+  dsl::{count_star, insert_into},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+  SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+
+impl Hashtag {
+  /// Replaces the hashtags of a post with the given names.
+  pub async fn update_for_post(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    names: Vec<String>,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(post_hashtag::table.filter(post_hashtag::post_id.eq(post_id)))
+            .execute(conn)
+            .await?;
+          if names.is_empty() {
+            return Ok(());
+          }
+
+          let forms: Vec<_> = names.iter().map(|n| hashtag::name.eq(n)).collect();
+          insert_into(hashtag::table)
+            .values(forms)
+            .on_conflict(hashtag::name)
+            .do_nothing()
+            .execute(conn)
+            .await?;
+          let hashtag_ids: Vec<HashtagId> = hashtag::table
+            .filter(hashtag::name.eq_any(&names))
+            .select(hashtag::id)
+            .get_results(conn)
+            .await?;
+          let forms: Vec<_> = hashtag_ids
+            .into_iter()
+            .map(|id| {
+              (
+                post_hashtag::post_id.eq(post_id),
+                post_hashtag::hashtag_id.eq(id),
+              )
+            })
+            .collect();
+          insert_into(post_hashtag::table)
+            .values(forms)
+            .execute(conn)
+            .await?;
+          Ok(())
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn list_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    hashtag::table
+      .inner_join(post_hashtag::table)
+      .filter(post_hashtag::post_id.eq(post_id))
+      .select(Self::as_select())
+      .order_by(hashtag::name)
+      .get_results(conn)
+      .await
+  }
+
+  /// Lists hashtags with the number of posts that use them, most used first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<(Self, i64)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    hashtag::table
+      .inner_join(post_hashtag::table)
+      .group_by(hashtag::id)
+      .select((Self::as_select(), count_star()))
+      .order_by((count_star().desc(), hashtag::name))
+      .limit(limit)
+      .offset(offset)
+      .get_results(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      hashtag::Hashtag,
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_post_hashtags() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person =
+      Person::create(pool, &PersonInsertForm::test_form(instance.id, "hashtags")).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_hashtags".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = |name: &str| {
+      PostInsertForm::builder()
+        .name(name.into())
+        .creator_id(person.id)
+        .community_id(community.id)
+        .build()
+    };
+    let post1 = Post::create(pool, &post_form("post 1")).await?;
+    let post2 = Post::create(pool, &post_form("post 2")).await?;
+
+    let names = |hashtags: Vec<Hashtag>| hashtags.into_iter().map(|h| h.name).collect::<Vec<_>>();
+    Hashtag::update_for_post(pool, post1.id, vec!["rust".into(), "lemmy".into()]).await?;
+    Hashtag::update_for_post(pool, post2.id, vec!["rust".into()]).await?;
+    assert_eq!(
+      vec!["lemmy", "rust"],
+      names(Hashtag::list_for_post(pool, post1.id).await?)
+    );
+
+    let list = Hashtag::list(pool, None, None).await?;
+    assert_eq!(
+      vec![("rust".to_string(), 2), ("lemmy".to_string(), 1)],
+      list
+        .into_iter()
+        .map(|(h, count)| (h.name, count))
+        .collect::<Vec<_>>()
+    );
+
+    // Updating replaces the previous hashtags
+    Hashtag::update_for_post(pool, post1.id, vec!["fediverse".into()]).await?;
+    assert_eq!(
+      vec!["fediverse"],
+      names(Hashtag::list_for_post(pool, post1.id).await?)
+    );
+    Hashtag::update_for_post(pool, post1.id, vec![]).await?;
+    assert!(Hashtag::list_for_post(pool, post1.id).await?.is_empty());
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,112 @@
+use crate::{
+  diesel::OptionalExtension,
+  newtypes::DbUrl,
+  schema::link_metadata_cache::dsl::{fetched, link_metadata_cache},
+  source::link_metadata_cache::{LinkMetadataCache, LinkMetadataCacheForm},
+  utils::{get_conn, now, DbPool},
+};
+use diesel::{
+  delete,
+  dsl::{insert_into, IntervalDsl},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl LinkMetadataCache {
+  /// Returns the cached metadata for the url, unless it is older than one day.
+  pub async fn read(pool: &mut DbPool<'_>, url: &DbUrl) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    link_metadata_cache
+      .find(url)
+      .filter(fetched.gt(now() - 1.days()))
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  /// Stores freshly fetched metadata, replacing any previous entry for the same url.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &LinkMetadataCacheForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(link_metadata_cache)
+      .values(form)
+      .on_conflict(crate::schema::link_metadata_cache::url)
+      .do_update()
+      .set((form, fetched.eq(now())))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Removes entries which are too old to be used anyway.
+  pub async fn delete_expired(pool: &mut DbPool<'_>) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(link_metadata_cache.filter(fetched.lt(now() - 1.days())))
+      .execute(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    schema::link_metadata_cache,
+    source::link_metadata_cache::{LinkMetadataCache, LinkMetadataCacheForm},
+    utils::{build_db_pool_for_tests, get_conn, now},
+  };
+  use diesel::{dsl::IntervalDsl, ExpressionMethods};
+  use diesel_async::RunQueryDsl;
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_link_metadata_cache() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let url = Url::parse("https://example.com/article")?.into();
+    let form = LinkMetadataCacheForm {
+      url: Url::parse("https://example.com/article")?.into(),
+      title: Some("Article".to_string()),
+      description: None,
+      image: None,
+      embed_video_url: None,
+      author: Some("Jane".to_string()),
+      published_date: None,
+      embed_html: None,
+      embed_video_width: None,
+      embed_video_height: None,
+      content_type: Some("text/html".to_string()),
+      canonical_url: None,
+      final_url: None,
+    };
+    LinkMetadataCache::upsert(pool, &form).await?;
+
+    // Refetching replaces all values, including ones which are gone now
+    let form = LinkMetadataCacheForm {
+      title: Some("Updated article".to_string()),
+      author: None,
+      ..form
+    };
+    LinkMetadataCache::upsert(pool, &form).await?;
+    let cached = LinkMetadataCache::read(pool, &url).await?.unwrap();
+    assert_eq!(Some("Updated article".to_string()), cached.title);
+    assert_eq!(None, cached.author);
+
+    // Entries older than a day are ignored, and cleaned up
+    diesel::update(link_metadata_cache::table)
+      .set(link_metadata_cache::fetched.eq(now() - 2.days()))
+      .execute(&mut get_conn(pool).await?)
+      .await?;
+    assert_eq!(None, LinkMetadataCache::read(pool, &url).await?);
+    assert_eq!(1, LinkMetadataCache::delete_expired(pool).await?);
+
+    Ok(())
+  }
+}
@@ -1,9 +1,10 @@
 use crate::{
+  newtypes::PersonId,
   schema::local_site,
   source::local_site::{LocalSite, LocalSiteInsertForm, LocalSiteUpdateForm},
-  utils::{get_conn, DbPool},
+  utils::{functions::coalesce, get_conn, DbPool},
 };
-use diesel::{dsl::insert_into, result::Error};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, NullableExpressionMethods};
 use diesel_async::RunQueryDsl;
 use lemmy_utils::{error::LemmyResult, CACHE_DURATION_API};
 use moka::future::Cache;
@@ -40,6 +41,22 @@ impl LocalSite {
       .get_result::<Self>(conn)
       .await
   }
+  /// Stores the given person as system account, unless one was already set. Returns the id of
+  /// the system account which is now in use.
+  pub async fn set_system_account_if_empty(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+  ) -> Result<Option<PersonId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(local_site::table)
+      .set(
+        local_site::system_account_id
+          .eq(coalesce(local_site::system_account_id, person_id).nullable()),
+      )
+      .returning(local_site::system_account_id)
+      .get_result(conn)
+      .await
+  }
   pub async fn delete(pool: &mut DbPool<'_>) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
     diesel::delete(local_site::table).execute(conn).await
@@ -0,0 +1,46 @@
+use crate::{
+  schema::local_site_url_cleaning_rule,
+  source::local_site_url_cleaning_rule::{LocalSiteUrlCleaningRule, LocalSiteUrlCleaningRuleForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, QueryDsl};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+impl LocalSiteUrlCleaningRule {
+  pub async fn replace(
+    pool: &mut DbPool<'_>,
+    forms: Vec<LocalSiteUrlCleaningRuleForm>,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          Self::clear(conn).await?;
+
+          insert_into(local_site_url_cleaning_rule::table)
+            .values(forms)
+            .execute(conn)
+            .await?;
+
+          Ok(())
+        }) as _
+      })
+      .await
+  }
+
+  async fn clear(conn: &mut AsyncPgConnection) -> Result<usize, Error> {
+    diesel::delete(local_site_url_cleaning_rule::table)
+      .execute(conn)
+      .await
+  }
+
+  pub async fn get_all(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    local_site_url_cleaning_rule::table
+      .order_by(local_site_url_cleaning_rule::id)
+      .get_results::<Self>(conn)
+      .await
+  }
+}
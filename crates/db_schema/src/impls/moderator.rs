@@ -1,4 +1,5 @@
 use crate::{
+  newtypes::{AutomodRuleId, CommentId, PostId},
   source::moderator::{
     AdminPurgeComment,
     AdminPurgeCommentForm,
@@ -12,6 +13,8 @@ use crate::{
     ModAddCommunity,
     ModAddCommunityForm,
     ModAddForm,
+    ModAutomodAction,
+    ModAutomodActionForm,
     ModBan,
     ModBanForm,
     ModBanFromCommunity,
@@ -34,7 +37,12 @@ use crate::{
   traits::Crud,
   utils::{get_conn, DbPool},
 };
-use diesel::{dsl::insert_into, result::Error, QueryDsl};
+use diesel::{
+  dsl::{exists, insert_into},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+};
 use diesel_async::RunQueryDsl;
 
 #[async_trait]
@@ -464,6 +472,63 @@ impl Crud for AdminPurgeComment {
   }
 }
 
+#[async_trait]
+impl Crud for ModAutomodAction {
+  type InsertForm = ModAutomodActionForm;
+  type UpdateForm = ModAutomodActionForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &ModAutomodActionForm) -> Result<Self, Error> {
+    use crate::schema::mod_automod_action::dsl::mod_automod_action;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_automod_action)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: i32,
+    form: &ModAutomodActionForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::mod_automod_action::dsl::mod_automod_action;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(mod_automod_action.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl ModAutomodAction {
+  /// Checks if the rule was already applied to the given post or comment, so that it doesn't
+  /// report or reply to the same content twice.
+  pub async fn exists_for_rule(
+    pool: &mut DbPool<'_>,
+    for_rule_id: AutomodRuleId,
+    for_post_id: PostId,
+    for_comment_id: Option<CommentId>,
+  ) -> Result<bool, Error> {
+    use crate::schema::mod_automod_action::dsl::{
+      comment_id,
+      mod_automod_action,
+      post_id,
+      rule_id,
+    };
+    let conn = &mut get_conn(pool).await?;
+    let mut query = mod_automod_action
+      .filter(rule_id.eq(for_rule_id))
+      .filter(post_id.eq(for_post_id))
+      .into_boxed();
+    query = match for_comment_id {
+      Some(for_comment_id) => query.filter(comment_id.eq(for_comment_id)),
+      None => query.filter(comment_id.is_null()),
+    };
+    diesel::select(exists(query)).get_result(conn).await
+  }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::indexing_slicing)]
@@ -554,6 +619,7 @@ mod tests {
       post_id: inserted_post.id,
       reason: None,
       removed: None,
+      removal_reason_id: None,
     };
     let inserted_mod_remove_post = ModRemovePost::create(pool, &mod_remove_post_form)
       .await
@@ -569,6 +635,7 @@ mod tests {
       reason: None,
       removed: true,
       when_: inserted_mod_remove_post.when_,
+      removal_reason_id: None,
     };
 
     // lock post
@@ -624,6 +691,7 @@ mod tests {
       comment_id: inserted_comment.id,
       reason: None,
       removed: None,
+      removal_reason_id: None,
     };
     let inserted_mod_remove_comment = ModRemoveComment::create(pool, &mod_remove_comment_form)
       .await
@@ -639,6 +707,7 @@ mod tests {
       reason: None,
       removed: true,
       when_: inserted_mod_remove_comment.when_,
+      removal_reason_id: None,
     };
 
     // community
@@ -1,19 +1,25 @@
 use crate::{
   diesel::OptionalExtension,
   newtypes::{CommunityId, DbUrl, InstanceId, PersonId},
-  schema::{comment, community, instance, local_user, person, person_follower, post},
-  source::person::{
-    Person,
-    PersonFollower,
-    PersonFollowerForm,
-    PersonInsertForm,
-    PersonUpdateForm,
+  schema::{
+    comment,
+    community,
+    community_follower,
+    instance,
+    local_user,
+    person,
+    person_follower,
+    post,
+  },
+  source::{
+    community::CommunityFollowerForm,
+    person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm, PersonUpdateForm},
   },
   traits::{ApubActor, Crud, Followable},
   utils::{functions::lower, get_conn, naive_now, DbPool},
 };
 use diesel::{
-  dsl::{insert_into, not},
+  dsl::{exists, insert_into, not, select},
   result::Error,
   CombineDsl,
   ExpressionMethods,
@@ -121,6 +127,79 @@ impl Person {
       .load::<CommunityId>(conn)
       .await
   }
+
+  /// Transfers follows of a moved account to the new account. This includes followers of the
+  /// person, and subscriptions to local communities.
+  pub async fn transfer_follows(
+    pool: &mut DbPool<'_>,
+    old_person_id: PersonId,
+    new_person_id: PersonId,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let follower_forms: Vec<_> = person_follower::table
+            .filter(person_follower::person_id.eq(old_person_id))
+            .filter(person_follower::follower_id.ne(new_person_id))
+            .select((person_follower::follower_id, person_follower::pending))
+            .load::<(PersonId, bool)>(conn)
+            .await?
+            .into_iter()
+            .map(|(follower_id, pending)| PersonFollowerForm {
+              person_id: new_person_id,
+              follower_id,
+              pending,
+            })
+            .collect();
+          insert_into(person_follower::table)
+            .values(follower_forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          diesel::delete(
+            person_follower::table.filter(person_follower::person_id.eq(old_person_id)),
+          )
+          .execute(conn)
+          .await?;
+
+          let local_community_ids = community::table
+            .filter(community::local)
+            .select(community::id);
+          let community_forms: Vec<_> = community_follower::table
+            .filter(community_follower::person_id.eq(old_person_id))
+            .filter(community_follower::community_id.eq_any(local_community_ids))
+            .select((
+              community_follower::community_id,
+              community_follower::pending,
+            ))
+            .load::<(CommunityId, bool)>(conn)
+            .await?
+            .into_iter()
+            .map(|(community_id, pending)| CommunityFollowerForm {
+              community_id,
+              person_id: new_person_id,
+              pending,
+            })
+            .collect();
+          insert_into(community_follower::table)
+            .values(community_forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          diesel::delete(
+            community_follower::table
+              .filter(community_follower::person_id.eq(old_person_id))
+              .filter(community_follower::community_id.eq_any(local_community_ids)),
+          )
+          .execute(conn)
+          .await?;
+          Ok(())
+        }) as _
+      })
+      .await
+  }
 }
 
 impl PersonInsertForm {
@@ -206,6 +285,19 @@ impl Followable for PersonFollower {
 }
 
 impl PersonFollower {
+  pub async fn is_following(
+    pool: &mut DbPool<'_>,
+    follower_id: PersonId,
+    person_id: PersonId,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      person_follower::table.find((follower_id, person_id)),
+    ))
+    .get_result(conn)
+    .await
+  }
+
   pub async fn list_followers(
     pool: &mut DbPool<'_>,
     for_person_id: PersonId,
@@ -225,13 +317,18 @@ impl PersonFollower {
 mod tests {
 
   use crate::{
+    newtypes::PersonId,
+    schema::community_follower,
     source::{
+      community::{Community, CommunityFollower, CommunityFollowerForm, CommunityInsertForm},
       instance::Instance,
       person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm, PersonUpdateForm},
     },
     traits::{Crud, Followable},
-    utils::build_db_pool_for_tests,
+    utils::{build_db_pool_for_tests, get_conn},
   };
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
   use lemmy_utils::{error::LemmyResult, LemmyErrorType};
   use pretty_assertions::assert_eq;
   use serial_test::serial;
@@ -270,6 +367,8 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      also_known_as: vec![],
     };
 
     let read_person = Person::read(pool, inserted_person.id)
@@ -323,4 +422,69 @@ mod tests {
 
     Ok(())
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_transfer_follows() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+
+    let old_person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(inserted_instance.id, "old"),
+    )
+    .await?;
+    let new_person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(inserted_instance.id, "new"),
+    )
+    .await?;
+    let follower = Person::create(
+      pool,
+      &PersonInsertForm::test_form(inserted_instance.id, "follower"),
+    )
+    .await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_transfer_follows".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+
+    let follow_form = PersonFollowerForm {
+      person_id: old_person.id,
+      follower_id: follower.id,
+      pending: false,
+    };
+    PersonFollower::follow(pool, &follow_form).await?;
+    let community_follow_form = CommunityFollowerForm {
+      community_id: community.id,
+      person_id: old_person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &community_follow_form).await?;
+
+    Person::transfer_follows(pool, old_person.id, new_person.id).await?;
+
+    assert!(PersonFollower::list_followers(pool, old_person.id)
+      .await?
+      .is_empty());
+    let followers = PersonFollower::list_followers(pool, new_person.id).await?;
+    assert_eq!(
+      vec![follower.id],
+      followers.iter().map(|p| p.id).collect::<Vec<_>>()
+    );
+    let community_followers: Vec<PersonId> = community_follower::table
+      .filter(community_follower::community_id.eq(community.id))
+      .select(community_follower::person_id)
+      .load(&mut get_conn(pool).await?)
+      .await?;
+    assert_eq!(vec![new_person.id], community_followers);
+
+    Instance::delete(pool, inserted_instance.id).await?;
+
+    Ok(())
+  }
 }
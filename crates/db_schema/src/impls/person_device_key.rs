@@ -0,0 +1,141 @@
+use crate::{
+  newtypes::PersonId,
+  schema::person_device_key,
+  source::person_device_key::{PersonDeviceKey, PersonDeviceKeyForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PersonDeviceKey {
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &PersonDeviceKeyForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(person_device_key::table)
+      .values(form)
+      .on_conflict((person_device_key::person_id, person_device_key::device_id))
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn delete(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    device_id: &str,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(person_device_key::table.find((person_id, device_id)))
+      .execute(conn)
+      .await
+  }
+
+  pub async fn list_for_person(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    person_device_key::table
+      .filter(person_device_key::person_id.eq(person_id))
+      .order_by(person_device_key::published)
+      .get_results(conn)
+      .await
+  }
+
+  /// Replaces all device keys of a person, used when a remote actor is updated.
+  pub async fn replace_for_person(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    forms: &[PersonDeviceKeyForm],
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(
+            person_device_key::table.filter(person_device_key::person_id.eq(person_id)),
+          )
+          .execute(conn)
+          .await?;
+          insert_into(person_device_key::table)
+            .values(forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          Ok(())
+        }) as _
+      })
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      person_device_key::{PersonDeviceKey, PersonDeviceKeyForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_device_keys() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "pdk")).await?;
+
+    let form = PersonDeviceKeyForm {
+      person_id: person.id,
+      device_id: "phone".to_string(),
+      public_key: "key1".to_string(),
+    };
+    PersonDeviceKey::upsert(pool, &form).await?;
+    let form = PersonDeviceKeyForm {
+      public_key: "key2".to_string(),
+      ..form
+    };
+    PersonDeviceKey::upsert(pool, &form).await?;
+    let keys = PersonDeviceKey::list_for_person(pool, person.id).await?;
+    assert_eq!(1, keys.len());
+    assert_eq!("key2", keys[0].public_key);
+
+    let forms = vec![
+      PersonDeviceKeyForm {
+        person_id: person.id,
+        device_id: "laptop".to_string(),
+        public_key: "key3".to_string(),
+      },
+      PersonDeviceKeyForm {
+        person_id: person.id,
+        device_id: "tablet".to_string(),
+        public_key: "key4".to_string(),
+      },
+    ];
+    PersonDeviceKey::replace_for_person(pool, person.id, &forms).await?;
+    let keys = PersonDeviceKey::list_for_person(pool, person.id).await?;
+    assert_eq!(2, keys.len());
+
+    assert_eq!(1, PersonDeviceKey::delete(pool, person.id, "laptop").await?);
+    let keys = PersonDeviceKey::list_for_person(pool, person.id).await?;
+    assert_eq!(
+      vec!["tablet"],
+      keys.iter().map(|k| &k.device_id).collect::<Vec<_>>()
+    );
+
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
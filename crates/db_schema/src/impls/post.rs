@@ -1,7 +1,7 @@
 use crate::{
   diesel::OptionalExtension,
   newtypes::{CommunityId, DbUrl, PersonId, PostId},
-  schema::{post, post_hide, post_like, post_read, post_saved},
+  schema::{comment_saved, post, post_hide, post_like, post_read, post_saved},
   source::post::{
     Post,
     PostHide,
@@ -14,12 +14,15 @@ use crate::{
     PostSaved,
     PostSavedForm,
     PostUpdateForm,
+    SavedItemId,
   },
   traits::{Crud, Likeable, Saveable},
   utils::{
     functions::coalesce,
     get_conn,
+    limit_and_offset,
     naive_now,
+    now,
     DbPool,
     DELETED_REPLACEMENT_TEXT,
     FETCH_LIMIT_MAX,
@@ -30,15 +33,18 @@ use crate::{
 use ::url::Url;
 use chrono::{DateTime, Utc};
 use diesel::{
-  dsl::insert_into,
+  dsl::{insert_into, IntervalDsl},
   result::Error,
+  BoolExpressionMethods,
   DecoratableTarget,
   ExpressionMethods,
+  NullableExpressionMethods,
+  PgSortExpressionMethods,
   QueryDsl,
   TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
-use std::collections::HashSet;
+use std::{cmp::Reverse, collections::HashSet};
 
 #[async_trait]
 impl Crud for Post {
@@ -118,6 +124,54 @@ impl Post {
       .await
   }
 
+  /// Link posts whose url wasn't checked yet, or not within the given number of days. The ones
+  /// checked longest ago come first.
+  pub async fn list_for_link_check(
+    pool: &mut DbPool<'_>,
+    recheck_days: i32,
+    limit: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post::table
+      .filter(post::url.is_not_null())
+      .filter(post::deleted.eq(false))
+      .filter(post::removed.eq(false))
+      .filter(
+        post::url_checked
+          .is_null()
+          .or(post::url_checked.lt((now() - recheck_days.days()).nullable())),
+      )
+      .order_by(post::url_checked.asc().nulls_first())
+      .then_order_by(post::published.desc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Finds a post in the community which links to the same canonical url, and was published after
+  /// the given time.
+  pub async fn find_duplicate(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+    url_canonical: &DbUrl,
+    published_after: DateTime<Utc>,
+    exclude_post_id: Option<PostId>,
+  ) -> Result<Option<PostId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = post::table
+      .select(post::id)
+      .filter(post::community_id.eq(for_community_id))
+      .filter(post::url_canonical.eq(url_canonical))
+      .filter(post::published.gt(published_after))
+      .filter(post::deleted.eq(false))
+      .filter(post::removed.eq(false))
+      .into_boxed();
+    if let Some(exclude_post_id) = exclude_post_id {
+      query = query.filter(post::id.ne(exclude_post_id));
+    }
+    query.first(conn).await.optional()
+  }
+
   pub async fn permadelete_for_creator(
     pool: &mut DbPool<'_>,
     for_creator_id: PersonId,
@@ -174,6 +228,22 @@ impl Post {
       .optional()
   }
 
+  /// When the person last posted in the community, including deleted and removed posts. Used for
+  /// community slow mode.
+  pub async fn latest_published_by_creator_in_community(
+    pool: &mut DbPool<'_>,
+    for_creator_id: PersonId,
+    for_community_id: CommunityId,
+  ) -> Result<Option<DateTime<Utc>>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post::table
+      .filter(post::creator_id.eq(for_creator_id))
+      .filter(post::community_id.eq(for_community_id))
+      .select(diesel::dsl::max(post::published))
+      .first(conn)
+      .await
+  }
+
   pub async fn fetch_pictrs_posts_for_creator(
     pool: &mut DbPool<'_>,
     for_creator_id: PersonId,
@@ -291,6 +361,53 @@ impl Saveable for PostSaved {
   }
 }
 
+impl PostSaved {
+  /// Lists the posts and comments which the person saved, the most recently saved first.
+  pub async fn list_with_comments(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<SavedItemId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    // Both lists need to include all items up to the end of the page, so that they can be merged
+    let posts = post_saved::table
+      .filter(post_saved::person_id.eq(person_id))
+      .order_by(post_saved::published.desc())
+      .limit(offset + limit)
+      .select((post_saved::published, post_saved::post_id))
+      .load::<(DateTime<Utc>, PostId)>(conn)
+      .await?;
+    let comments = comment_saved::table
+      .filter(comment_saved::person_id.eq(person_id))
+      .order_by(comment_saved::published.desc())
+      .limit(offset + limit)
+      .select((comment_saved::published, comment_saved::comment_id))
+      .load(conn)
+      .await?;
+
+    let mut items: Vec<_> = posts
+      .into_iter()
+      .map(|(published, id)| (published, SavedItemId::Post(id)))
+      .chain(
+        comments
+          .into_iter()
+          .map(|(published, id)| (published, SavedItemId::Comment(id))),
+      )
+      .collect();
+    items.sort_by_key(|(published, _)| Reverse(*published));
+    Ok(
+      items
+        .into_iter()
+        .skip(usize::try_from(offset).unwrap_or_default())
+        .take(usize::try_from(limit).unwrap_or_default())
+        .map(|(_, item)| item)
+        .collect(),
+    )
+  }
+}
+
 impl PostRead {
   pub async fn mark_as_read(
     pool: &mut DbPool<'_>,
@@ -369,7 +486,9 @@ impl PostHide {
 mod tests {
 
   use crate::{
+    newtypes::DbUrl,
     source::{
+      comment::{Comment, CommentInsertForm, CommentSaved, CommentSavedForm},
       community::{Community, CommunityInsertForm},
       instance::Instance,
       person::{Person, PersonInsertForm},
@@ -382,11 +501,14 @@ mod tests {
         PostSaved,
         PostSavedForm,
         PostUpdateForm,
+        SavedItemId,
       },
     },
     traits::{Crud, Likeable, Saveable},
     utils::build_db_pool_for_tests,
   };
+  use chrono::{TimeDelta, Utc};
+  use lemmy_utils::error::LemmyResult;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
   use std::collections::HashSet;
@@ -436,6 +558,12 @@ mod tests {
       url: None,
       body: None,
       alt_text: None,
+      flair: None,
+      url_status: None,
+      url_checked: None,
+      url_broken: false,
+      url_archive: None,
+      url_canonical: None,
       creator_id: inserted_person.id,
       community_id: inserted_community.id,
       published: inserted_post.published,
@@ -538,4 +666,171 @@ mod tests {
     assert_eq!(expected_post_like, inserted_post_like);
     assert_eq!(expected_post_saved, inserted_post_saved);
   }
+  #[tokio::test]
+  #[serial]
+  async fn test_list_for_link_check() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "link_checker"),
+    )
+    .await?;
+    let community = Community::create(
+      pool,
+      &CommunityInsertForm::builder()
+        .name("link_check".to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(instance.id)
+        .build(),
+    )
+    .await?;
+    let post_form = |name: &str, url: Option<&str>| {
+      PostInsertForm::builder()
+        .name(name.into())
+        .creator_id(person.id)
+        .community_id(community.id)
+        .url(url.map(|u| Url::parse(u).unwrap().into()))
+        .build()
+    };
+    let text_post = Post::create(pool, &post_form("text", None)).await?;
+    let unchecked =
+      Post::create(pool, &post_form("unchecked", Some("https://example.com/1"))).await?;
+    let checked = Post::create(pool, &post_form("checked", Some("https://example.com/2"))).await?;
+    let outdated =
+      Post::create(pool, &post_form("outdated", Some("https://example.com/3"))).await?;
+
+    let check_form = |days_ago: i64| PostUpdateForm {
+      url_status: Some(Some(404)),
+      url_checked: Some(Some(Utc::now() - TimeDelta::days(days_ago))),
+      url_broken: Some(true),
+      ..Default::default()
+    };
+    Post::update(pool, checked.id, &check_form(1)).await?;
+    Post::update(pool, outdated.id, &check_form(10)).await?;
+
+    let to_check: Vec<_> = Post::list_for_link_check(pool, 7, 10)
+      .await?
+      .into_iter()
+      .map(|p| p.id)
+      .filter(|id| [text_post.id, unchecked.id, checked.id, outdated.id].contains(id))
+      .collect();
+    assert_eq!(vec![unchecked.id, outdated.id], to_check);
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_find_duplicate() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person =
+      Person::create(pool, &PersonInsertForm::test_form(instance.id, "reposter")).await?;
+    let community_form = |name: &str| {
+      CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(instance.id)
+        .build()
+    };
+    let community = Community::create(pool, &community_form("duplicates")).await?;
+    let other_community = Community::create(pool, &community_form("other_duplicates")).await?;
+
+    let canonical: DbUrl = Url::parse("https://example.com/article")?.into();
+    let url: DbUrl = Url::parse("https://www.example.com/article?utm_source=x")?.into();
+    let post_form = |community_id| {
+      PostInsertForm::builder()
+        .name("repost".into())
+        .creator_id(person.id)
+        .community_id(community_id)
+        .url(Some(url.clone()))
+        .url_canonical(Some(canonical.clone()))
+        .build()
+    };
+    let post = Post::create(pool, &post_form(community.id)).await?;
+    Post::create(pool, &post_form(other_community.id)).await?;
+
+    let week_ago = Utc::now() - TimeDelta::days(7);
+    let duplicate = Post::find_duplicate(pool, community.id, &canonical, week_ago, None).await?;
+    assert_eq!(Some(post.id), duplicate);
+
+    // Editing the post itself isn't a duplicate
+    let duplicate =
+      Post::find_duplicate(pool, community.id, &canonical, week_ago, Some(post.id)).await?;
+    assert_eq!(None, duplicate);
+
+    // Outside of the window
+    let duplicate = Post::find_duplicate(pool, community.id, &canonical, Utc::now(), None).await?;
+    assert_eq!(None, duplicate);
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_list_saved_with_comments() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "saver")).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("saved_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let mut posts = vec![];
+    for name in ["saved 1", "saved 2"] {
+      let form = PostInsertForm::builder()
+        .name(name.into())
+        .creator_id(person.id)
+        .community_id(community.id)
+        .build();
+      posts.push(Post::create(pool, &form).await?);
+    }
+    let comment_form = CommentInsertForm::builder()
+      .content("saved comment".into())
+      .creator_id(person.id)
+      .post_id(posts[0].id)
+      .build();
+    let comment = Comment::create(pool, &comment_form, None).await?;
+
+    // Save a post, then the comment, then the other post
+    let save_post = |post_id| PostSavedForm {
+      post_id,
+      person_id: person.id,
+    };
+    PostSaved::save(pool, &save_post(posts[0].id)).await?;
+    let comment_saved_form = CommentSavedForm {
+      comment_id: comment.id,
+      person_id: person.id,
+    };
+    CommentSaved::save(pool, &comment_saved_form).await?;
+    PostSaved::save(pool, &save_post(posts[1].id)).await?;
+
+    let page1 = PostSaved::list_with_comments(pool, person.id, Some(1), Some(2)).await?;
+    assert_eq!(
+      vec![
+        SavedItemId::Post(posts[1].id),
+        SavedItemId::Comment(comment.id)
+      ],
+      page1
+    );
+    let page2 = PostSaved::list_with_comments(pool, person.id, Some(2), Some(2)).await?;
+    assert_eq!(vec![SavedItemId::Post(posts[0].id)], page2);
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
 }
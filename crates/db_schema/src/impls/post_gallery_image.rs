@@ -0,0 +1,136 @@
+use crate::{
+  newtypes::PostId,
+  schema::post_gallery_image,
+  source::post_gallery_image::{PostGalleryImage, PostGalleryImageForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PostGalleryImage {
+  /// Replaces all gallery images of the post. Their positions are set by the order of the list.
+  pub async fn replace(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    forms: Vec<PostGalleryImageForm>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(post_gallery_image::table.filter(post_gallery_image::post_id.eq(post_id)))
+            .execute(conn)
+            .await?;
+          let forms = forms
+            .iter()
+            .zip(0..)
+            .map(|(form, position)| {
+              (
+                form,
+                post_gallery_image::post_id.eq(post_id),
+                post_gallery_image::position.eq(position),
+              )
+            })
+            .collect::<Vec<_>>();
+          insert_into(post_gallery_image::table)
+            .values(forms)
+            .get_results::<Self>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn list_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_gallery_image::table
+      .filter(post_gallery_image::post_id.eq(post_id))
+      .order_by(post_gallery_image::position)
+      .get_results::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_gallery_image::{PostGalleryImage, PostGalleryImageForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_replace_gallery() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "gallery")).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("gallery".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("photos".into())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+
+    let form = |url: &str| -> LemmyResult<PostGalleryImageForm> {
+      Ok(PostGalleryImageForm {
+        url: Url::parse(url)?.into(),
+        caption: Some(format!("caption of {url}")),
+        alt_text: None,
+      })
+    };
+    PostGalleryImage::replace(
+      pool,
+      post.id,
+      vec![
+        form("https://example.com/1.jpg")?,
+        form("https://example.com/2.jpg")?,
+      ],
+    )
+    .await?;
+
+    // Replacing changes the order and drops the removed image
+    PostGalleryImage::replace(
+      pool,
+      post.id,
+      vec![
+        form("https://example.com/3.jpg")?,
+        form("https://example.com/1.jpg")?,
+      ],
+    )
+    .await?;
+    let gallery = PostGalleryImage::list_for_post(pool, post.id).await?;
+    assert_eq!(2, gallery.len());
+    assert_eq!("https://example.com/3.jpg", gallery[0].url.as_str());
+    assert_eq!((post.id, 1), (gallery[1].post_id, gallery[1].position));
+    assert_eq!(
+      Some("caption of https://example.com/1.jpg".to_string()),
+      gallery[1].caption
+    );
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
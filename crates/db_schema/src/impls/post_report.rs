@@ -1,6 +1,7 @@
 use crate::{
   newtypes::{PersonId, PostId, PostReportId},
   schema::post_report::{
+    creator_id,
     dsl::{post_report, resolved, resolver_id, updated},
     post_id,
   },
@@ -12,6 +13,7 @@ use diesel::{
   dsl::{insert_into, update},
   result::Error,
   ExpressionMethods,
+  OptionalExtension,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
@@ -79,6 +81,39 @@ impl Reportable for PostReport {
   }
 }
 
+impl PostReport {
+  /// Number of reports for the post which haven't been resolved yet.
+  pub async fn count_unresolved_for_object(
+    pool: &mut DbPool<'_>,
+    post_id_: PostId,
+  ) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_report
+      .filter(post_id.eq(post_id_))
+      .filter(resolved.eq(false))
+      .count()
+      .get_result(conn)
+      .await
+  }
+
+  /// Creates a report, unless the creator already reported the post. Used for automated reports,
+  /// which may happen more than once and shouldn't reopen reports which moderators resolved.
+  /// Returns None if there was an earlier report.
+  pub async fn report_once(
+    pool: &mut DbPool<'_>,
+    post_report_form: &PostReportForm,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_report)
+      .values(post_report_form)
+      .on_conflict((post_id, creator_id))
+      .do_nothing()
+      .get_result::<Self>(conn)
+      .await
+      .optional()
+  }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::indexing_slicing)]
@@ -167,4 +202,33 @@ mod tests {
     Person::delete(pool, person.id).await.unwrap();
     Post::delete(pool, report.post_id).await.unwrap();
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_report_once() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let (person, report) = init(pool).await;
+    PostReport::resolve(pool, report.id, person.id)
+      .await
+      .unwrap();
+
+    // A second report by the same creator doesn't reopen the resolved one
+    let report_form = PostReportForm {
+      post_id: report.post_id,
+      creator_id: person.id,
+      reason: "again".to_string(),
+      ..Default::default()
+    };
+    let second = PostReport::report_once(pool, &report_form).await.unwrap();
+    assert!(second.is_none());
+    let unresolved = PostReport::count_unresolved_for_object(pool, report.post_id)
+      .await
+      .unwrap();
+    assert_eq!(0, unresolved);
+
+    Person::delete(pool, person.id).await.unwrap();
+    Post::delete(pool, report.post_id).await.unwrap();
+  }
 }
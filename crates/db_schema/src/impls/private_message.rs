@@ -1,13 +1,22 @@
 use crate::{
   diesel::{DecoratableTarget, OptionalExtension},
-  newtypes::{DbUrl, PersonId, PrivateMessageId},
-  schema::private_message,
-  source::private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
+  newtypes::{DbUrl, PersonId, PrivateMessageConversationId, PrivateMessageId},
+  schema::{private_message, private_message_conversation_participant, private_message_read},
+  source::{
+    private_message::{
+      PrivateMessage,
+      PrivateMessageInsertForm,
+      PrivateMessageRead,
+      PrivateMessageReadForm,
+      PrivateMessageUpdateForm,
+    },
+    private_message_conversation::PrivateMessageConversation,
+  },
   traits::Crud,
   utils::{functions::coalesce, get_conn, DbPool},
 };
 use chrono::{DateTime, Utc};
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, JoinOnDsl, QueryDsl};
 use diesel_async::RunQueryDsl;
 use url::Url;
 
@@ -18,11 +27,20 @@ impl Crud for PrivateMessage {
   type IdType = PrivateMessageId;
 
   async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let form = with_conversation(pool, form).await?;
     let conn = &mut get_conn(pool).await?;
-    insert_into(private_message::table)
-      .values(form)
+    let pm = insert_into(private_message::table)
+      .values(&form)
       .get_result::<Self>(conn)
-      .await
+      .await?;
+    PrivateMessageConversation::message_received(
+      pool,
+      pm.conversation_id,
+      pm.creator_id,
+      pm.published,
+    )
+    .await?;
+    Ok(pm)
   }
 
   async fn update(
@@ -44,30 +62,24 @@ impl PrivateMessage {
     timestamp: DateTime<Utc>,
     form: &PrivateMessageInsertForm,
   ) -> Result<Self, Error> {
+    let form = with_conversation(pool, form).await?;
     let conn = &mut get_conn(pool).await?;
-    insert_into(private_message::table)
-      .values(form)
+    let pm = insert_into(private_message::table)
+      .values(&form)
       .on_conflict(private_message::ap_id)
       .filter_target(coalesce(private_message::updated, private_message::published).lt(timestamp))
       .do_update()
-      .set(form)
+      .set(&form)
       .get_result::<Self>(conn)
-      .await
-  }
-
-  pub async fn mark_all_as_read(
-    pool: &mut DbPool<'_>,
-    for_recipient_id: PersonId,
-  ) -> Result<Vec<PrivateMessage>, Error> {
-    let conn = &mut get_conn(pool).await?;
-    diesel::update(
-      private_message::table
-        .filter(private_message::recipient_id.eq(for_recipient_id))
-        .filter(private_message::read.eq(false)),
+      .await?;
+    PrivateMessageConversation::message_received(
+      pool,
+      pm.conversation_id,
+      pm.creator_id,
+      pm.published,
     )
-    .set(private_message::read.eq(true))
-    .get_results::<Self>(conn)
-    .await
+    .await?;
+    Ok(pm)
   }
 
   pub async fn read_from_apub_id(
@@ -84,6 +96,86 @@ impl PrivateMessage {
   }
 }
 
+impl PrivateMessageRead {
+  pub async fn mark_as_read(
+    pool: &mut DbPool<'_>,
+    private_message_id: PrivateMessageId,
+    person_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(private_message_read::table)
+      .values(PrivateMessageReadForm {
+        private_message_id,
+        person_id,
+      })
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  pub async fn mark_as_unread(
+    pool: &mut DbPool<'_>,
+    private_message_id: PrivateMessageId,
+    person_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(private_message_read::table.find((private_message_id, person_id)))
+      .execute(conn)
+      .await
+  }
+
+  /// Marks all messages which others wrote to conversations of the person as read, optionally
+  /// limited to a single conversation.
+  pub async fn mark_all_as_read(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    conversation_id: Option<PrivateMessageConversationId>,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut unread = private_message::table
+      .inner_join(
+        private_message_conversation_participant::table.on(
+          private_message_conversation_participant::conversation_id
+            .eq(private_message::conversation_id),
+        ),
+      )
+      .filter(private_message_conversation_participant::person_id.eq(person_id))
+      .filter(private_message::creator_id.ne(person_id))
+      .select((
+        private_message::id,
+        private_message_conversation_participant::person_id,
+      ))
+      .into_boxed();
+    if let Some(conversation_id) = conversation_id {
+      unread = unread.filter(private_message::conversation_id.eq(conversation_id));
+    }
+    insert_into(private_message_read::table)
+      .values(unread)
+      .into_columns((
+        private_message_read::private_message_id,
+        private_message_read::person_id,
+      ))
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+  }
+}
+
+/// Fills in the conversation between creator and recipient, if the form doesn't specify one.
+async fn with_conversation(
+  pool: &mut DbPool<'_>,
+  form: &PrivateMessageInsertForm,
+) -> Result<PrivateMessageInsertForm, Error> {
+  let mut form = form.clone();
+  if form.conversation_id.is_none() {
+    let conversation =
+      PrivateMessageConversation::read_or_create(pool, form.creator_id, &[form.recipient_id])
+        .await?;
+    form.conversation_id = Some(conversation.id);
+  }
+  Ok(form)
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::indexing_slicing)]
@@ -93,7 +185,12 @@ mod tests {
     source::{
       instance::Instance,
       person::{Person, PersonInsertForm},
-      private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
+      private_message::{
+        PrivateMessage,
+        PrivateMessageInsertForm,
+        PrivateMessageRead,
+        PrivateMessageUpdateForm,
+      },
     },
     traits::Crud,
     utils::build_db_pool_for_tests,
@@ -136,7 +233,6 @@ mod tests {
       creator_id: inserted_creator.id,
       recipient_id: inserted_recipient.id,
       deleted: false,
-      read: false,
       updated: None,
       published: inserted_private_message.published,
       ap_id: Url::parse(&format!(
@@ -146,6 +242,8 @@ mod tests {
       .unwrap()
       .into(),
       local: true,
+      conversation_id: inserted_private_message.conversation_id,
+      encrypted: false,
     };
 
     let read_private_message = PrivateMessage::read(pool, inserted_private_message.id)
@@ -175,16 +273,10 @@ mod tests {
     )
     .await
     .unwrap();
-    let marked_read_private_message = PrivateMessage::update(
-      pool,
-      inserted_private_message.id,
-      &PrivateMessageUpdateForm {
-        read: Some(true),
-        ..Default::default()
-      },
-    )
-    .await
-    .unwrap();
+    let marked_read =
+      PrivateMessageRead::mark_as_read(pool, inserted_private_message.id, inserted_recipient.id)
+        .await
+        .unwrap();
     Person::delete(pool, inserted_creator.id).await.unwrap();
     Person::delete(pool, inserted_recipient.id).await.unwrap();
     Instance::delete(pool, inserted_instance.id).await.unwrap();
@@ -193,6 +285,6 @@ mod tests {
     assert_eq!(expected_private_message, updated_private_message);
     assert_eq!(expected_private_message, inserted_private_message);
     assert!(deleted_private_message.deleted);
-    assert!(marked_read_private_message.read);
+    assert_eq!(1, marked_read);
   }
 }
@@ -0,0 +1,382 @@
+use crate::{
+  diesel::OptionalExtension,
+  newtypes::{PersonId, PrivateMessageConversationId},
+  schema::{
+    community_follower,
+    person_follower,
+    private_message,
+    private_message_conversation,
+    private_message_conversation_participant,
+  },
+  source::{
+    private_message::PrivateMessageRead,
+    private_message_conversation::{
+      PrivateMessageConversation,
+      PrivateMessageConversationParticipant,
+      PrivateMessageConversationParticipantUpdateForm,
+    },
+  },
+  utils::{functions::greatest, get_conn, DbPool},
+};
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::{exists, insert_into, not, select},
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  JoinOnDsl,
+  QueryDsl,
+  SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+
+impl PrivateMessageConversation {
+  /// Returns the conversation between the creator and the recipients, creating it if necessary.
+  /// Recipients who don't know the creator get the conversation as a message request.
+  pub async fn read_or_create(
+    pool: &mut DbPool<'_>,
+    creator_id: PersonId,
+    recipient_ids: &[PersonId],
+  ) -> Result<Self, Error> {
+    let mut participants = recipient_ids.to_vec();
+    participants.push(creator_id);
+    participants.sort_by_key(|p| p.0);
+    participants.dedup();
+    let participant_ids: Vec<_> = participants.iter().copied().map(Some).collect();
+
+    let mut forms = vec![];
+    for person_id in participants {
+      let request =
+        person_id != creator_id && !Self::is_contact(pool, person_id, creator_id).await?;
+      forms.push((
+        private_message_conversation_participant::person_id.eq(person_id),
+        private_message_conversation_participant::request.eq(request),
+      ));
+    }
+
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          insert_into(private_message_conversation::table)
+            .values(private_message_conversation::participant_ids.eq(&participant_ids))
+            .on_conflict(private_message_conversation::participant_ids)
+            .do_nothing()
+            .execute(conn)
+            .await?;
+          let conversation = private_message_conversation::table
+            .filter(private_message_conversation::participant_ids.eq(&participant_ids))
+            .select(Self::as_select())
+            .first::<Self>(conn)
+            .await?;
+
+          // Existing participants keep their state
+          let forms: Vec<_> = forms
+            .into_iter()
+            .map(|(person_id, request)| {
+              (
+                private_message_conversation_participant::conversation_id.eq(conversation.id),
+                person_id,
+                request,
+              )
+            })
+            .collect();
+          insert_into(private_message_conversation_participant::table)
+            .values(forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          Ok(conversation)
+        }) as _
+      })
+      .await
+  }
+
+  /// Whether the person follows the other person, shares a community with them or has written to
+  /// them before. Messages from anyone else are message requests.
+  pub async fn is_contact(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    other_id: PersonId,
+  ) -> Result<bool, Error> {
+    if Self::has_written_to(pool, person_id, other_id).await? {
+      return Ok(true);
+    }
+    let conn = &mut get_conn(pool).await?;
+    let follows = person_follower::table
+      .find((person_id, other_id))
+      .filter(not(person_follower::pending));
+    let other_follower = diesel::alias!(community_follower as other_follower);
+    let shares_community = community_follower::table
+      .inner_join(other_follower.on(
+        community_follower::community_id.eq(other_follower.field(community_follower::community_id)),
+      ))
+      .filter(community_follower::person_id.eq(person_id))
+      .filter(not(community_follower::pending))
+      .filter(
+        other_follower
+          .field(community_follower::person_id)
+          .eq(other_id),
+      )
+      .filter(not(other_follower.field(community_follower::pending)));
+    select(exists(follows).or(exists(shares_community)))
+      .get_result(conn)
+      .await
+  }
+
+  /// Whether the person sent a message to a conversation which the other person takes part in.
+  pub async fn has_written_to(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    other_id: PersonId,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      private_message::table
+        .inner_join(
+          private_message_conversation_participant::table.on(
+            private_message_conversation_participant::conversation_id
+              .eq(private_message::conversation_id),
+          ),
+        )
+        .filter(private_message::creator_id.eq(person_id))
+        .filter(private_message_conversation_participant::person_id.eq(other_id)),
+    ))
+    .get_result(conn)
+    .await
+  }
+
+  /// Returns the ids of all people taking part in the conversation.
+  pub async fn list_participants(
+    pool: &mut DbPool<'_>,
+    conversation_id: PrivateMessageConversationId,
+  ) -> Result<Vec<PersonId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    private_message_conversation_participant::table
+      .filter(private_message_conversation_participant::conversation_id.eq(conversation_id))
+      .select(private_message_conversation_participant::person_id)
+      .order_by(private_message_conversation_participant::person_id)
+      .get_results(conn)
+      .await
+  }
+
+  /// Updates the conversation after a new message was added. Participants who didn't mute the
+  /// conversation get it back out of their archive, and replying accepts a message request.
+  pub async fn message_received(
+    pool: &mut DbPool<'_>,
+    conversation_id: PrivateMessageConversationId,
+    creator_id: PersonId,
+    published: DateTime<Utc>,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(private_message_conversation::table.find(conversation_id))
+      .set(
+        private_message_conversation::last_message_published.eq(greatest(
+          private_message_conversation::last_message_published,
+          published,
+        )),
+      )
+      .execute(conn)
+      .await?;
+    diesel::update(
+      private_message_conversation_participant::table
+        .filter(private_message_conversation_participant::conversation_id.eq(conversation_id))
+        .filter(private_message_conversation_participant::muted.eq(false))
+        .filter(private_message_conversation_participant::archived.eq(true)),
+    )
+    .set(private_message_conversation_participant::archived.eq(false))
+    .execute(conn)
+    .await?;
+    diesel::update(
+      private_message_conversation_participant::table
+        .find((conversation_id, creator_id))
+        .filter(private_message_conversation_participant::request),
+    )
+    .set(private_message_conversation_participant::request.eq(false))
+    .execute(conn)
+    .await?;
+    Ok(())
+  }
+}
+
+impl PrivateMessageConversationParticipant {
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    conversation_id: PrivateMessageConversationId,
+    person_id: PersonId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    private_message_conversation_participant::table
+      .find((conversation_id, person_id))
+      .select(Self::as_select())
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn update(
+    pool: &mut DbPool<'_>,
+    conversation_id: PrivateMessageConversationId,
+    person_id: PersonId,
+    form: &PrivateMessageConversationParticipantUpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      private_message_conversation_participant::table.find((conversation_id, person_id)),
+    )
+    .set(form)
+    .returning(Self::as_select())
+    .get_result(conn)
+    .await
+  }
+
+  /// Marks all messages in the conversation as read for the given person.
+  pub async fn mark_as_read(
+    pool: &mut DbPool<'_>,
+    conversation_id: PrivateMessageConversationId,
+    person_id: PersonId,
+  ) -> Result<Self, Error> {
+    PrivateMessageRead::mark_all_as_read(pool, person_id, Some(conversation_id)).await?;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      private_message_conversation_participant::table.find((conversation_id, person_id)),
+    )
+    .set(private_message_conversation_participant::last_read.eq(Utc::now()))
+    .returning(Self::as_select())
+    .get_result(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    newtypes::PrivateMessageId,
+    schema::private_message_read,
+    source::{
+      instance::Instance,
+      person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm},
+      private_message::{PrivateMessage, PrivateMessageInsertForm},
+      private_message_conversation::{
+        PrivateMessageConversation,
+        PrivateMessageConversationParticipant,
+        PrivateMessageConversationParticipantUpdateForm,
+      },
+    },
+    traits::{Crud, Followable},
+    utils::{build_db_pool_for_tests, get_conn},
+  };
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_conversation() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let alice =
+      Person::create(pool, &PersonInsertForm::test_form(instance.id, "alice_pmc")).await?;
+    let bob = Person::create(pool, &PersonInsertForm::test_form(instance.id, "bob_pmc")).await?;
+    let carol =
+      Person::create(pool, &PersonInsertForm::test_form(instance.id, "carol_pmc")).await?;
+
+    // Messages in both directions end up in the same conversation
+    let form = PrivateMessageInsertForm::builder()
+      .content("hi bob".into())
+      .creator_id(alice.id)
+      .recipient_id(bob.id)
+      .build();
+    let pm1 = PrivateMessage::create(pool, &form).await?;
+    // Bob doesn't know Alice, so it is a message request until he replies
+    let bob_state =
+      PrivateMessageConversationParticipant::read(pool, pm1.conversation_id, bob.id).await?;
+    assert_eq!(Some(true), bob_state.map(|p| p.request));
+    assert!(!PrivateMessageConversation::is_contact(pool, bob.id, alice.id).await?);
+    assert!(PrivateMessageConversation::is_contact(pool, alice.id, bob.id).await?);
+    let form = PrivateMessageInsertForm::builder()
+      .content("hi alice".into())
+      .creator_id(bob.id)
+      .recipient_id(alice.id)
+      .build();
+    let pm2 = PrivateMessage::create(pool, &form).await?;
+    let bob_state =
+      PrivateMessageConversationParticipant::read(pool, pm1.conversation_id, bob.id).await?;
+    assert_eq!(Some(false), bob_state.map(|p| p.request));
+    assert_eq!(pm1.conversation_id, pm2.conversation_id);
+    assert_eq!(
+      vec![alice.id, bob.id],
+      PrivateMessageConversation::list_participants(pool, pm1.conversation_id).await?
+    );
+
+    // Following someone makes them a contact
+    assert!(!PrivateMessageConversation::is_contact(pool, carol.id, alice.id).await?);
+    let follow_form = PersonFollowerForm {
+      person_id: alice.id,
+      follower_id: carol.id,
+      pending: false,
+    };
+    PersonFollower::follow(pool, &follow_form).await?;
+    assert!(PrivateMessageConversation::is_contact(pool, carol.id, alice.id).await?);
+
+    // A group conversation is separate from the one between two of its members
+    let group =
+      PrivateMessageConversation::read_or_create(pool, alice.id, &[carol.id, bob.id]).await?;
+    assert_ne!(pm1.conversation_id, group.id);
+    let group2 =
+      PrivateMessageConversation::read_or_create(pool, carol.id, &[bob.id, alice.id]).await?;
+    assert_eq!(group.id, group2.id);
+    let carol_state = PrivateMessageConversationParticipant::read(pool, group.id, carol.id).await?;
+    assert_eq!(Some(false), carol_state.map(|p| p.request));
+
+    // A new message takes the conversation out of the archive, unless it is muted
+    let form = PrivateMessageConversationParticipantUpdateForm {
+      archived: Some(true),
+      ..Default::default()
+    };
+    PrivateMessageConversationParticipant::update(pool, group.id, bob.id, &form).await?;
+    let form = PrivateMessageConversationParticipantUpdateForm {
+      archived: Some(true),
+      muted: Some(true),
+      ..Default::default()
+    };
+    PrivateMessageConversationParticipant::update(pool, group.id, carol.id, &form).await?;
+    let form = PrivateMessageInsertForm::builder()
+      .content("hi all".into())
+      .creator_id(alice.id)
+      .recipient_id(bob.id)
+      .conversation_id(Some(group.id))
+      .build();
+    let pm3 = PrivateMessage::create(pool, &form).await?;
+    assert_eq!(group.id, pm3.conversation_id);
+    let bob_state = PrivateMessageConversationParticipant::read(pool, group.id, bob.id).await?;
+    assert_eq!(Some(false), bob_state.map(|p| p.archived));
+    let carol_state = PrivateMessageConversationParticipant::read(pool, group.id, carol.id).await?;
+    assert_eq!(Some(true), carol_state.map(|p| p.archived));
+
+    let bob_state =
+      PrivateMessageConversationParticipant::mark_as_read(pool, pm1.conversation_id, bob.id)
+        .await?;
+    assert!(bob_state.last_read.is_some());
+    // Only messages of that conversation which were written by others are marked as read
+    let bob_read = private_message_read::table
+      .filter(private_message_read::person_id.eq(bob.id))
+      .select(private_message_read::private_message_id)
+      .load::<PrivateMessageId>(&mut get_conn(pool).await?)
+      .await?;
+    assert_eq!(vec![pm1.id], bob_read);
+
+    Person::delete(pool, alice.id).await?;
+    Person::delete(pool, bob.id).await?;
+    Person::delete(pool, carol.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
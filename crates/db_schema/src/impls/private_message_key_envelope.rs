@@ -0,0 +1,66 @@
+use crate::{
+  newtypes::{PersonId, PrivateMessageId},
+  schema::private_message_key_envelope,
+  source::private_message_key_envelope::{
+    PrivateMessageKeyEnvelope,
+    PrivateMessageKeyEnvelopeForm,
+  },
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PrivateMessageKeyEnvelope {
+  /// Replaces the key envelopes of a message, after it was created or edited.
+  pub async fn replace_for_message(
+    pool: &mut DbPool<'_>,
+    private_message_id: PrivateMessageId,
+    forms: &[PrivateMessageKeyEnvelopeForm],
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(
+            private_message_key_envelope::table
+              .filter(private_message_key_envelope::private_message_id.eq(private_message_id)),
+          )
+          .execute(conn)
+          .await?;
+          insert_into(private_message_key_envelope::table)
+            .values(forms)
+            .execute(conn)
+            .await?;
+          Ok(())
+        }) as _
+      })
+      .await
+  }
+
+  /// All key envelopes of a message, for federation.
+  pub async fn list_for_message(
+    pool: &mut DbPool<'_>,
+    private_message_id: PrivateMessageId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    private_message_key_envelope::table
+      .filter(private_message_key_envelope::private_message_id.eq(private_message_id))
+      .get_results(conn)
+      .await
+  }
+
+  /// The key envelopes for the devices of one person.
+  pub async fn list_for_person(
+    pool: &mut DbPool<'_>,
+    private_message_ids: &[PrivateMessageId],
+    person_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    private_message_key_envelope::table
+      .filter(private_message_key_envelope::private_message_id.eq_any(private_message_ids))
+      .filter(private_message_key_envelope::person_id.eq(person_id))
+      .get_results(conn)
+      .await
+  }
+}
@@ -0,0 +1,141 @@
+use crate::{
+  newtypes::DbUrl,
+  schema::received_activity_raw,
+  source::received_activity_raw::{ReceivedActivityRaw, ReceivedActivityRawForm, TableChanges},
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use diesel::{
+  dsl::insert_into,
+  result::Error,
+  sql_query,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl ReceivedActivityRaw {
+  /// Stores the activity, unless one with the same id was already stored. Returns None in that
+  /// case.
+  pub async fn insert(
+    pool: &mut DbPool<'_>,
+    form: &ReceivedActivityRawForm,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(received_activity_raw::table)
+      .values(form)
+      .on_conflict(received_activity_raw::ap_id)
+      .do_nothing()
+      .get_result::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, ap_id: &DbUrl) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    received_activity_raw::table
+      .find(ap_id)
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  /// Lists stored activities, newest first. Can be limited to those which failed to be received.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    only_errors: bool,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let mut query = received_activity_raw::table.into_boxed();
+    if only_errors {
+      query = query.filter(received_activity_raw::error.is_not_null());
+    }
+    query
+      .order_by(received_activity_raw::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .get_results(conn)
+      .await
+  }
+}
+
+impl TableChanges {
+  /// Number of rows changed per table by the current transaction, including changes made by
+  /// triggers. Only useful with a connection which is in a transaction, like those from
+  /// [build_rollback_db_pool](crate::utils::build_rollback_db_pool).
+  pub async fn read_for_transaction(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    sql_query(
+      "SELECT relname AS table, n_tup_ins AS inserted, n_tup_upd AS updated, \
+       n_tup_del AS deleted FROM pg_stat_xact_user_tables \
+       WHERE n_tup_ins + n_tup_upd + n_tup_del > 0 ORDER BY relname",
+    )
+    .get_results(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    source::{
+      instance::Instance,
+      received_activity_raw::{ReceivedActivityRaw, ReceivedActivityRawForm, TableChanges},
+    },
+    utils::{build_db_pool_for_tests, build_rollback_db_pool},
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_received_activity_raw_rollback() -> LemmyResult<()> {
+    let rollback_pool = build_rollback_db_pool()?;
+    let pool = &mut (&rollback_pool).into();
+
+    let ap_id = Url::parse("https://example.com/activities/like/1")?.into();
+    let form = ReceivedActivityRawForm {
+      ap_id,
+      data: r#"{"type":"Like"}"#.to_string(),
+      error: Some("unknown".to_string()),
+    };
+    assert!(ReceivedActivityRaw::insert(pool, &form).await?.is_some());
+    // An activity with the same id doesn't overwrite the stored one
+    let form = ReceivedActivityRawForm {
+      error: None,
+      ..form
+    };
+    assert_eq!(None, ReceivedActivityRaw::insert(pool, &form).await?);
+    let instance = Instance::read_or_create(pool, "rollback.tld".to_string()).await?;
+
+    let errors = ReceivedActivityRaw::list(pool, true, None, None).await?;
+    assert_eq!(1, errors.len());
+    assert_eq!(Some("unknown".to_string()), errors[0].error);
+
+    let changes = TableChanges::read_for_transaction(pool).await?;
+    let change = |table: &str| changes.iter().find(|c| c.table == table).cloned();
+    assert_eq!(
+      Some((1, 0)),
+      change("received_activity_raw").map(|c| (c.inserted, c.updated))
+    );
+    assert_eq!(Some(1), change("instance").map(|c| c.inserted));
+
+    // Nothing was committed
+    drop(rollback_pool);
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    assert_eq!(None, ReceivedActivityRaw::read(pool, &form.ap_id).await?);
+    let instances = Instance::read_all(pool).await?;
+    assert!(!instances.iter().any(|i| i.id == instance.id));
+
+    Ok(())
+  }
+}
@@ -0,0 +1,242 @@
+use crate::{
+  newtypes::{CommunityId, DbUrl, InstanceId, RelayId},
+  schema::{relay, relay_community_filter},
+  source::relay::{
+    Relay,
+    RelayCommunityFilter,
+    RelayCommunityFilterForm,
+    RelayInsertForm,
+    RelayUpdateForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for Relay {
+  type InsertForm = RelayInsertForm;
+  type UpdateForm = RelayUpdateForm;
+  type IdType = RelayId;
+
+  /// Inserts the relay, or updates its keys and inbox if it is already known.
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(relay::table)
+      .values(form)
+      .on_conflict(relay::actor_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    relay_id: RelayId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(relay::table.find(relay_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl Relay {
+  pub async fn read_from_apub_id(
+    pool: &mut DbPool<'_>,
+    object_id: &DbUrl,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    relay::table
+      .filter(relay::actor_id.eq(object_id))
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn list(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    relay::table.order_by(relay::id).load(conn).await
+  }
+
+  /// Relays on the given instance which have accepted our follow, together with their community
+  /// filters.
+  pub async fn list_accepted_for_instance(
+    pool: &mut DbPool<'_>,
+    instance_id: InstanceId,
+  ) -> Result<Vec<(Self, Vec<RelayCommunityFilter>)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let relays: Vec<Self> = relay::table
+      .filter(relay::instance_id.eq(instance_id))
+      .filter(relay::follow_accepted)
+      .load(conn)
+      .await?;
+    let relay_ids: Vec<RelayId> = relays.iter().map(|r| r.id).collect();
+    let filters: Vec<RelayCommunityFilter> = relay_community_filter::table
+      .filter(relay_community_filter::relay_id.eq_any(relay_ids))
+      .load(conn)
+      .await?;
+    Ok(
+      relays
+        .into_iter()
+        .map(|r| {
+          let relay_filters = filters
+            .iter()
+            .filter(|f| f.relay_id == r.id)
+            .cloned()
+            .collect();
+          (r, relay_filters)
+        })
+        .collect(),
+    )
+  }
+}
+
+impl RelayCommunityFilter {
+  pub async fn set(pool: &mut DbPool<'_>, form: &RelayCommunityFilterForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(relay_community_filter::table)
+      .values(form)
+      .on_conflict((
+        relay_community_filter::relay_id,
+        relay_community_filter::community_id,
+      ))
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn remove(
+    pool: &mut DbPool<'_>,
+    for_relay_id: RelayId,
+    for_community_id: CommunityId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(relay_community_filter::table.find((for_relay_id, for_community_id)))
+      .execute(conn)
+      .await
+  }
+
+  pub async fn list_for_relay(
+    pool: &mut DbPool<'_>,
+    for_relay_id: RelayId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    relay_community_filter::table
+      .filter(relay_community_filter::relay_id.eq(for_relay_id))
+      .order_by(relay_community_filter::community_id)
+      .load(conn)
+      .await
+  }
+
+  /// Whether activities of the given community may be exchanged with a relay which has these
+  /// filters.
+  pub fn allows(filters: &[Self], community_id: CommunityId) -> bool {
+    let mut has_allowlist = false;
+    for f in filters {
+      if f.community_id == community_id {
+        return f.allow;
+      }
+      has_allowlist |= f.allow;
+    }
+    !has_allowlist
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      relay::{
+        Relay,
+        RelayCommunityFilter,
+        RelayCommunityFilterForm,
+        RelayInsertForm,
+        RelayUpdateForm,
+      },
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_relay_filters() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "relay.tld".to_string()).await?;
+    let community_form = |name: &str| {
+      CommunityInsertForm::builder()
+        .name(name.into())
+        .title(name.to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(instance.id)
+        .build()
+    };
+    let allowed = Community::create(pool, &community_form("relay_allowed")).await?;
+    let other = Community::create(pool, &community_form("relay_other")).await?;
+
+    let form = RelayInsertForm::builder()
+      .actor_id(Url::parse("https://relay.tld/actor")?.into())
+      .inbox_url(Url::parse("https://relay.tld/inbox")?.into())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let relay = Relay::create(pool, &form).await?;
+    // Fetching the relay again must not create a duplicate
+    assert_eq!(relay.id, Relay::create(pool, &form).await?.id);
+    assert!(!relay.follow_accepted);
+    assert!(Relay::list_accepted_for_instance(pool, instance.id)
+      .await?
+      .is_empty());
+
+    let update_form = RelayUpdateForm {
+      follow_accepted: Some(true),
+      ..Default::default()
+    };
+    Relay::update(pool, relay.id, &update_form).await?;
+
+    // Without filters everything is allowed
+    let accepted = Relay::list_accepted_for_instance(pool, instance.id).await?;
+    assert_eq!(1, accepted.len());
+    assert!(RelayCommunityFilter::allows(&accepted[0].1, other.id));
+
+    // Denying a community only excludes that one
+    let mut filter_form = RelayCommunityFilterForm {
+      relay_id: relay.id,
+      community_id: other.id,
+      allow: false,
+    };
+    RelayCommunityFilter::set(pool, &filter_form).await?;
+    let filters = RelayCommunityFilter::list_for_relay(pool, relay.id).await?;
+    assert!(!RelayCommunityFilter::allows(&filters, other.id));
+    assert!(RelayCommunityFilter::allows(&filters, allowed.id));
+
+    // Once a community is allowed, all others are excluded
+    RelayCommunityFilter::remove(pool, relay.id, other.id).await?;
+    filter_form.community_id = allowed.id;
+    filter_form.allow = true;
+    RelayCommunityFilter::set(pool, &filter_form).await?;
+    let accepted = Relay::list_accepted_for_instance(pool, instance.id).await?;
+    assert!(RelayCommunityFilter::allows(&accepted[0].1, allowed.id));
+    assert!(!RelayCommunityFilter::allows(&accepted[0].1, other.id));
+
+    Instance::delete(pool, instance.id).await?;
+    assert_eq!(None, Relay::read(pool, relay.id).await?);
+
+    Ok(())
+  }
+}
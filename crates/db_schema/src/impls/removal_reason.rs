@@ -0,0 +1,121 @@
+use crate::{
+  newtypes::{CommunityId, RemovalReasonId},
+  schema::removal_reason,
+  source::removal_reason::{RemovalReason, RemovalReasonInsertForm, RemovalReasonUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for RemovalReason {
+  type InsertForm = RemovalReasonInsertForm;
+  type UpdateForm = RemovalReasonUpdateForm;
+  type IdType = RemovalReasonId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(removal_reason::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    removal_reason_id: RemovalReasonId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(removal_reason::table.find(removal_reason_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl RemovalReason {
+  pub async fn list_for_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    removal_reason::table
+      .filter(removal_reason::community_id.eq(for_community_id))
+      .order_by(removal_reason::title)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      removal_reason::{RemovalReason, RemovalReasonInsertForm, RemovalReasonUpdateForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_list_for_community() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let mut communities = vec![];
+    for name in ["removal_reason_community_1", "removal_reason_community_2"] {
+      let form = CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      communities.push(Community::create(pool, &form).await?);
+    }
+
+    let mut reasons = vec![];
+    for (community, title) in [
+      (&communities[0], "spam"),
+      (&communities[0], "off topic"),
+      (&communities[1], "spam"),
+    ] {
+      let form = RemovalReasonInsertForm::builder()
+        .community_id(community.id)
+        .title(title.to_string())
+        .reason(format!("Removed for {title}"))
+        .build();
+      reasons.push(RemovalReason::create(pool, &form).await?);
+    }
+
+    // Only the templates of the community, ordered by title
+    let list = RemovalReason::list_for_community(pool, communities[0].id).await?;
+    assert_eq!(vec![reasons[1].clone(), reasons[0].clone()], list);
+
+    let form = RemovalReasonUpdateForm {
+      reason: Some("No spam allowed".to_string()),
+      ..Default::default()
+    };
+    let updated = RemovalReason::update(pool, reasons[0].id, &form).await?;
+    assert_eq!("No spam allowed", updated.reason);
+    assert_eq!(reasons[0].title, updated.title);
+
+    // Templates are deleted with their community
+    Community::delete(pool, communities[1].id).await?;
+    assert!(RemovalReason::read(pool, reasons[2].id).await?.is_none());
+
+    Community::delete(pool, communities[0].id).await?;
+    Instance::delete(pool, inserted_instance.id).await?;
+    Ok(())
+  }
+}
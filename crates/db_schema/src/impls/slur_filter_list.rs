@@ -0,0 +1,46 @@
+use crate::{
+  newtypes::SlurFilterListId,
+  schema::slur_filter_list,
+  source::slur_filter_list::{SlurFilterList, SlurFilterListInsertForm, SlurFilterListUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for SlurFilterList {
+  type InsertForm = SlurFilterListInsertForm;
+  type UpdateForm = SlurFilterListUpdateForm;
+  type IdType = SlurFilterListId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(slur_filter_list::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    slur_filter_list_id: SlurFilterListId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(slur_filter_list::table.find(slur_filter_list_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl SlurFilterList {
+  pub async fn get_all(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    slur_filter_list::table
+      .order_by(slur_filter_list::name)
+      .load::<Self>(conn)
+      .await
+  }
+}
@@ -208,6 +208,7 @@ pub enum ModlogActionType {
   AdminPurgeCommunity,
   AdminPurgePost,
   AdminPurgeComment,
+  ModAutomodAction,
 }
 
 #[derive(
@@ -224,6 +225,17 @@ pub enum PostFeatureType {
   Community,
 }
 
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// How the author of removed content is told about the removal reason.
+pub enum RemovalNotification {
+  /// Send a private message from the moderator to the author.
+  PrivateMessage,
+  /// Reply to the removed content with a distinguished comment.
+  Reply,
+}
+
 #[derive(
   EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
 )]
@@ -245,6 +257,100 @@ pub enum CommunityVisibility {
   LocalOnly,
 }
 
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::AutomodActionEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// The action taken when an automod rule matches a post or comment.
+pub enum AutomodAction {
+  /// Remove the content.
+  #[default]
+  Remove,
+  /// Lock the post. Not applied to comments.
+  Lock,
+  /// Create a report, so that moderators review the content.
+  Report,
+  /// Remove the content and create a report, so that moderators can restore it after review.
+  Hold,
+  /// Reply to the content with the rule message.
+  Reply,
+  /// Set the flair of the post to the rule message. Not applied to comments.
+  SetFlair,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::SlurFilterActionEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// What happens to a post or comment which contains words from a slur filter.
+pub enum SlurFilterAction {
+  /// Reject the content with an error.
+  #[default]
+  Reject,
+  /// Accept the content and create a report, so that moderators review it.
+  Report,
+  /// Accept the content, but remove it and create a report, so that moderators can restore it
+  /// after review.
+  Hold,
+  /// Replace the matching words with `*removed*`.
+  Mask,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::FederationQueueResetEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// How to reset a stuck federation queue.
+pub enum FederationQueueReset {
+  /// Clear the fail count, so that the next activity is sent immediately.
+  #[default]
+  Retry,
+  /// Mark all activities as sent, so that only new activities are sent to the instance.
+  Skip,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::PrivateMessagePrivacyEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// Who is allowed to send private messages to a local user. Local admins and people the user has
+/// written to before can always send messages.
+pub enum PrivateMessagePrivacy {
+  /// Anyone, including users from other instances.
+  #[default]
+  Everyone,
+  /// Only users from the same instance.
+  LocalOnly,
+  /// Only people the user follows or shares a community with.
+  Connected,
+  /// Nobody.
+  Nobody,
+}
+
 /// Wrapper for assert_eq! macro. Checks that vec matches the given length, and prints the
 /// vec on failure.
 #[macro_export]
@@ -5,10 +5,18 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "actor_type_enum"))]
     pub struct ActorTypeEnum;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "automod_action_enum"))]
+    pub struct AutomodActionEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "community_visibility"))]
     pub struct CommunityVisibility;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "federation_queue_reset_enum"))]
+    pub struct FederationQueueResetEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "listing_type_enum"))]
     pub struct ListingTypeEnum;
@@ -21,15 +29,32 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "post_listing_mode_enum"))]
     pub struct PostListingModeEnum;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "private_message_privacy_enum"))]
+    pub struct PrivateMessagePrivacyEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "registration_mode_enum"))]
     pub struct RegistrationModeEnum;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "slur_filter_action_enum"))]
+    pub struct SlurFilterActionEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "sort_type_enum"))]
     pub struct SortTypeEnum;
 }
 
+diesel::table! {
+    actor_previous_key (actor_id) {
+        actor_id -> Text,
+        public_key -> Text,
+        published -> Timestamptz,
+        expires -> Timestamptz,
+    }
+}
+
 diesel::table! {
     admin_purge_comment (id) {
         id -> Int4,
@@ -68,6 +93,31 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AutomodActionEnum;
+
+    automod_rule (id) {
+        id -> Int4,
+        community_id -> Nullable<Int4>,
+        creator_id -> Int4,
+        name -> Text,
+        enabled -> Bool,
+        apply_to_posts -> Bool,
+        apply_to_comments -> Bool,
+        title_regex -> Nullable<Text>,
+        body_regex -> Nullable<Text>,
+        domain -> Nullable<Text>,
+        max_account_age_days -> Nullable<Int4>,
+        max_karma -> Nullable<Int4>,
+        min_report_count -> Nullable<Int4>,
+        action -> AutomodActionEnum,
+        action_message -> Nullable<Text>,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     captcha_answer (uuid) {
         uuid -> Uuid,
@@ -191,6 +241,13 @@ diesel::table! {
         #[max_length = 255]
         featured_url -> Nullable<Varchar>,
         visibility -> CommunityVisibility,
+        comment_slow_mode_seconds -> Nullable<Int4>,
+        post_slow_mode_seconds -> Nullable<Int4>,
+        #[max_length = 255]
+        moved_to -> Nullable<Varchar>,
+        also_known_as -> Array<Nullable<Text>>,
+        duplicate_post_window_days -> Nullable<Int4>,
+        community_comment_slow_mode_seconds -> Nullable<Int4>,
     }
 }
 
@@ -210,6 +267,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    community_backfill (community_id) {
+        community_id -> Int4,
+        pages_fetched -> Int4,
+        posts_imported -> Int4,
+        comments_imported -> Int4,
+        error -> Nullable<Text>,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+        finished -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     community_block (person_id, community_id) {
         person_id -> Int4,
@@ -296,16 +366,42 @@ diesel::table! {
         instance_id -> Int4,
         published -> Timestamptz,
         updated -> Nullable<Timestamptz>,
+        reason -> Nullable<Text>,
+        comment -> Nullable<Text>,
+        expires -> Nullable<Timestamptz>,
     }
 }
 
 diesel::table! {
+    federation_limitlist (instance_id) {
+        instance_id -> Int4,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+        reason -> Nullable<Text>,
+        comment -> Nullable<Text>,
+        expires -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::FederationQueueResetEnum;
+
     federation_queue_state (instance_id) {
         instance_id -> Int4,
         last_successful_id -> Nullable<Int8>,
         fail_count -> Int4,
         last_retry -> Nullable<Timestamptz>,
         last_successful_published_time -> Nullable<Timestamptz>,
+        requested_reset -> Nullable<FederationQueueResetEnum>,
+    }
+}
+
+diesel::table! {
+    hashtag (id) {
+        id -> Int4,
+        name -> Text,
+        published -> Timestamptz,
     }
 }
 
@@ -349,6 +445,25 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    link_metadata_cache (url) {
+        url -> Text,
+        title -> Nullable<Text>,
+        description -> Nullable<Text>,
+        image -> Nullable<Text>,
+        embed_video_url -> Nullable<Text>,
+        author -> Nullable<Text>,
+        published_date -> Nullable<Timestamptz>,
+        embed_html -> Nullable<Text>,
+        embed_video_width -> Nullable<Int4>,
+        embed_video_height -> Nullable<Int4>,
+        content_type -> Nullable<Text>,
+        fetched -> Timestamptz,
+        canonical_url -> Nullable<Text>,
+        final_url -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     local_image (pictrs_alias) {
         local_user_id -> Nullable<Int4>,
@@ -364,6 +479,7 @@ diesel::table! {
     use super::sql_types::RegistrationModeEnum;
     use super::sql_types::PostListingModeEnum;
     use super::sql_types::SortTypeEnum;
+    use super::sql_types::SlurFilterActionEnum;
 
     local_site (id) {
         id -> Int4,
@@ -392,6 +508,8 @@ diesel::table! {
         federation_signed_fetch -> Bool,
         default_post_listing_mode -> PostListingModeEnum,
         default_sort_type -> SortTypeEnum,
+        system_account_id -> Nullable<Int4>,
+        slur_filter_action -> Nullable<SlurFilterActionEnum>,
     }
 }
 
@@ -414,6 +532,12 @@ diesel::table! {
         updated -> Nullable<Timestamptz>,
         import_user_settings -> Int4,
         import_user_settings_per_second -> Int4,
+        vote -> Int4,
+        vote_per_second -> Int4,
+        report -> Int4,
+        report_per_second -> Int4,
+        private_message -> Int4,
+        private_message_per_second -> Int4,
     }
 }
 
@@ -426,11 +550,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    local_site_url_cleaning_rule (id) {
+        id -> Int4,
+        url_pattern -> Text,
+        params -> Array<Nullable<Text>>,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::SortTypeEnum;
     use super::sql_types::ListingTypeEnum;
     use super::sql_types::PostListingModeEnum;
+    use super::sql_types::PrivateMessagePrivacyEnum;
 
     local_user (id) {
         id -> Int4,
@@ -460,6 +595,7 @@ diesel::table! {
         enable_keyboard_navigation -> Bool,
         enable_animated_images -> Bool,
         collapse_bot_comments -> Bool,
+        private_message_privacy -> PrivateMessagePrivacyEnum,
     }
 }
 
@@ -511,6 +647,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AutomodActionEnum;
+
+    mod_automod_action (id) {
+        id -> Int4,
+        mod_person_id -> Int4,
+        rule_id -> Nullable<Int4>,
+        post_id -> Int4,
+        comment_id -> Nullable<Int4>,
+        action -> AutomodActionEnum,
+        reason -> Nullable<Text>,
+        when_ -> Timestamptz,
+    }
+}
+
 diesel::table! {
     mod_ban (id) {
         id -> Int4,
@@ -576,6 +728,7 @@ diesel::table! {
         reason -> Nullable<Text>,
         removed -> Bool,
         when_ -> Timestamptz,
+        removal_reason_id -> Nullable<Int4>,
     }
 }
 
@@ -598,6 +751,7 @@ diesel::table! {
         reason -> Nullable<Text>,
         removed -> Bool,
         when_ -> Timestamptz,
+        removal_reason_id -> Nullable<Int4>,
     }
 }
 
@@ -648,6 +802,9 @@ diesel::table! {
         bot_account -> Bool,
         ban_expires -> Nullable<Timestamptz>,
         instance_id -> Int4,
+        #[max_length = 255]
+        moved_to -> Nullable<Varchar>,
+        also_known_as -> Array<Nullable<Text>>,
     }
 }
 
@@ -676,6 +833,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    person_device_key (person_id, device_id) {
+        person_id -> Int4,
+        device_id -> Text,
+        public_key -> Text,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     person_follower (follower_id, person_id) {
         person_id -> Int4,
@@ -732,6 +898,12 @@ diesel::table! {
         featured_local -> Bool,
         url_content_type -> Nullable<Text>,
         alt_text -> Nullable<Text>,
+        flair -> Nullable<Text>,
+        url_status -> Nullable<Int4>,
+        url_checked -> Nullable<Timestamptz>,
+        url_broken -> Bool,
+        url_archive -> Nullable<Text>,
+        url_canonical -> Nullable<Text>,
     }
 }
 
@@ -757,6 +929,25 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_gallery_image (id) {
+        id -> Int4,
+        post_id -> Int4,
+        position -> Int4,
+        url -> Text,
+        caption -> Nullable<Text>,
+        alt_text -> Nullable<Text>,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    post_hashtag (post_id, hashtag_id) {
+        post_id -> Int4,
+        hashtag_id -> Int4,
+    }
+}
+
 diesel::table! {
     post_hide (person_id, post_id) {
         post_id -> Int4,
@@ -814,12 +1005,50 @@ diesel::table! {
         recipient_id -> Int4,
         content -> Text,
         deleted -> Bool,
-        read -> Bool,
         published -> Timestamptz,
         updated -> Nullable<Timestamptz>,
         #[max_length = 255]
         ap_id -> Varchar,
         local -> Bool,
+        conversation_id -> Int4,
+        encrypted -> Bool,
+    }
+}
+
+diesel::table! {
+    private_message_conversation (id) {
+        id -> Int4,
+        participant_ids -> Array<Nullable<Int4>>,
+        published -> Timestamptz,
+        last_message_published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    private_message_conversation_participant (conversation_id, person_id) {
+        conversation_id -> Int4,
+        person_id -> Int4,
+        muted -> Bool,
+        archived -> Bool,
+        last_read -> Nullable<Timestamptz>,
+        request -> Bool,
+    }
+}
+
+diesel::table! {
+    private_message_key_envelope (private_message_id, person_id, device_id) {
+        private_message_id -> Int4,
+        person_id -> Int4,
+        device_id -> Text,
+        encrypted_key -> Text,
+    }
+}
+
+diesel::table! {
+    private_message_read (private_message_id, person_id) {
+        private_message_id -> Int4,
+        person_id -> Int4,
+        published -> Timestamptz,
     }
 }
 
@@ -834,6 +1063,7 @@ diesel::table! {
         resolver_id -> Nullable<Int4>,
         published -> Timestamptz,
         updated -> Nullable<Timestamptz>,
+        decrypted_excerpt -> Nullable<Text>,
     }
 }
 
@@ -844,6 +1074,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    received_activity_raw (ap_id) {
+        ap_id -> Text,
+        data -> Text,
+        error -> Nullable<Text>,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     registration_application (id) {
         id -> Int4,
@@ -855,6 +1094,30 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    relay (id) {
+        id -> Int4,
+        #[max_length = 255]
+        actor_id -> Varchar,
+        #[max_length = 255]
+        inbox_url -> Varchar,
+        public_key -> Text,
+        instance_id -> Int4,
+        follow_accepted -> Bool,
+        published -> Timestamptz,
+        last_refreshed_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    relay_community_filter (relay_id, community_id) {
+        relay_id -> Int4,
+        community_id -> Int4,
+        allow -> Bool,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     remote_image (link) {
         link -> Text,
@@ -862,6 +1125,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    removal_reason (id) {
+        id -> Int4,
+        community_id -> Int4,
+        title -> Text,
+        reason -> Text,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     secret (id) {
         id -> Int4,
@@ -932,6 +1206,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::SlurFilterActionEnum;
+
+    slur_filter_list (id) {
+        id -> Int4,
+        name -> Text,
+        regex -> Text,
+        action -> SlurFilterActionEnum,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     tagline (id) {
         id -> Int4,
@@ -948,6 +1236,8 @@ diesel::joinable!(admin_purge_community -> person (admin_person_id));
 diesel::joinable!(admin_purge_person -> person (admin_person_id));
 diesel::joinable!(admin_purge_post -> community (community_id));
 diesel::joinable!(admin_purge_post -> person (admin_person_id));
+diesel::joinable!(automod_rule -> community (community_id));
+diesel::joinable!(automod_rule -> person (creator_id));
 diesel::joinable!(comment -> language (language_id));
 diesel::joinable!(comment -> person (creator_id));
 diesel::joinable!(comment -> post (post_id));
@@ -962,6 +1252,7 @@ diesel::joinable!(comment_saved -> comment (comment_id));
 diesel::joinable!(comment_saved -> person (person_id));
 diesel::joinable!(community -> instance (instance_id));
 diesel::joinable!(community_aggregates -> community (community_id));
+diesel::joinable!(community_backfill -> community (community_id));
 diesel::joinable!(community_block -> community (community_id));
 diesel::joinable!(community_block -> person (person_id));
 diesel::joinable!(community_follower -> community (community_id));
@@ -977,10 +1268,12 @@ diesel::joinable!(custom_emoji_keyword -> custom_emoji (custom_emoji_id));
 diesel::joinable!(email_verification -> local_user (local_user_id));
 diesel::joinable!(federation_allowlist -> instance (instance_id));
 diesel::joinable!(federation_blocklist -> instance (instance_id));
+diesel::joinable!(federation_limitlist -> instance (instance_id));
 diesel::joinable!(federation_queue_state -> instance (instance_id));
 diesel::joinable!(instance_block -> instance (instance_id));
 diesel::joinable!(instance_block -> person (person_id));
 diesel::joinable!(local_image -> local_user (local_user_id));
+diesel::joinable!(local_site -> person (system_account_id));
 diesel::joinable!(local_site -> site (site_id));
 diesel::joinable!(local_site_rate_limit -> local_site (local_site_id));
 diesel::joinable!(local_user -> person (person_id));
@@ -989,6 +1282,10 @@ diesel::joinable!(local_user_language -> local_user (local_user_id));
 diesel::joinable!(local_user_vote_display_mode -> local_user (local_user_id));
 diesel::joinable!(login_token -> local_user (user_id));
 diesel::joinable!(mod_add_community -> community (community_id));
+diesel::joinable!(mod_automod_action -> automod_rule (rule_id));
+diesel::joinable!(mod_automod_action -> comment (comment_id));
+diesel::joinable!(mod_automod_action -> person (mod_person_id));
+diesel::joinable!(mod_automod_action -> post (post_id));
 diesel::joinable!(mod_ban_from_community -> community (community_id));
 diesel::joinable!(mod_feature_post -> person (mod_person_id));
 diesel::joinable!(mod_feature_post -> post (post_id));
@@ -998,15 +1295,18 @@ diesel::joinable!(mod_lock_post -> person (mod_person_id));
 diesel::joinable!(mod_lock_post -> post (post_id));
 diesel::joinable!(mod_remove_comment -> comment (comment_id));
 diesel::joinable!(mod_remove_comment -> person (mod_person_id));
+diesel::joinable!(mod_remove_comment -> removal_reason (removal_reason_id));
 diesel::joinable!(mod_remove_community -> community (community_id));
 diesel::joinable!(mod_remove_community -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> post (post_id));
+diesel::joinable!(mod_remove_post -> removal_reason (removal_reason_id));
 diesel::joinable!(mod_transfer_community -> community (community_id));
 diesel::joinable!(password_reset_request -> local_user (local_user_id));
 diesel::joinable!(person -> instance (instance_id));
 diesel::joinable!(person_aggregates -> person (person_id));
 diesel::joinable!(person_ban -> person (person_id));
+diesel::joinable!(person_device_key -> person (person_id));
 diesel::joinable!(person_mention -> comment (comment_id));
 diesel::joinable!(person_mention -> person (recipient_id));
 diesel::joinable!(person_post_aggregates -> person (person_id));
@@ -1018,6 +1318,9 @@ diesel::joinable!(post_aggregates -> community (community_id));
 diesel::joinable!(post_aggregates -> instance (instance_id));
 diesel::joinable!(post_aggregates -> person (creator_id));
 diesel::joinable!(post_aggregates -> post (post_id));
+diesel::joinable!(post_gallery_image -> post (post_id));
+diesel::joinable!(post_hashtag -> hashtag (hashtag_id));
+diesel::joinable!(post_hashtag -> post (post_id));
 diesel::joinable!(post_hide -> person (person_id));
 diesel::joinable!(post_hide -> post (post_id));
 diesel::joinable!(post_like -> person (person_id));
@@ -1027,9 +1330,20 @@ diesel::joinable!(post_read -> post (post_id));
 diesel::joinable!(post_report -> post (post_id));
 diesel::joinable!(post_saved -> person (person_id));
 diesel::joinable!(post_saved -> post (post_id));
+diesel::joinable!(private_message -> private_message_conversation (conversation_id));
+diesel::joinable!(private_message_conversation_participant -> person (person_id));
+diesel::joinable!(private_message_conversation_participant -> private_message_conversation (conversation_id));
+diesel::joinable!(private_message_key_envelope -> person (person_id));
+diesel::joinable!(private_message_key_envelope -> private_message (private_message_id));
+diesel::joinable!(private_message_read -> person (person_id));
+diesel::joinable!(private_message_read -> private_message (private_message_id));
 diesel::joinable!(private_message_report -> private_message (private_message_id));
 diesel::joinable!(registration_application -> local_user (local_user_id));
 diesel::joinable!(registration_application -> person (admin_id));
+diesel::joinable!(relay -> instance (instance_id));
+diesel::joinable!(relay_community_filter -> community (community_id));
+diesel::joinable!(relay_community_filter -> relay (relay_id));
+diesel::joinable!(removal_reason -> community (community_id));
 diesel::joinable!(site -> instance (instance_id));
 diesel::joinable!(site_aggregates -> site (site_id));
 diesel::joinable!(site_language -> language (language_id));
@@ -1037,10 +1351,12 @@ diesel::joinable!(site_language -> site (site_id));
 diesel::joinable!(tagline -> local_site (local_site_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    actor_previous_key,
     admin_purge_comment,
     admin_purge_community,
     admin_purge_person,
     admin_purge_post,
+    automod_rule,
     captcha_answer,
     comment,
     comment_aggregates,
@@ -1050,6 +1366,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     comment_saved,
     community,
     community_aggregates,
+    community_backfill,
     community_block,
     community_follower,
     community_language,
@@ -1060,21 +1377,26 @@ diesel::allow_tables_to_appear_in_same_query!(
     email_verification,
     federation_allowlist,
     federation_blocklist,
+    federation_limitlist,
     federation_queue_state,
+    hashtag,
     image_details,
     instance,
     instance_block,
     language,
+    link_metadata_cache,
     local_image,
     local_site,
     local_site_rate_limit,
     local_site_url_blocklist,
+    local_site_url_cleaning_rule,
     local_user,
     local_user_language,
     local_user_vote_display_mode,
     login_token,
     mod_add,
     mod_add_community,
+    mod_automod_action,
     mod_ban,
     mod_ban_from_community,
     mod_feature_post,
@@ -1089,25 +1411,37 @@ diesel::allow_tables_to_appear_in_same_query!(
     person_aggregates,
     person_ban,
     person_block,
+    person_device_key,
     person_follower,
     person_mention,
     person_post_aggregates,
     post,
     post_aggregates,
+    post_gallery_image,
+    post_hashtag,
     post_hide,
     post_like,
     post_read,
     post_report,
     post_saved,
     private_message,
+    private_message_conversation,
+    private_message_conversation_participant,
+    private_message_key_envelope,
+    private_message_read,
     private_message_report,
     received_activity,
+    received_activity_raw,
     registration_application,
+    relay,
+    relay_community_filter,
     remote_image,
+    removal_reason,
     secret,
     sent_activity,
     site,
     site_aggregates,
     site_language,
+    slur_filter_list,
     tagline,
 );
@@ -0,0 +1,29 @@
+use crate::newtypes::DbUrl;
+#[cfg(feature = "full")]
+use crate::schema::actor_previous_key;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// The public key which an actor used before rotating its keypair. Signatures made with it are
+/// still accepted until it expires.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = actor_previous_key))]
+#[cfg_attr(feature = "full", diesel(primary_key(actor_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+pub struct ActorPreviousKey {
+  pub actor_id: DbUrl,
+  pub public_key: String,
+  pub published: DateTime<Utc>,
+  pub expires: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = actor_previous_key))]
+pub struct ActorPreviousKeyForm {
+  pub actor_id: DbUrl,
+  pub public_key: String,
+  pub expires: DateTime<Utc>,
+}
@@ -0,0 +1,91 @@
+#[cfg(feature = "full")]
+use crate::schema::automod_rule;
+use crate::{
+  newtypes::{AutomodRuleId, CommunityId, PersonId},
+  AutomodAction,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+use typed_builder::TypedBuilder;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = automod_rule))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A rule which is checked against new and edited posts and comments, and which automatically
+/// takes a moderation action when all of its conditions match.
+pub struct AutomodRule {
+  pub id: AutomodRuleId,
+  /// The community the rule applies to. If empty, it applies to the whole site.
+  pub community_id: Option<CommunityId>,
+  pub creator_id: PersonId,
+  pub name: String,
+  pub enabled: bool,
+  pub apply_to_posts: bool,
+  pub apply_to_comments: bool,
+  /// Matches against the post title. Comments don't have a title, so they never match.
+  pub title_regex: Option<String>,
+  /// Matches against the post body or comment text.
+  pub body_regex: Option<String>,
+  /// Matches if the post url is on this domain or one of its subdomains.
+  pub domain: Option<String>,
+  /// Matches if the creator account is younger than this many days.
+  pub max_account_age_days: Option<i32>,
+  /// Matches if the creator has at most this combined post and comment score.
+  pub max_karma: Option<i32>,
+  /// Matches if the content has at least this many reports.
+  pub min_report_count: Option<i32>,
+  pub action: AutomodAction,
+  /// The removal or report reason, reply text, or flair, depending on the action.
+  pub action_message: Option<String>,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = automod_rule))]
+pub struct AutomodRuleInsertForm {
+  pub community_id: Option<CommunityId>,
+  #[builder(!default)]
+  pub creator_id: PersonId,
+  #[builder(!default)]
+  pub name: String,
+  pub enabled: Option<bool>,
+  pub apply_to_posts: Option<bool>,
+  pub apply_to_comments: Option<bool>,
+  pub title_regex: Option<String>,
+  pub body_regex: Option<String>,
+  pub domain: Option<String>,
+  pub max_account_age_days: Option<i32>,
+  pub max_karma: Option<i32>,
+  pub min_report_count: Option<i32>,
+  #[builder(!default)]
+  pub action: AutomodAction,
+  pub action_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = automod_rule))]
+pub struct AutomodRuleUpdateForm {
+  pub name: Option<String>,
+  pub enabled: Option<bool>,
+  pub apply_to_posts: Option<bool>,
+  pub apply_to_comments: Option<bool>,
+  pub title_regex: Option<Option<String>>,
+  pub body_regex: Option<Option<String>>,
+  pub domain: Option<Option<String>>,
+  pub max_account_age_days: Option<Option<i32>>,
+  pub max_karma: Option<Option<i32>>,
+  pub min_report_count: Option<Option<i32>>,
+  pub action: Option<AutomodAction>,
+  pub action_message: Option<Option<String>>,
+  pub updated: Option<Option<DateTime<Utc>>>,
+}
@@ -69,6 +69,22 @@ pub struct Community {
   #[serde(skip)]
   pub featured_url: Option<DbUrl>,
   pub visibility: CommunityVisibility,
+  /// Users can only comment once per this many seconds in each post. Mods and admins are exempt.
+  pub comment_slow_mode_seconds: Option<i32>,
+  /// Users can only post once per this many seconds in the community. Mods and admins are exempt.
+  pub post_slow_mode_seconds: Option<i32>,
+  /// If the community was moved to another instance, the federated actor_id of the new community.
+  pub moved_to: Option<DbUrl>,
+  /// Communities which were imported into this one, and which are allowed to move here.
+  #[cfg_attr(feature = "full", ts(skip))]
+  #[serde(skip)]
+  pub also_known_as: Vec<Option<DbUrl>>,
+  /// Posts linking to the same url as another post in the community from this many days are
+  /// rejected. Mods and admins are exempt.
+  pub duplicate_post_window_days: Option<i32>,
+  /// Users can only comment once per this many seconds anywhere in the community. Mods and admins
+  /// are exempt.
+  pub community_comment_slow_mode_seconds: Option<i32>,
 }
 
 #[derive(Debug, Clone, TypedBuilder, Default)]
@@ -103,6 +119,12 @@ pub struct CommunityInsertForm {
   #[builder(!default)]
   pub instance_id: InstanceId,
   pub visibility: Option<CommunityVisibility>,
+  pub comment_slow_mode_seconds: Option<i32>,
+  pub post_slow_mode_seconds: Option<i32>,
+  pub moved_to: Option<DbUrl>,
+  pub also_known_as: Option<Vec<Option<DbUrl>>>,
+  pub duplicate_post_window_days: Option<i32>,
+  pub community_comment_slow_mode_seconds: Option<i32>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -131,6 +153,12 @@ pub struct CommunityUpdateForm {
   pub hidden: Option<bool>,
   pub posting_restricted_to_mods: Option<bool>,
   pub visibility: Option<CommunityVisibility>,
+  pub comment_slow_mode_seconds: Option<Option<i32>>,
+  pub post_slow_mode_seconds: Option<Option<i32>>,
+  pub moved_to: Option<Option<DbUrl>>,
+  pub also_known_as: Option<Vec<Option<DbUrl>>>,
+  pub duplicate_post_window_days: Option<Option<i32>>,
+  pub community_comment_slow_mode_seconds: Option<Option<i32>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -0,0 +1,41 @@
+use crate::newtypes::CommunityId;
+#[cfg(feature = "full")]
+use crate::schema::community_backfill;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_backfill))]
+#[cfg_attr(feature = "full", diesel(primary_key(community_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Progress of importing the history of a remote community from its outbox.
+pub struct CommunityBackfill {
+  pub community_id: CommunityId,
+  pub pages_fetched: i32,
+  pub posts_imported: i32,
+  pub comments_imported: i32,
+  /// Set if the backfill was aborted because of an error.
+  pub error: Option<String>,
+  /// When the backfill was started.
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+  pub finished: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_backfill))]
+pub struct CommunityBackfillUpdateForm {
+  pub pages_fetched: Option<i32>,
+  pub posts_imported: Option<i32>,
+  pub comments_imported: Option<i32>,
+  pub error: Option<Option<String>>,
+  pub updated: Option<Option<DateTime<Utc>>>,
+  pub finished: Option<Option<DateTime<Utc>>>,
+}
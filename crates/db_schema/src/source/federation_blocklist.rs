@@ -21,6 +21,12 @@ pub struct FederationBlockList {
   pub instance_id: InstanceId,
   pub published: DateTime<Utc>,
   pub updated: Option<DateTime<Utc>>,
+  /// Public reason for the block.
+  pub reason: Option<String>,
+  /// Private comment, only visible to admins.
+  pub comment: Option<String>,
+  /// The block is removed automatically at this time.
+  pub expires: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, Default)]
@@ -29,4 +35,7 @@ pub struct FederationBlockList {
 pub struct FederationBlockListForm {
   pub instance_id: InstanceId,
   pub updated: Option<DateTime<Utc>>,
+  pub reason: Option<String>,
+  pub comment: Option<String>,
+  pub expires: Option<DateTime<Utc>>,
 }
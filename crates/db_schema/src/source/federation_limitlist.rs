@@ -0,0 +1,43 @@
+use crate::newtypes::InstanceId;
+#[cfg(feature = "full")]
+use crate::schema::federation_limitlist;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// An instance with limited federation. Its content is accepted, but not shown in the `All` listing
+/// to users who don't follow the community.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Associations, Identifiable)
+)]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::instance::Instance))
+)]
+#[cfg_attr(feature = "full", diesel(table_name = federation_limitlist))]
+#[cfg_attr(feature = "full", diesel(primary_key(instance_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+pub struct FederationLimitList {
+  pub instance_id: InstanceId,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+  /// Public reason for the limit.
+  pub reason: Option<String>,
+  /// Private comment, only visible to admins.
+  pub comment: Option<String>,
+  /// The limit is removed automatically at this time.
+  pub expires: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_limitlist))]
+pub struct FederationLimitListForm {
+  pub instance_id: InstanceId,
+  pub updated: Option<DateTime<Utc>>,
+  pub reason: Option<String>,
+  pub comment: Option<String>,
+  pub expires: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,20 @@
+use crate::newtypes::HashtagId;
+#[cfg(feature = "full")]
+use crate::schema::hashtag;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = hashtag))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A hashtag which is used in posts.
+pub struct Hashtag {
+  pub id: HashtagId,
+  /// The lowercase name, without leading `#`.
+  pub name: String,
+  pub published: DateTime<Utc>,
+}
@@ -0,0 +1,51 @@
+use crate::newtypes::DbUrl;
+#[cfg(feature = "full")]
+use crate::schema::link_metadata_cache;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = link_metadata_cache))]
+#[cfg_attr(feature = "full", diesel(primary_key(url)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+/// Metadata which was fetched for an external link, so it doesn't need to be fetched again
+/// whenever the same link is shared.
+pub struct LinkMetadataCache {
+  pub url: DbUrl,
+  pub title: Option<String>,
+  pub description: Option<String>,
+  pub image: Option<DbUrl>,
+  pub embed_video_url: Option<DbUrl>,
+  pub author: Option<String>,
+  pub published_date: Option<DateTime<Utc>>,
+  pub embed_html: Option<String>,
+  pub embed_video_width: Option<i32>,
+  pub embed_video_height: Option<i32>,
+  pub content_type: Option<String>,
+  pub fetched: DateTime<Utc>,
+  pub canonical_url: Option<DbUrl>,
+  pub final_url: Option<DbUrl>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = link_metadata_cache))]
+#[cfg_attr(feature = "full", diesel(treat_none_as_null = true))]
+pub struct LinkMetadataCacheForm {
+  pub url: DbUrl,
+  pub title: Option<String>,
+  pub description: Option<String>,
+  pub image: Option<DbUrl>,
+  pub embed_video_url: Option<DbUrl>,
+  pub author: Option<String>,
+  pub published_date: Option<DateTime<Utc>>,
+  pub embed_html: Option<String>,
+  pub embed_video_width: Option<i32>,
+  pub embed_video_height: Option<i32>,
+  pub content_type: Option<String>,
+  pub canonical_url: Option<DbUrl>,
+  pub final_url: Option<DbUrl>,
+}
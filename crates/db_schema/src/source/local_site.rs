@@ -1,10 +1,11 @@
 #[cfg(feature = "full")]
 use crate::schema::local_site;
 use crate::{
-  newtypes::{LocalSiteId, SiteId},
+  newtypes::{LocalSiteId, PersonId, SiteId},
   ListingType,
   PostListingMode,
   RegistrationMode,
+  SlurFilterAction,
   SortType,
 };
 use chrono::{DateTime, Utc};
@@ -68,6 +69,11 @@ pub struct LocalSite {
   pub default_post_listing_mode: PostListingMode,
   /// Default value for [LocalUser.post_listing_mode]
   pub default_sort_type: SortType,
+  /// Bot account which is shown as the moderator for automated actions.
+  pub system_account_id: Option<PersonId>,
+  /// What happens to posts and comments matching the slur filter regex. If empty, post titles
+  /// with slurs are rejected and slurs in text are masked.
+  pub slur_filter_action: Option<SlurFilterAction>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -98,6 +104,7 @@ pub struct LocalSiteInsertForm {
   pub federation_signed_fetch: Option<bool>,
   pub default_post_listing_mode: Option<PostListingMode>,
   pub default_sort_type: Option<SortType>,
+  pub slur_filter_action: Option<SlurFilterAction>,
 }
 
 #[derive(Clone, Default)]
@@ -126,4 +133,6 @@ pub struct LocalSiteUpdateForm {
   pub federation_signed_fetch: Option<bool>,
   pub default_post_listing_mode: Option<PostListingMode>,
   pub default_sort_type: Option<SortType>,
+  pub system_account_id: Option<Option<PersonId>>,
+  pub slur_filter_action: Option<Option<SlurFilterAction>>,
 }
@@ -38,6 +38,12 @@ pub struct LocalSiteRateLimit {
   pub updated: Option<DateTime<Utc>>,
   pub import_user_settings: i32,
   pub import_user_settings_per_second: i32,
+  pub vote: i32,
+  pub vote_per_second: i32,
+  pub report: i32,
+  pub report_per_second: i32,
+  pub private_message: i32,
+  pub private_message_per_second: i32,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -61,6 +67,12 @@ pub struct LocalSiteRateLimitInsertForm {
   pub search_per_second: Option<i32>,
   pub import_user_settings: Option<i32>,
   pub import_user_settings_per_second: Option<i32>,
+  pub vote: Option<i32>,
+  pub vote_per_second: Option<i32>,
+  pub report: Option<i32>,
+  pub report_per_second: Option<i32>,
+  pub private_message: Option<i32>,
+  pub private_message_per_second: Option<i32>,
 }
 
 #[derive(Clone, Default)]
@@ -81,5 +93,11 @@ pub struct LocalSiteRateLimitUpdateForm {
   pub search_per_second: Option<i32>,
   pub import_user_settings: Option<i32>,
   pub import_user_settings_per_second: Option<i32>,
+  pub vote: Option<i32>,
+  pub vote_per_second: Option<i32>,
+  pub report: Option<i32>,
+  pub report_per_second: Option<i32>,
+  pub private_message: Option<i32>,
+  pub private_message_per_second: Option<i32>,
   pub updated: Option<Option<DateTime<Utc>>>,
 }
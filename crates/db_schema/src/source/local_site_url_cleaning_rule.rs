@@ -0,0 +1,33 @@
+#[cfg(feature = "full")]
+use crate::schema::local_site_url_cleaning_rule;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = local_site_url_cleaning_rule))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A url cleaning rule added by the admins, applied after the bundled ClearURLs rules.
+pub struct LocalSiteUrlCleaningRule {
+  pub id: i32,
+  /// Regex for the urls this rule applies to.
+  pub url_pattern: String,
+  /// Regexes for the names of query parameters which are removed.
+  pub params: Vec<Option<String>>,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = local_site_url_cleaning_rule))]
+pub struct LocalSiteUrlCleaningRuleForm {
+  pub url_pattern: String,
+  pub params: Vec<Option<String>>,
+  pub updated: Option<DateTime<Utc>>,
+}
@@ -5,6 +5,7 @@ use crate::{
   sensitive::SensitiveString,
   ListingType,
   PostListingMode,
+  PrivateMessagePrivacy,
   SortType,
 };
 use serde::{Deserialize, Serialize};
@@ -63,6 +64,8 @@ pub struct LocalUser {
   pub enable_animated_images: bool,
   /// Whether to auto-collapse bot comments.
   pub collapse_bot_comments: bool,
+  /// Who is allowed to send private messages to the user.
+  pub private_message_privacy: PrivateMessagePrivacy,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -117,6 +120,8 @@ pub struct LocalUserInsertForm {
   pub enable_animated_images: Option<bool>,
   #[new(default)]
   pub collapse_bot_comments: Option<bool>,
+  #[new(default)]
+  pub private_message_privacy: Option<PrivateMessagePrivacy>,
 }
 
 #[derive(Clone, Default)]
@@ -147,4 +152,5 @@ pub struct LocalUserUpdateForm {
   pub enable_keyboard_navigation: Option<bool>,
   pub enable_animated_images: Option<bool>,
   pub collapse_bot_comments: Option<bool>,
+  pub private_message_privacy: Option<PrivateMessagePrivacy>,
 }
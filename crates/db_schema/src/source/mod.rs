@@ -4,25 +4,32 @@ use url::Url;
 #[cfg(feature = "full")]
 pub mod activity;
 pub mod actor_language;
+pub mod actor_previous_key;
+pub mod automod;
 pub mod captcha_answer;
 pub mod comment;
 pub mod comment_reply;
 pub mod comment_report;
 pub mod community;
+pub mod community_backfill;
 pub mod community_block;
 pub mod custom_emoji;
 pub mod custom_emoji_keyword;
 pub mod email_verification;
 pub mod federation_allowlist;
 pub mod federation_blocklist;
+pub mod federation_limitlist;
 pub mod federation_queue_state;
+pub mod hashtag;
 pub mod images;
 pub mod instance;
 pub mod instance_block;
 pub mod language;
+pub mod link_metadata_cache;
 pub mod local_site;
 pub mod local_site_rate_limit;
 pub mod local_site_url_blocklist;
+pub mod local_site_url_cleaning_rule;
 pub mod local_user;
 pub mod local_user_vote_display_mode;
 pub mod login_token;
@@ -30,14 +37,22 @@ pub mod moderator;
 pub mod password_reset_request;
 pub mod person;
 pub mod person_block;
+pub mod person_device_key;
 pub mod person_mention;
 pub mod post;
+pub mod post_gallery_image;
 pub mod post_report;
 pub mod private_message;
+pub mod private_message_conversation;
+pub mod private_message_key_envelope;
 pub mod private_message_report;
+pub mod received_activity_raw;
 pub mod registration_application;
+pub mod relay;
+pub mod removal_reason;
 pub mod secret;
 pub mod site;
+pub mod slur_filter_list;
 pub mod tagline;
 
 /// Default value for columns like [community::Community.inbox_url] which are marked as serde(skip).
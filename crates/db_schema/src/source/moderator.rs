@@ -1,4 +1,3 @@
-use crate::newtypes::{CommentId, CommunityId, PersonId, PostId};
 #[cfg(feature = "full")]
 use crate::schema::{
   admin_purge_comment,
@@ -7,6 +6,7 @@ use crate::schema::{
   admin_purge_post,
   mod_add,
   mod_add_community,
+  mod_automod_action,
   mod_ban,
   mod_ban_from_community,
   mod_feature_post,
@@ -17,6 +17,10 @@ use crate::schema::{
   mod_remove_post,
   mod_transfer_community,
 };
+use crate::{
+  newtypes::{AutomodRuleId, CommentId, CommunityId, PersonId, PostId, RemovalReasonId},
+  AutomodAction,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -37,6 +41,8 @@ pub struct ModRemovePost {
   pub reason: Option<String>,
   pub removed: bool,
   pub when_: DateTime<Utc>,
+  /// The removal reason template which was used, if any.
+  pub removal_reason_id: Option<RemovalReasonId>,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
@@ -46,6 +52,7 @@ pub struct ModRemovePostForm {
   pub post_id: PostId,
   pub reason: Option<String>,
   pub removed: Option<bool>,
+  pub removal_reason_id: Option<RemovalReasonId>,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -108,6 +115,8 @@ pub struct ModRemoveComment {
   pub reason: Option<String>,
   pub removed: bool,
   pub when_: DateTime<Utc>,
+  /// The removal reason template which was used, if any.
+  pub removal_reason_id: Option<RemovalReasonId>,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
@@ -117,6 +126,7 @@ pub struct ModRemoveCommentForm {
   pub comment_id: CommentId,
   pub reason: Option<String>,
   pub removed: Option<bool>,
+  pub removal_reason_id: Option<RemovalReasonId>,
 }
 
 #[skip_serializing_none]
@@ -380,3 +390,33 @@ pub struct AdminPurgeCommentForm {
   pub post_id: PostId,
   pub reason: Option<String>,
 }
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_automod_action))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an automod rule reports, replies to or sets the flair of a post or comment. Removals and
+/// locks are logged in their regular modlog tables instead.
+pub struct ModAutomodAction {
+  pub id: i32,
+  pub mod_person_id: PersonId,
+  pub rule_id: Option<AutomodRuleId>,
+  pub post_id: PostId,
+  pub comment_id: Option<CommentId>,
+  pub action: AutomodAction,
+  pub reason: Option<String>,
+  pub when_: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_automod_action))]
+pub struct ModAutomodActionForm {
+  pub mod_person_id: PersonId,
+  pub rule_id: Option<AutomodRuleId>,
+  pub post_id: PostId,
+  pub comment_id: Option<CommentId>,
+  pub action: AutomodAction,
+  pub reason: Option<String>,
+}
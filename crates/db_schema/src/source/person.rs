@@ -57,6 +57,12 @@ pub struct Person {
   /// When their ban, if it exists, expires, if at all.
   pub ban_expires: Option<DateTime<Utc>>,
   pub instance_id: InstanceId,
+  /// If the account was moved to another instance, the federated actor_id of the new account.
+  pub moved_to: Option<DbUrl>,
+  /// Previous accounts of the same user, which are allowed to move to this account.
+  #[cfg_attr(feature = "full", ts(skip))]
+  #[serde(skip)]
+  pub also_known_as: Vec<Option<DbUrl>>,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -100,6 +106,10 @@ pub struct PersonInsertForm {
   pub bot_account: Option<bool>,
   #[new(default)]
   pub ban_expires: Option<DateTime<Utc>>,
+  #[new(default)]
+  pub moved_to: Option<DbUrl>,
+  #[new(default)]
+  pub also_known_as: Option<Vec<Option<DbUrl>>>,
 }
 
 #[derive(Clone, Default)]
@@ -123,6 +133,8 @@ pub struct PersonUpdateForm {
   pub matrix_user_id: Option<Option<String>>,
   pub bot_account: Option<bool>,
   pub ban_expires: Option<Option<DateTime<Utc>>>,
+  pub moved_to: Option<Option<DbUrl>>,
+  pub also_known_as: Option<Vec<Option<DbUrl>>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -1,4 +1,4 @@
-use crate::newtypes::{CommunityId, DbUrl, LanguageId, PersonId, PostId};
+use crate::newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PersonId, PostId};
 #[cfg(feature = "full")]
 use crate::schema::{post, post_hide, post_like, post_read, post_saved};
 use chrono::{DateTime, Utc};
@@ -58,6 +58,20 @@ pub struct Post {
   pub url_content_type: Option<String>,
   /// An optional alt_text, usable for image posts.
   pub alt_text: Option<String>,
+  /// A short label set by moderators or automod rules.
+  pub flair: Option<String>,
+  /// The HTTP status returned by the last check which reached the url.
+  pub url_status: Option<i32>,
+  /// When the url was last checked.
+  pub url_checked: Option<DateTime<Utc>>,
+  /// Whether the url seems to be gone, so that an archived version should be shown instead.
+  pub url_broken: bool,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  /// A snapshot of the url by an archiving service.
+  pub url_archive: Option<DbUrl>,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  /// The url without tracking parameters and other variations, used to find reposts of it.
+  pub url_canonical: Option<DbUrl>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -90,6 +104,8 @@ pub struct PostInsertForm {
   pub featured_local: Option<bool>,
   pub url_content_type: Option<String>,
   pub alt_text: Option<String>,
+  pub flair: Option<String>,
+  pub url_canonical: Option<DbUrl>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -116,6 +132,12 @@ pub struct PostUpdateForm {
   pub featured_local: Option<bool>,
   pub url_content_type: Option<Option<String>>,
   pub alt_text: Option<Option<String>>,
+  pub flair: Option<Option<String>>,
+  pub url_status: Option<Option<i32>>,
+  pub url_checked: Option<Option<DateTime<Utc>>>,
+  pub url_broken: Option<bool>,
+  pub url_archive: Option<Option<DbUrl>>,
+  pub url_canonical: Option<Option<DbUrl>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -158,6 +180,13 @@ pub struct PostSaved {
   pub published: DateTime<Utc>,
 }
 
+/// A post or comment which a person saved.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SavedItemId {
+  Post(PostId),
+  Comment(CommentId),
+}
+
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
 #[cfg_attr(feature = "full", diesel(table_name = post_saved))]
 pub struct PostSavedForm {
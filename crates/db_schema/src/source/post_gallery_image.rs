@@ -0,0 +1,69 @@
+use crate::newtypes::{DbUrl, PostId};
+#[cfg(feature = "full")]
+use crate::schema::post_gallery_image;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "full")]
+use diesel::{
+  deserialize::FromSql,
+  pg::{Pg, PgValue},
+  sql_types::{Json, Nullable},
+};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Identifiable, Associations, TS)
+)]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_gallery_image))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An image in the gallery of a post.
+pub struct PostGalleryImage {
+  pub id: i32,
+  pub post_id: PostId,
+  /// Images are shown in ascending order of their position.
+  pub position: i32,
+  pub url: DbUrl,
+  pub caption: Option<String>,
+  pub alt_text: Option<String>,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_gallery_image))]
+pub struct PostGalleryImageForm {
+  pub url: DbUrl,
+  pub caption: Option<String>,
+  pub alt_text: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(FromSqlRow, TS))]
+#[cfg_attr(feature = "full", diesel(sql_type = Nullable<Json>))]
+#[cfg_attr(feature = "full", ts(export))]
+#[serde(transparent)]
+/// The gallery images of a post, in order. Selected as json so that it can be part of a view.
+pub struct PostGallery(pub Vec<PostGalleryImage>);
+
+#[cfg(feature = "full")]
+impl FromSql<Nullable<Json>, Pg> for PostGallery {
+  fn from_sql(bytes: PgValue) -> diesel::deserialize::Result<Self> {
+    let value = <serde_json::Value as FromSql<Json, Pg>>::from_sql(bytes)?;
+    Ok(serde_json::from_value(value)?)
+  }
+
+  fn from_nullable_sql(bytes: Option<PgValue>) -> diesel::deserialize::Result<Self> {
+    match bytes {
+      Some(bytes) => Self::from_sql(bytes),
+      // Posts without gallery
+      None => Ok(Self::default()),
+    }
+  }
+}
@@ -1,6 +1,6 @@
-use crate::newtypes::{DbUrl, PersonId, PrivateMessageId};
+use crate::newtypes::{DbUrl, PersonId, PrivateMessageConversationId, PrivateMessageId};
 #[cfg(feature = "full")]
-use crate::schema::private_message;
+use crate::schema::{private_message, private_message_read};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -28,11 +28,15 @@ pub struct PrivateMessage {
   pub recipient_id: PersonId,
   pub content: String,
   pub deleted: bool,
-  pub read: bool,
   pub published: DateTime<Utc>,
   pub updated: Option<DateTime<Utc>>,
   pub ap_id: DbUrl,
   pub local: bool,
+  /// The conversation which this message belongs to.
+  pub conversation_id: PrivateMessageConversationId,
+  /// The content is end-to-end encrypted. Key envelopes for the participants' devices are stored
+  /// separately.
+  pub encrypted: bool,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -47,11 +51,13 @@ pub struct PrivateMessageInsertForm {
   #[builder(!default)]
   pub content: String,
   pub deleted: Option<bool>,
-  pub read: Option<bool>,
   pub published: Option<DateTime<Utc>>,
   pub updated: Option<DateTime<Utc>>,
   pub ap_id: Option<DbUrl>,
   pub local: Option<bool>,
+  /// If not set, the message goes into the conversation between creator and recipient.
+  pub conversation_id: Option<PrivateMessageConversationId>,
+  pub encrypted: Option<bool>,
 }
 
 #[derive(Clone, Default)]
@@ -60,9 +66,35 @@ pub struct PrivateMessageInsertForm {
 pub struct PrivateMessageUpdateForm {
   pub content: Option<String>,
   pub deleted: Option<bool>,
-  pub read: Option<bool>,
   pub published: Option<DateTime<Utc>>,
   pub updated: Option<Option<DateTime<Utc>>>,
   pub ap_id: Option<DbUrl>,
   pub local: Option<bool>,
 }
+
+/// Marks that a participant of the conversation read the message. Each participant of a group
+/// conversation reads messages independently.
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(
+  feature = "full",
+  derive(Identifiable, Queryable, Selectable, Associations)
+)]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::private_message::PrivateMessage))
+)]
+#[cfg_attr(feature = "full", diesel(table_name = private_message_read))]
+#[cfg_attr(feature = "full", diesel(primary_key(private_message_id, person_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+pub struct PrivateMessageRead {
+  pub private_message_id: PrivateMessageId,
+  pub person_id: PersonId,
+  pub published: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = private_message_read))]
+pub(crate) struct PrivateMessageReadForm {
+  pub private_message_id: PrivateMessageId,
+  pub person_id: PersonId,
+}
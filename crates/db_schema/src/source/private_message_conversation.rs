@@ -0,0 +1,56 @@
+use crate::newtypes::{PersonId, PrivateMessageConversationId};
+#[cfg(feature = "full")]
+use crate::schema::{private_message_conversation, private_message_conversation_participant};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = private_message_conversation))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A conversation between two or more people, which groups their private messages. There is
+/// exactly one conversation for each set of participants.
+pub struct PrivateMessageConversation {
+  pub id: PrivateMessageConversationId,
+  pub published: DateTime<Utc>,
+  pub last_message_published: DateTime<Utc>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = private_message_conversation_participant))]
+#[cfg_attr(feature = "full", diesel(primary_key(conversation_id, person_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The state of a conversation for one of its participants.
+pub struct PrivateMessageConversationParticipant {
+  pub conversation_id: PrivateMessageConversationId,
+  pub person_id: PersonId,
+  /// Muted conversations don't send notifications and aren't included in the unread count.
+  pub muted: bool,
+  /// Archived conversations are hidden from the conversation list, until a new message arrives.
+  pub archived: bool,
+  /// Messages published after this time are unread.
+  pub last_read: Option<DateTime<Utc>>,
+  /// The conversation was started by someone the participant doesn't know. It is listed separately
+  /// and doesn't send notifications, until the participant accepts it or replies.
+  pub request: bool,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = private_message_conversation_participant))]
+pub struct PrivateMessageConversationParticipantUpdateForm {
+  pub muted: Option<bool>,
+  pub archived: Option<bool>,
+  pub last_read: Option<Option<DateTime<Utc>>>,
+  pub request: Option<bool>,
+}
+
+/// The maximum number of people in a group conversation, including the creator.
+pub const MAX_CONVERSATION_PARTICIPANTS: usize = 20;
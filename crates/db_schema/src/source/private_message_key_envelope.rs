@@ -0,0 +1,34 @@
+use crate::newtypes::{PersonId, PrivateMessageId};
+#[cfg(feature = "full")]
+use crate::schema::private_message_key_envelope;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = private_message_key_envelope))]
+#[cfg_attr(
+  feature = "full",
+  diesel(primary_key(private_message_id, person_id, device_id))
+)]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The key of an encrypted private message, encrypted with the public key of one device. It is
+/// opaque to the server.
+pub struct PrivateMessageKeyEnvelope {
+  pub private_message_id: PrivateMessageId,
+  pub person_id: PersonId,
+  pub device_id: String,
+  pub encrypted_key: String,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = private_message_key_envelope))]
+pub struct PrivateMessageKeyEnvelopeForm {
+  pub private_message_id: PrivateMessageId,
+  pub person_id: PersonId,
+  pub device_id: String,
+  pub encrypted_key: String,
+}
@@ -32,6 +32,9 @@ pub struct PrivateMessageReport {
   pub resolver_id: Option<PersonId>,
   pub published: DateTime<Utc>,
   pub updated: Option<DateTime<Utc>>,
+  /// For encrypted messages, an excerpt which the reporter's client decrypted. The server can't
+  /// verify that it matches the original text.
+  pub decrypted_excerpt: Option<String>,
 }
 
 #[derive(Clone)]
@@ -42,4 +45,5 @@ pub struct PrivateMessageReportForm {
   pub private_message_id: PrivateMessageId,
   pub original_pm_text: String,
   pub reason: String,
+  pub decrypted_excerpt: Option<String>,
 }
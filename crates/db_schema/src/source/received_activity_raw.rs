@@ -0,0 +1,48 @@
+use crate::newtypes::DbUrl;
+#[cfg(feature = "full")]
+use crate::schema::received_activity_raw;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = received_activity_raw))]
+#[cfg_attr(feature = "full", diesel(primary_key(ap_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The raw json of an incoming activity, stored for debugging if enabled in the config.
+pub struct ReceivedActivityRaw {
+  pub ap_id: DbUrl,
+  pub data: String,
+  /// The error which occurred while receiving the activity.
+  pub error: Option<String>,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = received_activity_raw))]
+pub struct ReceivedActivityRawForm {
+  pub ap_id: DbUrl,
+  pub data: String,
+  pub error: Option<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(QueryableByName, TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Number of rows which were changed in a table by the current transaction.
+pub struct TableChanges {
+  #[cfg_attr(feature = "full", diesel(sql_type = diesel::sql_types::Text))]
+  pub table: String,
+  #[cfg_attr(feature = "full", diesel(sql_type = diesel::sql_types::BigInt))]
+  pub inserted: i64,
+  #[cfg_attr(feature = "full", diesel(sql_type = diesel::sql_types::BigInt))]
+  pub updated: i64,
+  #[cfg_attr(feature = "full", diesel(sql_type = diesel::sql_types::BigInt))]
+  pub deleted: i64,
+}
@@ -0,0 +1,82 @@
+use crate::newtypes::{CommunityId, DbUrl, InstanceId, RelayId};
+#[cfg(feature = "full")]
+use crate::schema::{relay, relay_community_filter};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+use typed_builder::TypedBuilder;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = relay))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An ActivityPub relay which is followed by the local site actor.
+pub struct Relay {
+  pub id: RelayId,
+  /// The federated actor_id of the relay.
+  pub actor_id: DbUrl,
+  pub inbox_url: DbUrl,
+  #[serde(skip)]
+  pub public_key: String,
+  pub instance_id: InstanceId,
+  /// Set once the relay has sent an `Accept` for our follow.
+  pub follow_accepted: bool,
+  pub published: DateTime<Utc>,
+  pub last_refreshed_at: DateTime<Utc>,
+}
+
+#[derive(Clone, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = relay))]
+pub struct RelayInsertForm {
+  #[builder(!default)]
+  pub actor_id: DbUrl,
+  #[builder(!default)]
+  pub inbox_url: DbUrl,
+  #[builder(!default)]
+  pub public_key: String,
+  #[builder(!default)]
+  pub instance_id: InstanceId,
+  pub last_refreshed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = relay))]
+pub struct RelayUpdateForm {
+  pub inbox_url: Option<DbUrl>,
+  pub public_key: Option<String>,
+  pub follow_accepted: Option<bool>,
+  pub last_refreshed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = relay_community_filter))]
+#[cfg_attr(feature = "full", diesel(primary_key(relay_id, community_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A community which is explicitly allowed or denied for a relay.
+///
+/// If a relay has any allowed communities, only those are exchanged with it. Denied communities
+/// are never exchanged.
+pub struct RelayCommunityFilter {
+  pub relay_id: RelayId,
+  pub community_id: CommunityId,
+  pub allow: bool,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = relay_community_filter))]
+pub struct RelayCommunityFilterForm {
+  pub relay_id: RelayId,
+  pub community_id: CommunityId,
+  pub allow: bool,
+}
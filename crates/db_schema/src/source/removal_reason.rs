@@ -0,0 +1,45 @@
+use crate::newtypes::{CommunityId, RemovalReasonId};
+#[cfg(feature = "full")]
+use crate::schema::removal_reason;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+use typed_builder::TypedBuilder;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = removal_reason))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A removal reason template, which moderators can pick when removing a post or comment.
+pub struct RemovalReason {
+  pub id: RemovalReasonId,
+  pub community_id: CommunityId,
+  /// A short name to identify the template.
+  pub title: String,
+  /// The text which is used as removal reason.
+  pub reason: String,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = removal_reason))]
+pub struct RemovalReasonInsertForm {
+  pub community_id: CommunityId,
+  pub title: String,
+  pub reason: String,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = removal_reason))]
+pub struct RemovalReasonUpdateForm {
+  pub title: Option<String>,
+  pub reason: Option<String>,
+  pub updated: Option<Option<DateTime<Utc>>>,
+}
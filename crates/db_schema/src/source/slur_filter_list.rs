@@ -0,0 +1,47 @@
+#[cfg(feature = "full")]
+use crate::schema::slur_filter_list;
+use crate::{newtypes::SlurFilterListId, SlurFilterAction};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+use typed_builder::TypedBuilder;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = slur_filter_list))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A named word list, which is checked against posts and comments in addition to the slur filter
+/// regex of the site.
+pub struct SlurFilterList {
+  pub id: SlurFilterListId,
+  pub name: String,
+  /// A case insensitive regex matching the words of the list.
+  pub regex: String,
+  /// What happens to content containing words of the list.
+  pub action: SlurFilterAction,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = slur_filter_list))]
+pub struct SlurFilterListInsertForm {
+  pub name: String,
+  pub regex: String,
+  pub action: SlurFilterAction,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = slur_filter_list))]
+pub struct SlurFilterListUpdateForm {
+  pub name: Option<String>,
+  pub regex: Option<String>,
+  pub action: Option<SlurFilterAction>,
+  pub updated: Option<Option<DateTime<Utc>>>,
+}
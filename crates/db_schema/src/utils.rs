@@ -449,6 +449,35 @@ pub async fn build_db_pool() -> LemmyResult<ActualDbPool> {
   Ok(pool)
 }
 
+/// Builds a pool with a single connection, which runs all queries in a transaction that is never
+/// committed. Changes are rolled back when the pool is dropped, so this can be used to try out
+/// operations without affecting the database.
+pub fn build_rollback_db_pool() -> LemmyResult<ActualDbPool> {
+  let db_url = SETTINGS.get_database_url();
+  let mut config = ManagerConfig::default();
+  config.custom_setup = Box::new(|url| {
+    let conn = establish_connection(url);
+    async move {
+      let mut conn = conn.await?;
+      // Unlike a regular transaction, this doesn't mark the connection as broken when it is
+      // returned to the pool.
+      conn
+        .begin_test_transaction()
+        .await
+        .map_err(ConnectionError::CouldntSetupConfiguration)?;
+      Ok(conn)
+    }
+    .boxed()
+  });
+  let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new_with_config(&db_url, config);
+  let pool = Pool::builder(manager)
+    .max_size(1)
+    .runtime(Runtime::Tokio1)
+    .wait_timeout(Some(Duration::from_secs(10)))
+    .build()?;
+  Ok(pool)
+}
+
 pub async fn build_db_pool_for_tests() -> ActualDbPool {
   build_db_pool().await.expect("db pool missing")
 }
@@ -508,6 +537,9 @@ pub mod functions {
   sql_function!(fn coalesce<T: diesel::sql_types::SqlType + diesel::sql_types::SingleValue>(x: diesel::sql_types::Nullable<T>, y: T) -> T);
 
   sql_function!(fn set_config(setting_name: Text, new_value: Text, is_local: Bool) -> Text);
+
+  // really this function is variadic, this just adds the two-argument version
+  sql_function!(fn greatest(x: Timestamptz, y: Timestamptz) -> Timestamptz);
 }
 
 pub const DELETED_REPLACEMENT_TEXT: &str = "*Permanently Deleted*";
@@ -425,6 +425,12 @@ mod tests {
         featured_url: inserted_community.featured_url,
         instance_id: inserted_instance.id,
         visibility: CommunityVisibility::Public,
+        comment_slow_mode_seconds: None,
+        post_slow_mode_seconds: None,
+        duplicate_post_window_days: None,
+        community_comment_slow_mode_seconds: None,
+        moved_to: None,
+        also_known_as: vec![],
       },
       creator: Person {
         id: inserted_jessica.id,
@@ -445,6 +451,8 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: inserted_instance.id,
+        moved_to: None,
+        also_known_as: vec![],
         private_key: inserted_jessica.private_key,
         public_key: inserted_jessica.public_key,
         last_refreshed_at: inserted_jessica.last_refreshed_at,
@@ -468,6 +476,8 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: inserted_instance.id,
+        moved_to: None,
+        also_known_as: vec![],
         private_key: inserted_timmy.private_key.clone(),
         public_key: inserted_timmy.public_key.clone(),
         last_refreshed_at: inserted_timmy.last_refreshed_at,
@@ -510,6 +520,8 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      also_known_as: vec![],
       private_key: inserted_sara.private_key,
       public_key: inserted_sara.public_key,
       last_refreshed_at: inserted_sara.last_refreshed_at,
@@ -579,6 +591,8 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      moved_to: None,
+      also_known_as: vec![],
     });
 
     assert_eq!(
@@ -28,6 +28,7 @@ use lemmy_db_schema::{
     community_follower,
     community_moderator,
     community_person_ban,
+    federation_limitlist,
     instance_block,
     local_user,
     local_user_language,
@@ -216,15 +217,32 @@ fn queries<'a>() -> Queries<
       query = query.filter(post::community_id.eq(community_id));
     }
 
-    if let Some(listing_type) = options.listing_type {
-      let is_subscribed = exists(
-        community_follower::table.filter(
-          post::community_id
-            .eq(community_follower::community_id)
-            .and(community_follower::person_id.eq(person_id_join)),
+    let is_subscribed = exists(
+      community_follower::table.filter(
+        post::community_id
+          .eq(community_follower::community_id)
+          .and(community_follower::person_id.eq(person_id_join)),
+      ),
+    );
+
+    // Comments from instances with limited federation are hidden from general listings, unless
+    // the user follows the community.
+    let hide_limited = options.post_id.is_none()
+      && options.community_id.is_none()
+      && options.creator_id.is_none()
+      && matches!(options.listing_type, None | Some(ListingType::All));
+    if hide_limited {
+      let is_from_limited_instance = exists(
+        federation_limitlist::table.filter(
+          federation_limitlist::instance_id
+            .eq(community::instance_id)
+            .or(federation_limitlist::instance_id.eq(person::instance_id)),
         ),
       );
+      query = query.filter(not(is_from_limited_instance).or(is_subscribed));
+    }
 
+    if let Some(listing_type) = options.listing_type {
       match listing_type {
         ListingType::Subscribed => query = query.filter(is_subscribed), /* TODO could be this: and(community_follower::person_id.eq(person_id_join)), */
         ListingType::Local => {
@@ -444,6 +462,8 @@ mod tests {
       },
       community::{
         Community,
+        CommunityFollower,
+        CommunityFollowerForm,
         CommunityInsertForm,
         CommunityModerator,
         CommunityModeratorForm,
@@ -451,6 +471,7 @@ mod tests {
         CommunityPersonBanForm,
         CommunityUpdateForm,
       },
+      federation_limitlist::{FederationLimitList, FederationLimitListForm},
       instance::Instance,
       language::Language,
       local_user::{LocalUser, LocalUserInsertForm},
@@ -459,9 +480,10 @@ mod tests {
       person_block::{PersonBlock, PersonBlockForm},
       post::{Post, PostInsertForm},
     },
-    traits::{Bannable, Blockable, Crud, Joinable, Likeable, Saveable},
+    traits::{Bannable, Blockable, Crud, Followable, Joinable, Likeable, Saveable},
     utils::{build_db_pool_for_tests, RANK_DEFAULT},
     CommunityVisibility,
+    ListingType,
     SubscribedType,
   };
   use lemmy_utils::{error::LemmyResult, LemmyErrorType};
@@ -1055,6 +1077,8 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: data.inserted_instance.id,
+        moved_to: None,
+        also_known_as: vec![],
         private_key: data.timmy_local_user_view.person.private_key.clone(),
         public_key: data.timmy_local_user_view.person.public_key.clone(),
         last_refreshed_at: data.timmy_local_user_view.person.last_refreshed_at,
@@ -1066,6 +1090,12 @@ mod tests {
         url: None,
         body: None,
         alt_text: None,
+        flair: None,
+        url_status: None,
+        url_checked: None,
+        url_broken: false,
+        url_archive: None,
+        url_canonical: None,
         published: data.inserted_post.published,
         updated: None,
         community_id: data.inserted_community.id,
@@ -1110,6 +1140,12 @@ mod tests {
         moderators_url: data.inserted_community.moderators_url.clone(),
         featured_url: data.inserted_community.featured_url.clone(),
         visibility: CommunityVisibility::Public,
+        comment_slow_mode_seconds: None,
+        post_slow_mode_seconds: None,
+        duplicate_post_window_days: None,
+        community_comment_slow_mode_seconds: None,
+        moved_to: None,
+        also_known_as: vec![],
       },
       counts: CommentAggregates {
         comment_id: data.inserted_comment_0.id,
@@ -1170,6 +1206,50 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn limited_instance() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let limit_form = FederationLimitListForm {
+      instance_id: data.inserted_instance.id,
+      ..Default::default()
+    };
+    FederationLimitList::limit(pool, &limit_form).await?;
+    let all_query = || CommentQuery {
+      local_user: Some(&data.timmy_local_user_view.local_user),
+      listing_type: Some(ListingType::All),
+      ..Default::default()
+    };
+
+    // Comments from the limited instance are hidden in the all listing
+    let comments = all_query().list(pool).await?;
+    assert_length!(0, comments);
+
+    // but still shown on the post
+    let comments = CommentQuery {
+      post_id: Some(data.inserted_post.id),
+      ..all_query()
+    }
+    .list(pool)
+    .await?;
+    assert_length!(5, comments);
+
+    // and in the all listing after following the community
+    let follower_form = CommunityFollowerForm {
+      community_id: data.inserted_community.id,
+      person_id: data.timmy_local_user_view.person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &follower_form).await?;
+    let comments = all_query().list(pool).await?;
+    assert_length!(5, comments);
+
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn comment_listing_local_user_banned_from_community() -> LemmyResult<()> {
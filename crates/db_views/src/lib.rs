@@ -16,6 +16,8 @@ pub mod post_report_view;
 #[cfg(feature = "full")]
 pub mod post_view;
 #[cfg(feature = "full")]
+pub mod private_message_conversation_view;
+#[cfg(feature = "full")]
 pub mod private_message_report_view;
 #[cfg(feature = "full")]
 pub mod private_message_view;
@@ -1,7 +1,7 @@
 use crate::structs::{PaginationCursor, PostView};
 use diesel::{
   debug_query,
-  dsl::{exists, not, IntervalDsl},
+  dsl::{exists, not, sql, IntervalDsl},
   pg::Pg,
   query_builder::AsQuery,
   result::Error,
@@ -28,6 +28,8 @@ use lemmy_db_schema::{
     community_follower,
     community_moderator,
     community_person_ban,
+    federation_limitlist,
+    hashtag,
     image_details,
     instance_block,
     local_user,
@@ -37,6 +39,8 @@ use lemmy_db_schema::{
     person_post_aggregates,
     post,
     post_aggregates,
+    post_gallery_image,
+    post_hashtag,
     post_hide,
     post_like,
     post_read,
@@ -100,6 +104,16 @@ fn queries<'a>() -> Queries<
     ),
   );
 
+  // Selected as json array, so that the gallery can be part of the view
+  let gallery = || {
+    post_gallery_image::table
+      .filter(post_gallery_image::post_id.eq(post_aggregates::post_id))
+      .select(sql::<sql_types::Json>(
+        "json_agg(post_gallery_image.* ORDER BY post_gallery_image.position)",
+      ))
+      .single_value()
+  };
+
   let is_read = |person_id| {
     exists(
       post_read::table.filter(
@@ -232,6 +246,7 @@ fn queries<'a>() -> Queries<
         person::all_columns,
         community::all_columns,
         image_details::all_columns.nullable(),
+        gallery(),
         is_creator_banned_from_community,
         is_local_user_banned_from_community_selection,
         creator_is_moderator,
@@ -338,6 +353,17 @@ fn queries<'a>() -> Queries<
       query = query.filter(post_aggregates::creator_id.eq(creator_id));
     }
 
+    // Content from instances with limited federation is hidden from general listings, unless the
+    // user follows the community. It is still shown on community and user pages.
+    let hide_limited = options.community_id.is_none() && options.creator_id.is_none();
+    let is_from_limited_instance = exists(
+      federation_limitlist::table.filter(
+        federation_limitlist::instance_id
+          .eq(post_aggregates::instance_id)
+          .or(federation_limitlist::instance_id.eq(person::instance_id)),
+      ),
+    );
+
     if let Some(listing_type) = options.listing_type {
       if let Some(person_id) = options.local_user.person_id() {
         let is_subscribed = exists(
@@ -354,7 +380,12 @@ fn queries<'a>() -> Queries<
               .filter(community::local.eq(true))
               .filter(community::hidden.eq(false).or(is_subscribed));
           }
-          ListingType::All => query = query.filter(community::hidden.eq(false).or(is_subscribed)),
+          ListingType::All => {
+            query = query.filter(community::hidden.eq(false).or(is_subscribed));
+            if hide_limited {
+              query = query.filter(not(is_from_limited_instance).or(is_subscribed));
+            }
+          }
           ListingType::ModeratorView => {
             query = query.filter(exists(
               community_moderator::table.filter(
@@ -374,15 +405,50 @@ fn queries<'a>() -> Queries<
               .filter(community::local.eq(true))
               .filter(community::hidden.eq(false));
           }
-          _ => query = query.filter(community::hidden.eq(false)),
+          _ => {
+            query = query.filter(community::hidden.eq(false));
+            if hide_limited {
+              query = query.filter(not(is_from_limited_instance));
+            }
+          }
         }
       }
     } else {
       query = query.filter(community::hidden.eq(false));
+      // Without listing type, for example in search
+      if hide_limited {
+        let person_id = options.local_user.person_id().unwrap_or(PersonId(-1));
+        let is_subscribed = exists(
+          community_follower::table.filter(
+            post_aggregates::community_id
+              .eq(community_follower::community_id)
+              .and(community_follower::person_id.eq(person_id)),
+          ),
+        );
+        query = query.filter(not(is_from_limited_instance).or(is_subscribed));
+      }
     }
 
-    if let Some(url_search) = &options.url_search {
-      query = query.filter(post::url.eq(url_search));
+    match (&options.url_search, &options.url_canonical_search) {
+      (Some(url_search), Some(canonical)) => {
+        query = query.filter(
+          post::url
+            .eq(url_search)
+            .or(post::url_canonical.eq(canonical)),
+        );
+      }
+      (Some(url_search), None) => query = query.filter(post::url.eq(url_search)),
+      (None, Some(canonical)) => query = query.filter(post::url_canonical.eq(canonical)),
+      (None, None) => {}
+    }
+
+    if let Some(hashtag_name) = &options.hashtag {
+      query = query.filter(exists(
+        post_hashtag::table
+          .inner_join(hashtag::table)
+          .filter(post_hashtag::post_id.eq(post_aggregates::post_id))
+          .filter(hashtag::name.eq(hashtag_name.trim_start_matches('#').to_lowercase())),
+      ));
     }
 
     if let Some(search_term) = &options.search_term {
@@ -617,6 +683,11 @@ pub struct PostQuery<'a> {
   pub local_user: Option<&'a LocalUser>,
   pub search_term: Option<String>,
   pub url_search: Option<String>,
+  /// Posts whose canonical url matches. If `url_search` is also given, posts matching either of
+  /// them are returned.
+  pub url_canonical_search: Option<String>,
+  /// Only show posts with this hashtag, without leading `#`
+  pub hashtag: Option<String>,
   pub saved_only: Option<bool>,
   pub liked_only: Option<bool>,
   pub disliked_only: Option<bool>,
@@ -749,6 +820,8 @@ mod tests {
       comment::{Comment, CommentInsertForm},
       community::{
         Community,
+        CommunityFollower,
+        CommunityFollowerForm,
         CommunityInsertForm,
         CommunityModerator,
         CommunityModeratorForm,
@@ -757,6 +830,7 @@ mod tests {
         CommunityUpdateForm,
       },
       community_block::{CommunityBlock, CommunityBlockForm},
+      federation_limitlist::{FederationLimitList, FederationLimitListForm},
       instance::Instance,
       instance_block::{InstanceBlock, InstanceBlockForm},
       language::Language,
@@ -765,11 +839,13 @@ mod tests {
       person::{Person, PersonInsertForm},
       person_block::{PersonBlock, PersonBlockForm},
       post::{Post, PostHide, PostInsertForm, PostLike, PostLikeForm, PostRead, PostUpdateForm},
+      post_gallery_image::{PostGalleryImage, PostGalleryImageForm},
       site::Site,
     },
-    traits::{Bannable, Blockable, Crud, Joinable, Likeable},
+    traits::{Bannable, Blockable, Crud, Followable, Joinable, Likeable},
     utils::{build_db_pool, build_db_pool_for_tests, DbPool, RANK_DEFAULT},
     CommunityVisibility,
+    ListingType,
     SortType,
     SubscribedType,
   };
@@ -1398,6 +1474,83 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_limited_instance() -> LemmyResult<()> {
+    const POST_FROM_LIMITED_INSTANCE: &str = "post on limited instance";
+
+    let pool = &build_db_pool().await?;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let limited_instance = Instance::read_or_create(pool, "another_domain.tld".to_string()).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("test_community_limited".to_string())
+      .title("none".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(limited_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name(POST_FROM_LIMITED_INSTANCE.to_string())
+      .creator_id(data.inserted_bot.id)
+      .community_id(inserted_community.id)
+      .language_id(Some(LanguageId(1)))
+      .build();
+    Post::create(pool, &post_form).await?;
+
+    let limit_form = FederationLimitListForm {
+      instance_id: limited_instance.id,
+      ..Default::default()
+    };
+    FederationLimitList::limit(pool, &limit_form).await?;
+    let all_query = PostQuery {
+      listing_type: Some(ListingType::All),
+      ..data.default_post_query()
+    };
+
+    // posts from the limited instance are hidden in the all listing
+    let post_listings_all = all_query.clone().list(&data.site, pool).await?;
+    assert_eq!(vec![POST_BY_BOT, POST], names(&post_listings_all));
+
+    // and in search, which doesn't set a listing type
+    let post_listings_search = PostQuery {
+      search_term: Some("limited".to_string()),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert!(post_listings_search.is_empty());
+
+    // but still shown on the community page
+    let post_listings_community = PostQuery {
+      community_id: Some(inserted_community.id),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(
+      vec![POST_FROM_LIMITED_INSTANCE],
+      names(&post_listings_community)
+    );
+
+    // and in the all listing after following the community
+    let follower_form = CommunityFollowerForm {
+      community_id: inserted_community.id,
+      person_id: data.local_user_view.person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &follower_form).await?;
+    let post_listings_all = all_query.list(&data.site, pool).await?;
+    assert_eq!(
+      vec![POST_FROM_LIMITED_INSTANCE, POST_BY_BOT, POST],
+      names(&post_listings_all)
+    );
+
+    Instance::delete(pool, limited_instance.id).await?;
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn pagination_includes_each_post_once() -> LemmyResult<()> {
@@ -1669,6 +1822,12 @@ mod tests {
         url: None,
         body: None,
         alt_text: None,
+        flair: None,
+        url_status: None,
+        url_checked: None,
+        url_broken: false,
+        url_archive: None,
+        url_canonical: None,
         published: inserted_post.published,
         updated: None,
         community_id: inserted_community.id,
@@ -1708,11 +1867,14 @@ mod tests {
         matrix_user_id: None,
         ban_expires: None,
         instance_id: data.inserted_instance.id,
+        moved_to: None,
+        also_known_as: vec![],
         private_key: inserted_person.private_key.clone(),
         public_key: inserted_person.public_key.clone(),
         last_refreshed_at: inserted_person.last_refreshed_at,
       },
       image_details: None,
+      gallery: Default::default(),
       creator_banned_from_community: false,
       banned_from_community: false,
       creator_is_moderator: false,
@@ -1743,6 +1905,12 @@ mod tests {
         moderators_url: inserted_community.moderators_url.clone(),
         featured_url: inserted_community.featured_url.clone(),
         visibility: CommunityVisibility::Public,
+        comment_slow_mode_seconds: None,
+        post_slow_mode_seconds: None,
+        duplicate_post_window_days: None,
+        community_comment_slow_mode_seconds: None,
+        moved_to: None,
+        also_known_as: vec![],
       },
       counts: PostAggregates {
         post_id: inserted_post.id,
@@ -1883,4 +2051,45 @@ mod tests {
 
     cleanup(data, pool).await
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_gallery() -> LemmyResult<()> {
+    let pool = &build_db_pool().await?;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let post_id = data.inserted_post.id;
+    let forms = ["https://example.com/1.jpg", "https://example.com/2.jpg"]
+      .into_iter()
+      .map(|url| {
+        Ok(PostGalleryImageForm {
+          url: Url::parse(url)?.into(),
+          caption: None,
+          alt_text: Some(format!("image {url}")),
+        })
+      })
+      .collect::<LemmyResult<Vec<_>>>()?;
+    let gallery = PostGalleryImage::replace(pool, post_id, forms).await?;
+
+    let post_listings = data.default_post_query().list(&data.site, pool).await?;
+    let post_view = post_listings
+      .iter()
+      .find(|p| p.post.id == post_id)
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert_eq!(gallery, post_view.gallery.0);
+    assert_eq!(
+      Some("image https://example.com/1.jpg".to_string()),
+      post_view.gallery.0.first().and_then(|i| i.alt_text.clone())
+    );
+
+    // Posts without images have an empty gallery
+    let bot_post = post_listings
+      .iter()
+      .find(|p| p.post.id == data.inserted_bot_post.id)
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    assert!(bot_post.gallery.0.is_empty());
+
+    cleanup(data, pool).await
+  }
 }
@@ -0,0 +1,319 @@
+use crate::structs::{PrivateMessageConversationView, PrivateMessageView};
+use diesel::{
+  dsl::{count_star, exists, not},
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  JoinOnDsl,
+  QueryDsl,
+  SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  aliases,
+  newtypes::{PersonId, PrivateMessageConversationId},
+  schema::{
+    person,
+    private_message,
+    private_message_conversation,
+    private_message_conversation_participant,
+    private_message_read,
+  },
+  source::{
+    person::Person,
+    private_message_conversation::{
+      PrivateMessageConversation,
+      PrivateMessageConversationParticipant,
+    },
+  },
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use std::collections::HashMap;
+
+impl PrivateMessageConversationView {
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    conversation_id: PrivateMessageConversationId,
+    my_person_id: PersonId,
+  ) -> Result<Option<Self>, Error> {
+    let conversations = private_message_conversation::table
+      .inner_join(private_message_conversation_participant::table)
+      .filter(private_message_conversation::id.eq(conversation_id))
+      .filter(private_message_conversation_participant::person_id.eq(my_person_id))
+      .select((
+        PrivateMessageConversation::as_select(),
+        PrivateMessageConversationParticipant::as_select(),
+      ))
+      .load(&mut get_conn(pool).await?)
+      .await?;
+    Ok(
+      Self::with_details(pool, conversations, my_person_id)
+        .await?
+        .pop(),
+    )
+  }
+
+  /// Adds participants, last message and unread count to the given conversations.
+  async fn with_details(
+    pool: &mut DbPool<'_>,
+    conversations: Vec<(
+      PrivateMessageConversation,
+      PrivateMessageConversationParticipant,
+    )>,
+    my_person_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let ids: Vec<_> = conversations.iter().map(|(c, _)| c.id).collect();
+
+    let mut participants: HashMap<_, Vec<Person>> = HashMap::new();
+    private_message_conversation_participant::table
+      .inner_join(person::table)
+      .filter(private_message_conversation_participant::conversation_id.eq_any(&ids))
+      .select((
+        private_message_conversation_participant::conversation_id,
+        person::all_columns,
+      ))
+      .order_by(person::id)
+      .load::<(PrivateMessageConversationId, Person)>(conn)
+      .await?
+      .into_iter()
+      .for_each(|(id, p)| participants.entry(id).or_default().push(p));
+
+    let mut last_messages: HashMap<_, PrivateMessageView> = private_message::table
+      .inner_join(person::table.on(private_message::creator_id.eq(person::id)))
+      .inner_join(
+        aliases::person1.on(private_message::recipient_id.eq(aliases::person1.field(person::id))),
+      )
+      .filter(private_message::conversation_id.eq_any(&ids))
+      .filter(private_message::deleted.eq(false))
+      .distinct_on(private_message::conversation_id)
+      .order_by((
+        private_message::conversation_id,
+        private_message::published.desc(),
+      ))
+      .select((
+        private_message::all_columns,
+        person::all_columns,
+        aliases::person1.fields(person::all_columns),
+        private_message::creator_id.eq(my_person_id).or(exists(
+          private_message_read::table.find((private_message::id, my_person_id)),
+        )),
+      ))
+      .load::<PrivateMessageView>(conn)
+      .await?
+      .into_iter()
+      .map(|pm| (pm.private_message.conversation_id, pm))
+      .collect();
+
+    // Messages from others which I didn't read yet
+    let unread_counts: HashMap<_, i64> = private_message::table
+      .filter(private_message::conversation_id.eq_any(&ids))
+      .filter(private_message::creator_id.ne(my_person_id))
+      .filter(private_message::deleted.eq(false))
+      .filter(not(exists(
+        private_message_read::table.find((private_message::id, my_person_id)),
+      )))
+      .group_by(private_message::conversation_id)
+      .select((private_message::conversation_id, count_star()))
+      .load::<(PrivateMessageConversationId, i64)>(conn)
+      .await?
+      .into_iter()
+      .collect();
+
+    Ok(
+      conversations
+        .into_iter()
+        .map(|(conversation, participant)| Self {
+          participants: participants.remove(&conversation.id).unwrap_or_default(),
+          last_message: last_messages.remove(&conversation.id),
+          unread_count: unread_counts
+            .get(&conversation.id)
+            .copied()
+            .unwrap_or_default(),
+          conversation,
+          participant,
+        })
+        .collect(),
+    )
+  }
+}
+
+#[derive(Default)]
+pub struct PrivateMessageConversationQuery {
+  /// List archived conversations instead of active ones.
+  pub archived_only: bool,
+  /// List message requests instead of accepted conversations.
+  pub requests_only: bool,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+impl PrivateMessageConversationQuery {
+  pub async fn list(
+    self,
+    pool: &mut DbPool<'_>,
+    my_person_id: PersonId,
+  ) -> Result<Vec<PrivateMessageConversationView>, Error> {
+    let (limit, offset) = limit_and_offset(self.page, self.limit)?;
+    let conversations = private_message_conversation::table
+      .inner_join(private_message_conversation_participant::table)
+      .filter(private_message_conversation_participant::person_id.eq(my_person_id))
+      .filter(private_message_conversation_participant::archived.eq(self.archived_only))
+      .filter(private_message_conversation_participant::request.eq(self.requests_only))
+      .order_by(private_message_conversation::last_message_published.desc())
+      .limit(limit)
+      .offset(offset)
+      .select((
+        PrivateMessageConversation::as_select(),
+        PrivateMessageConversationParticipant::as_select(),
+      ))
+      .load(&mut get_conn(pool).await?)
+      .await?;
+    PrivateMessageConversationView::with_details(pool, conversations, my_person_id).await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::private_message_conversation_view::PrivateMessageConversationQuery;
+  use lemmy_db_schema::{
+    assert_length,
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      private_message::{PrivateMessage, PrivateMessageInsertForm},
+      private_message_conversation::{
+        PrivateMessageConversation,
+        PrivateMessageConversationParticipant,
+        PrivateMessageConversationParticipantUpdateForm,
+      },
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn list_conversations() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let timmy = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "timmy_pmcv"),
+    )
+    .await?;
+    let sara = Person::create(pool, &PersonInsertForm::test_form(instance.id, "sara_pmcv")).await?;
+    let jess = Person::create(pool, &PersonInsertForm::test_form(instance.id, "jess_pmcv")).await?;
+
+    let form = PrivateMessageInsertForm::builder()
+      .content("first".into())
+      .creator_id(sara.id)
+      .recipient_id(timmy.id)
+      .build();
+    PrivateMessage::create(pool, &form).await?;
+    let form = PrivateMessageInsertForm::builder()
+      .content("second".into())
+      .creator_id(sara.id)
+      .recipient_id(timmy.id)
+      .build();
+    let direct = PrivateMessage::create(pool, &form).await?;
+
+    let group =
+      PrivateMessageConversation::read_or_create(pool, jess.id, &[sara.id, timmy.id]).await?;
+    let form = PrivateMessageInsertForm::builder()
+      .content("hello group".into())
+      .creator_id(jess.id)
+      .recipient_id(sara.id)
+      .conversation_id(Some(group.id))
+      .build();
+    PrivateMessage::create(pool, &form).await?;
+
+    // Sara and Jess are strangers to Timmy, so both conversations are message requests
+    let conversations = PrivateMessageConversationQuery::default()
+      .list(pool, timmy.id)
+      .await?;
+    assert_length!(0, &conversations);
+    let requests = PrivateMessageConversationQuery {
+      requests_only: true,
+      ..Default::default()
+    }
+    .list(pool, timmy.id)
+    .await?;
+    assert_length!(2, &requests);
+    let form = PrivateMessageConversationParticipantUpdateForm {
+      request: Some(false),
+      ..Default::default()
+    };
+    for request in &requests {
+      PrivateMessageConversationParticipant::update(pool, request.conversation.id, timmy.id, &form)
+        .await?;
+    }
+
+    // Timmy sees the group conversation first, because it has the newest message
+    let conversations = PrivateMessageConversationQuery::default()
+      .list(pool, timmy.id)
+      .await?;
+    assert_length!(2, &conversations);
+    assert_eq!(group.id, conversations[0].conversation.id);
+    assert_length!(3, &conversations[0].participants);
+    assert_eq!(1, conversations[0].unread_count);
+    assert_eq!(
+      Some("hello group"),
+      conversations[0]
+        .last_message
+        .as_ref()
+        .map(|m| m.private_message.content.as_str())
+    );
+    assert_eq!(direct.conversation_id, conversations[1].conversation.id);
+    assert_eq!(2, conversations[1].unread_count);
+
+    // Archive and mark as read
+    let form = PrivateMessageConversationParticipantUpdateForm {
+      archived: Some(true),
+      ..Default::default()
+    };
+    PrivateMessageConversationParticipant::update(pool, group.id, timmy.id, &form).await?;
+    PrivateMessageConversationParticipant::mark_as_read(pool, direct.conversation_id, timmy.id)
+      .await?;
+    let conversations = PrivateMessageConversationQuery::default()
+      .list(pool, timmy.id)
+      .await?;
+    assert_length!(1, &conversations);
+    assert_eq!(0, conversations[0].unread_count);
+    let archived = PrivateMessageConversationQuery {
+      archived_only: true,
+      ..Default::default()
+    }
+    .list(pool, timmy.id)
+    .await?;
+    assert_length!(1, &archived);
+    assert_eq!(group.id, archived[0].conversation.id);
+
+    // Sara sent the direct messages, so she has nothing unread there. The group is a message
+    // request for her.
+    let conversations = PrivateMessageConversationQuery::default()
+      .list(pool, sara.id)
+      .await?;
+    assert_length!(1, &conversations);
+    assert_eq!(0, conversations[0].unread_count);
+    let requests = PrivateMessageConversationQuery {
+      requests_only: true,
+      ..Default::default()
+    }
+    .list(pool, sara.id)
+    .await?;
+    assert_length!(1, &requests);
+    assert_eq!(group.id, requests[0].conversation.id);
+    assert_eq!(1, requests[0].unread_count);
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -160,6 +160,7 @@ mod tests {
       original_pm_text: pm.content.clone(),
       private_message_id: pm.id,
       reason: "its offensive".to_string(),
+      decrypted_excerpt: None,
     };
     let pm_report = PrivateMessageReport::report(pool, &pm_report_form)
       .await
@@ -1,27 +1,36 @@
 use crate::structs::PrivateMessageView;
 use diesel::{
   debug_query,
+  dsl::{exists, not},
   pg::Pg,
   result::Error,
   BoolExpressionMethods,
   ExpressionMethods,
   JoinOnDsl,
+  NullableExpressionMethods,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   aliases,
-  newtypes::{PersonId, PrivateMessageId},
-  schema::{instance_block, person, person_block, private_message},
+  newtypes::{PersonId, PrivateMessageConversationId, PrivateMessageId},
+  schema::{
+    instance_block,
+    person,
+    person_block,
+    private_message,
+    private_message_conversation_participant,
+    private_message_read,
+  },
   utils::{get_conn, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
 };
 use tracing::debug;
 
 fn queries<'a>() -> Queries<
-  impl ReadFn<'a, PrivateMessageView, PrivateMessageId>,
+  impl ReadFn<'a, PrivateMessageView, (PrivateMessageId, PersonId)>,
   impl ListFn<'a, PrivateMessageView, (PrivateMessageQuery, PersonId)>,
 > {
-  let all_joins = |query: private_message::BoxedQuery<'a, Pg>| {
+  let all_joins = |query: private_message::BoxedQuery<'a, Pg>, my_person_id: PersonId| {
     query
       .inner_join(person::table.on(private_message::creator_id.eq(person::id)))
       .inner_join(
@@ -31,63 +40,92 @@ fn queries<'a>() -> Queries<
         person_block::table.on(
           private_message::creator_id
             .eq(person_block::target_id)
-            .and(person_block::person_id.eq(aliases::person1.field(person::id))),
+            .and(person_block::person_id.eq(my_person_id)),
         ),
       )
       .left_join(
         instance_block::table.on(
           person::instance_id
             .eq(instance_block::instance_id)
-            .and(instance_block::person_id.eq(aliases::person1.field(person::id))),
+            .and(instance_block::person_id.eq(my_person_id)),
         ),
       )
+      .left_join(
+        private_message_read::table.on(
+          private_message::id
+            .eq(private_message_read::private_message_id)
+            .and(private_message_read::person_id.eq(my_person_id)),
+        ),
+      )
+      .select((
+        private_message::all_columns,
+        person::all_columns,
+        aliases::person1.fields(person::all_columns),
+        // Own messages don't need to be read
+        private_message::creator_id
+          .eq(my_person_id)
+          .or(private_message_read::person_id.nullable().is_not_null()),
+      ))
   };
 
-  let selection = (
-    private_message::all_columns,
-    person::all_columns,
-    aliases::person1.fields(person::all_columns),
-  );
-
-  let read = move |mut conn: DbConn<'a>, private_message_id: PrivateMessageId| async move {
-    all_joins(private_message::table.find(private_message_id).into_boxed())
-      .order_by(private_message::published.desc())
-      .select(selection)
-      .first(&mut conn)
-      .await
+  let read = move |mut conn: DbConn<'a>,
+                   (private_message_id, my_person_id): (PrivateMessageId, PersonId)| async move {
+    all_joins(
+      private_message::table.find(private_message_id).into_boxed(),
+      my_person_id,
+    )
+    .order_by(private_message::published.desc())
+    .first(&mut conn)
+    .await
   };
 
   let list = move |mut conn: DbConn<'a>,
                    (options, recipient_id): (PrivateMessageQuery, PersonId)| async move {
-    let mut query = all_joins(private_message::table.into_boxed())
-      .select(selection)
+    let mut query = all_joins(private_message::table.into_boxed(), recipient_id)
       // Dont show replies from blocked users
       .filter(person_block::person_id.is_null())
       // Dont show replies from blocked instances
-      .filter(instance_block::person_id.is_null());
+      .filter(instance_block::person_id.is_null())
+      // Show sent and received messages, including group messages
+      .filter(exists(
+        private_message_conversation_participant::table
+          .filter(
+            private_message_conversation_participant::conversation_id
+              .eq(private_message::conversation_id),
+          )
+          .filter(private_message_conversation_participant::person_id.eq(recipient_id)),
+      ));
 
     // If its unread, I only want the ones to me
     if options.unread_only {
-      query = query.filter(private_message::read.eq(false));
+      query = query
+        .filter(private_message::creator_id.ne(recipient_id))
+        .filter(private_message_read::person_id.is_null());
       if let Some(i) = options.creator_id {
         query = query.filter(private_message::creator_id.eq(i))
       }
-      query = query.filter(private_message::recipient_id.eq(recipient_id));
+    } else if let Some(i) = options.creator_id {
+      query = query.filter(
+        private_message::creator_id
+          .eq(i)
+          .or(private_message::recipient_id.eq(i)),
+      )
     }
-    // Otherwise, I want the ALL view to show both sent and received
+
+    if let Some(conversation_id) = options.conversation_id {
+      query = query.filter(private_message::conversation_id.eq(conversation_id));
+    }
+    // Message requests are only shown when opening the conversation
     else {
-      query = query.filter(
-        private_message::recipient_id
-          .eq(recipient_id)
-          .or(private_message::creator_id.eq(recipient_id)),
-      );
-      if let Some(i) = options.creator_id {
-        query = query.filter(
-          private_message::creator_id
-            .eq(i)
-            .or(private_message::recipient_id.eq(i)),
-        )
-      }
+      query = query.filter(not(exists(
+        private_message_conversation_participant::table
+          .filter(
+            private_message_conversation_participant::conversation_id
+              .eq(private_message::conversation_id),
+          )
+          .filter(private_message_conversation_participant::person_id.eq(recipient_id))
+          .filter(private_message_conversation_participant::request),
+      )));
     }
 
     let (limit, offset) = limit_and_offset(options.page, options.limit)?;
@@ -113,8 +151,11 @@ impl PrivateMessageView {
   pub async fn read(
     pool: &mut DbPool<'_>,
     private_message_id: PrivateMessageId,
+    my_person_id: PersonId,
   ) -> Result<Option<Self>, Error> {
-    queries().read(pool, private_message_id).await
+    queries()
+      .read(pool, (private_message_id, my_person_id))
+      .await
   }
 
   /// Gets the number of unread messages
@@ -145,8 +186,32 @@ impl PrivateMessageView {
       .filter(person_block::person_id.is_null())
       // Dont count replies from blocked instances
       .filter(instance_block::person_id.is_null())
-      .filter(private_message::read.eq(false))
-      .filter(private_message::recipient_id.eq(my_person_id))
+      // Dont count messages in muted conversations or message requests
+      .filter(not(exists(
+        private_message_conversation_participant::table
+          .filter(
+            private_message_conversation_participant::conversation_id
+              .eq(private_message::conversation_id),
+          )
+          .filter(private_message_conversation_participant::person_id.eq(my_person_id))
+          .filter(
+            private_message_conversation_participant::muted
+              .or(private_message_conversation_participant::request),
+          ),
+      )))
+      // Count messages from others in my conversations which I didn't read yet
+      .filter(exists(
+        private_message_conversation_participant::table
+          .filter(
+            private_message_conversation_participant::conversation_id
+              .eq(private_message::conversation_id),
+          )
+          .filter(private_message_conversation_participant::person_id.eq(my_person_id)),
+      ))
+      .filter(private_message::creator_id.ne(my_person_id))
+      .filter(not(exists(
+        private_message_read::table.find((private_message::id, my_person_id)),
+      )))
       .filter(private_message::deleted.eq(false))
       .select(count(private_message::id))
       .first::<i64>(conn)
@@ -160,6 +225,7 @@ pub struct PrivateMessageQuery {
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub creator_id: Option<PersonId>,
+  pub conversation_id: Option<PrivateMessageConversationId>,
 }
 
 impl PrivateMessageQuery {
@@ -184,11 +250,12 @@ mod tests {
     source::{
       instance::Instance,
       instance_block::{InstanceBlock, InstanceBlockForm},
-      person::{Person, PersonInsertForm},
+      person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm},
       person_block::{PersonBlock, PersonBlockForm},
-      private_message::{PrivateMessage, PrivateMessageInsertForm},
+      private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageRead},
+      private_message_conversation::PrivateMessageConversation,
     },
-    traits::{Blockable, Crud},
+    traits::{Blockable, Crud, Followable},
     utils::{build_db_pool_for_tests, DbPool},
   };
   use lemmy_utils::error::LemmyResult;
@@ -221,6 +288,14 @@ mod tests {
 
     let jess = Person::create(pool, &jess_form).await.unwrap();
 
+    // Timmy follows Jess, so her message isn't a message request
+    let follow_form = PersonFollowerForm {
+      person_id: jess.id,
+      follower_id: timmy.id,
+      pending: false,
+    };
+    PersonFollower::follow(pool, &follow_form).await?;
+
     let sara_timmy_message_form = PrivateMessageInsertForm::builder()
       .creator_id(sara.id)
       .recipient_id(timmy.id)
@@ -439,4 +514,75 @@ mod tests {
     assert_eq!(timmy_unread_messages, 0);
     cleanup(instance.id, pool).await
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn group_conversation_read_state() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let Data {
+      timmy,
+      jess,
+      sara,
+      instance,
+    } = init_data(pool).await?;
+    assert_eq!(
+      2,
+      PrivateMessageView::get_unread_messages(pool, timmy.id).await?
+    );
+    assert_eq!(
+      0,
+      PrivateMessageView::get_unread_messages(pool, jess.id).await?
+    );
+
+    // Jess starts a group, and Sara writes to it. The message is stored with Timmy as recipient,
+    // but it is unread for Jess too.
+    let group =
+      PrivateMessageConversation::read_or_create(pool, jess.id, &[sara.id, timmy.id]).await?;
+    let form = PrivateMessageInsertForm::builder()
+      .creator_id(sara.id)
+      .recipient_id(timmy.id)
+      .content(String::new())
+      .conversation_id(Some(group.id))
+      .build();
+    let pm = PrivateMessage::create(pool, &form).await?;
+    assert_eq!(
+      3,
+      PrivateMessageView::get_unread_messages(pool, timmy.id).await?
+    );
+    assert_eq!(
+      1,
+      PrivateMessageView::get_unread_messages(pool, jess.id).await?
+    );
+    let jess_unread = PrivateMessageQuery {
+      unread_only: true,
+      ..Default::default()
+    }
+    .list(pool, jess.id)
+    .await?;
+    assert_eq!(
+      vec![pm.id],
+      jess_unread
+        .iter()
+        .map(|v| v.private_message.id)
+        .collect::<Vec<_>>()
+    );
+
+    // Reading the message only marks it as read for Jess
+    PrivateMessageRead::mark_as_read(pool, pm.id, jess.id).await?;
+    for (person_id, read) in [(jess.id, true), (timmy.id, false), (sara.id, true)] {
+      let view = PrivateMessageView::read(pool, pm.id, person_id).await?;
+      assert_eq!(Some(read), view.map(|v| v.read));
+    }
+    assert_eq!(
+      3,
+      PrivateMessageView::get_unread_messages(pool, timmy.id).await?
+    );
+    assert_eq!(
+      0,
+      PrivateMessageView::get_unread_messages(pool, jess.id).await?
+    );
+
+    cleanup(instance.id, pool).await
+  }
 }
@@ -265,6 +265,7 @@ mod tests {
         enable_keyboard_navigation: inserted_sara_local_user.enable_keyboard_navigation,
         enable_animated_images: inserted_sara_local_user.enable_animated_images,
         collapse_bot_comments: inserted_sara_local_user.collapse_bot_comments,
+        private_message_privacy: inserted_sara_local_user.private_message_privacy,
       },
       creator: Person {
         id: inserted_sara_person.id,
@@ -288,6 +289,8 @@ mod tests {
         private_key: inserted_sara_person.private_key,
         public_key: inserted_sara_person.public_key,
         last_refreshed_at: inserted_sara_person.last_refreshed_at,
+        moved_to: None,
+        also_known_as: vec![],
       },
       admin: None,
     };
@@ -367,6 +370,8 @@ mod tests {
       private_key: inserted_timmy_person.private_key,
       public_key: inserted_timmy_person.public_key,
       last_refreshed_at: inserted_timmy_person.last_refreshed_at,
+      moved_to: None,
+      also_known_as: vec![],
     });
     assert_eq!(read_sara_app_view_after_approve, expected_sara_app_view);
 
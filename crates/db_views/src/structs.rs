@@ -15,8 +15,13 @@ use lemmy_db_schema::{
     local_user_vote_display_mode::LocalUserVoteDisplayMode,
     person::Person,
     post::Post,
+    post_gallery_image::PostGallery,
     post_report::PostReport,
     private_message::PrivateMessage,
+    private_message_conversation::{
+      PrivateMessageConversation,
+      PrivateMessageConversationParticipant,
+    },
     private_message_report::PrivateMessageReport,
     registration_application::RegistrationApplication,
     site::Site,
@@ -132,6 +137,7 @@ pub struct PostView {
   pub creator: Person,
   pub community: Community,
   pub image_details: Option<ImageDetails>,
+  pub gallery: PostGallery,
   pub creator_banned_from_community: bool,
   pub banned_from_community: bool,
   pub creator_is_moderator: bool,
@@ -155,6 +161,23 @@ pub struct PrivateMessageView {
   pub private_message: PrivateMessage,
   pub creator: Person,
   pub recipient: Person,
+  /// Whether the person viewing the message has read it.
+  pub read: bool,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A private message conversation view.
+pub struct PrivateMessageConversationView {
+  pub conversation: PrivateMessageConversation,
+  /// Your own state of the conversation.
+  pub participant: PrivateMessageConversationParticipant,
+  /// Everyone taking part in the conversation, including yourself.
+  pub participants: Vec<Person>,
+  pub last_message: Option<PrivateMessageView>,
+  pub unread_count: i64,
 }
 
 #[skip_serializing_none]
@@ -1,5 +1,6 @@
 use crate::structs::{CommunityModeratorView, CommunityView, PersonView};
 use diesel::{
+  dsl::{exists, not},
   pg::Pg,
   result::Error,
   BoolExpressionMethods,
@@ -19,6 +20,7 @@ use lemmy_db_schema::{
     community_block,
     community_follower,
     community_person_ban,
+    federation_limitlist,
     instance_block,
   },
   source::{community::CommunityFollower, local_user::LocalUser, site::Site},
@@ -144,6 +146,17 @@ fn queries<'a>() -> Queries<
       TopWeek => query = query.order_by(community_aggregates::users_active_week.desc()),
     };
 
+    // Communities from instances with limited federation are hidden from general listings and
+    // search, unless the user follows them.
+    if matches!(options.listing_type, None | Some(ListingType::All)) {
+      let is_from_limited_instance = exists(
+        federation_limitlist::table
+          .filter(federation_limitlist::instance_id.eq(community::instance_id)),
+      );
+      query =
+        query.filter(not(is_from_limited_instance).or(community_follower::pending.is_not_null()));
+    }
+
     if let Some(listing_type) = options.listing_type {
       query = match listing_type {
         ListingType::Subscribed => query.filter(community_follower::pending.is_not_null()), /* TODO could be this: and(community_follower::person_id.eq(person_id_join)), */
@@ -244,13 +257,20 @@ mod tests {
   use crate::{community_view::CommunityQuery, structs::CommunityView};
   use lemmy_db_schema::{
     source::{
-      community::{Community, CommunityInsertForm, CommunityUpdateForm},
+      community::{
+        Community,
+        CommunityFollower,
+        CommunityFollowerForm,
+        CommunityInsertForm,
+        CommunityUpdateForm,
+      },
+      federation_limitlist::{FederationLimitList, FederationLimitListForm},
       instance::Instance,
       local_user::{LocalUser, LocalUserInsertForm},
       person::{Person, PersonInsertForm},
       site::Site,
     },
-    traits::Crud,
+    traits::{Crud, Followable},
     utils::{build_db_pool_for_tests, DbPool},
     CommunityVisibility,
   };
@@ -380,4 +400,41 @@ mod tests {
 
     cleanup(data, pool).await;
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn limited_instance_community() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let limit_form = FederationLimitListForm {
+      instance_id: data.inserted_instance.id,
+      ..Default::default()
+    };
+    FederationLimitList::limit(pool, &limit_form).await.unwrap();
+    let query = || CommunityQuery {
+      local_user: Some(&data.local_user),
+      search_term: Some("test_community".to_string()),
+      ..Default::default()
+    };
+
+    // Communities on limited instances are hidden in search
+    let communities = query().list(&data.site, pool).await.unwrap();
+    assert_eq!(0, communities.len());
+
+    // unless the user follows them
+    let follower_form = CommunityFollowerForm {
+      community_id: data.inserted_community.id,
+      person_id: data.local_user.person_id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &follower_form)
+      .await
+      .unwrap();
+    let communities = query().list(&data.site, pool).await.unwrap();
+    assert_eq!(1, communities.len());
+
+    cleanup(data, pool).await;
+  }
 }
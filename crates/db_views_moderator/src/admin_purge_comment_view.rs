@@ -45,6 +45,10 @@ impl AdminPurgeCommentView {
       return Ok(vec![]);
     }
 
+    if let Some(before) = params.before {
+      query = query.filter(admin_purge_comment::when_.lt(before));
+    }
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     query
@@ -43,6 +43,10 @@ impl AdminPurgeCommunityView {
       return Ok(vec![]);
     }
 
+    if let Some(before) = params.before {
+      query = query.filter(admin_purge_community::when_.lt(before));
+    }
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     query
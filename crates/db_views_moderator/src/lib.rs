@@ -11,6 +11,8 @@ pub mod mod_add_community_view;
 #[cfg(feature = "full")]
 pub mod mod_add_view;
 #[cfg(feature = "full")]
+pub mod mod_automod_action_view;
+#[cfg(feature = "full")]
 pub mod mod_ban_from_community_view;
 #[cfg(feature = "full")]
 pub mod mod_ban_view;
@@ -28,4 +30,6 @@ pub mod mod_remove_community_view;
 pub mod mod_remove_post_view;
 #[cfg(feature = "full")]
 pub mod mod_transfer_community_view;
+#[cfg(feature = "full")]
+pub mod removal_reason_view;
 pub mod structs;
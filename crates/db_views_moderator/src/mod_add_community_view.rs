@@ -57,6 +57,10 @@ impl ModAddCommunityView {
       return Ok(vec![]);
     }
 
+    if let Some(before) = params.before {
+      query = query.filter(mod_add_community::when_.lt(before));
+    }
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     query
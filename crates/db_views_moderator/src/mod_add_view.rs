@@ -49,6 +49,10 @@ impl ModAddView {
       return Ok(vec![]);
     }
 
+    if let Some(before) = params.before {
+      query = query.filter(mod_add::when_.lt(before));
+    }
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     query
@@ -0,0 +1,82 @@
+use crate::structs::{ModAutomodActionView, ModlogListParams};
+use diesel::{
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  IntoSql,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  schema::{comment, community, mod_automod_action, person, post},
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+
+impl ModAutomodActionView {
+  pub async fn list(pool: &mut DbPool<'_>, params: ModlogListParams) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let admin_person_id_join = params.mod_person_id.unwrap_or(PersonId(-1));
+    let show_mod_names = !params.hide_modlog_names;
+    let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
+
+    let admin_names_join = mod_automod_action::mod_person_id
+      .eq(person::id)
+      .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
+    let mut query = mod_automod_action::table
+      .left_join(person::table.on(admin_names_join))
+      .inner_join(post::table)
+      .left_join(comment::table)
+      .inner_join(community::table.on(post::community_id.eq(community::id)))
+      .select((
+        mod_automod_action::all_columns,
+        person::all_columns.nullable(),
+        post::all_columns,
+        comment::all_columns.nullable(),
+        community::all_columns,
+      ))
+      .into_boxed();
+
+    if let Some(community_id) = params.community_id {
+      query = query.filter(post::community_id.eq(community_id));
+    };
+
+    if let Some(mod_person_id) = params.mod_person_id {
+      query = query.filter(mod_automod_action::mod_person_id.eq(mod_person_id));
+    };
+
+    // The affected person is the comment creator, or the post creator for actions on posts
+    if let Some(other_person_id) = params.other_person_id {
+      query = query.filter(
+        comment::creator_id.nullable().eq(other_person_id).or(
+          mod_automod_action::comment_id
+            .is_null()
+            .and(post::creator_id.eq(other_person_id)),
+        ),
+      );
+    };
+
+    if let Some(post_id) = params.post_id {
+      query = query.filter(mod_automod_action::post_id.eq(post_id));
+    }
+
+    if let Some(comment_id) = params.comment_id {
+      query = query.filter(mod_automod_action::comment_id.eq(comment_id));
+    }
+
+    if let Some(before) = params.before {
+      query = query.filter(mod_automod_action::when_.lt(before));
+    }
+
+    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+
+    query
+      .limit(limit)
+      .offset(offset)
+      .order_by(mod_automod_action::when_.desc())
+      .load::<ModAutomodActionView>(conn)
+      .await
+  }
+}
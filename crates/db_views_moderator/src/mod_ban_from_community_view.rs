@@ -59,6 +59,10 @@ impl ModBanFromCommunityView {
       return Ok(vec![]);
     }
 
+    if let Some(before) = params.before {
+      query = query.filter(mod_ban_from_community::when_.lt(before));
+    }
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     query
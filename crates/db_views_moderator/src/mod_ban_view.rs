@@ -49,6 +49,10 @@ impl ModBanView {
       return Ok(vec![]);
     }
 
+    if let Some(before) = params.before {
+      query = query.filter(mod_ban::when_.lt(before));
+    }
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     query
@@ -60,6 +60,10 @@ impl ModFeaturePostView {
       return Ok(vec![]);
     }
 
+    if let Some(before) = params.before {
+      query = query.filter(mod_feature_post::when_.lt(before));
+    }
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     query
@@ -11,7 +11,7 @@ use diesel::{
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
-  schema::{comment, community, mod_remove_comment, person, post},
+  schema::{comment, community, mod_remove_comment, person, post, removal_reason},
   utils::{get_conn, limit_and_offset, DbPool},
 };
 
@@ -32,6 +32,7 @@ impl ModRemoveCommentView {
       .inner_join(person_alias_1.on(comment::creator_id.eq(person_alias_1.field(person::id))))
       .inner_join(post::table.on(comment::post_id.eq(post::id)))
       .inner_join(community::table.on(post::community_id.eq(community::id)))
+      .left_join(removal_reason::table)
       .select((
         mod_remove_comment::all_columns,
         person::all_columns.nullable(),
@@ -39,6 +40,7 @@ impl ModRemoveCommentView {
         person_alias_1.fields(person::all_columns),
         post::all_columns,
         community::all_columns,
+        removal_reason::all_columns.nullable(),
       ))
       .into_boxed();
 
@@ -63,6 +65,10 @@ impl ModRemoveCommentView {
       return Ok(vec![]);
     }
 
+    if let Some(before) = params.before {
+      query = query.filter(mod_remove_comment::when_.lt(before));
+    }
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     query
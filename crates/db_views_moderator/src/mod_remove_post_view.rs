@@ -11,7 +11,7 @@ use diesel::{
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
-  schema::{community, mod_remove_post, person, post},
+  schema::{community, mod_remove_post, person, post, removal_reason},
   utils::{get_conn, limit_and_offset, DbPool},
 };
 
@@ -32,11 +32,13 @@ impl ModRemovePostView {
       .inner_join(post::table)
       .inner_join(community::table.on(post::community_id.eq(community::id)))
       .inner_join(person_alias_1.on(post::creator_id.eq(person_alias_1.field(person::id))))
+      .left_join(removal_reason::table)
       .select((
         mod_remove_post::all_columns,
         person::all_columns.nullable(),
         post::all_columns,
         community::all_columns,
+        removal_reason::all_columns.nullable(),
       ))
       .into_boxed();
 
@@ -61,6 +63,10 @@ impl ModRemovePostView {
       return Ok(vec![]);
     }
 
+    if let Some(before) = params.before {
+      query = query.filter(mod_remove_post::when_.lt(before));
+    }
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     query
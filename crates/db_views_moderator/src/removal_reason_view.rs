@@ -0,0 +1,37 @@
+use crate::structs::RemovalReasonView;
+use diesel::{result::Error, ExpressionMethods, NullableExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  schema::{mod_remove_comment, mod_remove_post, removal_reason},
+  utils::{functions::coalesce, get_conn, DbPool},
+};
+
+impl RemovalReasonView {
+  /// Lists the removal reasons of a community, together with how often each one was used to
+  /// remove posts and comments.
+  pub async fn list(pool: &mut DbPool<'_>, community_id: CommunityId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+
+    let post_removals = mod_remove_post::table
+      .filter(mod_remove_post::removal_reason_id.eq(removal_reason::id.nullable()))
+      .filter(mod_remove_post::removed)
+      .count()
+      .single_value();
+    let comment_removals = mod_remove_comment::table
+      .filter(mod_remove_comment::removal_reason_id.eq(removal_reason::id.nullable()))
+      .filter(mod_remove_comment::removed)
+      .count()
+      .single_value();
+
+    removal_reason::table
+      .filter(removal_reason::community_id.eq(community_id))
+      .select((
+        removal_reason::all_columns,
+        coalesce(post_removals, 0) + coalesce(comment_removals, 0),
+      ))
+      .order_by(removal_reason::title)
+      .load::<Self>(conn)
+      .await
+  }
+}
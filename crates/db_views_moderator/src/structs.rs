@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 #[cfg(feature = "full")]
 use diesel::Queryable;
 use lemmy_db_schema::{
@@ -12,6 +13,7 @@ use lemmy_db_schema::{
       AdminPurgePost,
       ModAdd,
       ModAddCommunity,
+      ModAutomodAction,
       ModBan,
       ModBanFromCommunity,
       ModFeaturePost,
@@ -24,6 +26,7 @@ use lemmy_db_schema::{
     },
     person::Person,
     post::Post,
+    removal_reason::RemovalReason,
   },
 };
 use serde::{Deserialize, Serialize};
@@ -119,6 +122,7 @@ pub struct ModRemoveCommentView {
   pub commenter: Person,
   pub post: Post,
   pub community: Community,
+  pub removal_reason: Option<RemovalReason>,
 }
 
 #[skip_serializing_none]
@@ -144,6 +148,7 @@ pub struct ModRemovePostView {
   pub moderator: Option<Person>,
   pub post: Post,
   pub community: Community,
+  pub removal_reason: Option<RemovalReason>,
 }
 
 #[skip_serializing_none]
@@ -218,6 +223,30 @@ pub struct AdminPurgePostView {
   pub community: Community,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS, Queryable))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an automod rule reports, replies to or sets the flair of a post or comment.
+pub struct ModAutomodActionView {
+  pub mod_automod_action: ModAutomodAction,
+  pub moderator: Option<Person>,
+  pub post: Post,
+  pub comment: Option<Comment>,
+  pub community: Community,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS, Queryable))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A removal reason template, with the number of times moderators used it.
+pub struct RemovalReasonView {
+  pub removal_reason: RemovalReason,
+  pub usage_count: i64,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 #[cfg_attr(feature = "full", derive(TS, Queryable))]
@@ -233,4 +262,6 @@ pub struct ModlogListParams {
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub hide_modlog_names: bool,
+  /// Only list entries from before this time. Allows paging through several lists at once.
+  pub before: Option<DateTime<Utc>>,
 }
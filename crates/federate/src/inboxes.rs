@@ -2,9 +2,14 @@ use crate::util::LEMMY_TEST_FAST_FEDERATION;
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, TimeZone, Utc};
+use lemmy_apub::activity_lists::SharedInboxActivities;
 use lemmy_db_schema::{
   newtypes::{CommunityId, DbUrl, InstanceId},
-  source::{activity::SentActivity, site::Site},
+  source::{
+    activity::{ActorType, SentActivity},
+    relay::{Relay, RelayCommunityFilter},
+    site::Site,
+  },
   utils::{ActualDbPool, DbPool},
 };
 use lemmy_db_views_actor::structs::CommunityFollowerView;
@@ -47,6 +52,10 @@ pub trait DataSource: Send + Sync {
     instance_id: InstanceId,
     last_fetch: DateTime<Utc>,
   ) -> Result<Vec<(CommunityId, DbUrl)>, diesel::result::Error>;
+  async fn get_instance_relays(
+    &self,
+    instance_id: InstanceId,
+  ) -> Result<Vec<(Relay, Vec<RelayCommunityFilter>)>, diesel::result::Error>;
 }
 pub struct DbDataSource {
   pool: ActualDbPool,
@@ -79,6 +88,13 @@ impl DataSource for DbDataSource {
     )
     .await
   }
+
+  async fn get_instance_relays(
+    &self,
+    instance_id: InstanceId,
+  ) -> Result<Vec<(Relay, Vec<RelayCommunityFilter>)>, diesel::result::Error> {
+    Relay::list_accepted_for_instance(&mut DbPool::Pool(&self.pool), instance_id).await
+  }
 }
 
 pub(crate) struct CommunityInboxCollector<T: DataSource> {
@@ -89,6 +105,8 @@ pub(crate) struct CommunityInboxCollector<T: DataSource> {
   site_loaded: bool,
   site: Option<Site>,
   followed_communities: HashMap<CommunityId, HashSet<Url>>,
+  /// inboxes of relays on this instance which receive announces of local communities
+  relays: Vec<(Url, Vec<RelayCommunityFilter>)>,
   last_full_communities_fetch: DateTime<Utc>,
   last_incremental_communities_fetch: DateTime<Utc>,
   instance_id: InstanceId,
@@ -116,6 +134,7 @@ impl<T: DataSource> CommunityInboxCollector<T> {
       site_loaded: false,
       site: None,
       followed_communities: HashMap::new(),
+      relays: Vec::new(),
       last_full_communities_fetch: Utc.timestamp_nanos(0),
       last_incremental_communities_fetch: Utc.timestamp_nanos(0),
       instance_id,
@@ -168,6 +187,33 @@ impl<T: DataSource> CommunityInboxCollector<T> {
     Ok(inbox_urls.into_iter().collect())
   }
 
+  /// get inbox urls of relays which should receive the given activity.
+  ///
+  /// Relays only receive announces of new or edited posts and comments by local communities.
+  /// Local-only communities never send any activities, so these are always public.
+  pub fn get_relay_inbox_urls(
+    &self,
+    activity: &SentActivity,
+    object: &SharedInboxActivities,
+  ) -> Vec<Url> {
+    let Some(community_id) = activity.send_community_followers_of else {
+      return vec![];
+    };
+    if self.relays.is_empty()
+      || activity.actor_type != ActorType::Community
+      || activity.sensitive
+      || !is_announced_content(object)
+    {
+      return vec![];
+    }
+    self
+      .relays
+      .iter()
+      .filter(|(_, filters)| RelayCommunityFilter::allows(filters, community_id))
+      .map(|(inbox, _)| inbox.clone())
+      .collect()
+  }
+
   pub async fn update_communities(&mut self) -> Result<()> {
     if (Utc::now() - self.last_full_communities_fetch) > *FOLLOW_REMOVALS_RECHECK_DELAY {
       tracing::debug!("{}: fetching full list of communities", self.domain);
@@ -176,6 +222,7 @@ impl<T: DataSource> CommunityInboxCollector<T> {
         .get_communities(self.instance_id, Utc.timestamp_nanos(0))
         .await?;
       self.last_incremental_communities_fetch = self.last_full_communities_fetch;
+      self.update_relays().await?;
     }
     if (Utc::now() - self.last_incremental_communities_fetch) > *FOLLOW_ADDITIONS_RECHECK_DELAY {
       // process additions every minute
@@ -191,10 +238,23 @@ impl<T: DataSource> CommunityInboxCollector<T> {
       }
       self.followed_communities.extend(news);
       self.last_incremental_communities_fetch = time;
+      self.update_relays().await?;
     }
     Ok(())
   }
 
+  /// relays are few and rarely change, so simply reload all of them together with the communities
+  async fn update_relays(&mut self) -> Result<()> {
+    self.relays = self
+      .data_source
+      .get_instance_relays(self.instance_id)
+      .await?
+      .into_iter()
+      .map(|(relay, filters)| (relay.inbox_url.into(), filters))
+      .collect();
+    Ok(())
+  }
+
   /// get a list of local communities with the remote inboxes on the given instance that cares about
   /// them
   async fn get_communities(
@@ -221,13 +281,31 @@ impl<T: DataSource> CommunityInboxCollector<T> {
   }
 }
 
+/// whether this is an announce of a created or updated post or comment
+fn is_announced_content(object: &SharedInboxActivities) -> bool {
+  if !matches!(object, SharedInboxActivities::AnnounceActivity(_)) {
+    return false;
+  }
+  // the announced activity is only accessible in serialized form
+  let Ok(announce) = serde_json::to_value(object) else {
+    return false;
+  };
+  let kind = |value: Option<&serde_json::Value>| value?.get("type")?.as_str().map(str::to_string);
+  let announced = announce.get("object");
+  matches!(kind(announced).as_deref(), Some("Create" | "Update"))
+    && matches!(
+      kind(announced.and_then(|a| a.get("object"))).as_deref(),
+      Some("Page" | "Note")
+    )
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::indexing_slicing)]
 mod tests {
   use super::*;
   use lemmy_db_schema::{
-    newtypes::{ActivityId, CommunityId, InstanceId, SiteId},
+    newtypes::{ActivityId, CommunityId, InstanceId, RelayId, SiteId},
     source::activity::{ActorType, SentActivity},
   };
   use mockall::{mock, predicate::*};
@@ -242,11 +320,18 @@ mod tests {
               instance_id: InstanceId,
               last_fetch: DateTime<Utc>,
           ) -> Result<Vec<(CommunityId, DbUrl)>, diesel::result::Error>;
+          async fn get_instance_relays(
+              &self,
+              instance_id: InstanceId,
+          ) -> Result<Vec<(Relay, Vec<RelayCommunityFilter>)>, diesel::result::Error>;
       }
   }
 
   fn setup_collector() -> CommunityInboxCollector<MockDataSource> {
-    let mock_data_source = MockDataSource::new();
+    let mut mock_data_source = MockDataSource::new();
+    mock_data_source
+      .expect_get_instance_relays()
+      .returning(|_| Ok(vec![]));
     let instance_id = InstanceId(1);
     let domain = "example.com".to_string();
     CommunityInboxCollector::new(mock_data_source, instance_id, domain)
@@ -571,4 +656,91 @@ mod tests {
     assert_eq!(result.len(), 1);
     assert!(result.contains(&Url::parse("https://example.com/site_inbox").unwrap()));
   }
+
+  #[tokio::test]
+  async fn test_get_inbox_urls_relays() {
+    let community_id = CommunityId(1);
+    let other_community_id = CommunityId(2);
+    let relay = |id: i32| Relay {
+      id: RelayId(id),
+      actor_id: Url::parse(&format!("https://relay{id}.example.com/actor"))
+        .unwrap()
+        .into(),
+      inbox_url: Url::parse(&format!("https://relay{id}.example.com/inbox"))
+        .unwrap()
+        .into(),
+      public_key: "test_key".to_string(),
+      instance_id: InstanceId(1),
+      follow_accepted: true,
+      published: Utc::now(),
+      last_refreshed_at: Utc::now(),
+    };
+    let filter = |relay_id: i32, community_id: CommunityId, allow: bool| RelayCommunityFilter {
+      relay_id: RelayId(relay_id),
+      community_id,
+      allow,
+      published: Utc::now(),
+    };
+
+    let mut mock_data_source = MockDataSource::new();
+    mock_data_source
+      .expect_get_instance_followed_community_inboxes()
+      .return_once(|_, _| Ok(vec![]));
+    mock_data_source
+      .expect_get_instance_relays()
+      .return_once(move |_| {
+        Ok(vec![
+          // relays everything
+          (relay(1), vec![]),
+          // denies the community
+          (relay(2), vec![filter(2, community_id, false)]),
+          // only allows another community
+          (relay(3), vec![filter(3, other_community_id, true)]),
+        ])
+      });
+    let mut collector =
+      CommunityInboxCollector::new(mock_data_source, InstanceId(1), "example.com".to_string());
+    collector.update_communities().await.unwrap();
+
+    let activity = SentActivity {
+      id: ActivityId(1),
+      ap_id: Url::parse("https://example.com/activities/1")
+        .unwrap()
+        .into(),
+      data: json!({}),
+      sensitive: false,
+      published: Utc::now(),
+      send_inboxes: vec![],
+      send_community_followers_of: Some(community_id),
+      send_all_instances: false,
+      actor_type: ActorType::Community,
+      actor_apub_id: None,
+    };
+    let announce = |kind: &str| -> SharedInboxActivities {
+      serde_json::from_value(json!({
+        "actor": "https://example.com/c/main",
+        "to": ["https://www.w3.org/ns/activitystreams#Public"],
+        "object": {
+          "actor": "https://example.com/u/alice",
+          "object": {"type": "Page", "id": "https://example.com/post/1"},
+          "type": kind,
+          "id": "https://example.com/activities/1",
+        },
+        "cc": [],
+        "type": "Announce",
+        "id": "https://example.com/activities/2",
+      }))
+      .unwrap()
+    };
+
+    let result = collector.get_relay_inbox_urls(&activity, &announce("Create"));
+    assert_eq!(
+      vec![Url::parse("https://relay1.example.com/inbox").unwrap()],
+      result
+    );
+
+    // votes and other activities of the community are not relayed
+    let result = collector.get_relay_inbox_urls(&activity, &announce("Like"));
+    assert!(result.is_empty());
+  }
 }
@@ -1,10 +1,23 @@
-use crate::{util::CancellableTask, worker::InstanceWorker};
-use activitypub_federation::config::FederationConfig;
+use crate::{
+  util::{get_latest_activity_id, invalidate_actor_cache, CancellableTask},
+  worker::InstanceWorker,
+};
+use activitypub_federation::config::{FederationConfig, FederationConfigBuilder};
+use chrono::{DateTime, Utc};
 use lemmy_api_common::{
   context::LemmyContext,
   lemmy_utils::settings::structs::FederationWorkerConfig,
 };
-use lemmy_db_schema::{newtypes::InstanceId, source::instance::Instance};
+use lemmy_db_schema::{
+  newtypes::InstanceId,
+  source::{
+    actor_previous_key::ActorPreviousKey,
+    federation_queue_state::FederationQueueState,
+    instance::Instance,
+  },
+  utils::DbPool,
+  FederationQueueReset,
+};
 use lemmy_utils::error::LemmyResult;
 use stats::receive_print_stats;
 use std::{collections::HashMap, time::Duration};
@@ -41,6 +54,10 @@ pub struct SendManager {
   opts: Opts,
   workers: HashMap<InstanceId, CancellableTask>,
   context: FederationConfig<LemmyContext>,
+  /// Used to rebuild `context` after a local actor rotated its keypair, because the federation
+  /// config caches private keys.
+  context_builder: FederationConfigBuilder<LemmyContext>,
+  latest_key_rotation: Option<DateTime<Utc>>,
   stats_sender: UnboundedSender<FederationQueueStateWithDomain>,
   exit_print: JoinHandle<()>,
   federation_worker_config: FederationWorkerConfig,
@@ -50,6 +67,7 @@ impl SendManager {
   fn new(
     opts: Opts,
     context: FederationConfig<LemmyContext>,
+    context_builder: FederationConfigBuilder<LemmyContext>,
     federation_worker_config: FederationWorkerConfig,
   ) -> Self {
     assert!(opts.process_count > 0);
@@ -66,21 +84,24 @@ impl SendManager {
         stats_receiver,
       )),
       context,
+      context_builder,
+      latest_key_rotation: None,
       federation_worker_config,
     }
   }
 
   pub fn run(
     opts: Opts,
-    context: FederationConfig<LemmyContext>,
+    context_builder: FederationConfigBuilder<LemmyContext>,
     config: FederationWorkerConfig,
   ) -> CancellableTask {
     CancellableTask::spawn(WORKER_EXIT_TIMEOUT, move |cancel| {
       let opts = opts.clone();
       let config = config.clone();
-      let context = context.clone();
-      let mut manager = Self::new(opts, context, config);
+      let context_builder = context_builder.clone();
       async move {
+        let context = context_builder.clone().build().await?;
+        let mut manager = Self::new(opts, context, context_builder, config);
         let result = manager.do_loop(cancel).await;
         // the loop function will only return if there is (a) an internal error (e.g. db connection
         // failure) or (b) it was cancelled from outside.
@@ -106,8 +127,13 @@ impl SendManager {
       self.opts.process_count, process_index
     );
     let local_domain = self.context.settings().get_hostname_without_port()?;
-    let mut pool = self.context.pool();
+    let context = self.context.clone();
+    let mut pool = context.pool();
     loop {
+      self.reload_keys_if_rotated(&mut pool).await?;
+      self
+        .apply_requested_resets(&mut pool, process_index)
+        .await?;
       let mut total_count = 0;
       let mut dead_count = 0;
       let mut disallowed_count = 0;
@@ -170,6 +196,61 @@ impl SendManager {
     }
   }
 
+  /// Private keys of local actors are cached, both in the federation config and with the actors.
+  /// When a local actor rotates its keypair, the config and cache are rebuilt and the workers
+  /// stopped, so that they are restarted by the main loop and sign with the new key.
+  async fn reload_keys_if_rotated(&mut self, pool: &mut DbPool<'_>) -> LemmyResult<()> {
+    let latest_key_rotation = ActorPreviousKey::latest_local_rotation(pool).await?;
+    if latest_key_rotation == self.latest_key_rotation {
+      return Ok(());
+    }
+    self.latest_key_rotation = latest_key_rotation;
+    self.context = self.context_builder.clone().build().await?;
+    invalidate_actor_cache();
+    // the workers save their state when stopping
+    futures::future::join_all(
+      self
+        .workers
+        .drain()
+        .map(|(_, worker)| util::CancellableTask::cancel(worker)),
+    )
+    .await;
+    Ok(())
+  }
+
+  /// Applies queue resets which were requested by admins. The worker of the instance is stopped
+  /// first so that it doesn't overwrite the state, and started again by the main loop.
+  async fn apply_requested_resets(
+    &mut self,
+    pool: &mut DbPool<'_>,
+    process_index: i32,
+  ) -> LemmyResult<()> {
+    for (mut state, reset) in FederationQueueState::read_requested_resets(pool).await? {
+      if state.instance_id.inner() % self.opts.process_count != process_index {
+        continue;
+      }
+      if let Some(worker) = self.workers.remove(&state.instance_id) {
+        if let Err(e) = worker.cancel().await {
+          tracing::error!("error stopping worker: {e}");
+        }
+        // the worker saves its state when stopping
+        state = FederationQueueState::load(pool, state.instance_id).await?;
+      }
+      state.fail_count = 0;
+      state.last_retry = None;
+      if reset == FederationQueueReset::Skip {
+        state.last_successful_id = Some(get_latest_activity_id(pool).await?);
+        state.last_successful_published_time = None;
+      }
+      FederationQueueState::finish_reset(pool, &state).await?;
+      info!(
+        "Applied federation queue reset {reset} for instance {}",
+        state.instance_id.inner()
+      );
+    }
+    Ok(())
+  }
+
   pub async fn cancel(self) -> LemmyResult<()> {
     drop(self.stats_sender);
     tracing::warn!(
@@ -224,11 +305,11 @@ mod test {
         process_count,
         process_index,
       };
-      let federation_config = FederationConfig::builder()
+      let mut federation_config_builder = FederationConfig::builder();
+      federation_config_builder
         .domain("local.com")
-        .app_data(context.clone())
-        .build()
-        .await?;
+        .app_data(context.clone());
+      let federation_config = federation_config_builder.build().await?;
       let concurrent_sends_per_instance = std::env::var("LEMMY_TEST_FEDERATION_CONCURRENT_SENDS")
         .ok()
         .and_then(|s| s.parse().ok())
@@ -236,6 +317,7 @@ mod test {
 
       let federation_worker_config = FederationWorkerConfig {
         concurrent_sends_per_instance,
+        ..Default::default()
       };
       let pool = &mut context.pool();
       let instances = vec![
@@ -244,7 +326,12 @@ mod test {
         Instance::read_or_create(pool, "gamma.com".to_string()).await?,
       ];
 
-      let send_manager = SendManager::new(opts, federation_config, federation_worker_config);
+      let send_manager = SendManager::new(
+        opts,
+        federation_config,
+        federation_config_builder,
+        federation_worker_config,
+      );
       Ok(Self {
         send_manager,
         context,
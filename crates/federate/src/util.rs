@@ -1,6 +1,4 @@
 use anyhow::{anyhow, Context, Result};
-use diesel::prelude::*;
-use diesel_async::RunQueryDsl;
 use lemmy_apub::{
   activity_lists::SharedInboxActivities,
   fetcher::{site_or_community_or_user::SiteOrCommunityOrUser, user_or_community::UserOrCommunity},
@@ -15,7 +13,7 @@ use lemmy_db_schema::{
     site::Site,
   },
   traits::ApubActor,
-  utils::{get_conn, DbPool},
+  utils::DbPool,
 };
 use moka::future::Cache;
 use reqwest::Url;
@@ -77,6 +75,11 @@ pub(crate) static CACHE_DURATION_LATEST_ID: LazyLock<Duration> = LazyLock::new(|
   }
 });
 
+/// Actors with their private keys, for signing outgoing activities.
+/// TODO: capacity should be configurable maybe based on memory use
+static ACTOR_CACHE: LazyLock<Cache<Url, Arc<SiteOrCommunityOrUser>>> =
+  LazyLock::new(|| Cache::builder().max_capacity(10000).build());
+
 /// A task that will be run in an infinite loop, unless it is cancelled.
 /// If the task exits without being cancelled, an error will be logged and the task will be
 /// restarted.
@@ -130,16 +133,14 @@ impl CancellableTask {
   }
 }
 
-/// assuming apub priv key and ids are immutable, then we don't need to have TTL
-/// TODO: capacity should be configurable maybe based on memory use
+/// Actor ids are immutable, and keys only change when a local actor rotates its keypair, in which
+/// case the cache is cleared with [invalidate_actor_cache]. So there is no need for a TTL.
 pub(crate) async fn get_actor_cached(
   pool: &mut DbPool<'_>,
   actor_type: ActorType,
   actor_apub_id: &Url,
 ) -> Result<Arc<SiteOrCommunityOrUser>> {
-  static CACHE: LazyLock<Cache<Url, Arc<SiteOrCommunityOrUser>>> =
-    LazyLock::new(|| Cache::builder().max_capacity(10000).build());
-  CACHE
+  ACTOR_CACHE
     .try_get_with(actor_apub_id.clone(), async {
       let url = actor_apub_id.clone().into();
       let person = match actor_type {
@@ -168,6 +169,11 @@ pub(crate) async fn get_actor_cached(
     .map_err(|e| anyhow::anyhow!("err getting actor {actor_type:?} {actor_apub_id}: {e:?}"))
 }
 
+/// Clears cached actors so that their current private keys are loaded.
+pub(crate) fn invalidate_actor_cache() {
+  ACTOR_CACHE.invalidate_all();
+}
+
 type CachedActivityInfo = Option<Arc<(SentActivity, SharedInboxActivities)>>;
 /// activities are immutable so cache does not need to have TTL
 /// May return None if the corresponding id does not exist or is a received activity.
@@ -207,12 +213,10 @@ pub(crate) async fn get_latest_activity_id(pool: &mut DbPool<'_>) -> Result<Acti
   });
   CACHE
     .try_get_with((), async {
-      use diesel::dsl::max;
-      use lemmy_db_schema::schema::sent_activity::dsl::{id, sent_activity};
-      let conn = &mut get_conn(pool).await?;
-      let seq: Option<ActivityId> = sent_activity.select(max(id)).get_result(conn).await?;
-      let latest_id = seq.unwrap_or(ActivityId(0));
-      anyhow::Result::<_, anyhow::Error>::Ok(latest_id as ActivityId)
+      let latest_id = SentActivity::read_latest_id(pool)
+        .await?
+        .unwrap_or(ActivityId(0));
+      anyhow::Result::<_, anyhow::Error>::Ok(latest_id)
     })
     .await
     .map_err(|e| anyhow::anyhow!("err getting id: {e:?}"))
@@ -357,11 +357,16 @@ impl InstanceWorker {
       return Ok(());
     };
     let activity = &ele.0;
-    let inbox_urls = self
+    let mut inbox_urls = self
       .inbox_collector
       .get_inbox_urls(activity)
       .await
       .context("failed figuring out inbox urls")?;
+    for url in self.inbox_collector.get_relay_inbox_urls(activity, &ele.1) {
+      if !inbox_urls.contains(&url) {
+        inbox_urls.push(url);
+      }
+    }
     if inbox_urls.is_empty() {
       // this is the case when the activity is not relevant to this receiving instance (e.g. no user
       // subscribed to the relevant community)
@@ -454,7 +459,9 @@ mod test {
     newtypes::DbUrl,
     source::{
       activity::{ActorType, SentActivity, SentActivityForm},
+      community::{Community, CommunityInsertForm},
       person::{Person, PersonInsertForm},
+      relay::{Relay, RelayInsertForm, RelayUpdateForm},
     },
     traits::Crud,
   };
@@ -511,6 +518,7 @@ mod test {
 
       let fed_config = FederationWorkerConfig {
         concurrent_sends_per_instance,
+        ..Default::default()
       };
       spawn(InstanceWorker::init_and_loop(
         instance.clone(),
@@ -677,6 +685,73 @@ mod test {
     Ok(())
   }
 
+  /// The test server acts as a relay which has accepted our follow. Announces of local
+  /// communities must be forwarded to it, even though nobody on the relay follows the community.
+  #[test_context(Data)]
+  #[tokio::test]
+  #[serial]
+  async fn test_send_to_relay(data: &mut Data) -> LemmyResult<()> {
+    let pool = &mut data.context.pool();
+    let keypair = generate_actor_keypair()?;
+    let community_form = CommunityInsertForm::builder()
+      .name("relayed".to_string())
+      .title("relayed".to_string())
+      .actor_id(Some(Url::parse("http://local.com/c/relayed")?.into()))
+      .public_key(keypair.public_key)
+      .private_key(Some(keypair.private_key))
+      .instance_id(data.instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+
+    let relay_form = RelayInsertForm::builder()
+      .actor_id(Url::parse("http://localhost:8085/actor")?.into())
+      .inbox_url(Url::parse("http://localhost:8085/inbox")?.into())
+      .public_key("pubkey".to_string())
+      .instance_id(data.instance.id)
+      .build();
+    let relay = Relay::create(pool, &relay_form).await?;
+    let accepted = RelayUpdateForm {
+      follow_accepted: Some(true),
+      ..Default::default()
+    };
+    Relay::update(pool, relay.id, &accepted).await?;
+    // wait until the worker has loaded the relay
+    sleep(Duration::from_secs(2)).await;
+
+    let announce = json!({
+      "actor": community.actor_id,
+      "to": ["https://www.w3.org/ns/activitystreams#Public"],
+      "object": {
+        "actor": data.person.actor_id,
+        "object": {"type": "Page", "id": "http://local.com/post/1"},
+        "type": "Create",
+        "id": "http://local.com/activities/create/1",
+      },
+      "cc": [format!("{}/followers", community.actor_id)],
+      "type": "Announce",
+      "id": format!("http://local.com/activities/announce/{}", uuid::Uuid::new_v4()),
+    });
+    let form = SentActivityForm {
+      ap_id: Url::parse(announce["id"].as_str().unwrap())?.into(),
+      data: announce.clone(),
+      sensitive: false,
+      send_inboxes: vec![],
+      send_all_instances: false,
+      send_community_followers_of: Some(community.id.0),
+      actor_type: ActorType::Community,
+      actor_apub_id: community.actor_id.clone(),
+    };
+    SentActivity::create(pool, form).await?;
+
+    let inbox_rcv = data.inbox_receiver.recv().await.unwrap();
+    let parsed_activity = serde_json::from_str::<WithContext<Value>>(&inbox_rcv)?;
+    assert_eq!(&announce, parsed_activity.inner());
+
+    Community::delete(pool, community.id).await?;
+    data.cleanup().await?;
+    Ok(())
+  }
+
   fn listen_activities(inbox_sender: UnboundedSender<String>) -> LemmyResult<ServerHandle> {
     let run = HttpServer::new(move || {
       App::new()
@@ -1,10 +1,35 @@
 use crate::local_user_view_from_jwt;
-use actix_web::{error::ErrorBadRequest, web, Error, HttpRequest, HttpResponse, Result};
+use actix_web::{
+  error::ErrorBadRequest,
+  http::header::{
+    Accept,
+    ETag,
+    EntityTag,
+    Header,
+    HttpDate,
+    IfModifiedSince,
+    IfNoneMatch,
+    LastModified,
+    IF_NONE_MATCH,
+    VARY,
+  },
+  web,
+  Error,
+  HttpRequest,
+  HttpResponse,
+  Result,
+};
 use anyhow::anyhow;
-use chrono::{DateTime, Utc};
+use atom_syndication::{Content, Entry, Feed, Link, Text};
+use chrono::{DateTime, FixedOffset, Utc};
 use lemmy_api_common::{context::LemmyContext, utils::check_private_instance};
 use lemmy_db_schema::{
-  source::{community::Community, person::Person},
+  newtypes::PostId,
+  source::{
+    community::Community,
+    person::Person,
+    post::{PostSaved, SavedItemId},
+  },
   traits::ApubActor,
   CommentSortType,
   CommunityVisibility,
@@ -12,14 +37,26 @@ use lemmy_db_schema::{
   SortType,
 };
 use lemmy_db_views::{
+  comment_view::CommentQuery,
   post_view::PostQuery,
-  structs::{PostView, SiteView},
+  structs::{CommentView, PostView, SiteView},
 };
 use lemmy_db_views_actor::{
   comment_reply_view::CommentReplyQuery,
   person_mention_view::PersonMentionQuery,
   structs::{CommentReplyView, PersonMentionView},
 };
+use lemmy_db_views_moderator::structs::{
+  ModAddCommunityView,
+  ModAutomodActionView,
+  ModBanFromCommunityView,
+  ModFeaturePostView,
+  ModLockPostView,
+  ModRemoveCommentView,
+  ModRemovePostView,
+  ModTransferCommunityView,
+  ModlogListParams,
+};
 use lemmy_utils::{
   cache_header::cache_1hour,
   error::{LemmyError, LemmyErrorType, LemmyResult},
@@ -32,10 +69,21 @@ use rss::{
   Guid,
   Item,
 };
-use serde::Deserialize;
-use std::{collections::BTreeMap, str::FromStr, sync::LazyLock};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use std::{
+  cmp::Reverse,
+  collections::BTreeMap,
+  hash::{DefaultHasher, Hash, Hasher},
+  str::FromStr,
+  sync::LazyLock,
+  time::SystemTime,
+};
 
 const RSS_FETCH_LIMIT: i64 = 20;
+/// Each page of the modlog feed needs all the pages before it to be read, so only the first ones
+/// are available.
+const MODLOG_FEED_MAX_PAGE: i64 = 10;
 
 #[derive(Deserialize)]
 struct Params {
@@ -65,17 +113,74 @@ enum RequestType {
   User,
   Front,
   Inbox,
+  Hashtag,
+  Post,
+  Saved,
+  Modlog,
+}
+
+/// The formats in which every feed is available.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FeedFormat {
+  Rss,
+  Atom,
+  Json,
+}
+
+impl FeedFormat {
+  /// Uses the file extension of the feed url if there is one, otherwise the `Accept` header.
+  fn from_request(req: &HttpRequest) -> Self {
+    match req.match_info().get("format") {
+      Some("atom") => FeedFormat::Atom,
+      Some("json") => FeedFormat::Json,
+      Some(_) => FeedFormat::Rss,
+      None => Accept::parse(req)
+        .map(|accept| Self::from_accept(&accept))
+        .unwrap_or(FeedFormat::Rss),
+    }
+  }
+
+  /// Picks the preferred feed format from the `Accept` header, falling back to RSS.
+  fn from_accept(accept: &Accept) -> Self {
+    accept
+      .ranked()
+      .iter()
+      .find_map(|mime| match mime.essence_str() {
+        "application/rss+xml" => Some(FeedFormat::Rss),
+        "application/atom+xml" => Some(FeedFormat::Atom),
+        "application/feed+json" | "application/json" => Some(FeedFormat::Json),
+        _ => None,
+      })
+      .unwrap_or(FeedFormat::Rss)
+  }
+
+  fn content_type(&self) -> &'static str {
+    match self {
+      FeedFormat::Rss => "application/rss+xml",
+      FeedFormat::Atom => "application/atom+xml",
+      FeedFormat::Json => "application/feed+json",
+    }
+  }
 }
 
 pub fn config(cfg: &mut web::ServiceConfig) {
   cfg.service(
     web::scope("/feeds")
-      .route("/{type}/{name}.xml", web::get().to(get_feed))
-      .route("/all.xml", web::get().to(get_all_feed).wrap(cache_1hour()))
       .route(
-        "/local.xml",
+        "/{type}/{name}.{format:xml|atom|json}",
+        web::get().to(get_feed),
+      )
+      .route(
+        "/all.{format:xml|atom|json}",
+        web::get().to(get_all_feed).wrap(cache_1hour()),
+      )
+      .route(
+        "/local.{format:xml|atom|json}",
         web::get().to(get_local_feed).wrap(cache_1hour()),
-      ),
+      )
+      .route("/all", web::get().to(get_all_feed).wrap(cache_1hour()))
+      .route("/local", web::get().to(get_local_feed).wrap(cache_1hour()))
+      .route("/{type}/{name}", web::get().to(get_feed)),
   );
 }
 
@@ -111,36 +216,36 @@ fn sanitize_xml(input: String) -> String {
 
 #[tracing::instrument(skip_all)]
 async fn get_all_feed(
+  req: HttpRequest,
   info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  Ok(
-    get_feed_data(
-      &context,
-      ListingType::All,
-      info.sort_type()?,
-      info.get_limit(),
-      info.get_page(),
-    )
-    .await?,
+  let channel = get_feed_data(
+    &context,
+    ListingType::All,
+    info.sort_type()?,
+    info.get_limit(),
+    info.get_page(),
   )
+  .await?;
+  Ok(feed_response(&req, channel, &context)?)
 }
 
 #[tracing::instrument(skip_all)]
 async fn get_local_feed(
+  req: HttpRequest,
   info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  Ok(
-    get_feed_data(
-      &context,
-      ListingType::Local,
-      info.sort_type()?,
-      info.get_limit(),
-      info.get_page(),
-    )
-    .await?,
+  let channel = get_feed_data(
+    &context,
+    ListingType::Local,
+    info.sort_type()?,
+    info.get_limit(),
+    info.get_page(),
   )
+  .await?;
+  Ok(feed_response(&req, channel, &context)?)
 }
 
 #[tracing::instrument(skip_all)]
@@ -150,7 +255,7 @@ async fn get_feed_data(
   sort_type: SortType,
   limit: i64,
   page: i64,
-) -> LemmyResult<HttpResponse> {
+) -> LemmyResult<Channel> {
   let site_view = SiteView::read_local(&mut context.pool()).await?;
 
   check_private_instance(&None, &site_view.local_site)?;
@@ -179,12 +284,7 @@ async fn get_feed_data(
     channel.set_description(&site_desc);
   }
 
-  let rss = channel.to_string();
-  Ok(
-    HttpResponse::Ok()
-      .content_type("application/rss+xml")
-      .body(rss),
-  )
+  Ok(channel)
 }
 
 #[tracing::instrument(skip_all)]
@@ -201,6 +301,10 @@ async fn get_feed(
     "c" => RequestType::Community,
     "front" => RequestType::Front,
     "inbox" => RequestType::Inbox,
+    "tag" => RequestType::Hashtag,
+    "post" => RequestType::Post,
+    "saved" => RequestType::Saved,
+    "modlog" => RequestType::Modlog,
     _ => return Err(ErrorBadRequest(LemmyError::from(anyhow!("wrong_type")))),
   };
 
@@ -236,16 +340,29 @@ async fn get_feed(
       .await
     }
     RequestType::Inbox => get_feed_inbox(&context, &param).await,
+    RequestType::Hashtag => {
+      get_feed_hashtag(
+        &context,
+        &info.sort_type()?,
+        &info.get_limit(),
+        &info.get_page(),
+        &param,
+      )
+      .await
+    }
+    RequestType::Post => {
+      get_feed_post_comments(&context, &info.get_limit(), &info.get_page(), &param).await
+    }
+    RequestType::Saved => {
+      get_feed_saved(&context, &info.get_limit(), &info.get_page(), &param).await
+    }
+    RequestType::Modlog => {
+      get_feed_modlog(&context, &info.get_limit(), &info.get_page(), &param).await
+    }
   }
   .map_err(ErrorBadRequest)?;
 
-  let rss = builder.to_string();
-
-  Ok(
-    HttpResponse::Ok()
-      .content_type("application/rss+xml")
-      .body(rss),
-  )
+  Ok(feed_response(&req, builder, &context)?)
 }
 
 #[tracing::instrument(skip_all)]
@@ -331,6 +448,357 @@ async fn get_feed_community(
   Ok(channel)
 }
 
+#[tracing::instrument(skip_all)]
+async fn get_feed_hashtag(
+  context: &LemmyContext,
+  sort_type: &SortType,
+  limit: &i64,
+  page: &i64,
+  hashtag: &str,
+) -> LemmyResult<Channel> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+
+  check_private_instance(&None, &site_view.local_site)?;
+
+  let posts = PostQuery {
+    listing_type: (Some(ListingType::All)),
+    sort: (Some(*sort_type)),
+    hashtag: (Some(hashtag.to_string())),
+    limit: (Some(*limit)),
+    page: (Some(*page)),
+    ..Default::default()
+  }
+  .list(&site_view.site, &mut context.pool())
+  .await?;
+
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let items = create_post_items(posts, &protocol_and_hostname)?;
+  let channel = Channel {
+    namespaces: RSS_NAMESPACE.clone(),
+    title: format!("{} - #{}", sanitize_xml(site_view.site.name), hashtag),
+    link: format!("{protocol_and_hostname}/feeds/tag/{hashtag}.xml"),
+    items,
+    ..Default::default()
+  };
+
+  Ok(channel)
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_post_comments(
+  context: &LemmyContext,
+  limit: &i64,
+  page: &i64,
+  post_id: &str,
+) -> LemmyResult<Channel> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+
+  check_private_instance(&None, &site_view.local_site)?;
+
+  let post_id = PostId(
+    post_id
+      .parse()
+      .map_err(|_| LemmyErrorType::CouldntFindPost)?,
+  );
+  let post_view = PostView::read(&mut context.pool(), post_id, None, false)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindPost)?;
+  if post_view.community.visibility != CommunityVisibility::Public {
+    return Err(LemmyErrorType::CouldntFindPost.into());
+  }
+
+  let comments = CommentQuery {
+    post_id: (Some(post_id)),
+    sort: (Some(CommentSortType::New)),
+    limit: (Some(*limit)),
+    page: (Some(*page)),
+    ..Default::default()
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let items = create_comment_items(comments, &protocol_and_hostname)?;
+  let channel = Channel {
+    namespaces: RSS_NAMESPACE.clone(),
+    title: format!(
+      "{} - Comments on {}",
+      sanitize_xml(site_view.site.name),
+      sanitize_xml(post_view.post.name)
+    ),
+    link: format!("{protocol_and_hostname}/post/{}", post_id.0),
+    items,
+    ..Default::default()
+  };
+
+  Ok(channel)
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_saved(
+  context: &LemmyContext,
+  limit: &i64,
+  page: &i64,
+  jwt: &str,
+) -> LemmyResult<Channel> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let local_user = local_user_view_from_jwt(jwt, context).await?;
+
+  check_private_instance(&Some(local_user.clone()), &site_view.local_site)?;
+
+  // Posts and comments are listed together, the most recently saved first
+  let saved = PostSaved::list_with_comments(
+    &mut context.pool(),
+    local_user.person.id,
+    Some(*page),
+    Some(*limit),
+  )
+  .await?;
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let mut items = vec![];
+  for item in saved {
+    match item {
+      SavedItemId::Post(post_id) => {
+        let post = PostView::read(
+          &mut context.pool(),
+          post_id,
+          Some(&local_user.local_user),
+          false,
+        )
+        .await?;
+        items.append(&mut create_post_items(
+          post.into_iter().collect(),
+          &protocol_and_hostname,
+        )?);
+      }
+      SavedItemId::Comment(comment_id) => {
+        let comment = CommentView::read(
+          &mut context.pool(),
+          comment_id,
+          Some(&local_user.local_user),
+        )
+        .await?;
+        items.append(&mut create_comment_items(
+          comment.into_iter().collect(),
+          &protocol_and_hostname,
+        )?);
+      }
+    }
+  }
+  let channel = Channel {
+    namespaces: RSS_NAMESPACE.clone(),
+    title: format!("{} - Saved", sanitize_xml(site_view.site.name)),
+    link: local_user.person.actor_id.to_string(),
+    items,
+    ..Default::default()
+  };
+
+  Ok(channel)
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_feed_modlog(
+  context: &LemmyContext,
+  limit: &i64,
+  page: &i64,
+  community_name: &str,
+) -> LemmyResult<Channel> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let community = Community::read_from_name(&mut context.pool(), community_name, false)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+  if community.visibility != CommunityVisibility::Public {
+    return Err(LemmyErrorType::CouldntFindCommunity.into());
+  }
+
+  check_private_instance(&None, &site_view.local_site)?;
+  if !(1..=MODLOG_FEED_MAX_PAGE).contains(page) {
+    Err(LemmyErrorType::InvalidQuery)?
+  }
+
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let modlog_url = format!("{protocol_and_hostname}/modlog/{}", community.id.0);
+  // Each action type is listed separately, so only the newest entries of all lists make up a
+  // page. Go through the pages one by one, each continuing after the oldest entry of the previous
+  // page.
+  let mut entries = vec![];
+  let mut before = None;
+  for _ in 0..*page {
+    let params = ModlogListParams {
+      community_id: Some(community.id),
+      mod_person_id: None,
+      other_person_id: None,
+      post_id: None,
+      comment_id: None,
+      page: None,
+      limit: Some(*limit),
+      hide_modlog_names: site_view.local_site.hide_modlog_mod_names,
+      before,
+    };
+    entries = list_modlog_items(context, params, &modlog_url).await?;
+    match entries.last() {
+      Some(oldest) => before = Some(oldest.when),
+      None => break,
+    }
+  }
+
+  let channel = Channel {
+    namespaces: RSS_NAMESPACE.clone(),
+    title: format!(
+      "{} - Modlog of {}",
+      sanitize_xml(site_view.site.name),
+      community.name
+    ),
+    link: modlog_url,
+    items: entries.into_iter().map(ModlogItem::into_item).collect(),
+    ..Default::default()
+  };
+
+  Ok(channel)
+}
+
+/// Lists the newest entries of all modlog types which are included in the feed.
+async fn list_modlog_items(
+  context: &LemmyContext,
+  params: ModlogListParams,
+  modlog_url: &str,
+) -> LemmyResult<Vec<ModlogItem>> {
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let post_url = |post_id: PostId| format!("{protocol_and_hostname}/post/{}", post_id.0);
+  let guid = |action: &str, id: i32| format!("{modlog_url}#{action}-{id}");
+  let mut entries = vec![];
+
+  for v in ModRemovePostView::list(&mut context.pool(), params).await? {
+    let action = if v.mod_remove_post.removed {
+      "Removed"
+    } else {
+      "Restored"
+    };
+    entries.push(ModlogItem {
+      reason: v.mod_remove_post.reason,
+      moderator: v.moderator,
+      when: v.mod_remove_post.when_,
+      link: post_url(v.post.id),
+      guid: guid("remove_post", v.mod_remove_post.id),
+      title: format!("{action} post {}", v.post.name),
+    });
+  }
+  for v in ModLockPostView::list(&mut context.pool(), params).await? {
+    let action = if v.mod_lock_post.locked {
+      "Locked"
+    } else {
+      "Unlocked"
+    };
+    entries.push(ModlogItem {
+      moderator: v.moderator,
+      when: v.mod_lock_post.when_,
+      link: post_url(v.post.id),
+      guid: guid("lock_post", v.mod_lock_post.id),
+      title: format!("{action} post {}", v.post.name),
+      ..Default::default()
+    });
+  }
+  for v in ModFeaturePostView::list(&mut context.pool(), params).await? {
+    let action = if v.mod_feature_post.featured {
+      "Featured"
+    } else {
+      "Unfeatured"
+    };
+    entries.push(ModlogItem {
+      moderator: v.moderator,
+      when: v.mod_feature_post.when_,
+      link: post_url(v.post.id),
+      guid: guid("feature_post", v.mod_feature_post.id),
+      title: format!("{action} post {}", v.post.name),
+      ..Default::default()
+    });
+  }
+  for v in ModRemoveCommentView::list(&mut context.pool(), params).await? {
+    let action = if v.mod_remove_comment.removed {
+      "Removed"
+    } else {
+      "Restored"
+    };
+    entries.push(ModlogItem {
+      reason: v.mod_remove_comment.reason,
+      moderator: v.moderator,
+      when: v.mod_remove_comment.when_,
+      link: format!("{protocol_and_hostname}/comment/{}", v.comment.id),
+      guid: guid("remove_comment", v.mod_remove_comment.id),
+      title: format!(
+        "{action} comment by {} on {}",
+        v.commenter.name, v.post.name
+      ),
+    });
+  }
+  for v in ModBanFromCommunityView::list(&mut context.pool(), params).await? {
+    let action = if v.mod_ban_from_community.banned {
+      "Banned"
+    } else {
+      "Unbanned"
+    };
+    entries.push(ModlogItem {
+      reason: v.mod_ban_from_community.reason,
+      moderator: v.moderator,
+      when: v.mod_ban_from_community.when_,
+      link: v.banned_person.actor_id.to_string(),
+      guid: guid("ban_from_community", v.mod_ban_from_community.id),
+      title: format!("{action} {}", v.banned_person.name),
+    });
+  }
+  for v in ModAddCommunityView::list(&mut context.pool(), params).await? {
+    let action = if v.mod_add_community.removed {
+      "Removed"
+    } else {
+      "Appointed"
+    };
+    entries.push(ModlogItem {
+      moderator: v.moderator,
+      when: v.mod_add_community.when_,
+      link: v.modded_person.actor_id.to_string(),
+      guid: guid("add_community", v.mod_add_community.id),
+      title: format!("{action} {} as moderator", v.modded_person.name),
+      ..Default::default()
+    });
+  }
+  for v in ModTransferCommunityView::list(&mut context.pool(), params).await? {
+    entries.push(ModlogItem {
+      moderator: v.moderator,
+      when: v.mod_transfer_community.when_,
+      link: v.modded_person.actor_id.to_string(),
+      guid: guid("transfer_community", v.mod_transfer_community.id),
+      title: format!("Transferred community to {}", v.modded_person.name),
+      ..Default::default()
+    });
+  }
+  for v in ModAutomodActionView::list(&mut context.pool(), params).await? {
+    let link = match &v.comment {
+      Some(comment) => format!("{protocol_and_hostname}/comment/{}", comment.id),
+      None => post_url(v.post.id),
+    };
+    entries.push(ModlogItem {
+      reason: v.mod_automod_action.reason,
+      moderator: v.moderator,
+      when: v.mod_automod_action.when_,
+      link,
+      guid: guid("automod_action", v.mod_automod_action.id),
+      title: format!(
+        "Automod action {} on post {}",
+        v.mod_automod_action.action, v.post.name
+      ),
+    });
+  }
+
+  entries.sort_by_key(|e| Reverse(e.when));
+  entries.truncate(
+    params
+      .limit
+      .and_then(|l| usize::try_from(l).ok())
+      .unwrap_or_default(),
+  );
+  Ok(entries)
+}
+
 #[tracing::instrument(skip_all)]
 async fn get_feed_front(
   context: &LemmyContext,
@@ -434,6 +902,7 @@ fn create_reply_and_mention_items(
     .map(|r| {
       let reply_url = format!("{}/comment/{}", protocol_and_hostname, r.comment.id);
       build_item(
+        &format!("Reply from {}", r.creator.name),
         &r.creator.name,
         &r.comment.published,
         &reply_url,
@@ -448,6 +917,7 @@ fn create_reply_and_mention_items(
     .map(|m| {
       let mention_url = format!("{}/comment/{}", protocol_and_hostname, m.comment.id);
       build_item(
+        &format!("Reply from {}", m.creator.name),
         &m.creator.name,
         &m.comment.published,
         &mention_url,
@@ -461,8 +931,68 @@ fn create_reply_and_mention_items(
   Ok(reply_items)
 }
 
+/// A moderation action, which is shown as an item of the modlog feed.
+#[derive(Default)]
+struct ModlogItem {
+  /// Modlog entries don't have their own page, so the guid only identifies the action.
+  guid: String,
+  title: String,
+  reason: Option<String>,
+  /// None if the site hides moderator names.
+  moderator: Option<Person>,
+  when: DateTime<Utc>,
+  /// The post, comment or person affected by the action.
+  link: String,
+}
+
+impl ModlogItem {
+  fn into_item(self) -> Item {
+    let dublin_core_ext = self.moderator.map(|m| DublinCoreExtension {
+      creators: vec![m.actor_id.to_string()],
+      ..DublinCoreExtension::default()
+    });
+    Item {
+      title: Some(sanitize_html(sanitize_xml(self.title).as_str())),
+      pub_date: Some(self.when.to_rfc2822()),
+      guid: Some(Guid {
+        permalink: false,
+        value: self.guid,
+      }),
+      description: self
+        .reason
+        .map(|r| sanitize_xml(format!("Reason: {}", sanitize_html(&r)))),
+      dublin_core_ext,
+      link: Some(self.link),
+      ..Default::default()
+    }
+  }
+}
+
+#[tracing::instrument(skip_all)]
+fn create_comment_items(
+  comments: Vec<CommentView>,
+  protocol_and_hostname: &str,
+) -> LemmyResult<Vec<Item>> {
+  comments
+    .iter()
+    .filter(|c| !c.comment.deleted && !c.comment.removed)
+    .map(|c| {
+      let comment_url = format!("{}/comment/{}", protocol_and_hostname, c.comment.id);
+      build_item(
+        &format!("Comment from {}", c.creator.name),
+        &c.creator.name,
+        &c.comment.published,
+        &comment_url,
+        &c.comment.content,
+        protocol_and_hostname,
+      )
+    })
+    .collect()
+}
+
 #[tracing::instrument(skip_all)]
 fn build_item(
+  title: &str,
   creator_name: &str,
   published: &DateTime<Utc>,
   url: &str,
@@ -476,12 +1006,17 @@ fn build_item(
     value: url.to_owned(),
   });
   let description = Some(markdown_to_html(content));
+  let dublin_core_ext = Some(DublinCoreExtension {
+    creators: vec![author_url.clone()],
+    ..DublinCoreExtension::default()
+  });
 
   Ok(Item {
-    title: Some(format!("Reply from {creator_name}")),
+    title: Some(title.to_owned()),
     author: Some(format!(
       "/u/{creator_name} <a href=\"{author_url}\">(link)</a>"
     )),
+    dublin_core_ext,
     pub_date: Some(published.to_rfc2822()),
     comments: Some(url.to_owned()),
     link: Some(url.to_owned()),
@@ -578,3 +1113,324 @@ fn create_post_items(posts: Vec<PostView>, protocol_and_hostname: &str) -> Lemmy
 
   Ok(items)
 }
+
+/// Renders the channel in the format requested by the client. Conditional requests are answered
+/// with `304 Not Modified` if the feed didn't change since the client last fetched it.
+fn feed_response(
+  req: &HttpRequest,
+  channel: Channel,
+  context: &LemmyContext,
+) -> LemmyResult<HttpResponse> {
+  let format = FeedFormat::from_request(req);
+  let feed_url = format!(
+    "{}{}",
+    context.settings().get_protocol_and_hostname(),
+    req.path()
+  );
+  let body = match format {
+    FeedFormat::Rss => channel.to_string(),
+    FeedFormat::Atom => channel_to_atom(&channel, &feed_url).to_string(),
+    FeedFormat::Json => serde_json::to_string(&JsonFeed::from_channel(&channel, &feed_url))?,
+  };
+
+  let mut hasher = DefaultHasher::new();
+  body.hash(&mut hasher);
+  let etag = EntityTag::new_strong(format!("{:x}", hasher.finish()));
+  let last_modified = last_modified(&channel).map(SystemTime::from);
+
+  // If-Modified-Since is only used by clients which don't know the ETag
+  let not_modified = if req.headers().contains_key(IF_NONE_MATCH) {
+    match IfNoneMatch::parse(req) {
+      Ok(IfNoneMatch::Any) => true,
+      Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|t| t.weak_eq(&etag)),
+      Err(_) => false,
+    }
+  } else if let (Ok(since), Some(last_modified)) = (IfModifiedSince::parse(req), last_modified) {
+    SystemTime::from(since.0) >= last_modified
+  } else {
+    false
+  };
+
+  let mut response = if not_modified {
+    HttpResponse::NotModified()
+  } else {
+    HttpResponse::Ok()
+  };
+  response
+    .insert_header(ETag(etag))
+    .insert_header((VARY, "Accept"));
+  if let Some(last_modified) = last_modified {
+    response.insert_header(LastModified(HttpDate::from(last_modified)));
+  }
+  if not_modified {
+    Ok(response.finish())
+  } else {
+    Ok(response.content_type(format.content_type()).body(body))
+  }
+}
+
+/// The publish time of the newest item, which is also the last time the feed changed.
+fn last_modified(channel: &Channel) -> Option<DateTime<FixedOffset>> {
+  channel.items.iter().filter_map(item_published).max()
+}
+
+fn item_published(item: &Item) -> Option<DateTime<FixedOffset>> {
+  DateTime::parse_from_rfc2822(item.pub_date.as_deref()?).ok()
+}
+
+/// The name and profile url of the item author.
+fn item_author(item: &Item) -> Option<(String, String)> {
+  let url = item.dublin_core_ext.as_ref()?.creators.first()?;
+  let name = url.rsplit('/').next().unwrap_or(url);
+  Some((name.to_string(), url.clone()))
+}
+
+fn item_thumbnail(item: &Item) -> Option<String> {
+  item
+    .extensions
+    .get("media")?
+    .get("content")?
+    .first()?
+    .attrs
+    .get("url")
+    .cloned()
+}
+
+fn channel_to_atom(channel: &Channel, feed_url: &str) -> Feed {
+  let entries = channel
+    .items
+    .iter()
+    .map(|item| {
+      let url = item.link.clone().unwrap_or_default();
+      let published = item_published(item);
+      let mut links = vec![Link {
+        href: url.clone(),
+        ..Default::default()
+      }];
+      if let Some(enclosure) = &item.enclosure {
+        links.push(Link {
+          href: enclosure.url.clone(),
+          rel: "enclosure".to_string(),
+          mime_type: Some(enclosure.mime_type.clone()),
+          ..Default::default()
+        });
+      }
+      Entry {
+        title: Text::plain(item.title.clone().unwrap_or_default()),
+        id: item.guid.as_ref().map_or(url, |g| g.value.clone()),
+        updated: published.unwrap_or_default(),
+        published,
+        authors: item_author(item)
+          .map(|(name, uri)| atom_syndication::Person {
+            name,
+            email: None,
+            uri: Some(uri),
+          })
+          .into_iter()
+          .collect(),
+        links,
+        content: item.description.clone().map(|value| Content {
+          value: Some(value),
+          content_type: Some("html".to_string()),
+          ..Default::default()
+        }),
+        ..Default::default()
+      }
+    })
+    .collect();
+
+  Feed {
+    title: Text::plain(channel.title.clone()),
+    id: feed_url.to_string(),
+    updated: last_modified(channel).unwrap_or_else(|| Utc::now().into()),
+    links: vec![
+      Link {
+        href: channel.link.clone(),
+        ..Default::default()
+      },
+      Link {
+        href: feed_url.to_string(),
+        rel: "self".to_string(),
+        mime_type: Some(FeedFormat::Atom.content_type().to_string()),
+        ..Default::default()
+      },
+    ],
+    subtitle: (!channel.description.is_empty()).then(|| Text::html(channel.description.clone())),
+    entries,
+    ..Default::default()
+  }
+}
+
+/// A feed in the [JSON Feed 1.1](https://www.jsonfeed.org/version/1.1/) format.
+#[skip_serializing_none]
+#[derive(Serialize)]
+struct JsonFeed {
+  version: &'static str,
+  title: String,
+  home_page_url: String,
+  feed_url: String,
+  description: Option<String>,
+  items: Vec<JsonFeedItem>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize)]
+struct JsonFeedItem {
+  id: String,
+  url: Option<String>,
+  title: Option<String>,
+  content_html: String,
+  image: Option<String>,
+  date_published: Option<DateTime<FixedOffset>>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  authors: Vec<JsonFeedAuthor>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  attachments: Vec<JsonFeedAttachment>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor {
+  name: String,
+  url: String,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAttachment {
+  url: String,
+  mime_type: String,
+}
+
+impl JsonFeed {
+  fn from_channel(channel: &Channel, feed_url: &str) -> Self {
+    let items = channel
+      .items
+      .iter()
+      .map(|item| JsonFeedItem {
+        id: item
+          .guid
+          .as_ref()
+          .map(|g| g.value.clone())
+          .or_else(|| item.link.clone())
+          .unwrap_or_default(),
+        url: item.link.clone(),
+        title: item.title.clone(),
+        content_html: item.description.clone().unwrap_or_default(),
+        image: item_thumbnail(item),
+        date_published: item_published(item),
+        authors: item_author(item)
+          .map(|(name, url)| JsonFeedAuthor { name, url })
+          .into_iter()
+          .collect(),
+        attachments: item
+          .enclosure
+          .iter()
+          .map(|e| JsonFeedAttachment {
+            url: e.url.clone(),
+            mime_type: e.mime_type.clone(),
+          })
+          .collect(),
+      })
+      .collect();
+
+    JsonFeed {
+      version: "https://jsonfeed.org/version/1.1",
+      title: channel.title.clone(),
+      home_page_url: channel.link.clone(),
+      feed_url: feed_url.to_string(),
+      description: (!channel.description.is_empty()).then(|| channel.description.clone()),
+      items,
+    }
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use super::*;
+  use actix_web::http::header::{q, QualityItem};
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_format_from_accept() {
+    let accept = Accept(vec![
+      QualityItem::new("application/rss+xml".parse().unwrap(), q(0.5)),
+      QualityItem::max("application/atom+xml".parse().unwrap()),
+    ]);
+    assert_eq!(FeedFormat::Atom, FeedFormat::from_accept(&accept));
+    let accept = Accept(vec![QualityItem::max(
+      "application/feed+json".parse().unwrap(),
+    )]);
+    assert_eq!(FeedFormat::Json, FeedFormat::from_accept(&accept));
+    let accept = Accept(vec![QualityItem::max("text/html".parse().unwrap())]);
+    assert_eq!(FeedFormat::Rss, FeedFormat::from_accept(&accept));
+  }
+
+  #[test]
+  fn test_convert_channel() -> LemmyResult<()> {
+    let published = DateTime::parse_from_rfc3339("2024-11-02T10:00:00Z")?.to_utc();
+    let item = build_item(
+      "Comment from alice",
+      "alice",
+      &published,
+      "https://lemmy.tld/comment/1",
+      "hello *world*",
+      "https://lemmy.tld",
+    )?;
+    let channel = Channel {
+      title: "Lemmy - Comments on test".to_string(),
+      link: "https://lemmy.tld/post/1".to_string(),
+      items: vec![item],
+      ..Default::default()
+    };
+    assert_eq!(Some(published.fixed_offset()), last_modified(&channel));
+
+    let atom = channel_to_atom(&channel, "https://lemmy.tld/feeds/post/1.atom");
+    assert_eq!("https://lemmy.tld/feeds/post/1.atom", atom.id);
+    assert_eq!(published, atom.updated);
+    let entry = &atom.entries[0];
+    assert_eq!("https://lemmy.tld/comment/1", entry.id);
+    assert_eq!("Comment from alice", entry.title.value);
+    assert_eq!("alice", entry.authors[0].name);
+    assert_eq!(
+      Some("https://lemmy.tld/u/alice"),
+      entry.authors[0].uri.as_deref()
+    );
+
+    let json = serde_json::to_value(JsonFeed::from_channel(
+      &channel,
+      "https://lemmy.tld/feeds/post/1.json",
+    ))?;
+    assert_eq!("https://jsonfeed.org/version/1.1", json["version"]);
+    assert_eq!("https://lemmy.tld/comment/1", json["items"][0]["id"]);
+    assert_eq!("2024-11-02T10:00:00Z", json["items"][0]["date_published"]);
+    assert_eq!("alice", json["items"][0]["authors"][0]["name"]);
+    assert!(json.get("description").is_none());
+    Ok(())
+  }
+
+  #[test]
+  fn test_modlog_item() {
+    let item = ModlogItem {
+      guid: "https://lemmy.tld/modlog/1#remove_post-3".to_string(),
+      title: "Removed post <b>spam</b>".to_string(),
+      reason: Some("rule 1".to_string()),
+      moderator: None,
+      when: Utc::now(),
+      link: "https://lemmy.tld/post/2".to_string(),
+    }
+    .into_item();
+    assert_eq!(
+      Some("Removed post &lt;b>spam&lt;/b>"),
+      item.title.as_deref()
+    );
+    assert_eq!(Some("Reason: rule 1"), item.description.as_deref());
+    assert_eq!(
+      Some("https://lemmy.tld/modlog/1#remove_post-3"),
+      item.guid.as_ref().map(|g| g.value.as_str())
+    );
+    // Hidden moderators don't show up as author
+    assert!(item_author(&item).is_none());
+  }
+}
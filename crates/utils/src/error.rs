@@ -181,6 +181,39 @@ pub enum LemmyErrorType {
   Unknown(String),
   CantDeleteSite,
   UrlLengthOverflow,
+  CouldntFindAutomodRule,
+  AutomodRuleWithoutCondition,
+  AutomodActionNeedsMessage,
+  CouldntFindRemovalReason,
+  CouldntFindSlurFilterList,
+  SlurFilterListAlreadyExists,
+  InvalidSlowModeSeconds,
+  /// Slow mode is enabled in the community. Contains the number of seconds until the user can
+  /// post again.
+  SlowMode(i32),
+  CantBackfillLocalCommunity,
+  CommunityBackfillAlreadyRunning,
+  CouldntFindCommunityBackfill,
+  CouldntFindFederationQueue,
+  CouldntFindRelay,
+  RelayFollowNotAccepted,
+  CannotReceiveRelayFollow,
+  InvalidMoveTarget,
+  MoveTargetMissingAlias,
+  CantMigrateRemoteCommunity,
+  InstanceIsLimited,
+  CouldntFindPrivateMessageConversation,
+  TooManyRecipients,
+  InvalidKeyEnvelope,
+  InvalidDeviceKey,
+  PrivateMessagesNotAllowed,
+  InvalidDuplicatePostWindow,
+  /// The url was already posted to the community within its duplicate post window. Contains the
+  /// id of the existing post.
+  DuplicatePost(i32),
+  InvalidUrlCleaningRule,
+  TooManyGalleryImages,
+  CaptionLengthOverflow,
 }
 
 cfg_if! {
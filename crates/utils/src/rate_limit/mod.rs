@@ -1,8 +1,11 @@
 use crate::error::{LemmyError, LemmyErrorType};
-use actix_web::dev::{ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{
+  dev::{ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform},
+  HttpMessage,
+};
 use enum_map::{enum_map, EnumMap};
 use futures::future::{ok, Ready};
-pub use rate_limiter::{ActionType, BucketConfig};
+pub use rate_limiter::{ActionType, BucketConfig, ThrottledUser};
 use rate_limiter::{InstantSecs, RateLimitState};
 use std::{
   future::Future,
@@ -12,11 +15,16 @@ use std::{
   str::FromStr,
   sync::{Arc, Mutex},
   task::{Context, Poll},
-  time::Duration,
+  time::{Duration, SystemTime},
 };
 
 pub mod rate_limiter;
 
+/// Request extension with the id of the authenticated local user. If present, the request is
+/// rate limited per user, in addition to a looser limit per IP address.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitUserId(pub i32);
+
 #[derive(Debug, Clone)]
 pub struct RateLimitChecker {
   state: Arc<Mutex<RateLimitState>>,
@@ -59,6 +67,25 @@ impl RateLimitCell {
       .set_config(config);
   }
 
+  /// Returns the local users which were throttled within the last day, by local user id, with the
+  /// time when they were last throttled.
+  pub fn throttled_users(&self) -> Vec<(i32, ThrottledUser, SystemTime)> {
+    let now = InstantSecs::now();
+    let system_now = SystemTime::now();
+    self
+      .state
+      .lock()
+      .expect("Failed to lock rate limit mutex for reading")
+      .throttled_users()
+      .iter()
+      .map(|(local_user_id, throttled)| {
+        let secs_ago = Duration::from_secs(throttled.last_throttled.secs_until(now).into());
+        let last_throttled = system_now.checked_sub(secs_ago).unwrap_or(system_now);
+        (*local_user_id, throttled.clone(), last_throttled)
+      })
+      .collect()
+  }
+
   pub fn message(&self) -> RateLimitChecker {
     self.new_checker(ActionType::Message)
   }
@@ -87,6 +114,18 @@ impl RateLimitCell {
     self.new_checker(ActionType::ImportUserSettings)
   }
 
+  pub fn vote(&self) -> RateLimitChecker {
+    self.new_checker(ActionType::Vote)
+  }
+
+  pub fn report(&self) -> RateLimitChecker {
+    self.new_checker(ActionType::Report)
+  }
+
+  pub fn private_message(&self) -> RateLimitChecker {
+    self.new_checker(ActionType::PrivateMessage)
+  }
+
   fn new_checker(&self, action_type: ActionType) -> RateLimitChecker {
     RateLimitChecker {
       state: self.state.clone(),
@@ -124,6 +163,18 @@ impl RateLimitCell {
         capacity: 1,
         secs_to_refill: 24 * 60 * 60,
       },
+      ActionType::Vote => BucketConfig {
+        capacity: 120,
+        secs_to_refill: 60,
+      },
+      ActionType::Report => BucketConfig {
+        capacity: 20,
+        secs_to_refill: 600,
+      },
+      ActionType::PrivateMessage => BucketConfig {
+        capacity: 30,
+        secs_to_refill: 600,
+      },
     })
   }
 }
@@ -135,7 +186,7 @@ pub struct RateLimitedMiddleware<S> {
 
 impl RateLimitChecker {
   /// Returns true if the request passed the rate limit, false if it failed and should be rejected.
-  pub fn check(self, ip_addr: IpAddr) -> bool {
+  pub fn check(self, ip_addr: IpAddr, local_user_id: Option<i32>) -> bool {
     // Does not need to be blocking because the RwLock in settings never held across await points,
     // and the operation here locks only long enough to clone
     let mut state = self
@@ -143,7 +194,7 @@ impl RateLimitChecker {
       .lock()
       .expect("Failed to lock rate limit mutex for reading");
 
-    state.check(self.action_type, ip_addr, InstantSecs::now())
+    state.check(self.action_type, ip_addr, local_user_id, InstantSecs::now())
   }
 }
 
@@ -183,12 +234,13 @@ where
 
   fn call(&self, req: ServiceRequest) -> Self::Future {
     let ip_addr = get_ip(&req.connection_info());
+    let local_user_id = req.extensions().get::<RateLimitUserId>().map(|u| u.0);
 
     let checker = self.checker.clone();
     let service = self.service.clone();
 
     Box::pin(async move {
-      if checker.check(ip_addr) {
+      if checker.check(ip_addr, local_user_id) {
         service.call(req).await
       } else {
         let (http_req, _) = req.into_parts();
@@ -201,7 +253,7 @@ where
   }
 }
 
-fn get_ip(conn_info: &ConnectionInfo) -> IpAddr {
+pub fn get_ip(conn_info: &ConnectionInfo) -> IpAddr {
   conn_info
     .realip_remote_addr()
     .and_then(parse_ip)
@@ -25,6 +25,11 @@ impl InstantSecs {
         .expect("server has been running for over 136 years"),
     }
   }
+
+  /// Seconds which passed between `self` and `now`
+  pub fn secs_until(self, now: InstantSecs) -> u32 {
+    now.secs.saturating_sub(self.secs)
+  }
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
@@ -75,6 +80,9 @@ pub enum ActionType {
   Comment,
   Search,
   ImportUserSettings,
+  Vote,
+  Report,
+  PrivateMessage,
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -233,7 +241,22 @@ impl<C: Default> RateLimitedGroup<C> {
   }
 }
 
-/// Rate limiting based on rate type and IP addr
+/// How often an authenticated user was rejected by the rate limiter.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ThrottledUser {
+  /// Number of rejected requests for each action type
+  pub counts: EnumMap<ActionType, u32>,
+  pub last_throttled: InstantSecs,
+}
+
+/// Throttling statistics are kept for one day after the last rejected request.
+const THROTTLED_USER_RETENTION_SECS: u32 = 24 * 60 * 60;
+
+/// Authenticated requests from one IP address can use this many times the limits of a single
+/// user, as many users may share an address (e.g. carrier-grade NAT).
+const AUTHENTICATED_IP_CAPACITY_FACTOR: u32 = 10;
+
+/// Rate limiting based on rate type and IP addr, or local user for authenticated requests
 #[derive(PartialEq, Debug, Clone)]
 pub struct RateLimitState {
   /// Each individual IPv4 address gets one `RateLimitedGroup`.
@@ -246,6 +269,16 @@ pub struct RateLimitState {
   /// first 64 bits. It could be as low as 48 bits for some networks, which is the reason for 48
   /// and 56 bit address groups.
   ipv6_buckets: Map<[u8; 6], Map<u8, Map<u8, ()>>>,
+  /// Authenticated requests are limited per local user, so that users can't get around the limits
+  /// by switching addresses.
+  user_buckets: Map<i32, ()>,
+  /// Authenticated requests are also limited per IP address, so that creating more accounts
+  /// doesn't multiply the limits. These buckets have a larger capacity than those for anonymous
+  /// requests, see [AUTHENTICATED_IP_CAPACITY_FACTOR].
+  user_ipv4_buckets: Map<Ipv4Addr, ()>,
+  user_ipv6_buckets: Map<[u8; 6], Map<u8, Map<u8, ()>>>,
+  /// Keyed by local user id
+  throttled_users: HashMap<i32, ThrottledUser>,
   /// This stores a `BucketConfig` for each `ActionType`. `EnumMap` makes it impossible to have a
   /// missing `BucketConfig`.
   bucket_configs: EnumMap<ActionType, BucketConfig>,
@@ -256,33 +289,73 @@ impl RateLimitState {
     RateLimitState {
       ipv4_buckets: HashMap::new(),
       ipv6_buckets: HashMap::new(),
+      user_buckets: HashMap::new(),
+      user_ipv4_buckets: HashMap::new(),
+      user_ipv6_buckets: HashMap::new(),
+      throttled_users: HashMap::new(),
       bucket_configs,
     }
   }
 
   /// Rate limiting Algorithm described here: https://stackoverflow.com/a/668327/1655478
   ///
+  /// Requests with a `local_user_id` are checked against the buckets of that user and, with a
+  /// larger capacity, those of the IP address. All others only against the buckets of the IP
+  /// address.
+  ///
   /// Returns true if the request passed the rate limit, false if it failed and should be rejected.
-  pub fn check(&mut self, action_type: ActionType, ip: IpAddr, now: InstantSecs) -> bool {
-    let result = match ip {
-      IpAddr::V4(ipv4) => {
-        self
-          .ipv4_buckets
-          .check(action_type, now, self.bucket_configs, (1, ()), (ipv4, ()))
-      }
-
-      IpAddr::V6(ipv6) => {
-        let (key_48, key_56, key_64) = split_ipv6(ipv6);
-        self.ipv6_buckets.check(
-          action_type,
-          now,
-          self.bucket_configs,
-          (16, (4, (1, ()))),
-          (key_48, (key_56, (key_64, ()))),
-        )
+  pub fn check(
+    &mut self,
+    action_type: ActionType,
+    ip: IpAddr,
+    local_user_id: Option<i32>,
+    now: InstantSecs,
+  ) -> bool {
+    if let Some(local_user_id) = local_user_id {
+      let user_passes = self.user_buckets.check(
+        action_type,
+        now,
+        self.bucket_configs,
+        (1, ()),
+        (local_user_id, ()),
+      );
+      let ip_passes = check_ip(
+        &mut self.user_ipv4_buckets,
+        &mut self.user_ipv6_buckets,
+        action_type,
+        ip,
+        now,
+        self.bucket_configs,
+        AUTHENTICATED_IP_CAPACITY_FACTOR,
+      );
+      let result = user_passes && ip_passes;
+      if !result {
+        debug!("Rate limited local user: {local_user_id}, IP: {ip}, type: {action_type:?}");
+        let throttled = self
+          .throttled_users
+          .entry(local_user_id)
+          .or_insert(ThrottledUser {
+            counts: EnumMap::default(),
+            last_throttled: now,
+          });
+        #[allow(clippy::indexing_slicing)]
+        {
+          throttled.counts[action_type] = throttled.counts[action_type].saturating_add(1);
+        }
+        throttled.last_throttled = now;
       }
-    };
+      return result;
+    }
 
+    let result = check_ip(
+      &mut self.ipv4_buckets,
+      &mut self.ipv6_buckets,
+      action_type,
+      ip,
+      now,
+      self.bucket_configs,
+      1,
+    );
     if !result {
       debug!("Rate limited IP: {ip}, type: {action_type:?}");
     }
@@ -298,6 +371,23 @@ impl RateLimitState {
     self
       .ipv6_buckets
       .remove_full_buckets(now, self.bucket_configs);
+    self
+      .user_buckets
+      .remove_full_buckets(now, self.bucket_configs);
+    self
+      .user_ipv4_buckets
+      .remove_full_buckets(now, self.bucket_configs);
+    self
+      .user_ipv6_buckets
+      .remove_full_buckets(now, self.bucket_configs);
+    self.throttled_users.retain(|_, throttled| {
+      throttled.last_throttled.secs_until(now) < THROTTLED_USER_RETENTION_SECS
+    });
+  }
+
+  /// Local users which were rejected by the rate limiter within the last day
+  pub fn throttled_users(&self) -> &HashMap<i32, ThrottledUser> {
+    &self.throttled_users
   }
 
   pub fn set_config(&mut self, new_configs: EnumMap<ActionType, BucketConfig>) {
@@ -305,6 +395,37 @@ impl RateLimitState {
   }
 }
 
+/// Checks the buckets of the IP address, with their capacities multiplied by `capacity_factor`.
+fn check_ip(
+  ipv4_buckets: &mut Map<Ipv4Addr, ()>,
+  ipv6_buckets: &mut Map<[u8; 6], Map<u8, Map<u8, ()>>>,
+  action_type: ActionType,
+  ip: IpAddr,
+  now: InstantSecs,
+  configs: EnumMap<ActionType, BucketConfig>,
+  capacity_factor: u32,
+) -> bool {
+  match ip {
+    IpAddr::V4(ipv4) => {
+      ipv4_buckets.check(action_type, now, configs, (capacity_factor, ()), (ipv4, ()))
+    }
+
+    IpAddr::V6(ipv6) => {
+      let (key_48, key_56, key_64) = split_ipv6(ipv6);
+      ipv6_buckets.check(
+        action_type,
+        now,
+        configs,
+        (
+          16 * capacity_factor,
+          (4 * capacity_factor, (capacity_factor, ())),
+        ),
+        (key_48, (key_56, (key_64, ()))),
+      )
+    }
+  }
+}
+
 fn split_ipv6(ip: Ipv6Addr) -> ([u8; 6], u8, u8) {
   let [a0, a1, a2, a3, a4, a5, b, c, ..] = ip.octets();
   ([a0, a1, a2, a3, a4, a5], b, c)
@@ -317,6 +438,7 @@ mod tests {
 
   use super::{ActionType, BucketConfig, InstantSecs, RateLimitState, RateLimitedGroup};
   use pretty_assertions::assert_eq;
+  use std::collections::HashMap;
 
   #[test]
   fn test_split_ipv6() {
@@ -355,8 +477,8 @@ mod tests {
     ];
     for ip in ips {
       let ip = ip.parse().unwrap();
-      let message_passed = rate_limiter.check(ActionType::Message, ip, now);
-      let post_passed = rate_limiter.check(ActionType::Post, ip, now);
+      let message_passed = rate_limiter.check(ActionType::Message, ip, None, now);
+      let post_passed = rate_limiter.check(ActionType::Post, ip, None, now);
       assert!(message_passed);
       assert!(post_passed);
     }
@@ -407,13 +529,17 @@ mod tests {
           }
         )]
         .into(),
+        user_buckets: HashMap::new(),
+        user_ipv4_buckets: HashMap::new(),
+        user_ipv6_buckets: HashMap::new(),
+        throttled_users: HashMap::new(),
       }
     );
 
     // Do 2 `Message` actions for 1 IP address and expect only the 2nd one to fail
     for expected_to_pass in [true, false] {
       let ip = "1:2:3:0400::".parse().unwrap();
-      let passed = rate_limiter.check(ActionType::Message, ip, now);
+      let passed = rate_limiter.check(ActionType::Message, ip, None, now);
       assert_eq!(passed, expected_to_pass);
     }
 
@@ -426,8 +552,8 @@ mod tests {
     // `remove full buckets` should not remove empty buckets
     let ip = "1.1.1.1".parse().unwrap();
     // empty the bucket with 2 requests
-    assert!(rate_limiter.check(ActionType::Post, ip, now));
-    assert!(rate_limiter.check(ActionType::Post, ip, now));
+    assert!(rate_limiter.check(ActionType::Post, ip, None, now));
+    assert!(rate_limiter.check(ActionType::Post, ip, None, now));
 
     rate_limiter.remove_full_buckets(now);
     assert!(!rate_limiter.ipv4_buckets.is_empty());
@@ -436,9 +562,59 @@ mod tests {
     now.secs += 2;
     let ip = "1.1.1.1".parse().unwrap();
     // Only make one request, so bucket still has 1 token
-    assert!(rate_limiter.check(ActionType::Post, ip, now));
+    assert!(rate_limiter.check(ActionType::Post, ip, None, now));
 
     rate_limiter.remove_full_buckets(now);
     assert!(!rate_limiter.ipv4_buckets.is_empty());
   }
+
+  #[test]
+  fn test_rate_limiter_local_user() {
+    let bucket_configs = enum_map::enum_map! {
+      _ => BucketConfig {
+        capacity: 1,
+        secs_to_refill: 1,
+      },
+    };
+    let mut rate_limiter = RateLimitState::new(bucket_configs);
+    let mut now = InstantSecs::now();
+    let ip = "1.1.1.1".parse().unwrap();
+
+    // Users sharing an IP address have separate limits, which also don't use up the limit of the
+    // address for anonymous requests
+    assert!(rate_limiter.check(ActionType::Vote, ip, Some(1), now));
+    assert!(rate_limiter.check(ActionType::Vote, ip, Some(2), now));
+    assert!(rate_limiter.check(ActionType::Vote, ip, None, now));
+    assert!(rate_limiter.ipv4_buckets.len() == 1);
+    assert!(!rate_limiter.check(ActionType::Vote, ip, Some(1), now));
+    assert!(!rate_limiter.check(ActionType::Vote, ip, Some(1), now));
+    assert!(!rate_limiter.check(ActionType::Vote, ip, None, now));
+    // Unauthenticated requests are not recorded as throttled users
+    let throttled = rate_limiter.throttled_users();
+    assert_eq!(1, throttled.len());
+    assert_eq!(2, throttled[&1].counts[ActionType::Vote]);
+    assert_eq!(0, throttled[&1].counts[ActionType::Report]);
+
+    // Switching to another address doesn't help
+    let other_ip = "2.2.2.2".parse().unwrap();
+    assert!(!rate_limiter.check(ActionType::Vote, other_ip, Some(1), now));
+
+    // Many users behind the same address together only get a larger limit
+    let shared_ip = "3.3.3.3".parse().unwrap();
+    let authenticated_ip_capacity = i32::try_from(super::AUTHENTICATED_IP_CAPACITY_FACTOR).unwrap();
+    for user in 100..100 + authenticated_ip_capacity {
+      assert!(rate_limiter.check(ActionType::Vote, shared_ip, Some(user), now));
+    }
+    assert!(!rate_limiter.check(ActionType::Vote, shared_ip, Some(200), now));
+
+    now.secs += 2;
+    rate_limiter.remove_full_buckets(now);
+    assert!(rate_limiter.user_buckets.is_empty());
+    assert!(rate_limiter.user_ipv4_buckets.is_empty());
+    assert_eq!(2, rate_limiter.throttled_users().len());
+
+    now.secs += super::THROTTLED_USER_RETENTION_SECS;
+    rate_limiter.remove_full_buckets(now);
+    assert!(rate_limiter.throttled_users().is_empty());
+  }
 }
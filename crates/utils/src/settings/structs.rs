@@ -45,6 +45,10 @@ pub struct Settings {
   pub opentelemetry_url: Option<Url>,
   #[default(Default::default())]
   pub federation: FederationWorkerConfig,
+  /// Periodically check if the urls of link posts are still reachable. Disabled if not set.
+  #[default(None)]
+  #[doku(example = "Some(Default::default())")]
+  pub link_check: Option<LinkCheckConfig>,
   // Prometheus configuration.
   #[default(None)]
   #[doku(example = "Some(Default::default())")]
@@ -234,6 +238,25 @@ pub struct PrometheusConfig {
   pub port: i32,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinkCheckConfig {
+  /// Check the url of each link post again after this many days.
+  #[default(7)]
+  #[doku(example = "7")]
+  pub recheck_interval_days: i32,
+  /// Maximum number of urls to check every hour.
+  #[default(100)]
+  #[doku(example = "100")]
+  pub urls_per_hour: i64,
+  /// Snapshot working links with an archiving service, by requesting this url with the link
+  /// appended. The url which the service redirects to is stored as archived version of the link.
+  /// Disabled if not set.
+  #[default(None)]
+  #[doku(example = "https://web.archive.org/save/")]
+  pub archive_service: Option<Url>,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
 #[serde(default)]
 // named federation"worker"config to disambiguate from the activitypub library configuration
@@ -243,4 +266,8 @@ pub struct FederationWorkerConfig {
   /// per second) and if a receiving instance is not keeping up.
   #[default(1)]
   pub concurrent_sends_per_instance: i8,
+  /// Store the raw json of incoming activities up to this size in bytes, so that admins can replay
+  /// them to debug federation problems. Disabled if set to 0.
+  #[default(0)]
+  pub record_received_activities_max_bytes: usize,
 }
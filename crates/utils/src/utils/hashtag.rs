@@ -0,0 +1,37 @@
+use itertools::Itertools;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Hashtags must be preceded by whitespace or punctuation, so that urls with fragments, html
+/// entities and markdown headings are not matched.
+static HASHTAG_REGEX: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"(?:^|[^\w&/#\])])#(?P<name>\w{1,64})").expect("compile regex"));
+
+/// Maximum number of hashtags stored for a post.
+pub const MAX_HASHTAGS_PER_POST: usize = 20;
+
+/// Returns the lowercase names of all hashtags in the text, without `#`. Tags consisting only of
+/// digits like `#1` are ignored.
+pub fn scrape_text_for_hashtags(text: &str) -> Vec<String> {
+  HASHTAG_REGEX
+    .captures_iter(text)
+    .filter_map(|caps| caps.name("name"))
+    .map(|name| name.as_str().to_lowercase())
+    .filter(|name| !name.chars().all(|c| c.is_ascii_digit() || c == '_'))
+    .unique()
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+
+  use crate::utils::hashtag::scrape_text_for_hashtags;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_hashtags_regex() {
+    let text = "#Rust release notes\n\n# Heading\n\nSee https://example.com/page#section and \
+                [#Lemmy](https://lemmy.ml), issue #123, it&#39;s #rust again.";
+    assert_eq!(vec!["rust", "lemmy"], scrape_text_for_hashtags(text));
+  }
+}
@@ -2,6 +2,7 @@ use crate::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 use clearurls::UrlCleaner;
 use itertools::Itertools;
 use regex::{Regex, RegexBuilder, RegexSet};
+use serde_json::json;
 use std::sync::LazyLock;
 use url::{ParseError, Url};
 
@@ -23,6 +24,9 @@ const ALT_TEXT_MAX_LENGTH: usize = 1500;
 const SITE_NAME_MAX_LENGTH: usize = 20;
 const SITE_NAME_MIN_LENGTH: usize = 1;
 const SITE_DESCRIPTION_MAX_LENGTH: usize = 150;
+const SLOW_MODE_MAX_SECONDS: i32 = 86400;
+const DUPLICATE_POST_WINDOW_MAX_DAYS: i32 = 365;
+pub const POST_GALLERY_MAX_IMAGES: usize = 20;
 //Invisible unicode characters, taken from https://invisible-characters.com/
 const FORBIDDEN_DISPLAY_CHARS: [char; 53] = [
   '\u{0009}',
@@ -200,6 +204,41 @@ pub fn site_description_length_check(description: &str) -> LemmyResult<()> {
   )
 }
 
+/// Checks that a community slow mode interval is positive and at most one day.
+pub fn is_valid_slow_mode_seconds(seconds: i32) -> LemmyResult<()> {
+  if (1..=SLOW_MODE_MAX_SECONDS).contains(&seconds) {
+    Ok(())
+  } else {
+    Err(LemmyErrorType::InvalidSlowModeSeconds.into())
+  }
+}
+
+/// Checks that a community duplicate post window is positive and at most one year.
+pub fn is_valid_duplicate_post_window_days(days: i32) -> LemmyResult<()> {
+  if (1..=DUPLICATE_POST_WINDOW_MAX_DAYS).contains(&days) {
+    Ok(())
+  } else {
+    Err(LemmyErrorType::InvalidDuplicatePostWindow.into())
+  }
+}
+
+/// Checks the number of images in a post gallery, and the length of their captions.
+pub fn is_valid_post_gallery<'a>(
+  captions: impl ExactSizeIterator<Item = Option<&'a str>>,
+) -> LemmyResult<()> {
+  if captions.len() > POST_GALLERY_MAX_IMAGES {
+    Err(LemmyErrorType::TooManyGalleryImages)?
+  }
+  for caption in captions.flatten() {
+    max_length_check(
+      caption,
+      ALT_TEXT_MAX_LENGTH,
+      LemmyErrorType::CaptionLengthOverflow,
+    )?;
+  }
+  Ok(())
+}
+
 /// Check minimum and maximum length of input string. If the string is too short or too long, the
 /// corresponding error is returned.
 ///
@@ -263,6 +302,31 @@ pub fn clean_url(url: &Url) -> Url {
   }
 }
 
+/// Normalizes a url so that different links to the same content compare equal. Removes tracking
+/// parameters and the fragment, and ignores the scheme, a `www.` prefix and trailing slashes.
+pub fn canonicalize_url(url: &Url) -> Url {
+  let mut url = clean_url(url);
+  if url.scheme() != "http" && url.scheme() != "https" {
+    return url;
+  }
+  url.set_fragment(None);
+  if url.scheme() == "http" {
+    url.set_scheme("https").ok();
+  }
+  if let Some(host) = url.host_str().and_then(|h| h.strip_prefix("www.")) {
+    let host = host.to_string();
+    url.set_host(Some(&host)).ok();
+  }
+  if url.path().len() > 1 && url.path().ends_with('/') {
+    let path = url.path().trim_end_matches('/').to_string();
+    url.set_path(&path);
+  }
+  if url.query() == Some("") {
+    url.set_query(None);
+  }
+  url
+}
+
 /// Cleans all the links in a string of tracking parameters.
 pub fn clean_urls_in_text(text: &str) -> String {
   match URL_CLEANER.clear_text(text) {
@@ -272,6 +336,60 @@ pub fn clean_urls_in_text(text: &str) -> String {
   }
 }
 
+/// Url cleaning rules added by the admins, applied after the rules bundled with ClearURLs.
+#[derive(Default)]
+pub struct UrlCleaningRules(Option<UrlCleaner>);
+
+impl UrlCleaningRules {
+  /// Builds the rules from pairs of url pattern and query parameter patterns. Both are case
+  /// insensitive regexes, and parameter patterns have to match the whole parameter name.
+  pub fn new<'a>(
+    rules: impl IntoIterator<Item = (&'a str, &'a [Option<String>])>,
+  ) -> LemmyResult<Self> {
+    let providers = rules
+      .into_iter()
+      .enumerate()
+      .map(|(i, (url_pattern, params))| {
+        let params = params.iter().flatten().collect::<Vec<_>>();
+        (
+          format!("admin_rule_{i}"),
+          json!({ "urlPattern": url_pattern, "rules": params }),
+        )
+      })
+      .collect::<serde_json::Map<_, _>>();
+    if providers.is_empty() {
+      return Ok(Self::default());
+    }
+    let rules = json!({ "providers": providers }).to_string();
+    let cleaner =
+      UrlCleaner::from_rules_str(&rules).with_lemmy_type(LemmyErrorType::InvalidUrlCleaningRule)?;
+    Ok(Self(Some(cleaner)))
+  }
+
+  /// Cleans a url of tracking parameters, using both the bundled and the admin rules.
+  pub fn clean_url(&self, url: &Url) -> Url {
+    let url = clean_url(url);
+    match self.0.as_ref().map(|c| c.clear_single_url(&url)) {
+      Some(Ok(res)) => res.into_owned(),
+      _ => url,
+    }
+  }
+
+  /// Like [canonicalize_url], but also applies the admin rules.
+  pub fn canonicalize_url(&self, url: &Url) -> Url {
+    canonicalize_url(&self.clean_url(url))
+  }
+
+  /// Cleans all the links in a string, using both the bundled and the admin rules.
+  pub fn clean_urls_in_text(&self, text: &str) -> String {
+    let text = clean_urls_in_text(text);
+    match self.0.as_ref().map(|c| c.clear_text(&text)) {
+      Some(Ok(res)) => res.into_owned(),
+      _ => text,
+    }
+  }
+}
+
 pub fn check_site_visibility_valid(
   current_private_instance: bool,
   current_federation_enabled: bool,
@@ -358,6 +476,7 @@ mod tests {
     error::{LemmyErrorType, LemmyResult},
     utils::validation::{
       build_and_check_regex,
+      canonicalize_url,
       check_site_visibility_valid,
       check_urls_are_valid,
       clean_url,
@@ -366,11 +485,15 @@ mod tests {
       is_valid_actor_name,
       is_valid_bio_field,
       is_valid_display_name,
+      is_valid_duplicate_post_window_days,
       is_valid_matrix_id,
+      is_valid_post_gallery,
       is_valid_post_title,
+      is_valid_slow_mode_seconds,
       is_valid_url,
       site_description_length_check,
       site_name_length_check,
+      UrlCleaningRules,
       BIO_MAX_LENGTH,
       SITE_DESCRIPTION_MAX_LENGTH,
       SITE_NAME_MAX_LENGTH,
@@ -394,6 +517,60 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_canonicalize_url() -> LemmyResult<()> {
+    let expected = Url::parse("https://example.com/news/123?id=5")?;
+    for url in [
+      "https://example.com/news/123?id=5",
+      "http://example.com/news/123?id=5",
+      "https://www.example.com/news/123/?id=5",
+      "https://EXAMPLE.com/news/123?id=5&utm_source=feed#comments",
+    ] {
+      assert_eq!(expected, canonicalize_url(&Url::parse(url)?));
+    }
+
+    let url = Url::parse("https://example.com/?utm_source=feed")?;
+    assert_eq!(Url::parse("https://example.com/")?, canonicalize_url(&url));
+    // Other schemes are only cleaned
+    let url = Url::parse("magnet:?xt=urn:btih:123")?;
+    assert_eq!(clean_url(&url), canonicalize_url(&url));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_url_cleaning_rules() -> LemmyResult<()> {
+    let params = [Some("ref".to_string()), Some("src_.*".to_string())];
+    let rules = UrlCleaningRules::new([(r"^https?://([a-z0-9-]+\.)?example\.com", &params[..])])?;
+
+    // Bundled rules are applied too
+    let url = Url::parse("https://news.example.com/1?id=5&REF=feed&src_campaign=x&utm_source=y")?;
+    let expected = Url::parse("https://news.example.com/1?id=5")?;
+    assert_eq!(expected, rules.clean_url(&url));
+    // Parameter names have to match completely, and other sites are not affected
+    let url = Url::parse("https://example.com/1?referrer=feed")?;
+    assert_eq!(url, rules.clean_url(&url));
+    let url = Url::parse("https://example.org/1?ref=feed")?;
+    assert_eq!(url, rules.clean_url(&url));
+
+    let text = "[a link](http://www.example.com/1/?ref=feed)";
+    assert_eq!(
+      "[a link](http://www.example.com/1/)",
+      rules.clean_urls_in_text(text)
+    );
+    let url = Url::parse("http://www.example.com/1/?ref=feed")?;
+    assert_eq!(
+      Url::parse("https://example.com/1")?,
+      rules.canonicalize_url(&url)
+    );
+
+    let invalid = [Some("(".to_string())];
+    assert!(UrlCleaningRules::new([("example", &invalid[..])])
+      .is_err_and(|e| e.error_type == LemmyErrorType::InvalidUrlCleaningRule));
+
+    Ok(())
+  }
+
   #[test]
   fn test_clean_body() -> LemmyResult<()> {
     let text = "[a link](https://example.com/path/123?utm_content=buffercf3b2&utm_medium=social&user+name=random+user&id=123)";
@@ -593,6 +770,34 @@ mod tests {
       });
   }
 
+  #[test]
+  fn test_valid_slow_mode_seconds() {
+    assert!(is_valid_slow_mode_seconds(30).is_ok());
+    assert!(is_valid_slow_mode_seconds(86400).is_ok());
+    assert!(is_valid_slow_mode_seconds(0).is_err());
+    assert!(is_valid_slow_mode_seconds(-5).is_err());
+    assert!(is_valid_slow_mode_seconds(86401).is_err());
+  }
+  #[test]
+  fn test_valid_duplicate_post_window_days() {
+    assert!(is_valid_duplicate_post_window_days(1).is_ok());
+    assert!(is_valid_duplicate_post_window_days(365).is_ok());
+    assert!(is_valid_duplicate_post_window_days(0).is_err());
+    assert!(is_valid_duplicate_post_window_days(366).is_err());
+  }
+
+  #[test]
+  fn test_valid_post_gallery() {
+    let caption = "a".repeat(1500);
+    assert!(is_valid_post_gallery([Some(caption.as_str()), None].into_iter()).is_ok());
+    assert!(is_valid_post_gallery(vec![None; 20].into_iter()).is_ok());
+    assert!(is_valid_post_gallery(vec![None; 21].into_iter())
+      .is_err_and(|e| e.error_type == LemmyErrorType::TooManyGalleryImages));
+    let caption = "a".repeat(1501);
+    assert!(is_valid_post_gallery([Some(caption.as_str())].into_iter())
+      .is_err_and(|e| e.error_type == LemmyErrorType::CaptionLengthOverflow));
+  }
+
   #[test]
   fn test_check_site_visibility_valid() {
     assert!(check_site_visibility_valid(true, true, &None, &None).is_err());
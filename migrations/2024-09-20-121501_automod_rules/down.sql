@@ -0,0 +1,12 @@
+ALTER TABLE local_site
+    DROP COLUMN system_account_id;
+
+ALTER TABLE post
+    DROP COLUMN flair;
+
+DROP TABLE mod_automod_action;
+
+DROP TABLE automod_rule;
+
+DROP TYPE automod_action_enum;
+
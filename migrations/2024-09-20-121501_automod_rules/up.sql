@@ -0,0 +1,51 @@
+CREATE TYPE automod_action_enum AS enum (
+    'Remove',
+    'Lock',
+    'Report',
+    'Hold',
+    'Reply',
+    'SetFlair'
+);
+
+-- Rules with a null community_id apply to the whole site, and can only be managed by admins.
+CREATE TABLE automod_rule (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE,
+    creator_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    name text NOT NULL,
+    enabled boolean NOT NULL DEFAULT TRUE,
+    apply_to_posts boolean NOT NULL DEFAULT TRUE,
+    apply_to_comments boolean NOT NULL DEFAULT TRUE,
+    title_regex text,
+    body_regex text,
+    domain text,
+    max_account_age_days int,
+    max_karma int,
+    min_report_count int,
+    action automod_action_enum NOT NULL,
+    action_message text,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    updated timestamp with time zone
+);
+
+CREATE INDEX idx_automod_rule_community ON automod_rule (community_id);
+
+-- Modlog entries for automated actions which don't have a modlog table of their own
+CREATE TABLE mod_automod_action (
+    id serial PRIMARY KEY,
+    mod_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    rule_id int REFERENCES automod_rule ON UPDATE CASCADE ON DELETE SET NULL,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    comment_id int REFERENCES comment ON UPDATE CASCADE ON DELETE CASCADE,
+    action automod_action_enum NOT NULL,
+    reason text,
+    when_ timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE post
+    ADD COLUMN flair text;
+
+-- Bot account which is shown as moderator for automated actions. Created on first use.
+ALTER TABLE local_site
+    ADD COLUMN system_account_id int REFERENCES person ON UPDATE CASCADE ON DELETE SET NULL;
+
@@ -0,0 +1,8 @@
+ALTER TABLE mod_remove_post
+    DROP COLUMN removal_reason_id;
+
+ALTER TABLE mod_remove_comment
+    DROP COLUMN removal_reason_id;
+
+DROP TABLE removal_reason;
+
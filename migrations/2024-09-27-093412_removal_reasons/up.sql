@@ -0,0 +1,22 @@
+-- Removal reason templates which moderators can pick when removing content
+CREATE TABLE removal_reason (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    title text NOT NULL,
+    reason text NOT NULL,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    updated timestamp with time zone
+);
+
+CREATE INDEX idx_removal_reason_community ON removal_reason (community_id);
+
+ALTER TABLE mod_remove_post
+    ADD COLUMN removal_reason_id int REFERENCES removal_reason ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE mod_remove_comment
+    ADD COLUMN removal_reason_id int REFERENCES removal_reason ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_mod_remove_post_removal_reason ON mod_remove_post (removal_reason_id);
+
+CREATE INDEX idx_mod_remove_comment_removal_reason ON mod_remove_comment (removal_reason_id);
+
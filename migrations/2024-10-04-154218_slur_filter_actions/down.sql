@@ -0,0 +1,7 @@
+DROP TABLE slur_filter_list;
+
+ALTER TABLE local_site
+    DROP COLUMN slur_filter_action;
+
+DROP TYPE slur_filter_action_enum;
+
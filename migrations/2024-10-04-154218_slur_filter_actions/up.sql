@@ -0,0 +1,22 @@
+CREATE TYPE slur_filter_action_enum AS enum (
+    'Reject',
+    'Report',
+    'Hold',
+    'Mask'
+);
+
+-- What happens to posts and comments matching slur_filter_regex. If null, post titles are rejected
+-- and slurs in text are masked, as before.
+ALTER TABLE local_site
+    ADD COLUMN slur_filter_action slur_filter_action_enum;
+
+-- Additional named word lists, each with its own action
+CREATE TABLE slur_filter_list (
+    id serial PRIMARY KEY,
+    name text NOT NULL UNIQUE,
+    regex text NOT NULL,
+    action slur_filter_action_enum NOT NULL,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    updated timestamp with time zone
+);
+
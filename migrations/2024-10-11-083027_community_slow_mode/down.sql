@@ -0,0 +1,4 @@
+ALTER TABLE community
+    DROP COLUMN comment_slow_mode_seconds,
+    DROP COLUMN post_slow_mode_seconds;
+
@@ -0,0 +1,6 @@
+-- Minimum number of seconds between two comments of the same user in a post, and between two posts
+-- of the same user in the community. Moderators and admins are exempt.
+ALTER TABLE community
+    ADD COLUMN comment_slow_mode_seconds int,
+    ADD COLUMN post_slow_mode_seconds int;
+
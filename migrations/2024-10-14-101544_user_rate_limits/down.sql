@@ -0,0 +1,8 @@
+ALTER TABLE local_site_rate_limit
+    DROP COLUMN vote,
+    DROP COLUMN vote_per_second,
+    DROP COLUMN report,
+    DROP COLUMN report_per_second,
+    DROP COLUMN private_message,
+    DROP COLUMN private_message_per_second;
+
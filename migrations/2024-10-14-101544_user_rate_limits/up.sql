@@ -0,0 +1,8 @@
+ALTER TABLE local_site_rate_limit
+    ADD COLUMN vote int NOT NULL DEFAULT 120,
+    ADD COLUMN vote_per_second int NOT NULL DEFAULT 60,
+    ADD COLUMN report int NOT NULL DEFAULT 20,
+    ADD COLUMN report_per_second int NOT NULL DEFAULT 600,
+    ADD COLUMN private_message int NOT NULL DEFAULT 30,
+    ADD COLUMN private_message_per_second int NOT NULL DEFAULT 600;
+
@@ -0,0 +1,11 @@
+-- Progress of importing the full history of a remote community from its outbox
+CREATE TABLE community_backfill (
+    community_id int PRIMARY KEY REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE,
+    pages_fetched int NOT NULL DEFAULT 0,
+    posts_imported int NOT NULL DEFAULT 0,
+    comments_imported int NOT NULL DEFAULT 0,
+    error text,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    updated timestamp with time zone,
+    finished timestamp with time zone
+);
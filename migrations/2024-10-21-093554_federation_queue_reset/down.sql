@@ -0,0 +1,4 @@
+ALTER TABLE federation_queue_state
+    DROP COLUMN requested_reset;
+
+DROP TYPE federation_queue_reset_enum;
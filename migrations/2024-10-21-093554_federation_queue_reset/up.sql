@@ -0,0 +1,9 @@
+CREATE TYPE federation_queue_reset_enum AS enum (
+    'Retry',
+    'Skip'
+);
+
+-- Set by admins to reset a stuck federation queue. The federation worker applies and clears it:
+-- Retry sends the next activity immediately, Skip drops all activities which weren't sent yet.
+ALTER TABLE federation_queue_state
+    ADD COLUMN requested_reset federation_queue_reset_enum;
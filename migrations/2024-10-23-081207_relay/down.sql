@@ -0,0 +1,4 @@
+DROP TABLE relay_community_filter;
+
+DROP TABLE relay;
+
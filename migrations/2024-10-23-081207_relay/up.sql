@@ -0,0 +1,21 @@
+-- ActivityPub relays which the local site actor follows
+CREATE TABLE relay (
+    id serial PRIMARY KEY,
+    actor_id varchar(255) NOT NULL UNIQUE,
+    inbox_url varchar(255) NOT NULL,
+    public_key text NOT NULL,
+    instance_id int NOT NULL REFERENCES instance ON UPDATE CASCADE ON DELETE CASCADE,
+    follow_accepted boolean NOT NULL DEFAULT FALSE,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    last_refreshed_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+-- Communities which are explicitly allowed or denied for a relay, both for sending and receiving
+CREATE TABLE relay_community_filter (
+    relay_id int REFERENCES relay ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    allow boolean NOT NULL,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    PRIMARY KEY (relay_id, community_id)
+);
+
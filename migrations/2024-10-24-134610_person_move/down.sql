@@ -0,0 +1,4 @@
+ALTER TABLE person
+    DROP COLUMN moved_to,
+    DROP COLUMN also_known_as;
+
@@ -0,0 +1,6 @@
+-- Account migration via ActivityPub `Move`. `moved_to` is the actor id of the account which this
+-- one was moved to, `also_known_as` lists previous accounts of the same user.
+ALTER TABLE person
+    ADD COLUMN moved_to varchar(255),
+    ADD COLUMN also_known_as text[] NOT NULL DEFAULT '{}';
+
@@ -0,0 +1,6 @@
+-- Community migration via ActivityPub `Move`. `moved_to` is the actor id of the community which
+-- this one was moved to, `also_known_as` lists communities which were imported into this one.
+ALTER TABLE community
+    ADD COLUMN moved_to varchar(255),
+    ADD COLUMN also_known_as text[] NOT NULL DEFAULT '{}';
+
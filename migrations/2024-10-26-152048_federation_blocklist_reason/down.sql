@@ -0,0 +1,4 @@
+ALTER TABLE federation_blocklist
+    DROP COLUMN reason,
+    DROP COLUMN comment,
+    DROP COLUMN expires;
@@ -0,0 +1,6 @@
+-- `reason` is shown publicly in the list of defederated instances, `comment` is only visible to
+-- admins. Entries are removed automatically once `expires` has passed.
+ALTER TABLE federation_blocklist
+    ADD COLUMN reason text,
+    ADD COLUMN comment text,
+    ADD COLUMN expires timestamptz;
@@ -0,0 +1 @@
+DROP TABLE federation_limitlist;
@@ -0,0 +1,11 @@
+-- Instances with limited federation. Their content is accepted, but hidden from the `All` listing
+-- unless the user follows the community, and their users can only send private messages to users
+-- who follow them.
+CREATE TABLE federation_limitlist (
+    instance_id int PRIMARY KEY REFERENCES instance ON UPDATE CASCADE ON DELETE CASCADE,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    updated timestamp with time zone,
+    reason text,
+    comment text,
+    expires timestamp with time zone
+);
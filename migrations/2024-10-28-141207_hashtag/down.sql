@@ -0,0 +1,3 @@
+DROP TABLE post_hashtag;
+
+DROP TABLE hashtag;
@@ -0,0 +1,14 @@
+-- Hashtags which are used in posts. Names are stored in lowercase, without leading `#`.
+CREATE TABLE hashtag (
+    id serial PRIMARY KEY,
+    name text NOT NULL UNIQUE,
+    published timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE TABLE post_hashtag (
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    hashtag_id int REFERENCES hashtag ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    PRIMARY KEY (post_id, hashtag_id)
+);
+
+CREATE INDEX idx_post_hashtag_hashtag ON post_hashtag (hashtag_id);
@@ -0,0 +1,10 @@
+-- Public keys which actors used before rotating their keypair. Signatures made with the previous
+-- key are still accepted until it expires, so that activities in flight during the rotation aren't
+-- rejected.
+CREATE TABLE actor_previous_key (
+    actor_id text PRIMARY KEY,
+    public_key text NOT NULL,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    expires timestamp with time zone NOT NULL
+);
+
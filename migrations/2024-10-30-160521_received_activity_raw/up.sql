@@ -0,0 +1,12 @@
+-- Raw json of incoming activities, only stored if enabled in the config. Admins can replay them to
+-- debug federation problems.
+CREATE TABLE received_activity_raw (
+    ap_id text PRIMARY KEY,
+    data text NOT NULL,
+    -- Error which occurred while receiving the activity, if any
+    error text,
+    published timestamp with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_received_activity_raw_published ON received_activity_raw (published);
+
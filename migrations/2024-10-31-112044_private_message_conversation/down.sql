@@ -0,0 +1,7 @@
+ALTER TABLE private_message
+    DROP COLUMN conversation_id;
+
+DROP TABLE private_message_conversation_participant;
+
+DROP TABLE private_message_conversation;
+
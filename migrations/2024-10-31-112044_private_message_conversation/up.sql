@@ -0,0 +1,76 @@
+-- Private messages are grouped into conversations. A conversation is identified by the set of its
+-- participants, so that messages between the same people end up in the same thread on every
+-- instance.
+CREATE TABLE private_message_conversation (
+    id serial PRIMARY KEY,
+    -- Sorted ids of all participants, including the creator of each message
+    participant_ids int[] NOT NULL UNIQUE,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    last_message_published timestamp with time zone NOT NULL DEFAULT now()
+);
+
+-- Per-participant state of a conversation
+CREATE TABLE private_message_conversation_participant (
+    conversation_id int REFERENCES private_message_conversation ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    muted boolean NOT NULL DEFAULT FALSE,
+    archived boolean NOT NULL DEFAULT FALSE,
+    last_read timestamp with time zone,
+    PRIMARY KEY (conversation_id, person_id)
+);
+
+CREATE INDEX idx_private_message_conversation_participant_person ON private_message_conversation_participant (person_id);
+
+ALTER TABLE private_message
+    ADD COLUMN conversation_id int REFERENCES private_message_conversation ON UPDATE CASCADE ON DELETE CASCADE;
+
+-- Create a conversation for each pair of people who already exchanged messages
+INSERT INTO private_message_conversation (participant_ids, published, last_message_published)
+SELECT
+    CASE WHEN creator_id = recipient_id THEN
+        ARRAY[creator_id]
+    ELSE
+        ARRAY[least (creator_id, recipient_id), greatest (creator_id, recipient_id)]
+    END AS participant_ids,
+    min(published),
+    max(published)
+FROM
+    private_message
+GROUP BY
+    participant_ids;
+
+UPDATE
+    private_message pm
+SET
+    conversation_id = c.id
+FROM
+    private_message_conversation c
+WHERE
+    c.participant_ids = CASE WHEN pm.creator_id = pm.recipient_id THEN
+        ARRAY[pm.creator_id]
+    ELSE
+        ARRAY[least (pm.creator_id, pm.recipient_id), greatest (pm.creator_id, pm.recipient_id)]
+    END;
+
+INSERT INTO private_message_conversation_participant (conversation_id, person_id, last_read)
+SELECT
+    c.id,
+    p.person_id,
+    (
+        SELECT
+            max(pm.published)
+        FROM
+            private_message pm
+        WHERE
+            pm.conversation_id = c.id
+            AND pm.recipient_id = p.person_id
+            AND pm.read)
+FROM
+    private_message_conversation c,
+    unnest(c.participant_ids) AS p (person_id);
+
+ALTER TABLE private_message
+    ALTER COLUMN conversation_id SET NOT NULL;
+
+CREATE INDEX idx_private_message_conversation ON private_message (conversation_id, published DESC);
+
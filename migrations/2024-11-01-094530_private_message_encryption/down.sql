@@ -0,0 +1,10 @@
+ALTER TABLE private_message_report
+    DROP COLUMN decrypted_excerpt;
+
+DROP TABLE private_message_key_envelope;
+
+ALTER TABLE private_message
+    DROP COLUMN encrypted;
+
+DROP TABLE person_device_key;
+
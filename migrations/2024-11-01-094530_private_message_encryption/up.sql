@@ -0,0 +1,26 @@
+-- Public keys of the devices which a person uses for end-to-end encrypted private messages
+CREATE TABLE person_device_key (
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    device_id text NOT NULL,
+    public_key text NOT NULL,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    PRIMARY KEY (person_id, device_id)
+);
+
+-- The content of encrypted messages is ciphertext, which the server can't read
+ALTER TABLE private_message
+    ADD COLUMN encrypted boolean NOT NULL DEFAULT FALSE;
+
+-- The message key, encrypted for one device of a participant
+CREATE TABLE private_message_key_envelope (
+    private_message_id int REFERENCES private_message ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    device_id text NOT NULL,
+    encrypted_key text NOT NULL,
+    PRIMARY KEY (private_message_id, person_id, device_id)
+);
+
+-- Excerpt of an encrypted message, decrypted by the reporter's client
+ALTER TABLE private_message_report
+    ADD COLUMN decrypted_excerpt text;
+
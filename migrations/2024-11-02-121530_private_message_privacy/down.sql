@@ -0,0 +1,8 @@
+ALTER TABLE private_message_conversation_participant
+    DROP COLUMN request;
+
+ALTER TABLE local_user
+    DROP COLUMN private_message_privacy;
+
+DROP TYPE private_message_privacy_enum;
+
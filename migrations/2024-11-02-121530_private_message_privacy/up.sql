@@ -0,0 +1,15 @@
+CREATE TYPE private_message_privacy_enum AS enum (
+    'Everyone',
+    'LocalOnly',
+    'Connected',
+    'Nobody'
+);
+
+ALTER TABLE local_user
+    ADD COLUMN private_message_privacy private_message_privacy_enum NOT NULL DEFAULT 'Everyone';
+
+-- Conversations started by people the participant doesn't know are message requests, until the
+-- participant accepts them or replies.
+ALTER TABLE private_message_conversation_participant
+    ADD COLUMN request boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,18 @@
+-- Metadata fetched for external links, so that sharing the same url repeatedly doesn't refetch it.
+CREATE TABLE link_metadata_cache (
+    url text PRIMARY KEY,
+    title text,
+    description text,
+    image text,
+    embed_video_url text,
+    author text,
+    published_date timestamptz,
+    embed_html text,
+    embed_video_width int,
+    embed_video_height int,
+    content_type text,
+    fetched timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_link_metadata_cache_fetched ON link_metadata_cache (fetched);
+
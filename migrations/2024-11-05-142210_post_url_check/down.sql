@@ -0,0 +1,6 @@
+ALTER TABLE post
+    DROP COLUMN url_status,
+    DROP COLUMN url_checked,
+    DROP COLUMN url_broken,
+    DROP COLUMN url_archive;
+
@@ -0,0 +1,11 @@
+-- Results of periodically re-requesting the urls of link posts
+ALTER TABLE post
+    ADD COLUMN url_status int,
+    ADD COLUMN url_checked timestamptz,
+    ADD COLUMN url_broken boolean NOT NULL DEFAULT FALSE,
+    ADD COLUMN url_archive text;
+
+CREATE INDEX idx_post_url_checked ON post (url_checked NULLS FIRST)
+WHERE
+    url IS NOT NULL;
+
@@ -0,0 +1,9 @@
+ALTER TABLE post
+    DROP COLUMN url_canonical;
+
+ALTER TABLE link_metadata_cache
+    DROP COLUMN canonical_url;
+
+ALTER TABLE community
+    DROP COLUMN duplicate_post_window_days;
+
@@ -0,0 +1,15 @@
+-- Normalized form of the post url, used to find reposts of the same link
+ALTER TABLE post
+    ADD COLUMN url_canonical text;
+
+CREATE INDEX idx_post_url_canonical ON post (url_canonical)
+WHERE
+    url_canonical IS NOT NULL;
+
+ALTER TABLE link_metadata_cache
+    ADD COLUMN canonical_url text;
+
+-- Reject posts linking to the same url as another post in the community from the last days
+ALTER TABLE community
+    ADD COLUMN duplicate_post_window_days int;
+
@@ -0,0 +1,5 @@
+DROP TABLE local_site_url_cleaning_rule;
+
+ALTER TABLE link_metadata_cache
+    DROP COLUMN final_url;
+
@@ -0,0 +1,13 @@
+-- Url cleaning rules added by admins, in addition to the ones bundled with ClearURLs
+CREATE TABLE local_site_url_cleaning_rule (
+    id serial PRIMARY KEY,
+    url_pattern text NOT NULL,
+    params text[] NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    updated timestamptz
+);
+
+-- The url which was reached after following redirects
+ALTER TABLE link_metadata_cache
+    ADD COLUMN final_url text;
+
@@ -0,0 +1,12 @@
+-- Images attached to a post in addition to its url, shown as a gallery
+CREATE TABLE post_gallery_image (
+    id serial PRIMARY KEY,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    position int NOT NULL,
+    url text NOT NULL,
+    caption text,
+    alt_text text,
+    published timestamptz NOT NULL DEFAULT now(),
+    UNIQUE (post_id, position)
+);
+
@@ -0,0 +1,3 @@
+ALTER TABLE community
+    DROP COLUMN community_comment_slow_mode_seconds;
+
@@ -0,0 +1,5 @@
+-- Minimum number of seconds between two comments of the same user anywhere in the community, in
+-- addition to the per post comment slow mode. Moderators and admins are exempt.
+ALTER TABLE community
+    ADD COLUMN community_comment_slow_mode_seconds int;
+
@@ -0,0 +1,15 @@
+ALTER TABLE private_message
+    ADD COLUMN read boolean NOT NULL DEFAULT FALSE;
+
+UPDATE
+    private_message pm
+SET
+    read = TRUE
+FROM
+    private_message_read r
+WHERE
+    r.private_message_id = pm.id
+    AND r.person_id = pm.recipient_id;
+
+DROP TABLE private_message_read;
+
@@ -0,0 +1,37 @@
+-- Group conversations have several recipients for each message, so whether a message was read is
+-- tracked per person instead of with a single flag.
+CREATE TABLE private_message_read (
+    private_message_id int REFERENCES private_message ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    published timestamp with time zone NOT NULL DEFAULT now(),
+    PRIMARY KEY (private_message_id, person_id)
+);
+
+CREATE INDEX idx_private_message_read_person ON private_message_read (person_id);
+
+INSERT INTO private_message_read (private_message_id, person_id)
+SELECT
+    id,
+    recipient_id
+FROM
+    private_message
+WHERE
+    read;
+
+-- Messages which were published before a participant last read the whole conversation
+INSERT INTO private_message_read (private_message_id, person_id)
+SELECT
+    pm.id,
+    p.person_id
+FROM
+    private_message pm
+    INNER JOIN private_message_conversation_participant p ON p.conversation_id = pm.conversation_id
+WHERE
+    pm.creator_id != p.person_id
+    AND pm.published <= p.last_read
+ON CONFLICT
+    DO NOTHING;
+
+ALTER TABLE private_message
+    DROP COLUMN read;
+
@@ -0,0 +1,2 @@
+DROP INDEX idx_post_url_canonical_pending;
+
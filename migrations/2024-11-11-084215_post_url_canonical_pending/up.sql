@@ -0,0 +1,6 @@
+-- Local posts whose url still has to be cleaned and canonicalized. Keeps the check for them at
+-- startup cheap once all existing posts are handled.
+CREATE INDEX idx_post_url_canonical_pending ON post (id)
+WHERE
+    local AND url IS NOT NULL AND url_canonical IS NULL;
+
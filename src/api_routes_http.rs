@@ -52,6 +52,7 @@ use lemmy_api::{
     get_link_metadata::get_link_metadata,
     hide::hide_post,
     like::like_post,
+    list_hashtags::list_hashtags,
     list_post_likes::list_post_likes,
     lock::lock_post,
     mark_read::mark_post_as_read,
@@ -62,7 +63,12 @@ use lemmy_api::{
     list::list_post_reports,
     resolve::resolve_post_report,
   },
-  private_message::mark_read::mark_pm_as_read,
+  private_message::{
+    edit_conversation::edit_pm_conversation,
+    list_conversations::list_pm_conversations,
+    mark_conversation_read::mark_pm_conversation_as_read,
+    mark_read::mark_pm_as_read,
+  },
   private_message_report::{
     create::create_pm_report,
     list::list_pm_reports,
@@ -71,8 +77,16 @@ use lemmy_api::{
   site::{
     block::block_instance,
     federated_instances::get_federated_instances,
+    federation_blocklist::{
+      block::admin_block_instance,
+      import::import_federation_blocklist,
+      limit::admin_limit_instance,
+      list::get_federation_blocklist,
+    },
+    federation_health::get_federation_health,
     leave_admin::leave_admin,
     list_all_media::list_all_media,
+    list_throttled_users::list_throttled_users,
     mod_log::get_mod_log,
     purge::{
       comment::purge_comment,
@@ -86,10 +100,17 @@ use lemmy_api::{
       list::list_registration_applications,
       unread_count::get_unread_registration_application_count,
     },
+    reset_federation_queue::reset_federation_queue,
   },
   sitemap::get_sitemap,
 };
 use lemmy_api_crud::{
+  automod::{
+    create::create_automod_rule,
+    delete::delete_automod_rule,
+    list::list_automod_rules,
+    update::update_automod_rule,
+  },
   comment::{
     create::create_comment,
     delete::delete_comment,
@@ -122,15 +143,39 @@ use lemmy_api_crud::{
     read::get_private_message,
     update::update_private_message,
   },
+  removal_reason::{
+    create::create_removal_reason,
+    delete::delete_removal_reason,
+    list::list_removal_reasons,
+    update::update_removal_reason,
+  },
   site::{create::create_site, read::get_site, update::update_site},
+  slur_filter::{
+    create::create_slur_filter_list,
+    delete::delete_slur_filter_list,
+    list::list_slur_filter_lists,
+    update::update_slur_filter_list,
+  },
   user::{create::register, delete::delete_account},
 };
 use lemmy_apub::api::{
+  community_backfill::{backfill_community, get_community_backfill},
+  community_migration::{
+    export_community,
+    import_community,
+    move_community,
+    MAX_COMMUNITY_BACKUP_SIZE,
+  },
+  device_keys::{delete_device_key, list_device_keys, save_device_key},
   list_comments::list_comments,
   list_posts::list_posts,
+  move_account::move_account,
   read_community::get_community,
   read_person::read_person,
+  relay::{add_relay, list_relays, remove_relay, set_relay_community_filter},
+  replay_activity::{list_received_activities, replay_activity},
   resolve_object::resolve_object,
+  rotate_keys::{admin_rotate_keys, rotate_keys},
   search::search,
   user_settings_backup::{export_settings, import_settings},
 };
@@ -187,12 +232,20 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/remove", web::post().to(remove_community))
           .route("/transfer", web::post().to(transfer_community))
           .route("/ban_user", web::post().to(ban_from_community))
-          .route("/mod", web::post().to(add_mod_to_community)),
+          .route("/mod", web::post().to(add_mod_to_community))
+          .route("/removal_reason", web::post().to(create_removal_reason))
+          .route("/removal_reason", web::put().to(update_removal_reason))
+          .route(
+            "/removal_reason/delete",
+            web::post().to(delete_removal_reason),
+          )
+          .route("/removal_reason/list", web::get().to(list_removal_reasons)),
       )
       .service(
         web::scope("/federated_instances")
           .wrap(rate_limit.message())
-          .route("", web::get().to(get_federated_instances)),
+          .route("", web::get().to(get_federated_instances))
+          .route("/blocklist", web::get().to(get_federation_blocklist)),
       )
       // Post
       .service(
@@ -202,6 +255,19 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.post())
           .route(web::post().to(create_post)),
       )
+      // Handle votes and reports separately to add the vote() and report() rate limiters
+      .service(
+        web::resource("/post/like")
+          .guard(guard::Post())
+          .wrap(rate_limit.vote())
+          .route(web::post().to(like_post)),
+      )
+      .service(
+        web::resource("/post/report")
+          .guard(guard::Post())
+          .wrap(rate_limit.report())
+          .route(web::post().to(create_post_report)),
+      )
       .service(
         web::scope("/post")
           .wrap(rate_limit.message())
@@ -214,10 +280,9 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/lock", web::post().to(lock_post))
           .route("/feature", web::post().to(feature_post))
           .route("/list", web::get().to(list_posts))
-          .route("/like", web::post().to(like_post))
           .route("/like/list", web::get().to(list_post_likes))
+          .route("/hashtag/list", web::get().to(list_hashtags))
           .route("/save", web::put().to(save_post))
-          .route("/report", web::post().to(create_post_report))
           .route("/report/resolve", web::put().to(resolve_post_report))
           .route("/report/list", web::get().to(list_post_reports))
           .route("/site_metadata", web::get().to(get_link_metadata)),
@@ -230,6 +295,18 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.comment())
           .route(web::post().to(create_comment)),
       )
+      .service(
+        web::resource("/comment/like")
+          .guard(guard::Post())
+          .wrap(rate_limit.vote())
+          .route(web::post().to(like_comment)),
+      )
+      .service(
+        web::resource("/comment/report")
+          .guard(guard::Post())
+          .wrap(rate_limit.report())
+          .route(web::post().to(create_comment_report)),
+      )
       .service(
         web::scope("/comment")
           .wrap(rate_limit.message())
@@ -239,24 +316,39 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/remove", web::post().to(remove_comment))
           .route("/mark_as_read", web::post().to(mark_reply_as_read))
           .route("/distinguish", web::post().to(distinguish_comment))
-          .route("/like", web::post().to(like_comment))
           .route("/like/list", web::get().to(list_comment_likes))
           .route("/save", web::put().to(save_comment))
           .route("/list", web::get().to(list_comments))
-          .route("/report", web::post().to(create_comment_report))
           .route("/report/resolve", web::put().to(resolve_comment_report))
           .route("/report/list", web::get().to(list_comment_reports)),
       )
       // Private Message
+      .service(
+        // Handle POST to /private_message separately to add the private_message() rate limiter
+        web::resource("/private_message")
+          .guard(guard::Post())
+          .wrap(rate_limit.private_message())
+          .route(web::post().to(create_private_message)),
+      )
+      .service(
+        web::resource("/private_message/report")
+          .guard(guard::Post())
+          .wrap(rate_limit.report())
+          .route(web::post().to(create_pm_report)),
+      )
       .service(
         web::scope("/private_message")
           .wrap(rate_limit.message())
           .route("/list", web::get().to(get_private_message))
-          .route("", web::post().to(create_private_message))
           .route("", web::put().to(update_private_message))
           .route("/delete", web::post().to(delete_private_message))
           .route("/mark_as_read", web::post().to(mark_pm_as_read))
-          .route("/report", web::post().to(create_pm_report))
+          .route("/conversation/list", web::get().to(list_pm_conversations))
+          .route("/conversation", web::put().to(edit_pm_conversation))
+          .route(
+            "/conversation/mark_as_read",
+            web::post().to(mark_pm_conversation_as_read),
+          )
           .route("/report/resolve", web::put().to(resolve_pm_report))
           .route("/report/list", web::get().to(list_pm_reports)),
       )
@@ -325,6 +417,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           // TODO Account actions. I don't like that they're in /user maybe /accounts
           .route("/logout", web::post().to(logout))
           .route("/delete_account", web::post().to(delete_account))
+          .route("/move_account", web::post().to(move_account))
+          .route("/rotate_keys", web::post().to(rotate_keys))
+          .route("/device_key", web::post().to(save_device_key))
+          .route("/device_key/delete", web::post().to(delete_device_key))
+          .route("/device_key/list", web::get().to(list_device_keys))
           .route(
             "/password_change",
             web::post().to(change_password_after_reset),
@@ -367,12 +464,62 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             web::get().to(get_registration_application),
           )
           .route("/list_all_media", web::get().to(list_all_media))
+          .route("/rate_limit/throttled", web::get().to(list_throttled_users))
           .service(
             web::scope("/purge")
               .route("/person", web::post().to(purge_person))
               .route("/community", web::post().to(purge_community))
               .route("/post", web::post().to(purge_post))
               .route("/comment", web::post().to(purge_comment)),
+          )
+          .service(
+            web::scope("/federation")
+              .route("/health", web::get().to(get_federation_health))
+              .route("/reset_queue", web::post().to(reset_federation_queue))
+              .route("/block", web::post().to(admin_block_instance))
+              .route("/limit", web::post().to(admin_limit_instance))
+              .route("/rotate_keys", web::post().to(admin_rotate_keys))
+              .route(
+                "/received_activities",
+                web::get().to(list_received_activities),
+              )
+              .route("/replay_activity", web::post().to(replay_activity))
+              .route(
+                "/import_blocklist",
+                web::post().to(import_federation_blocklist),
+              ),
+          )
+          .service(
+            web::scope("/community/backfill")
+              .route("", web::post().to(backfill_community))
+              .route("", web::get().to(get_community_backfill)),
+          )
+          .service(
+            web::scope("/community/migration")
+              .route("/export", web::get().to(export_community))
+              .route("/move", web::post().to(move_community))
+              .service(
+                web::resource("/import")
+                  .app_data(web::JsonConfig::default().limit(MAX_COMMUNITY_BACKUP_SIZE))
+                  .route(web::post().to(import_community)),
+              ),
+          )
+          .service(
+            web::scope("/relay")
+              .route("", web::post().to(add_relay))
+              .route("/delete", web::post().to(remove_relay))
+              .route("/list", web::get().to(list_relays))
+              .route(
+                "/community_filter",
+                web::post().to(set_relay_community_filter),
+              ),
+          )
+          .service(
+            web::scope("/slur_filter")
+              .route("", web::post().to(create_slur_filter_list))
+              .route("", web::put().to(update_slur_filter_list))
+              .route("/delete", web::post().to(delete_slur_filter_list))
+              .route("/list", web::get().to(list_slur_filter_lists)),
           ),
       )
       .service(
@@ -381,6 +528,14 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("", web::post().to(create_custom_emoji))
           .route("", web::put().to(update_custom_emoji))
           .route("/delete", web::post().to(delete_custom_emoji)),
+      )
+      .service(
+        web::scope("/automod")
+          .wrap(rate_limit.message())
+          .route("", web::post().to(create_automod_rule))
+          .route("", web::put().to(update_automod_rule))
+          .route("/delete", web::post().to(delete_automod_rule))
+          .route("/list", web::get().to(list_automod_rules)),
       ),
   );
   cfg.service(
@@ -1,9 +1,11 @@
 // This is for db migrations that require code
 use activitypub_federation::http_signatures::generate_actor_keypair;
 use diesel::{
-  sql_types::{Nullable, Text},
+  sql_query,
+  sql_types::{Array, Integer, Nullable, Text},
   ExpressionMethods,
   IntoSql,
+  NullableExpressionMethods,
   QueryDsl,
   TextExpressionMethods,
 };
@@ -19,12 +21,14 @@ use lemmy_api_common::{
   },
 };
 use lemmy_db_schema::{
+  newtypes::{DbUrl, PostId},
   source::{
     comment::{Comment, CommentUpdateForm},
     community::{Community, CommunityUpdateForm},
     instance::Instance,
     local_site::{LocalSite, LocalSiteInsertForm},
     local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitInsertForm},
+    local_site_url_cleaning_rule::LocalSiteUrlCleaningRule,
     local_user::{LocalUser, LocalUserInsertForm},
     person::{Person, PersonInsertForm, PersonUpdateForm},
     post::{Post, PostUpdateForm},
@@ -34,7 +38,11 @@ use lemmy_db_schema::{
   traits::Crud,
   utils::{get_conn, naive_now, DbPool},
 };
-use lemmy_utils::{error::LemmyResult, settings::structs::Settings};
+use lemmy_utils::{
+  error::LemmyResult,
+  settings::structs::Settings,
+  utils::validation::UrlCleaningRules,
+};
 use tracing::info;
 use url::Url;
 
@@ -53,6 +61,7 @@ pub async fn run_advanced_migrations(
   instance_actor_2022_01_28(pool, protocol_and_hostname, settings).await?;
   regenerate_public_keys_2022_07_05(pool).await?;
   initialize_local_site_2022_10_10(pool, settings).await?;
+  clean_post_urls_2024_11_07(pool).await?;
 
   Ok(())
 }
@@ -423,6 +432,69 @@ async fn regenerate_public_keys_2022_07_05(pool: &mut DbPool<'_>) -> LemmyResult
   Ok(())
 }
 
+/// Cleans tracking parameters from the urls of existing local posts, and stores their canonical
+/// form for finding reposts. New posts always get a canonical url, so each post is only handled
+/// once. Urls of remote posts are left as their instance sent them.
+async fn clean_post_urls_2024_11_07(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  use lemmy_db_schema::schema::post::dsl::{id, local, post, url, url_canonical};
+
+  let rules = LocalSiteUrlCleaningRule::get_all(pool).await?;
+  let url_cleaning_rules = UrlCleaningRules::new(
+    rules
+      .iter()
+      .map(|r| (r.url_pattern.as_str(), r.params.as_slice())),
+  )?;
+
+  let conn = &mut get_conn(pool).await?;
+  let mut count = 0;
+  let mut last_id = PostId(0);
+  loop {
+    // Uses the partial index idx_post_url_canonical_pending
+    let posts = post
+      .filter(local.eq(true))
+      .filter(url.is_not_null())
+      .filter(url_canonical.is_null())
+      .filter(id.gt(last_id))
+      .order_by(id)
+      .select((id, url.assume_not_null()))
+      .limit(1000)
+      .get_results::<(PostId, DbUrl)>(conn)
+      .await?;
+    let Some((batch_last_id, _)) = posts.last() else {
+      break;
+    };
+    last_id = *batch_last_id;
+    if count == 0 {
+      info!("Running clean_post_urls_2024_11_07");
+    }
+
+    let mut ids = Vec::with_capacity(posts.len());
+    let mut urls = Vec::with_capacity(posts.len());
+    let mut canonical_urls = Vec::with_capacity(posts.len());
+    for (post_id, post_url) in posts {
+      let cleaned = url_cleaning_rules.clean_url(&post_url);
+      ids.push(post_id.0);
+      canonical_urls.push(url_cleaning_rules.canonicalize_url(&cleaned).to_string());
+      urls.push(cleaned.to_string());
+    }
+    // Update the whole batch in a single statement
+    count += sql_query(
+      "UPDATE post SET url = batch.url, url_canonical = batch.url_canonical \
+       FROM unnest($1, $2, $3) AS batch(id, url, url_canonical) WHERE post.id = batch.id",
+    )
+    .bind::<Array<Integer>, _>(ids)
+    .bind::<Array<Text>, _>(urls)
+    .bind::<Array<Text>, _>(canonical_urls)
+    .execute(conn)
+    .await?;
+  }
+  if count > 0 {
+    info!("{count} Post url rows cleaned.");
+  }
+
+  Ok(())
+}
+
 /// This ensures that your local site is initialized and exists.
 ///
 /// If a site already exists, the DB migration should generate a local_site row.
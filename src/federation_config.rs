@@ -0,0 +1,91 @@
+use activitypub_federation::config::{FederationConfig, FederationConfigBuilder};
+use lemmy_api_common::{context::LemmyContext, lemmy_db_views::structs::SiteView};
+use lemmy_apub::objects::instance::ApubSite;
+use lemmy_db_schema::source::actor_previous_key::ActorPreviousKey;
+use lemmy_utils::error::LemmyResult;
+use std::{
+  ops::Deref,
+  sync::{Arc, RwLock},
+  time::Duration,
+};
+use tokio::time::sleep;
+use tracing::warn;
+
+/// How often to check if a local actor rotated its keypair.
+const KEY_ROTATION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The federation config used for HTTP requests. It caches private keys for signing, including the
+/// site key which signs fetches. So it needs to be rebuilt when a local actor rotates its keypair,
+/// otherwise the previous keys would be used until restart.
+#[derive(Clone)]
+pub struct ReloadableFederationConfig {
+  builder: FederationConfigBuilder<LemmyContext>,
+  current: Arc<RwLock<FederationConfig<LemmyContext>>>,
+}
+
+impl ReloadableFederationConfig {
+  pub async fn new(
+    builder: FederationConfigBuilder<LemmyContext>,
+    context: &LemmyContext,
+  ) -> LemmyResult<Self> {
+    let config = build(&builder, context).await?;
+    Ok(Self {
+      builder,
+      current: Arc::new(RwLock::new(config)),
+    })
+  }
+
+  pub fn current(&self) -> FederationConfig<LemmyContext> {
+    self
+      .current
+      .read()
+      .expect("Failed to lock federation config for reading")
+      .clone()
+  }
+
+  /// Rebuilds the config whenever a local actor rotated its keypair. Rotations by other processes
+  /// are also noticed, as they are detected through the database.
+  pub async fn reload_on_key_rotation(self) {
+    let context = self.current().deref().clone();
+    let mut latest_key_rotation = ActorPreviousKey::latest_local_rotation(&mut context.pool())
+      .await
+      .ok()
+      .flatten();
+    loop {
+      sleep(KEY_ROTATION_CHECK_INTERVAL).await;
+      let key_rotation = match ActorPreviousKey::latest_local_rotation(&mut context.pool()).await {
+        Ok(key_rotation) => key_rotation,
+        Err(e) => {
+          warn!("Failed to check for key rotation: {e}");
+          continue;
+        }
+      };
+      if key_rotation == latest_key_rotation {
+        continue;
+      }
+      match build(&self.builder, &context).await {
+        Ok(config) => {
+          *self
+            .current
+            .write()
+            .expect("Failed to lock federation config for updating") = config;
+          latest_key_rotation = key_rotation;
+        }
+        Err(e) => warn!("Failed to reload federation config: {e}"),
+      }
+    }
+  }
+}
+
+async fn build(
+  builder: &FederationConfigBuilder<LemmyContext>,
+  context: &LemmyContext,
+) -> LemmyResult<FederationConfig<LemmyContext>> {
+  let mut builder = builder.clone();
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  if site_view.local_site.federation_signed_fetch {
+    let site: ApubSite = site_view.site.into();
+    builder.signed_fetch_actor(&site);
+  }
+  Ok(builder.build().await?)
+}
@@ -1,17 +1,23 @@
 pub mod api_routes_http;
 pub mod code_migrations;
+pub mod federation_config;
 pub mod prometheus_metrics;
 pub mod scheduled_tasks;
 pub mod session_middleware;
 
-use crate::{code_migrations::run_advanced_migrations, session_middleware::SessionMiddleware};
-use activitypub_federation::config::{FederationConfig, FederationMiddleware};
+use crate::{
+  code_migrations::run_advanced_migrations,
+  federation_config::ReloadableFederationConfig,
+  session_middleware::SessionMiddleware,
+};
+use activitypub_federation::config::FederationConfig;
 use actix_cors::Cors;
 use actix_web::{
-  dev::{ServerHandle, ServiceResponse},
+  dev::{ServerHandle, Service, ServiceResponse},
   middleware::{self, Condition, ErrorHandlerResponse, ErrorHandlers},
   web::Data,
   App,
+  HttpMessage,
   HttpResponse,
   HttpServer,
 };
@@ -29,7 +35,6 @@ use lemmy_api_common::{
 };
 use lemmy_apub::{
   activities::{handle_outgoing_activities, match_outgoing_activities},
-  objects::instance::ApubSite,
   VerifyUrlData,
   FEDERATION_HTTP_FETCH_LIMIT,
 };
@@ -175,18 +180,16 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
     .debug(cfg!(debug_assertions))
     .http_signature_compat(true)
     .url_verifier(Box::new(VerifyUrlData(context.inner_pool().clone())));
-  if local_site.federation_signed_fetch {
-    let site: ApubSite = site_view.site.into();
-    federation_config_builder.signed_fetch_actor(&site);
-  }
-  let federation_config = federation_config_builder.build().await?;
+  let federation_config =
+    ReloadableFederationConfig::new(federation_config_builder.clone(), &context).await?;
+  tokio::task::spawn(federation_config.clone().reload_on_key_rotation());
 
   MATCH_OUTGOING_ACTIVITIES
     .set(Box::new(move |d, c| {
       Box::pin(match_outgoing_activities(d, c))
     }))
     .expect("set function pointer");
-  let request_data = federation_config.to_request_data();
+  let request_data = federation_config.current().to_request_data();
   let outgoing_activities_task = tokio::task::spawn(handle_outgoing_activities(request_data));
 
   let server = if !args.disable_http_server {
@@ -205,13 +208,11 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
 
   // This FederationConfig instance is exclusively used to send activities, so we can safely
   // increase the timeout without affecting timeouts for resolving objects anywhere.
-  let federation_sender_config = if !args.disable_activity_sending {
+  let federation_sender_config = (!args.disable_activity_sending).then(|| {
     let mut federation_sender_config = federation_config_builder.clone();
     federation_sender_config.request_timeout(ACTIVITY_SENDING_TIMEOUT);
-    Some(federation_sender_config.build().await?)
-  } else {
-    None
-  };
+    federation_sender_config
+  });
   let federate = federation_sender_config.map(|cfg| {
     SendManager::run(
       Opts {
@@ -272,7 +273,7 @@ fn create_startup_server() -> LemmyResult<ServerHandle> {
 }
 
 fn create_http_server(
-  federation_config: FederationConfig<LemmyContext>,
+  federation_config: ReloadableFederationConfig,
   settings: Settings,
   federation_enabled: bool,
 ) -> LemmyResult<ServerHandle> {
@@ -283,8 +284,8 @@ fn create_http_server(
     .build()
     .expect("Should always be buildable");
 
-  let context: LemmyContext = federation_config.deref().clone();
-  let rate_limit_cell = federation_config.rate_limit_cell().clone();
+  let context: LemmyContext = federation_config.current().deref().clone();
+  let rate_limit_cell = context.rate_limit_cell().clone();
 
   // Pictrs cannot use proxy
   let pictrs_client = ClientBuilder::new(client_builder(&SETTINGS).no_proxy().build()?)
@@ -308,7 +309,14 @@ fn create_http_server(
       .wrap(ErrorHandlers::new().default_handler(jsonify_plain_text_errors))
       .app_data(Data::new(context.clone()))
       .app_data(Data::new(rate_limit_cell.clone()))
-      .wrap(FederationMiddleware::new(federation_config.clone()))
+      // Same as `FederationMiddleware`, but with the current config after key rotation
+      .wrap_fn({
+        let federation_config = federation_config.clone();
+        move |req, srv| {
+          req.extensions_mut().insert(federation_config.current());
+          srv.call(req)
+        }
+      })
       .wrap(SessionMiddleware::new(context.clone()))
       .wrap(Condition::new(
         SETTINGS.prometheus.is_some(),
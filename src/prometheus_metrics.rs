@@ -1,13 +1,14 @@
 use actix_web::{rt::System, web, App, HttpServer};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, utils::build_federation_health};
 use lemmy_utils::{error::LemmyResult, settings::structs::PrometheusConfig};
-use prometheus::{default_registry, Encoder, Gauge, Opts, TextEncoder};
+use prometheus::{default_registry, Encoder, Gauge, IntGauge, IntGaugeVec, Opts, TextEncoder};
 use std::{sync::Arc, thread};
 use tracing::error;
 
 struct PromContext {
   lemmy: LemmyContext,
   db_pool_metrics: DbPoolMetrics,
+  federation_metrics: FederationMetrics,
 }
 
 struct DbPoolMetrics {
@@ -16,10 +17,19 @@ struct DbPoolMetrics {
   available: Gauge,
 }
 
+struct FederationMetrics {
+  latest_activity_id: IntGauge,
+  activities_behind: IntGaugeVec,
+  fail_count: IntGaugeVec,
+  last_successful_published_time: IntGaugeVec,
+  next_retry_time: IntGaugeVec,
+}
+
 pub fn serve_prometheus(config: PrometheusConfig, lemmy_context: LemmyContext) -> LemmyResult<()> {
   let context = Arc::new(PromContext {
     lemmy: lemmy_context,
     db_pool_metrics: create_db_pool_metrics()?,
+    federation_metrics: create_federation_metrics()?,
   });
 
   // spawn thread that blocks on handling requests
@@ -48,6 +58,7 @@ pub fn serve_prometheus(config: PrometheusConfig, lemmy_context: LemmyContext) -
 async fn metrics(context: web::Data<Arc<PromContext>>) -> LemmyResult<String> {
   // collect metrics
   collect_db_pool_metrics(&context).await;
+  collect_federation_metrics(&context).await?;
 
   let mut buffer = Vec::new();
   let encoder = TextEncoder::new();
@@ -96,3 +107,88 @@ async fn collect_db_pool_metrics(context: &PromContext) {
     .available
     .set(pool_status.available as f64);
 }
+
+// create lemmy_federation_* metrics and register them with the default registry
+fn create_federation_metrics() -> LemmyResult<FederationMetrics> {
+  let metrics = FederationMetrics {
+    latest_activity_id: IntGauge::with_opts(Opts::new(
+      "lemmy_federation_latest_activity_id",
+      "Id of the most recent outgoing activity",
+    ))?,
+    activities_behind: IntGaugeVec::new(
+      Opts::new(
+        "lemmy_federation_activities_behind",
+        "Number of activities which were not sent to the instance yet",
+      ),
+      &["domain"],
+    )?,
+    fail_count: IntGaugeVec::new(
+      Opts::new(
+        "lemmy_federation_fail_count",
+        "Number of consecutive failed attempts to send an activity to the instance",
+      ),
+      &["domain"],
+    )?,
+    last_successful_published_time: IntGaugeVec::new(
+      Opts::new(
+        "lemmy_federation_last_successful_published_time_seconds",
+        "Publish time of the last activity which was sent to the instance, as unix timestamp",
+      ),
+      &["domain"],
+    )?,
+    next_retry_time: IntGaugeVec::new(
+      Opts::new(
+        "lemmy_federation_next_retry_time_seconds",
+        "Time of the next attempt to send an activity to a failing instance, as unix timestamp",
+      ),
+      &["domain"],
+    )?,
+  };
+
+  default_registry().register(Box::new(metrics.latest_activity_id.clone()))?;
+  default_registry().register(Box::new(metrics.activities_behind.clone()))?;
+  default_registry().register(Box::new(metrics.fail_count.clone()))?;
+  default_registry().register(Box::new(metrics.last_successful_published_time.clone()))?;
+  default_registry().register(Box::new(metrics.next_retry_time.clone()))?;
+
+  Ok(metrics)
+}
+
+async fn collect_federation_metrics(context: &PromContext) -> LemmyResult<()> {
+  let health = build_federation_health(&mut context.lemmy.pool()).await?;
+  let metrics = &context.federation_metrics;
+  metrics
+    .latest_activity_id
+    .set(health.latest_activity_id.map(|id| id.0).unwrap_or(0));
+
+  // Remove instances which no longer have a federation queue
+  metrics.activities_behind.reset();
+  metrics.fail_count.reset();
+  metrics.last_successful_published_time.reset();
+  metrics.next_retry_time.reset();
+  for instance in health.instances {
+    let domain = [instance.instance.domain.as_str()];
+    let state = instance.federation_state;
+    metrics
+      .activities_behind
+      .with_label_values(&domain)
+      .set(instance.activities_behind);
+    metrics
+      .fail_count
+      .with_label_values(&domain)
+      .set(state.internal_state.fail_count.into());
+    if let Some(published) = state.internal_state.last_successful_published_time {
+      metrics
+        .last_successful_published_time
+        .with_label_values(&domain)
+        .set(published.timestamp());
+    }
+    if let Some(next_retry) = state.next_retry {
+      metrics
+        .next_retry_time
+        .with_label_values(&domain)
+        .set(next_retry.timestamp());
+    }
+  }
+  Ok(())
+}
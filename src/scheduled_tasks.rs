@@ -13,26 +13,35 @@ use diesel_async::{AsyncPgConnection, RunQueryDsl};
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
   schema::{
+    actor_previous_key,
     captcha_answer,
     comment,
     community_person_ban,
+    federation_blocklist,
+    federation_limitlist,
     instance,
     person,
     post,
     received_activity,
+    received_activity_raw,
     sent_activity,
   },
   source::{
     instance::{Instance, InstanceForm},
+    link_metadata_cache::LinkMetadataCache,
     local_user::LocalUser,
+    post::{Post, PostUpdateForm},
   },
+  traits::Crud,
   utils::{get_conn, naive_now, now, DbPool, DELETED_REPLACEMENT_TEXT},
 };
 use lemmy_routes::nodeinfo::{NodeInfo, NodeInfoWellKnown};
 use lemmy_utils::error::LemmyResult;
+use reqwest::StatusCode;
 use reqwest_middleware::ClientWithMiddleware;
 use std::time::Duration;
 use tracing::{error, info, warn};
+use url::Url;
 
 /// Schedules various cleanup tasks for lemmy in a background thread
 pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
@@ -51,6 +60,19 @@ pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
     }
   });
 
+  let context_1 = context.clone();
+  // Check the urls of link posts every hour
+  scheduler.every(CTimeUnits::hour(1)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      check_post_links(&context)
+        .await
+        .map_err(|e| warn!("Failed to check post links: {e}"))
+        .ok();
+    }
+  });
+
   let context_1 = context.clone();
   // Update hot ranks every 15 minutes
   scheduler.every(CTimeUnits::minutes(10)).run(move || {
@@ -85,6 +107,7 @@ pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
   // Daily tasks:
   // - Overwrite deleted & removed posts and comments every day
   // - Delete old denied users
+  // - Delete expired link metadata
   // - Update instance software
   scheduler.every(CTimeUnits::days(1)).run(move || {
     let context = context_1.clone();
@@ -92,6 +115,7 @@ pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
     async move {
       overwrite_deleted_posts_and_comments(&mut context.pool()).await;
       delete_old_denied_users(&mut context.pool()).await;
+      delete_expired_link_metadata(&mut context.pool()).await;
       update_instance_software(&mut context.pool(), context.client())
         .await
         .map_err(|e| warn!("Failed to update instance software: {e}"))
@@ -309,9 +333,18 @@ async fn clear_old_activities(pool: &mut DbPool<'_>) {
       )
       .execute(&mut conn)
       .await
-      .map(|_| info!("Done."))
       .map_err(|e| error!("Failed to clear old received activities: {e}"))
       .ok();
+
+      diesel::delete(
+        received_activity_raw::table
+          .filter(received_activity_raw::published.lt(now() - IntervalDsl::days(7))),
+      )
+      .execute(&mut conn)
+      .await
+      .map(|_| info!("Done."))
+      .map_err(|e| error!("Failed to clear old raw received activities: {e}"))
+      .ok();
     }
     Err(e) => {
       error!("Failed to get connection from pool: {e}");
@@ -329,6 +362,16 @@ async fn delete_old_denied_users(pool: &mut DbPool<'_>) {
     .ok();
 }
 
+async fn delete_expired_link_metadata(pool: &mut DbPool<'_>) {
+  LinkMetadataCache::delete_expired(pool)
+    .await
+    .map(|_| {
+      info!("Done.");
+    })
+    .map_err(|e| error!("Failed to delete expired link metadata: {e}"))
+    .ok();
+}
+
 /// overwrite posts and comments 30d after deletion
 async fn overwrite_deleted_posts_and_comments(pool: &mut DbPool<'_>) {
   info!("Overwriting deleted posts...");
@@ -443,6 +486,28 @@ async fn update_banned_when_expired(pool: &mut DbPool<'_>) {
       .await
       .map_err(|e| error!("Failed to remove community_ban expired rows: {e}"))
       .ok();
+
+      diesel::delete(
+        federation_blocklist::table.filter(federation_blocklist::expires.lt(now().nullable())),
+      )
+      .execute(&mut conn)
+      .await
+      .map_err(|e| error!("Failed to remove expired federation_blocklist rows: {e}"))
+      .ok();
+
+      diesel::delete(
+        federation_limitlist::table.filter(federation_limitlist::expires.lt(now().nullable())),
+      )
+      .execute(&mut conn)
+      .await
+      .map_err(|e| error!("Failed to remove expired federation_limitlist rows: {e}"))
+      .ok();
+
+      diesel::delete(actor_previous_key::table.filter(actor_previous_key::expires.lt(now())))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| error!("Failed to remove expired actor_previous_key rows: {e}"))
+        .ok();
     }
     Err(e) => {
       error!("Failed to get connection from pool: {e}");
@@ -450,6 +515,88 @@ async fn update_banned_when_expired(pool: &mut DbPool<'_>) {
   }
 }
 
+/// Re-requests the urls of link posts which weren't checked recently, to mark the ones which are
+/// gone as broken. Working links are submitted to the archiving service if one is configured, so
+/// that there is a snapshot to fall back to later.
+async fn check_post_links(context: &LemmyContext) -> LemmyResult<()> {
+  let Some(config) = context.settings().link_check.clone() else {
+    return Ok(());
+  };
+  info!("Checking post links...");
+  let posts = Post::list_for_link_check(
+    &mut context.pool(),
+    config.recheck_interval_days,
+    config.urls_per_hour,
+  )
+  .await?;
+
+  for post in posts {
+    let Some(url) = post.url else {
+      continue;
+    };
+    let status = context
+      .client()
+      .get(url.as_str())
+      .send()
+      .await
+      .map(|res| res.status())
+      .ok();
+    // Request errors like timeouts or DNS failures may be on our side, so they only count as a
+    // check and leave the previous result in place.
+    let Some(status) = status else {
+      let form = PostUpdateForm {
+        url_checked: Some(Some(naive_now())),
+        ..Default::default()
+      };
+      Post::update(&mut context.pool(), post.id, &form).await?;
+      continue;
+    };
+    let broken = is_broken_link_status(status);
+
+    let mut form = PostUpdateForm {
+      url_status: Some(Some(status.as_u16().into())),
+      url_checked: Some(Some(naive_now())),
+      url_broken: Some(broken),
+      ..Default::default()
+    };
+    if let (false, None, Some(archive_service)) =
+      (broken, &post.url_archive, &config.archive_service)
+    {
+      form.url_archive = archive_link(&url, archive_service, context.client())
+        .await
+        .map_err(|e| warn!("Failed to archive {url}: {e}"))
+        .ok()
+        .map(|archive| Some(archive.into()));
+    }
+    Post::update(&mut context.pool(), post.id, &form).await?;
+  }
+  info!("Finished checking post links");
+  Ok(())
+}
+
+/// Many sites reject automated requests with codes like 401, 403 or 429, so only responses which
+/// indicate that the content is actually gone count as broken.
+fn is_broken_link_status(status: StatusCode) -> bool {
+  status == StatusCode::NOT_FOUND || status == StatusCode::GONE || status.is_server_error()
+}
+
+/// Submits the url to the archiving service, and returns the url of the snapshot.
+async fn archive_link(
+  url: &Url,
+  archive_service: &Url,
+  client: &ClientWithMiddleware,
+) -> LemmyResult<Url> {
+  let submit_url = Url::parse(&format!("{archive_service}{url}"))?;
+  let res = client
+    .get(submit_url.as_str())
+    // Taking a snapshot can take a while
+    .timeout(Duration::from_secs(60))
+    .send()
+    .await?
+    .error_for_status()?;
+  Ok(res.url().clone())
+}
+
 /// Updates the instance software and version.
 ///
 /// Does so using the /.well-known/nodeinfo protocol described here:
@@ -550,13 +697,24 @@ async fn build_update_instance_form(
 #[allow(clippy::indexing_slicing)]
 mod tests {
 
-  use crate::scheduled_tasks::build_update_instance_form;
+  use crate::scheduled_tasks::{build_update_instance_form, is_broken_link_status};
   use lemmy_api_common::request::client_builder;
   use lemmy_utils::{error::LemmyResult, settings::structs::Settings, LemmyErrorType};
   use pretty_assertions::assert_eq;
+  use reqwest::StatusCode;
   use reqwest_middleware::ClientBuilder;
   use serial_test::serial;
 
+  #[test]
+  fn test_is_broken_link_status() {
+    assert!(!is_broken_link_status(StatusCode::OK));
+    assert!(!is_broken_link_status(StatusCode::FORBIDDEN));
+    assert!(!is_broken_link_status(StatusCode::TOO_MANY_REQUESTS));
+    assert!(is_broken_link_status(StatusCode::NOT_FOUND));
+    assert!(is_broken_link_status(StatusCode::GONE));
+    assert!(is_broken_link_status(StatusCode::BAD_GATEWAY));
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_nodeinfo_lemmy_ml() -> LemmyResult<()> {
@@ -9,6 +9,7 @@ use core::future::Ready;
 use futures_util::future::LocalBoxFuture;
 use lemmy_api::{local_user_view_from_jwt, read_auth_token};
 use lemmy_api_common::context::LemmyContext;
+use lemmy_utils::rate_limit::RateLimitUserId;
 use std::{future::ready, rc::Rc};
 
 #[derive(Clone)]
@@ -72,6 +73,9 @@ where
         //       https://github.com/LemmyNet/lemmy/issues/3702
         let local_user_view = local_user_view_from_jwt(jwt, &context).await.ok();
         if let Some(local_user_view) = local_user_view {
+          req
+            .extensions_mut()
+            .insert(RateLimitUserId(local_user_view.local_user.id.0));
           req.extensions_mut().insert(local_user_view);
         }
       }